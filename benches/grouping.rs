@@ -0,0 +1,152 @@
+// 그룹핑 루프의 성능을 측정하는 criterion 벤치마크.
+// 라이브러리의 process_docks를 같은 프로세스 안에서 호출하므로 프로세스 시작, 입력 파싱, 출력 비용은 포함되지 않는다.
+// 도크 수를 늘려가며 측정하여 그룹핑 시간이 도크 수에 비례(선형)하는지 확인한다.
+// 도크마다 집합에 넣고 그룹마다 도크 목록에서 현재 도크를 다시 찾던 예전 루프(reference)도 같은 입력으로 나란히 측정한다.
+//
+// 실행: cargo bench --bench grouping
+// 빌드된 실행 파일로 빠르게 확인하려면 `dock_sorter bench --docks 1000000`을 사용한다.
+
+use std::{
+  collections::{HashMap, HashSet},
+  hint::black_box,
+};
+
+use clap::Parser;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use dock_sorter::{
+  cli::{Args, ReplayOverrides},
+  models::Priority,
+  processor::{PlanRequest, process_docks},
+};
+
+// 예전 루프는 도크 수의 제곱에 비례하므로 이 크기까지만 측정한다.
+const REFERENCE_MAX_DOCKS: u32 = 50_000;
+
+/// 1..=docks 범위를 주어진 추가 인자로 계획하는 Args를 만드는 함수.
+fn plan_args(docks: u32, extra: &[&str]) -> Args {
+  let max = docks.to_string();
+  let mut args = ReplayOverrides::try_parse_from(
+    ["-p", "3", "--min", "1", "--max", &max, "-f", "10-20", "-s", "100-150", "-e", "500-502"]
+      .iter()
+      .chain(extra),
+  )
+  .expect("invalid benchmark arguments")
+  .args;
+  args.max_range_docks = Some(u64::from(docks));
+  args.validate_input().expect("invalid benchmark arguments");
+  args
+}
+
+/// 지금의 process_docks가 만드는 그룹들의 도크 번호.
+fn current_groups(args: &Args) -> Vec<Vec<u32>> {
+  process_docks(PlanRequest::new(args)).result_groups.into_iter().map(|group| group.docks.iter().map(|dock| dock.0).collect()).collect()
+}
+
+/// 예전 그룹핑 루프(reference). 범위를 모두 도크 하나하나로 펼쳐 HashSet과 HashMap에 넣고,
+/// 그룹을 시작할 때마다 도크 목록에서 현재 도크의 위치를 position()으로 다시 찾는다.
+/// 이 벤치마크의 입력(-f, -s, -e, --row-size)에 쓰이는 규칙만 옮겨왔다.
+fn reference_groups(args: &Args) -> Vec<Vec<u32>> {
+  let (fpp, spp, gpp) = (args.per_page, args.per_page, args.per_page);
+  let (min, max) = (args.min.0, args.max.0);
+  let in_range = |dock: &u32| (min..=max).contains(dock);
+
+  let mut final_exception_groups: Vec<Vec<u32>> = Vec::new();
+  let mut all_exception_docks: HashSet<u32> = HashSet::new();
+  for raw_ex_group in &args.exception_groups_raw {
+    let group: Vec<u32> = raw_ex_group.ranges.iter().flat_map(|range| range.start.0..=range.end.0).filter(in_range).collect();
+    all_exception_docks.extend(&group);
+    final_exception_groups.push(group);
+  }
+  let mut priorities: HashMap<u32, Priority> = HashMap::new();
+  for range in &args.first_priority {
+    for dock in (range.range.start.0..=range.range.end.0).filter(|dock| in_range(dock) && !all_exception_docks.contains(dock)) {
+      priorities.insert(dock, Priority::First);
+    }
+  }
+  for range in &args.second_priority {
+    for dock in (range.range.start.0..=range.range.end.0).filter(|dock| in_range(dock) && !all_exception_docks.contains(dock)) {
+      priorities.entry(dock).or_insert(Priority::Second);
+    }
+  }
+
+  let all_docks_in_range: Vec<u32> = match args.row_size {
+    Some(row_size) => {
+      let docks: Vec<u32> = (min..=max).collect();
+      docks
+        .chunks(row_size as usize)
+        .enumerate()
+        .flat_map(|(row, chunk)| if row % 2 == 1 { chunk.iter().rev().copied().collect() } else { chunk.to_vec() })
+        .collect()
+    }
+    None => (min..=max).collect(),
+  };
+  let mut result_groups: Vec<Vec<u32>> = Vec::new();
+  let mut processed_docks_in_grouping: HashSet<u32> = HashSet::new();
+  for &current_dock in &all_docks_in_range {
+    if processed_docks_in_grouping.contains(&current_dock) {
+      continue;
+    }
+    if all_exception_docks.contains(&current_dock) {
+      // 처리된 예외 그룹의 도크는 위에서 건너뛰므로, 여기까지 왔다면 순회 순서상 예외 그룹에서 처음 만나는 도크이다.
+      if let Some(ex_group) = final_exception_groups.iter().find(|group| group.contains(&current_dock)) {
+        processed_docks_in_grouping.extend(ex_group);
+        result_groups.push(ex_group.clone());
+      }
+      continue;
+    }
+    let mut regular_group = vec![current_dock];
+    processed_docks_in_grouping.insert(current_dock);
+    let current_dock_priority = priorities.get(&current_dock).unwrap_or(&Priority::Third);
+    let current_target_per_page = match current_dock_priority {
+      Priority::First => fpp,
+      Priority::Second => spp,
+      Priority::Third => gpp,
+    };
+    let mut next_dock_idx_in_range = all_docks_in_range.iter().position(|&d| d == current_dock).unwrap_or(0) + 1;
+    while regular_group.len() < usize::from(current_target_per_page) && next_dock_idx_in_range < all_docks_in_range.len() {
+      let next_dock_candidate = all_docks_in_range[next_dock_idx_in_range];
+      if processed_docks_in_grouping.contains(&next_dock_candidate) || all_exception_docks.contains(&next_dock_candidate) {
+        break;
+      }
+      let regular_group_first_prio = priorities.get(&regular_group[0]).unwrap_or(&Priority::Third);
+      let next_candidate_prio = priorities.get(&next_dock_candidate).unwrap_or(&Priority::Third);
+      if next_candidate_prio < regular_group_first_prio {
+        break;
+      }
+      regular_group.push(next_dock_candidate);
+      processed_docks_in_grouping.insert(next_dock_candidate);
+      next_dock_idx_in_range += 1;
+    }
+    result_groups.push(regular_group);
+  }
+  result_groups
+}
+
+fn grouping(c: &mut Criterion) {
+  let mut group = c.benchmark_group("grouping");
+  group.sample_size(10);
+  for docks in [10_000, 50_000, 100_000, 200_000] {
+    // 도크당 시간이 크기와 관계없이 같다면 그룹핑은 선형이다.
+    group.throughput(Throughput::Elements(u64::from(docks)));
+    // 오름차순 순회는 도크 목록을 만들지 않고 번호로 바로 계산하는 경로이다.
+    // serpentine 순회는 도크 목록(Listed)을 따라 그룹을 만드는 경로이며,
+    // 그룹마다 목록에서 현재 도크를 다시 찾던 시절에는 도크 수의 제곱에 비례해 느려졌다.
+    for (name, extra) in [("ascending", &[][..]), ("serpentine", &["--row-size", "100"][..])] {
+      let args = plan_args(docks, extra);
+      group.bench_with_input(BenchmarkId::new(name, docks), &args, |b, args| {
+        b.iter(|| process_docks(PlanRequest::new(black_box(args))))
+      });
+      if docks <= REFERENCE_MAX_DOCKS {
+        // 같은 그룹을 만드는 루프끼리 비교하는지 먼저 확인한다.
+        assert_eq!(reference_groups(&args), current_groups(&args), "reference loop differs for {name} {docks}");
+        group.bench_with_input(BenchmarkId::new(format!("{name}-reference"), docks), &args, |b, args| {
+          b.iter(|| reference_groups(black_box(args)))
+        });
+      }
+    }
+  }
+  group.finish();
+}
+
+criterion_group!(benches, grouping);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+// `grpc` 기능을 켰을 때 proto/dock_sorter.proto로 gRPC 서버 코드를 만든다.
+// protoc를 따로 설치하지 않아도 되도록 protoc-bin-vendored의 protoc를 사용한다.
+fn main() {
+  println!("cargo:rerun-if-changed=build.rs");
+  #[cfg(feature = "grpc")]
+  {
+    println!("cargo:rerun-if-changed=proto/dock_sorter.proto");
+    let mut config = prost_build::Config::new();
+    config.protoc_executable(protoc_bin_vendored::protoc_bin_path().expect("vendored protoc is available for this platform"));
+    tonic_prost_build::configure()
+      .build_client(false)
+      .compile_with_config(config, &["proto/dock_sorter.proto"], &["proto"])
+      .expect("failed to compile proto/dock_sorter.proto");
+  }
+}
@@ -0,0 +1,69 @@
+use std::{
+  fs::{self, OpenOptions},
+  io::{ErrorKind, Write},
+  path::Path,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// `serve --audit-log`에 경로를 지정하지 않았을 때 사용하는 기본 감사 기록 파일.
+pub const DEFAULT_AUDIT_LOG: &str = "dock_audit.jsonl";
+
+/// plan 요청 하나의 감사 기록. 파일에는 한 줄에 하나씩 JSON으로 덧붙이기만 하며, 고치거나 지우지 않는다.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(description = "Audit record of one planning request")]
+pub struct AuditEntry {
+  /// Time the request was answered (UTC, RFC 3339)
+  pub timestamp: String,
+  /// IP address of the caller
+  pub caller: String,
+  /// First 16 hex digits of the SHA-256 of the caller's bearer token, when the server has tokens configured
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub token: Option<String>,
+  /// Planning endpoint that was called (`/plan`, `/plan/batch` or the same under `/profiles/{name}`)
+  pub endpoint: String,
+  /// Config profile selected by the request
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub profile: Option<String>,
+  /// HTTP status of the response
+  pub status: u16,
+  /// SHA-256 (hex) of the request body
+  pub inputs_hash: String,
+  /// SHA-256 (hex) of the returned plan JSON; absent when the request failed
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub plan_hash: Option<String>,
+}
+
+/// bytes의 SHA-256을 hex 문자열로 만드는 함수.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+  Sha256::digest(bytes).iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// 감사 기록 한 건을 파일 끝에 한 줄로 덧붙이는 함수. 파일이 없다면 만든다.
+pub fn append_audit(path: &Path, entry: &AuditEntry) -> Result<(), String> {
+  let failed = |e: std::io::Error| format!("Failed to write audit log '{}': {e}", path.display());
+  let line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize audit entry: {e}"))?;
+  let mut file = OpenOptions::new().create(true).append(true).open(path).map_err(failed)?;
+  // 한 번의 write로 줄 전체를 써서 줄이 다른 기록과 섞이지 않도록 한다.
+  file.write_all(format!("{line}\n").as_bytes()).and_then(|()| file.sync_data()).map_err(failed)
+}
+
+/// 감사 기록을 오래된 것부터 읽는 함수. limit이 있다면 마지막 limit건만 반환한다. 파일이 없다면 빈 목록이다.
+pub fn read_audit(path: &Path, limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+  let content = match fs::read_to_string(path) {
+    Ok(content) => content,
+    Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(format!("Failed to read audit log '{}': {e}", path.display())),
+  };
+  let lines: Vec<(usize, &str)> = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).collect();
+  let skip = limit.map_or(0, |limit| lines.len().saturating_sub(limit));
+  lines
+    .into_iter()
+    .skip(skip)
+    .map(|(i, line)| {
+      serde_json::from_str(line).map_err(|e| format!("Corrupted audit log '{}' at line {}: {e}", path.display(), i + 1))
+    })
+    .collect()
+}
@@ -0,0 +1,39 @@
+// Code 128 기호들의 막대와 공백 폭(모듈 수). 값 0부터 105까지이며, 막대부터 시작하여 막대와 공백이 번갈아 온다.
+const PATTERNS: [&str; 106] = [
+  "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213", "221312", "231212", "112232",
+  "122132", "122231", "113222", "123122", "123221", "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122",
+  "321221", "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311",
+  "211313", "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331", "231131", "213113",
+  "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111", "111224", "111422",
+  "121124", "121421", "141122", "141221", "112214", "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111",
+  "241112", "134111", "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141", "214121",
+  "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141", "114131", "311141", "411131", "211412",
+  "211214", "211232",
+];
+// 코드 세트 B의 시작 기호 값
+const START_B: usize = 104;
+// 정지 기호. 마지막 막대(2모듈)가 더 붙어 있다.
+const STOP: &str = "2331112";
+
+/// data를 Code 128(코드 세트 B) 바코드로 만드는 함수.
+/// 막대부터 시작하여 막대와 공백이 번갈아 오는 폭(모듈 수)들을 반환한다. 여백(quiet zone)은 포함하지 않는다.
+/// 코드 세트 B는 출력 가능한 ASCII 문자(공백부터 ~까지)만 나타낼 수 있다.
+pub fn code128(data: &str) -> Result<Vec<u8>, String> {
+  if data.is_empty() {
+    return Err("Barcode data is empty.".to_string());
+  }
+  let values = data
+    .chars()
+    .map(|c| match c {
+      ' '..='~' => Ok(c as usize - 32),
+      _ => Err(format!("Barcode data '{data}' contains '{c}'; Code 128 labels can only encode printable ASCII characters.")),
+    })
+    .collect::<Result<Vec<usize>, String>>()?;
+  // 검사 기호는 시작 기호 값과 (위치 x 값)들의 합을 103으로 나눈 나머지이다.
+  let checksum = values.iter().enumerate().fold(START_B, |sum, (i, value)| sum + (i + 1) * value) % 103;
+
+  let symbols = std::iter::once(START_B).chain(values).chain(std::iter::once(checksum));
+  let mut widths: Vec<u8> = symbols.flat_map(|value| PATTERNS[value].bytes()).map(|digit| digit - b'0').collect();
+  widths.extend(STOP.bytes().map(|digit| digit - b'0'));
+  Ok(widths)
+}
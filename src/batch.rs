@@ -0,0 +1,56 @@
+use std::{fs, path::Path};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  cli::Args,
+  plan::SCHEMA_VERSION,
+  processor::ProcessingResult,
+  server::compute_api_plan,
+};
+
+/// 일괄 처리의 작업 하나. 이름(선택)과 CLI와 같은 plan 인자들로 이루어진다.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(description = "One independent planning job: an optional name plus the usual plan parameters")]
+pub struct BatchJob {
+  /// Optional job name (e.g. the building), echoed in the result
+  #[serde(default)]
+  pub name: Option<String>,
+  #[serde(flatten)]
+  pub args: Args,
+}
+
+/// HTTP API에서 일괄 처리한 작업 하나의 결과. 성공했다면 args와 result가, 실패했다면 error가 채워진다.
+#[derive(Debug, Serialize, JsonSchema)]
+#[schemars(description = "Result of one batch job: either the plan (args and result) or an error")]
+pub struct BatchResult {
+  /// Version of the plan format
+  pub schema_version: u32,
+  pub name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub args: Option<Args>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub result: Option<ProcessingResult>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+/// 작업 목록 파일(YAML 또는 JSON)을 읽어 BatchJob들로 파싱하는 함수.
+/// 파일의 최상위는 작업들의 리스트여야 한다.
+pub fn load_jobs(path: &Path) -> Result<Vec<BatchJob>, String> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read jobs file '{}': {e}", path.display()))?;
+  // YAML은 JSON의 상위 집합이므로 JSON 형식의 파일도 그대로 읽을 수 있다.
+  serde_yaml::from_str(&content).map_err(|e| format!("Invalid jobs file '{}': {e}", path.display()))
+}
+
+/// HTTP API로 들어온 작업 하나를 계산하여 BatchResult로 만드는 함수.
+/// 서버의 파일을 참조하는 인자는 허용하지 않으며, 실패하더라도 다른 작업에 영향을 주지 않는다.
+pub fn run_api_job(job: BatchJob) -> BatchResult {
+  let BatchJob { name, args } = job;
+  match compute_api_plan(&args) {
+    Ok(result) => BatchResult { schema_version: SCHEMA_VERSION, name, args: Some(args), result: Some(result), error: None },
+    Err(e) => BatchResult { schema_version: SCHEMA_VERSION, name, args: None, result: None, error: Some(e) },
+  }
+}
@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+
+use crate::{
+  cli::{BenchOptions, ReplayOverrides},
+  models::{DockId, DockRange},
+  processor::{PlanRequest, process_docks_with},
+};
+
+// 합성 입력의 도크 배치. 도크 100개마다 1차 도크 5개와 2차 도크 10개를,
+// 도크 1000개마다 2개짜리 예외 그룹 하나를 둔다.
+const FIRST_EVERY: u32 = 100;
+const SECOND_OFFSET: u32 = 40;
+const EXCEPTION_EVERY: u32 = 1000;
+const EXCEPTION_OFFSET: u32 = 700;
+
+/// `bench` 서브커맨드를 실행하는 함수.
+/// 1부터 --docks까지의 합성 입력으로 plan을 여러 번 계산하여 걸린 시간과 처리량을 출력한다.
+/// 출력 비용을 제외한 계산만 측정하기 위해 그룹들은 모으지 않고 개수만 센다.
+pub fn run_bench(options: &BenchOptions) -> Result<(), String> {
+  if options.docks == 0 {
+    return Err("`--docks` must be 1 or greater.".to_string());
+  }
+  if options.iterations == 0 {
+    return Err("`--iterations` must be 1 or greater.".to_string());
+  }
+
+  let per_page = options.per_page.to_string();
+  let max = options.docks.to_string();
+  let mut args = ReplayOverrides::try_parse_from(["-p", &per_page, "--min", "1", "--max", &max])
+    .map_err(|e| e.to_string())?
+    .args;
+  args.row_size = options.row_size;
+  // 합성 입력은 --docks 전체를 다루므로 범위 상한에 걸리지 않도록 한다.
+  args.max_range_docks = Some(u64::from(options.docks));
+  for base in (0..options.docks).step_by(FIRST_EVERY as usize) {
+    args.first_priority.push(DockRange { start: DockId(base + 1), end: DockId(base.saturating_add(5)) }.into());
+    args.second_priority.push(DockRange { start: DockId(base.saturating_add(SECOND_OFFSET + 1)), end: DockId(base.saturating_add(SECOND_OFFSET + 10)) }.into());
+  }
+  for base in (0..options.docks).step_by(EXCEPTION_EVERY as usize) {
+    args.exception_groups_raw.push(DockRange { start: DockId(base.saturating_add(EXCEPTION_OFFSET + 1)), end: DockId(base.saturating_add(EXCEPTION_OFFSET + 2)) }.into());
+  }
+  args.validate_input()?;
+
+  println!(
+    "Benchmark: {} docks ({} first, {} second ranges, {} exception groups), {} docks per group{}",
+    options.docks,
+    args.first_priority.len(),
+    args.second_priority.len(),
+    args.exception_groups_raw.len(),
+    options.per_page,
+    match options.row_size {
+      Some(row_size) => format!(", serpentine rows of {row_size}"),
+      None => String::new(),
+    }
+  );
+
+  let mut best = Duration::MAX;
+  let mut groups = 0;
+  for iteration in 1..=options.iterations {
+    let mut count = 0;
+    let started = Instant::now();
+    process_docks_with(PlanRequest::new(&args), |_, _| count += 1);
+    let elapsed = started.elapsed();
+    println!("  run {iteration}: {:.1} ms, {count} groups", elapsed.as_secs_f64() * 1000.0);
+    best = best.min(elapsed);
+    groups = count;
+  }
+
+  let docks_per_sec = f64::from(options.docks) / best.as_secs_f64();
+  println!(
+    "Best: {:.1} ms ({:.2} M docks/s, {groups} groups)",
+    best.as_secs_f64() * 1000.0,
+    docks_per_sec / 1_000_000.0
+  );
+  Ok(())
+}
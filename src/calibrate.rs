@@ -0,0 +1,136 @@
+use std::io::Write;
+
+use crate::{
+  cli::{CalibrateOptions, PrinterLanguage},
+  config::{load_config, save_profile_offset},
+  i18n::tr,
+  printing::{escpos_raster, open_printer, shift_commands},
+  raster::{Bitmap, LabelDimensions, LabelOffset, LabelSize, MM_PER_INCH},
+};
+
+// 보정 라벨의 표시 하나. 좌표와 크기는 dot 단위이다.
+enum Mark {
+  // 검게 채운 사각형 (테두리, 눈금, 십자선)
+  Bar { x: u32, y: u32, width: u32, height: u32 },
+  // 눈금 숫자. height는 글자 높이이다.
+  Number { x: u32, y: u32, height: u32, text: String },
+}
+
+// 보정 라벨의 표시들을 만든다. 라벨 가장자리의 테두리, 위쪽과 왼쪽 가장자리의 밀리미터 눈금(10mm마다 숫자),
+// 가운데의 십자선이다. 테두리가 용지 가장자리에서 벗어난 만큼을 눈금으로 읽어 보정값을 정한다.
+fn calibration_marks(size: LabelSize, dimensions: LabelDimensions, dpi: u32) -> Vec<Mark> {
+  let dots = |mm: f64| (mm * f64::from(dpi) / MM_PER_INCH).round() as u32;
+  let LabelSize { width, height } = size;
+  let line = dots(0.25).max(2);
+  let digit_height = dots(2.5).max(5);
+  // 눈금 길이: 10mm마다 5mm, 5mm마다 3mm, 나머지는 1.5mm
+  let tick = |mm: u32| if mm.is_multiple_of(10) { dots(5.0) } else if mm.is_multiple_of(5) { dots(3.0) } else { dots(1.5) };
+
+  let mut marks = vec![
+    Mark::Bar { x: 0, y: 0, width, height: line },
+    Mark::Bar { x: 0, y: height.saturating_sub(line), width, height: line },
+    Mark::Bar { x: 0, y: 0, width: line, height },
+    Mark::Bar { x: width.saturating_sub(line), y: 0, width: line, height },
+  ];
+  // 위쪽 눈금. 숫자는 긴 눈금의 오른쪽에 쓴다.
+  for mm in 1..dimensions.width_mm.ceil() as u32 {
+    let x = dots(f64::from(mm)).saturating_sub(line / 2);
+    marks.push(Mark::Bar { x, y: 0, width: line, height: tick(mm) });
+    if mm.is_multiple_of(10) {
+      marks.push(Mark::Number { x: x + 2 * line, y: dots(2.5), height: digit_height, text: mm.to_string() });
+    }
+  }
+  // 왼쪽 눈금. 숫자는 긴 눈금의 아래쪽에 쓴다.
+  for mm in 1..dimensions.height_mm.ceil() as u32 {
+    let y = dots(f64::from(mm)).saturating_sub(line / 2);
+    marks.push(Mark::Bar { x: 0, y, width: tick(mm), height: line });
+    if mm.is_multiple_of(10) {
+      marks.push(Mark::Number { x: dots(2.5), y: y + 2 * line, height: digit_height, text: mm.to_string() });
+    }
+  }
+  // 가운데 십자선
+  let arm = dots(10.0).min(width / 4).min(height / 4);
+  let (center_x, center_y) = (width / 2, height / 2);
+  marks.push(Mark::Bar { x: center_x.saturating_sub(arm), y: center_y.saturating_sub(line / 2), width: 2 * arm, height: line });
+  marks.push(Mark::Bar { x: center_x.saturating_sub(line / 2), y: center_y.saturating_sub(arm), width: line, height: 2 * arm });
+  marks
+}
+
+// 라벨 크기, 해상도, 보정값을 알려주는 글. 보정 라벨에 함께 인쇄한다.
+fn calibration_caption(dimensions: LabelDimensions, dpi: u32, offset: LabelOffset) -> [String; 2] {
+  [format!("{dimensions} mm  {dpi} dpi"), format!("offset {offset} mm")]
+}
+
+/// 보정 라벨을 프린터 명령으로 만드는 함수. 라벨 내용은 offset만큼 옮겨 인쇄된다.
+/// ZPL은 도형과 글자 명령으로, ESC/POS는 래스터 이미지(GS v 0)와 그 아래의 글로 그린다.
+pub fn calibration_commands(language: PrinterLanguage, dimensions: LabelDimensions, dpi: u32, offset: LabelOffset) -> Result<Vec<u8>, String> {
+  let size = dimensions.to_pixels(dpi)?;
+  let marks = calibration_marks(size, dimensions, dpi);
+  let caption = calibration_caption(dimensions, dpi, offset);
+  let mut commands = Vec::new();
+  match language {
+    PrinterLanguage::Zpl => {
+      let LabelSize { width, height } = size;
+      commands.extend_from_slice(b"^XA\n");
+      commands.extend(shift_commands(language, offset.to_dots(dpi)));
+      let mut zpl = format!("^PW{width}\n^LL{height}\n");
+      for mark in &marks {
+        match mark {
+          Mark::Bar { x, y, width, height } => zpl.push_str(&format!("^FO{x},{y}^GB{width},{height},{}^FS\n", width.min(height))),
+          Mark::Number { x, y, height, text } => zpl.push_str(&format!("^FO{x},{y}^A0N,{height},{height}^FD{text}^FS\n")),
+        }
+      }
+      // 십자선 아래에 가운데 맞춤으로 쓴다.
+      let text_height = (height / 12).max(1);
+      let mut y = height / 2 + height / 8;
+      for line in &caption {
+        zpl.push_str(&format!("^FO0,{y}^FB{width},1,0,C^A0N,{text_height},{text_height}^FD{line}^FS\n"));
+        y += text_height * 5 / 4;
+      }
+      zpl.push_str("^XZ\n");
+      commands.extend(zpl.into_bytes());
+    }
+    PrinterLanguage::EscPos => {
+      let mut image = Bitmap::new(size.width, size.height);
+      for mark in &marks {
+        match mark {
+          Mark::Bar { x, y, width, height } => image.fill_rect(*x, *y, *width, *height),
+          Mark::Number { x, y, height, text } => image.draw_text(text, *x, *y, (height / 5).max(1)),
+        }
+      }
+      commands.extend_from_slice(&[0x1B, b'@']);
+      commands.extend(shift_commands(language, offset.to_dots(dpi)));
+      commands.extend(escpos_raster(&image));
+      commands.extend_from_slice(&[0x1B, b'a', 1]);
+      for line in &caption {
+        commands.extend_from_slice(format!("{line}\n").as_bytes());
+      }
+      commands.extend_from_slice(&[0x1B, b'd', 3, 0x1D, b'V', 66, 0]);
+    }
+  }
+  Ok(commands)
+}
+
+/// `calibrate` 서브커맨드를 실행하는 함수. 보정 라벨을 인쇄하고, --profile이 지정되었다면 보정값을 설정 파일에 저장한다.
+pub fn run_calibrate(options: &CalibrateOptions) -> Result<(), String> {
+  if options.baud == 0 {
+    return Err(tr("error.baud", &[]));
+  }
+  // 인쇄한 뒤에 프로필이 없다는 것을 알게 되지 않도록 먼저 확인한다.
+  if let (Some(config), Some(profile)) = (&options.config, &options.profile) {
+    load_config(config)?.profile(profile)?;
+  }
+  let commands = calibration_commands(options.printer_language, options.label, options.printer_dpi, options.offset)?;
+  let mut printer = open_printer(&options.printer, options.baud)?;
+  printer
+    .write_all(&commands)
+    .and_then(|()| printer.flush())
+    .map_err(|e| format!("Failed to send the calibration label to printer '{}': {e}", options.printer))?;
+  eprintln!("{}", tr("status.calibration", &[("device", &options.printer), ("offset", &options.offset)]));
+
+  if let (Some(config), Some(profile)) = (&options.config, &options.profile) {
+    save_profile_offset(config, profile, options.offset)?;
+    eprintln!("{}", tr("status.offset_saved", &[("offset", &options.offset), ("profile", profile), ("path", &config.display())]));
+  }
+  Ok(())
+}
@@ -1,41 +1,911 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[cfg(unix)]
+use crate::cron::CronSchedule;
+use crate::{
+  audit::DEFAULT_AUDIT_LOG,
+  dates::{parse_date, parse_date_span},
+  history::DEFAULT_HISTORY_DB,
+  i18n::{Lang, tr},
+  intervals::IntervalSet,
+  metadata::DEFAULT_DOCK_DB,
+  models::{DockId, DockRange, ExceptionGroup, PerPageRange, Priority, PriorityRange},
+  pdf::{DEFAULT_COLUMNS, DEFAULT_PAPER, DEFAULT_ROWS, DEFAULT_SHEET_DPI, parse_paper},
+  placeholders::check_group_template,
+  queue::DEFAULT_QUEUE_FILE,
+  raster::{LabelDimensions, LabelOffset, parse_label_dimensions, parse_label_offset},
+  schedule::{format_time_of_day, parse_time_of_day},
+  upload::{UploadTarget, parse_upload_target},
+  wms::parse_json_path,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Dock Label Output Order and Range Calculator", long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+  #[command(subcommand)]
+  pub command: Option<Command>,
+
+  // 서브커맨드 없이 실행되는 경우의 기본 도크 정렬 인자들. 서브커맨드가 입력되면 None이 된다.
+  #[command(flatten)]
+  pub args: Option<Args>,
+
+  // 메세지 언어. 지정되지 않았다면 LC_ALL, LC_MESSAGES, LANG 환경 변수의 locale을 따른다.
+  /// Language of messages, warnings and headers (defaults from the locale)
+  #[arg(long, value_enum, global = true)]
+  pub lang: Option<Lang>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Re-print a plan saved with --save-plan without recomputing it
+  Load {
+    /// Path of the saved plan file (.dockplan)
+    path: PathBuf,
+  },
+  /// Recompute the checksum of a saved plan and check that its groups were not edited
+  VerifyPlan {
+    /// Path of the saved plan file
+    path: PathBuf,
+
+    /// Also check the plan's HMAC signature with the shared key at env:NAME or file:PATH
+    #[arg(long = "sign-key", value_name = "SOURCE")]
+    sign_key: Option<String>,
+  },
+  /// Compare two saved plans and report changed groups, moved docks and priority changes
+  Diff {
+    /// Path of the previous plan file
+    old: PathBuf,
+    /// Path of the revised plan file
+    new: PathBuf,
+  },
+  /// Compare a plan's group order with the scan sequence from handheld scanners and report deviations
+  Reconcile {
+    /// Path of the saved plan file
+    plan: PathBuf,
+    /// Scan export CSV with the dock number in the first field of each line, in scan order
+    scans: PathBuf,
+  },
+  /// Render the labels of a saved plan as files for label software (e.g. `export png plan.dockplan --out labels/`)
+  Export {
+    #[command(subcommand)]
+    target: ExportTarget,
+  },
+  /// Review runs recorded with --history
+  History {
+    #[command(subcommand)]
+    action: HistoryAction,
+
+    /// Path of the history database
+    #[arg(long, default_value = DEFAULT_HISTORY_DB)]
+    db: PathBuf,
+  },
+  /// Review, cancel or re-send the label print jobs submitted with --serial
+  Queue {
+    #[command(subcommand)]
+    action: QueueAction,
+
+    /// Path of the print queue state file
+    #[arg(long, default_value = DEFAULT_QUEUE_FILE)]
+    file: PathBuf,
+  },
+  /// Print a ruler and alignment test label to find a label printer's print offset (e.g. `calibrate --printer /dev/ttyUSB0 --label 100x50mm`)
+  ///
+  /// Print again with --offset until the border sits on the label edges, then add --config and --profile
+  /// to store the offset as the profile's label_offset.
+  Calibrate(CalibrateOptions),
+  /// Re-execute a stored invocation (history id or plan file) with the same args
+  ///
+  /// Flags given after the source override the stored ones, e.g. `replay 3 -p 4`.
+  /// --save-plan and --history are not replayed unless given again.
+  Replay {
+    /// History run id or path of a plan file
+    #[arg(value_parser = parse_replay_source)]
+    source: ReplaySource,
+
+    /// Path of the history database (used when the source is a run id)
+    #[arg(long, default_value = DEFAULT_HISTORY_DB)]
+    db: PathBuf,
+
+    /// Flags overriding the stored args
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    overrides: Vec<String>,
+  },
+  /// Run several independent planning jobs from a YAML (or JSON) file
+  Batch {
+    /// Jobs file: a list of plan parameter sets, each with an optional `name`
+    path: PathBuf,
+  },
+  /// Interactively tweak the plan args and see the re-computed plan after each change
+  ///
+  /// Optional flags set the starting args (same as a normal run, default `-p 3`).
+  Repl {
+    /// Starting flags, e.g. `repl -p 4 --min 51 --max 70`
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    initial: Vec<String>,
+  },
+  /// Open a small window to edit the plan args and preview the groups live
+  #[cfg(feature = "gui")]
+  Gui,
+  /// Print the JSON Schema of the plan format (plan files, `POST /plan` responses)
+  Schema,
+  /// Measure planning throughput on synthesized inputs (e.g. `bench --docks 1000000`)
+  Bench(BenchOptions),
+  /// Generate reproducible random plan arguments for training and integration tests (e.g. `gen --docks 51-78 --first 4 --seed 42`)
+  Gen(GenOptions),
+  /// Plan many random scenarios and report group statistics per docks-per-group value (e.g. `simulate --runs 1000 -p 2,3,4`)
+  Simulate(SimulateOptions),
+  /// Run an HTTP API server exposing `POST /plan`
+  Serve(ServeOptions),
+  /// Run a long-lived planning daemon on a Unix domain socket (one JSON request per line)
+  #[cfg(unix)]
+  Daemon(DaemonOptions),
+}
+
+/// `bench` 서브커맨드의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct BenchOptions {
+  /// Number of docks to plan (docks 1 to N, with synthesized priority and exception docks)
+  #[arg(long, default_value_t = 1_000_000)]
+  pub docks: u32,
+
+  /// Number of docks per group
+  #[arg(short = 'p', long, default_value_t = 3)]
+  pub per_page: u16,
+
+  /// Number of timed runs; the best one is reported
+  #[arg(long, default_value_t = 3)]
+  pub iterations: u32,
+
+  /// Traverse the docks in serpentine rows of this size (same as --row-size of a normal run)
+  #[arg(long = "row-size")]
+  pub row_size: Option<u32>,
+}
+
+/// `gen`, `simulate` 서브커맨드가 무작위 입력을 만드는 방식.
+#[derive(clap::Args, Debug)]
+pub struct ScenarioOptions {
+  /// Dock range of the generated plan
+  #[arg(long, default_value = "51-78", value_parser = parse_dock_ranges)]
+  pub docks: DockRange,
+
+  /// Number of 1st priority docks
+  #[arg(long, default_value_t = 3)]
+  pub first: u32,
+
+  /// Number of 2nd priority docks
+  #[arg(long, default_value_t = 4)]
+  pub second: u32,
+
+  /// Number of exception groups (two adjacent docks each)
+  #[arg(long, default_value_t = 1)]
+  pub exceptions: u32,
+
+  /// Seed of the generator; the same seed always gives the same inputs (printed to stderr when omitted)
+  #[arg(long)]
+  pub seed: Option<u64>,
+}
+
+/// `gen` 서브커맨드의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct GenOptions {
+  #[command(flatten)]
+  pub scenario: ScenarioOptions,
+
+  /// Docks per group in the generated arguments
+  #[arg(short = 'p', long, default_value_t = 3)]
+  pub per_page: u16,
+
+  /// Write the priorities as a shipment count CSV for --auto-priority instead of -f/-s
+  #[arg(long)]
+  pub manifest: Option<PathBuf>,
+}
+
+/// `simulate` 서브커맨드의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct SimulateOptions {
+  #[command(flatten)]
+  pub scenario: ScenarioOptions,
+
+  /// Number of random scenarios to plan
+  #[arg(long, default_value_t = 1000)]
+  pub runs: u32,
+
+  /// Docks per group to compare on the same scenarios (comma-separated, e.g. 2,3,4)
+  #[arg(short = 'p', long, value_delimiter = ',', default_value = "3")]
+  pub per_page: Vec<u16>,
+}
+
+/// `serve` 서브커맨드의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct ServeOptions {
+  /// Address to listen on
+  #[arg(long, default_value = "127.0.0.1")]
+  pub host: String,
+
+  /// Port to listen on
+  #[arg(long, default_value_t = 8080)]
+  pub port: u16,
+
+  /// Print the OpenAPI document of the API and exit
+  #[arg(long, action = clap::ArgAction::SetTrue)]
+  pub openapi: bool,
+
+  // 배포 환경을 구분하기 위한 설정 프로필 이름. GET /version에 함께 표시된다.
+  /// Name of the active configuration profile, reported by `GET /version`
+  #[arg(long)]
+  pub profile: Option<String>,
+
+  // 서버 설정(bearer token 등)을 읽어올 TOML 설정 파일.
+  /// Config file (TOML) with server settings such as `[server] tokens = [...]`
+  ///
+  /// Bearer tokens can also be given comma-separated in the DOCK_SORTER_TOKENS environment variable.
+  /// Its `[profiles.NAME]` tables can be selected per request with `/profiles/NAME/plan` or the `X-Dock-Profile` header.
+  #[arg(long)]
+  pub config: Option<PathBuf>,
+
+  // 도크 메타데이터(이름, 가중치, 사용 중지 여부)를 저장할 SQLite 데이터베이스. 경로 없이 --dock-db만 입력하면 기본 파일을 사용한다.
+  /// Serve the dock metadata endpoints (`/docks`) backed by this SQLite database; closed docks are skipped by planning requests
+  #[arg(long = "dock-db", num_args = 0..=1, default_missing_value = DEFAULT_DOCK_DB)]
+  pub dock_db: Option<PathBuf>,
+
+  // plan 요청의 감사 기록(호출자, 시각, 입력과 plan의 hash)을 덧붙일 JSON Lines 파일. 경로 없이 입력하면 기본 파일을 사용한다.
+  /// Append an audit record (caller, timestamp, inputs hash, plan hash) of every planning request to this JSON Lines file, listed by `GET /audit`
+  #[arg(long = "audit-log", num_args = 0..=1, default_missing_value = DEFAULT_AUDIT_LOG)]
+  pub audit_log: Option<PathBuf>,
+
+  // HTTPS로 서비스할 때의 인증서 체인(PEM). --tls-key와 함께 입력해야 한다.
+  /// Serve HTTPS with this PEM certificate chain (requires --tls-key)
+  #[arg(long = "tls-cert", requires = "tls_key")]
+  pub tls_cert: Option<PathBuf>,
+
+  // --tls-cert 인증서의 개인 키(PEM).
+  /// PEM private key of --tls-cert
+  #[arg(long = "tls-key", requires = "tls_cert")]
+  pub tls_key: Option<PathBuf>,
+
+  // 브라우저의 배차 화면이 API를 직접 호출할 수 있도록 허용하는 origin들. 설정 파일의 `[server] cors_origins`에도 더해진다.
+  /// Allow browser pages from these origins (e.g. `https://dispatch.example.com`, or `*` for any) to call the API
+  ///
+  /// Can be repeated or comma-separated, and extended by `[server] cors_origins = [...]` in the config file.
+  #[arg(long = "cors-origin", value_delimiter = ',', num_args = 1.., action = clap::ArgAction::Append)]
+  pub cors_origins: Vec<String>,
+
+  // 요청 하나(배치라면 작업 하나와 배치 전체)의 범위가 펼쳐졌을 때 허용하는 최대 도크 수. 공용 서버를 거대한 요청 하나가 붙잡지 않도록 한다.
+  /// Refuse planning requests (and batch jobs, or batches in total) whose ranges would expand to more docks than this
+  #[arg(long = "max-range-docks", default_value_t = DEFAULT_SERVER_MAX_RANGE_DOCKS)]
+  pub max_range_docks: u64,
+
+  // token(token이 설정되지 않았다면 IP 주소)마다 분당 허용하는 plan 요청 수.
+  /// Allow at most this many planning requests per minute per token (per IP address when no tokens are configured)
+  #[arg(long = "rate-limit", value_parser = clap::value_parser!(u32).range(1..))]
+  pub rate_limit: Option<u32>,
+
+  // 같은 plan API를 gRPC(proto/dock_sorter.proto)로도 받는 포트. `grpc` 기능으로 빌드해야 한다.
+  /// Also serve the planning API over gRPC on this port (see proto/dock_sorter.proto)
+  #[cfg(feature = "grpc")]
+  #[arg(long = "grpc-port")]
+  pub grpc_port: Option<u16>,
+}
+
+/// `daemon` 서브커맨드의 옵션들.
+#[cfg(unix)]
+#[derive(clap::Args, Debug)]
+pub struct DaemonOptions {
+  /// Path of the Unix domain socket
+  #[arg(long, default_value = "/tmp/dock_sorter.sock")]
+  pub socket: PathBuf,
+
+  // 정해진 시각에 설정 프로필로 plan을 자동 생성하기 위한 cron 표현식(분 시 일 월 요일, 로컬 시각 기준).
+  /// Cron schedule (minute hour day-of-month month day-of-week, local time) for unattended runs of --profile
+  #[arg(long, value_parser = CronSchedule::parse, requires_all = ["profile", "config"])]
+  pub schedule: Option<CronSchedule>,
+
+  /// Config profile whose plan args (including exports) are run on each --schedule tick
+  #[arg(long, requires = "schedule")]
+  pub profile: Option<String>,
+
+  /// Config file (TOML) defining the profiles
+  #[arg(long)]
+  pub config: Option<PathBuf>,
+}
+
+/// `replay`에서 저장된 Args를 덮어쓸 플래그들을 파싱하기 위한 Parser.
+#[derive(Parser, Debug)]
+#[command(name = "replay", no_binary_name = true)]
+pub struct ReplayOverrides {
+  #[command(flatten)]
+  pub args: Args,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryAction {
+  /// List all recorded runs
+  List,
+  /// Re-print the plan of a recorded run
+  Show {
+    /// Id of the run (see `history list`)
+    id: i64,
+  },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum QueueAction {
+  /// List the print jobs (one per group) with their status
+  List,
+  /// Cancel pending or failed jobs so that `queue retry` does not send them
+  Cancel {
+    /// Ids of the jobs (see `queue list`)
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    ids: Vec<u64>,
+
+    /// Cancel every pending or failed job
+    #[arg(long)]
+    all: bool,
+  },
+  /// Re-send jobs to the printer: the given ids (even if already sent), or every pending or failed job
+  Retry {
+    /// Ids of the jobs (see `queue list`)
+    ids: Vec<u64>,
+
+    /// Send to this printer instead of the one the jobs were submitted to (e.g. a replacement for a jammed printer)
+    #[arg(long = "serial", value_name = "DEVICE")]
+    device: Option<String>,
+
+    /// Ask for confirmation on the terminal before sending each group
+    #[arg(long = "confirm-each-group")]
+    confirm_each_group: bool,
+
+    /// Wait this many milliseconds after sending each group
+    #[arg(long = "delay-ms")]
+    delay_ms: Option<u64>,
+  },
+}
+
+/// `calibrate`의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct CalibrateOptions {
+  /// Serial port (e.g. /dev/ttyUSB0, COM3) or Bluetooth address (rfcomm:AA:BB:CC:DD:EE:FF[/CHANNEL]) of the printer
+  #[arg(long, value_name = "DEVICE")]
+  pub printer: String,
+
+  /// Label size in millimeters, WIDTHxHEIGHT
+  #[arg(long, default_value = "100x60", value_parser = parse_label_dimensions)]
+  pub label: LabelDimensions,
+
+  /// Print offset to test in millimeters, X,Y (positive moves right and down)
+  #[arg(long, value_name = "X,Y", default_value = "0,0", value_parser = parse_label_offset, allow_hyphen_values = true)]
+  pub offset: LabelOffset,
+
+  /// Baud rate of the serial port (not used for RFCOMM addresses)
+  #[arg(long, default_value_t = DEFAULT_BAUD)]
+  pub baud: u32,
+
+  /// Command language of the printer
+  #[arg(long = "printer-language", value_enum, default_value_t = PrinterLanguage::Zpl)]
+  pub printer_language: PrinterLanguage,
+
+  /// Resolution of the printer in dots per inch
+  #[arg(long = "printer-dpi", default_value_t = DEFAULT_PRINTER_DPI)]
+  pub printer_dpi: u32,
+
+  /// Config file (TOML) to store the offset into
+  #[arg(long, requires = "profile")]
+  pub config: Option<PathBuf>,
+
+  /// Profile of --config whose label_offset is set to --offset after printing
+  #[arg(long, requires = "config")]
+  pub profile: Option<String>,
+}
+
+/// `export`로 만드는 파일 형식.
+#[derive(Subcommand, Debug)]
+pub enum ExportTarget {
+  /// One PNG image per dock label, named in printing order (e.g. `0001-dock52.png`)
+  Png(PngOptions),
+  /// Sheets of labels in one PDF file, filling a grid of cells on each page in printing order
+  Pdf(PdfOptions),
+  /// One SVG file per dock label plus `overview.svg`, a diagram of the whole dock face with groups color-coded
+  Svg(SvgOptions),
+  /// A map of the dock range as a horizontal strip with group boundaries, priorities, exception and skipped docks
+  Map(MapOptions),
+}
+
+/// `export png`의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct PngOptions {
+  /// Path of the saved plan file
+  pub plan: PathBuf,
+
+  /// Directory to write the images to (created if missing)
+  #[arg(long)]
+  pub out: PathBuf,
+
+  /// Printer resolution in dots per inch (recorded in the PNG files)
+  #[arg(long, default_value_t = 203)]
+  pub dpi: u32,
+
+  /// Label size in millimeters, WIDTHxHEIGHT
+  #[arg(long, default_value = "100x60", value_parser = parse_label_dimensions)]
+  pub size: LabelDimensions,
+
+  #[command(flatten)]
+  pub text: LabelTextOptions,
+
+  /// Label template (TOML or YAML) placing the dock, group, marker, barcode and text fields; its size replaces --size
+  #[arg(long)]
+  pub template: Option<PathBuf>,
+}
+
+/// `export png`와 `export pdf`에서 라벨 글자의 글꼴과 모양을 정하는 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct LabelTextOptions {
+  /// TrueType or OpenType font for the label text, e.g. a Noto Sans CJK or NanumGothic file for Korean template text (default: the built-in block digits of --preview)
+  #[arg(long)]
+  pub font: Option<PathBuf>,
+
+  /// Height of the group number and marker text in millimeters (default: scales with the label height); the dock number still fills the rest of the label
+  #[arg(long = "font-size", value_name = "MM", conflicts_with = "template")]
+  pub font_size: Option<f64>,
+
+  /// Thicken the strokes of the label text so it reads from further away
+  #[arg(long)]
+  pub bold: bool,
+
+  /// Slant the label text
+  #[arg(long)]
+  pub italic: bool,
+}
+
+/// `export pdf`의 용지 방향.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Orientation {
+  /// Long side vertical
+  #[default]
+  Portrait,
+  /// Long side horizontal
+  Landscape,
+}
+
+/// `export pdf`의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct PdfOptions {
+  /// Path of the saved plan file
+  pub plan: PathBuf,
+
+  /// PDF file to write
+  #[arg(long)]
+  pub out: PathBuf,
+
+  /// Paper size: a4, letter, or WIDTHxHEIGHT in millimeters (e.g. 100x150)
+  #[arg(long, default_value = DEFAULT_PAPER, value_parser = parse_paper)]
+  pub paper: LabelDimensions,
+
+  /// Page orientation; a custom paper size is turned to match
+  #[arg(long, value_enum, default_value_t = Orientation::Portrait)]
+  pub orientation: Orientation,
+
+  /// Label cells across each sheet
+  #[arg(long, default_value_t = DEFAULT_COLUMNS)]
+  pub columns: u32,
+
+  /// Label cells down each sheet
+  #[arg(long, default_value_t = DEFAULT_ROWS)]
+  pub rows: u32,
+
+  /// Blank border between the paper edge and the cells, in millimeters
+  #[arg(long, default_value_t = 0.0)]
+  pub margin: f64,
+
+  /// Space between neighboring cells, in millimeters
+  #[arg(long, default_value_t = 0.0)]
+  pub gap: f64,
+
+  /// Resolution the labels are drawn at, in dots per inch
+  #[arg(long, default_value_t = DEFAULT_SHEET_DPI)]
+  pub dpi: u32,
+
+  #[command(flatten)]
+  pub text: LabelTextOptions,
+
+  /// Label template (TOML or YAML) placing the dock, group, marker, barcode and text fields; the cell size replaces its size
+  #[arg(long)]
+  pub template: Option<PathBuf>,
+
+  /// Also upload the PDF to s3://BUCKET/PREFIX/ or with HTTP PUT to an http(s):// URL; a destination ending in `/` gets the --out file name appended
+  #[arg(long, value_parser = parse_upload_target)]
+  pub upload: Option<UploadTarget>,
+}
+
+/// `export svg`의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct SvgOptions {
+  /// Path of the saved plan file
+  pub plan: PathBuf,
+
+  /// Directory to write the SVG files to (created if missing)
+  #[arg(long)]
+  pub out: PathBuf,
+
+  /// Label size in millimeters, WIDTHxHEIGHT
+  #[arg(long, default_value = "100x60", value_parser = parse_label_dimensions)]
+  pub size: LabelDimensions,
+
+  /// CSS font family of the label and overview text
+  #[arg(long = "font-family", default_value = "sans-serif")]
+  pub font_family: String,
+
+  /// Label template (TOML or YAML) placing the dock, group, marker, barcode and text fields; its size replaces --size
+  #[arg(long)]
+  pub template: Option<PathBuf>,
+}
+
+/// `export map`의 형식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum MapFormat {
+  /// Text strip for the terminal
+  #[default]
+  Text,
+  /// SVG strip
+  Svg,
+}
+
+/// `export map`의 옵션들.
+#[derive(clap::Args, Debug)]
+pub struct MapOptions {
+  /// Path of the saved plan file
+  pub plan: PathBuf,
+
+  /// Output format
+  #[arg(long, value_enum, default_value_t = MapFormat::Text)]
+  pub format: MapFormat,
+
+  /// File to write the map to (default: standard output)
+  #[arg(long)]
+  pub out: Option<PathBuf>,
+
+  /// Maximum line width of the text map, in characters
+  #[arg(long, default_value_t = 80)]
+  pub width: usize,
+
+  /// CSS font family of the SVG map text
+  #[arg(long = "font-family", default_value = "sans-serif")]
+  pub font_family: String,
+}
+
+/// replay 대상. 숫자라면 실행 기록 id로, 그 외에는 plan 파일 경로로 취급한다.
+#[derive(Debug, Clone)]
+pub enum ReplaySource {
+  History(i64),
+  PlanFile(PathBuf),
+}
+
+/// replay 대상 문자열을 파싱하는 함수. 같은 이름의 파일이 있다면 숫자라도 plan 파일로 취급한다.
+fn parse_replay_source(s: &str) -> Result<ReplaySource, String> {
+  match s.parse::<i64>() {
+    Ok(id) if !std::path::Path::new(s).exists() => Ok(ReplaySource::History(id)),
+    _ => Ok(ReplaySource::PlanFile(PathBuf::from(s))),
+  }
+}
+
+/// 출력 순서를 보여주는 형식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+  /// One line of comma-separated docks per group
+  #[default]
+  Text,
+  /// Fixed-width table with group number, docks, priority composition and size
+  Table,
+  /// `group<TAB>dock<TAB>marker` per dock, without headers (group numbers start at 1)
+  Lines,
+}
+
+/// --no-header로 숨길 수 있는 plan 출력 머리글의 부분.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderSection {
+  /// Run ID and timestamp line added by --stamp
+  Stamp,
+  /// Site and shift line from --site and --shift
+  Site,
+  /// Date line from --date or --dates
+  Date,
+  /// Checksum line below the output order
+  Checksum,
+  /// Label and page count line below the output order
+  Labels,
+  /// "Processing dock range" line
+  Range,
+  /// "Docks per group" lines, including --pp-range sections
+  PerPage,
+  /// Lines describing enabled options (spread, crews, serpentine order, arrival windows, pairs, strict modes)
+  Options,
+  /// List of exception groups
+  Exceptions,
+  /// "--- Docks ---" section with the -f, -s and --skip input
+  Docks,
+  /// "--- Output Order ---" banner
+  Banner,
+}
+
+/// --post로 그룹들을 만든 뒤 차례로 적용하는 후처리 단계.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PostStep {
+  /// Merge one-dock groups of the same priority into groups of up to the per-page (same as --merge-singles)
+  MergeSingles,
+  /// Even out the sizes of consecutive 3rd priority groups, e.g. 3+1 docks become 2+2
+  Balance,
+  /// Move 1st priority groups to the front and 2nd priority groups after them, keeping their order
+  Reorder,
+  /// Record the number of docks of each priority in every group (`composition` in structured output)
+  Annotate,
+}
+
+/// --crews로 그룹을 작업조에 나누는 방식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CrewAssignment {
+  /// Crews take groups in turn, in output order
+  #[default]
+  RoundRobin,
+  /// Each group goes to the crew with the fewest docks so far (estimated time is proportional to group size)
+  Balanced,
+}
+
+/// `--format table`의 테두리 모양.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TableStyle {
+  /// Columns separated by spaces, with a rule under the header
+  #[default]
+  Plain,
+  /// Box-drawing borders around every cell
+  Box,
+}
+
+/// 텍스트 출력의 문자 인코딩.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+pub enum OutputEncoding {
+  /// UTF-8
+  #[default]
+  #[value(name = "utf-8")]
+  #[serde(rename = "utf-8")]
+  Utf8,
+  /// EUC-KR (KS X 1001) for legacy Korean printers and terminals
+  #[value(name = "euc-kr")]
+  #[serde(rename = "euc-kr")]
+  EucKr,
+  /// CP949 (Windows Korean, a superset of EUC-KR)
+  #[value(name = "cp949")]
+  #[serde(rename = "cp949")]
+  Cp949,
+}
+
+/// 라벨 프린터로 직접 보낼 때 사용하는 프린터 명령 언어.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PrinterLanguage {
+  /// Zebra Programming Language (Zebra and compatible label printers)
+  #[default]
+  Zpl,
+  /// ESC/POS (receipt-style mobile printers)
+  #[value(name = "escpos")]
+  EscPos,
+}
+
+/// `--preview` 라벨 모양에 사용하는 문자 종류.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewStyle {
+  /// Box-drawing borders and block digits
+  #[default]
+  Unicode,
+  /// Plain ASCII for terminals without Unicode fonts
+  Ascii,
+}
+
+/// `--preview-image`로 라벨 이미지를 터미널에 보여줄 때 사용하는 그래픽 프로토콜.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageProtocol {
+  /// Detect the protocol from the terminal's environment variables
+  #[default]
+  Auto,
+  /// Kitty graphics protocol (kitty, Ghostty, Konsole)
+  Kitty,
+  /// iTerm2 inline images (iTerm2, WezTerm)
+  Iterm,
+  /// Sixel graphics (foot, mlterm, xterm -ti vt340)
+  Sixel,
+}
+
+/// --smtp 서버와의 연결 보안 방식.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+  /// Upgrade a plain connection with STARTTLS (default port 587)
+  #[default]
+  Starttls,
+  /// TLS from the start of the connection (default port 465)
+  Tls,
+  /// No encryption, for a relay on the local network (default port 25)
+  None,
+}
+
+/// --email로 보내는 메일에 붙이는 파일.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailAttachment {
+  /// `group,dock,marker` rows in printing order
+  Csv,
+  /// Label sheets as from `export pdf` with its defaults (A4, 2x7 cells)
+  Pdf,
+  /// The plan JSON, as written by --save-plan
+  Json,
+}
+
+// 기본값들. clap의 기본값과 JSON 요청(serde)에서 빠진 필드의 기본값으로 함께 사용된다.
+const DEFAULT_MIN: DockId = DockId(51);
+const DEFAULT_MAX: DockId = DockId(78);
+const DEFAULT_ARRIVAL_WINDOW: u32 = 60;
+const DEFAULT_MQTT_TOPIC: &str = "warehouse/dock-plan";
+const DEFAULT_MAX_RANGE_DOCKS: u64 = 100_000_000;
+const DEFAULT_SERVER_MAX_RANGE_DOCKS: u64 = 100_000;
+const DEFAULT_BAUD: u32 = 9600;
+const DEFAULT_PRINTER_DPI: u32 = 203;
+const DEFAULT_EMAIL_FROM: &str = "dock_sorter@localhost";
+const DEFAULT_WMS_FIRST: &str = "$.first";
+const DEFAULT_WMS_SECOND: &str = "$.second";
+
+fn default_min() -> DockId {
+  DEFAULT_MIN
+}
+
+fn default_max() -> DockId {
+  DEFAULT_MAX
+}
+
+fn default_arrival_window() -> u32 {
+  DEFAULT_ARRIVAL_WINDOW
+}
+
+fn default_mqtt_topic() -> String {
+  DEFAULT_MQTT_TOPIC.to_string()
+}
+
+fn default_baud() -> u32 {
+  DEFAULT_BAUD
+}
+
+fn default_printer_dpi() -> u32 {
+  DEFAULT_PRINTER_DPI
+}
+
+fn default_email_from() -> String {
+  DEFAULT_EMAIL_FROM.to_string()
+}
+
+fn default_wms_first() -> String {
+  DEFAULT_WMS_FIRST.to_string()
+}
+
+fn default_wms_second() -> String {
+  DEFAULT_WMS_SECOND.to_string()
+}
+
+fn default_email_attach() -> Vec<EmailAttachment> {
+  vec![EmailAttachment::Csv, EmailAttachment::Pdf]
+}
+
+fn default_print_queue() -> PathBuf {
+  PathBuf::from(DEFAULT_QUEUE_FILE)
+}
+
+// per_page를 제외한 모든 필드는 JSON에서 생략할 수 있으며, 생략된 경우 CLI와 같은 기본값을 갖는다.
+#[derive(clap::Args, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Args {
-  /// First priority docks. Can be single numbers or ranges (e.g., 1-3 5 7-9)
-  #[arg(short = 'f', long, value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_dock_ranges, action = clap::ArgAction::Append)]
-  pub first_priority: Vec<Vec<u32>>, // clap이 Vec<Vec<u32>>를 만들도록 하고, 나중에 flatten
+  /// First priority docks. Can be single numbers or ranges, and '!' removes docks (e.g., 1-3 5 7-9, or 60-70 !65 !67-68)
+  #[arg(short = 'f', long, value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_priority_range, action = clap::ArgAction::Append)]
+  #[serde(default)]
+  pub first_priority: Vec<PriorityRange>, // clap이 Vec<PriorityRange>를 만들도록 하고, 처리할 때 펼친다
   // 예를들어서 -f 65-66 71 56 62 이런식으로 입력됐다면,
-  // parse_dock_ranges 함수에 의해 각각 [[65, 66], [71], [56], [62]] 이런식으로 리스트가 만들어진다.
-  /// Second priority docks. Can be single numbers or ranges (e.g., 10-12 15)
-  #[arg(short = 's', long, value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_dock_ranges, action = clap::ArgAction::Append)]
-  pub second_priority: Vec<Vec<u32>>, // clap이 Vec<Vec<u32>>를 만들도록 하고, 나중에 flatten
-
-  /// Exception docks to be grouped together, ignoring -p. (e.g., 1-3 7-9 10)
-  #[arg(long = "exceptions", short = 'e', value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_dock_ranges, action = clap::ArgAction::Append)]
-  pub exception_groups_raw: Vec<Vec<u32>>, // 각 예외 그룹을 Vec<u32>로 받음
-  // 예외 그룹은 1-3 같은 연속 범위나 10 같은 단일 그룹으로 지정될 수 있다.
-  // _raw는 flatten되지 않은 [[1, 2, 3], [10]] 같은 형식의 Vec이다.
+  // parse_priority_range 함수에 의해 각각 [65-66, 71, 56, 62] 이런식으로 범위 리스트가 만들어진다.
+  // -f 60-70 !65 처럼 !로 시작하는 범위는 제외 범위가 되어 60-70에서 65를 뺀다.
+  /// Second priority docks. Can be single numbers or ranges, and '!' removes docks (e.g., 10-12 15 !11)
+  #[arg(short = 's', long, value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_priority_range, action = clap::ArgAction::Append)]
+  #[serde(default)]
+  pub second_priority: Vec<PriorityRange>, // clap이 Vec<PriorityRange>를 만들도록 하고, 처리할 때 펼친다
+
+  /// Exception docks to be grouped together, ignoring -p. Comma-separated docks form one group even if not adjacent (e.g., 1-3 7-9 10 52,57,61)
+  #[arg(long = "exceptions", short = 'e', value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_exception_group, action = clap::ArgAction::Append)]
+  #[serde(default)]
+  pub exception_groups_raw: Vec<ExceptionGroup>, // 각 예외 그룹을 ExceptionGroup으로 받음
+  // 예외 그룹은 1-3 같은 연속 범위나 10 같은 단일 그룹, 52,57,61 같이 쉼표로 이은 이웃하지 않은 도크들로 지정될 수 있다.
+  // _raw는 펼쳐지지 않은 [1-3, 10, 52,57,61] 같은 형식의 Vec이다.
+
+  // 예외 그룹이 per-page보다 작다면 뒤따르는 3차 도크들로 채워, 반쯤 빈 라벨 페이지가 나오지 않도록 한다.
+  /// Fill exception groups smaller than the per-page with the following non-priority docks
+  #[arg(long = "pad-exceptions", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub pad_exceptions: bool,
+
+  // 고장이나 공사 등으로 사용하지 않는 도크들. 어떤 그룹에도 들어가지 않으며, -f, -s, -e로 입력되었더라도 제외된다.
+  /// Docks that are out of service and left out of every group (e.g., 55 60-61)
+  #[arg(long = "skip", value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_dock_ranges, action = clap::ArgAction::Append)]
+  #[serde(default)]
+  pub skip: Vec<DockRange>,
+
+  // 우선순위나 per-page와 관계없이 항상 혼자 한 그룹으로 출력되는 도크들. -e로 입력되었더라도 예외 그룹에서 빠진다.
+  /// Docks always printed as a one-dock group, whatever their priority or the per-page (e.g., 55 77)
+  #[arg(long = "solo", value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_dock_ranges, action = clap::ArgAction::Append)]
+  #[serde(default)]
+  pub solo: Vec<DockRange>,
+
+  // 사용 중지된 도크 목록(JSON)을 돌려주는 URL. 실행할 때마다 받아와 --skip 목록에 더한다.
+  /// URL returning the out-of-service docks as JSON (e.g., `[55, "60-61"]`); they are skipped like --skip
+  #[arg(long = "closed-docks-url", required = false)]
+  #[serde(default)]
+  pub closed_docks_url: Option<String>,
+
+  // 제외된 도크 양옆의 도크들은 실제로는 떨어져 있으므로, 지정되면 그룹이 제외된 도크를 건너 확장되지 않는다.
+  // 제외된 도크는 --skip, --closed-docks-url, 현장에 없는 도크(--site-docks)이다.
+  /// Never group docks across a skipped, closed (--closed-docks-url) or off-site (--site-docks) dock; by default groups close over the gap
+  #[arg(long = "break-on-gap", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub break_on_gap: bool,
+
+  // 배차 담당자가 같은 도크를 여러 목록에 중복으로 입력하는 경우가 잦으므로, 둘 이상의 입력 범위에 나타난 도크들과
+  // 각 도크가 최종적으로 어떻게 처리되었는지를 stderr로 보고한다.
+  /// Report every dock listed in more than one -f/-s/-e/--skip range, and how it was resolved
+  #[arg(long = "report-overlaps", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub report_overlaps: bool,
   /// Number of docks to print per group
   #[arg(short = 'p', long)]
   pub per_page: u16,
 
   /// Number of docks per group for 1st priority docks (defaults to -p value if not set)
   #[arg(short = '1', long = "fp", required = false)] // short: -1, long: --fpp
+  #[serde(default)]
   pub first_priority_per_page: Option<u16>,
 
   /// Number of docks per group for 2nd priority docks (defaults to -p value if not set)
   #[arg(short = '2', long = "sp", required = false)] // short: -2, long: --spp
+  #[serde(default)]
   pub second_priority_per_page: Option<u16>,
 
+  // 도크 면의 구간별 그룹당 도크 수. 구간 안에서 시작하는 그룹은 -p 대신 구간의 값을 사용하며,
+  // 그룹은 구간 경계를 넘어 확장되지 않는다. --fp, --sp가 지정되었다면 1차, 2차 그룹은 그 값을 따른다.
+  /// Number of docks per group within a dock range, replacing -p there (e.g., --pp-range 51-60=2 --pp-range 61-78=3)
+  #[arg(long = "pp-range", value_name = "RANGE=COUNT", required = false, value_parser = parse_per_page_range, action = clap::ArgAction::Append)]
+  #[serde(default)]
+  pub per_page_ranges: Vec<PerPageRange>,
+
   /// Minimum dock number to process
-  #[arg(long, required = false, default_value_t = 51)] // 기본값 51로 설정, optional
-  pub min: u32,
+  #[arg(long, required = false, default_value_t = DEFAULT_MIN)] // 기본값 51로 설정, optional
+  #[serde(default = "default_min")]
+  pub min: DockId,
 
   /// Maximum dock number to process
-  #[arg(long, required = false, default_value_t = 78)] // 기본값 78로 설정, optional
-  pub max: u32,
+  #[arg(long, required = false, default_value_t = DEFAULT_MAX)] // 기본값 78로 설정, optional
+  #[serde(default = "default_max")]
+  pub max: DockId,
+
+  // 현장에 실제로 있는 도크 번호들. 지정되었다면 --min, --max와 모든 입력 도크가 이 범위들 안에 있는지
+  // 계산을 시작하기 전에 확인하여, 잘못 입력한 도크를 처리 범위 밖이라는 경고 대신 에러로 알려준다.
+  // 지정되지 않았다면 --config의 `[sites.이름]` 중 --site의 docks를 사용한다.
+  // --min..--max가 범위들 사이의 빈 곳을 지난다면 빈 곳의 도크들은 --skip으로 제외한 것처럼 어떤 그룹에도 들어가지 않는다.
+  /// Dock numbers that exist at this site (e.g., 51-78 101-110); --min, --max and every input dock must be within them.
+  /// Docks between --min and --max that are not site docks are skipped.
+  #[arg(long = "site-docks", value_delimiter = ' ', num_args = 0.., required = false, value_parser = parse_dock_ranges, action = clap::ArgAction::Append)]
+  #[serde(default)]
+  pub site_docks: Vec<DockRange>,
 
   // 그룹 확장 조건을 더 엄격하게 하는 플래그이다. 이 플래그가 입력되면
   // 1차 그룹은 1차 그룹끼리만 그루핑된다. 플래그가 입력되지 않으면 1차 그룹 뒤에 하위 그룹 도크들이 붙을 수 있다.
@@ -43,6 +913,7 @@ pub struct Args {
   ///
   /// When this flag is not set, lower priority docks can be appended to a 1st priority group.
   #[arg(long = "strict-first", short = 'F', action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
   pub strict_first: bool,
 
   // 2차 그룹 끼리만 엄격히 묶는 플래그. 윗 플래그와 동일한 기능이다.
@@ -50,47 +921,883 @@ pub struct Args {
   ///
   /// When this flag is not set, 3rd priority docks can be appended to a 2nd priority group.
   #[arg(long = "strict-second", short = 'S', action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
   pub strict_second: bool,
 
+  // strict mode 등으로 만들어진 같은 우선순위의 한 도크 그룹들을 출력 순서대로 per-page 크기까지 다시 합쳐 라벨 낭비를 줄인다.
+  /// Merge one-dock groups of the same priority (e.g. from strict mode) into groups of up to the per-page
+  #[arg(long = "merge-singles", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub merge_singles: bool,
+
+  // 그룹들을 만든 뒤 차례로 적용할 후처리 단계들. 예를들어 --post merge-singles,balance라면 한 도크 그룹들을 합친 뒤 크기를 고른다.
+  // --merge-singles가 지정되었고 목록에 merge-singles가 없다면 맨 앞에 더해진다.
+  /// Group transforms applied in order after grouping (comma-separated)
+  #[arg(long = "post", value_enum, value_delimiter = ',', required = false)]
+  #[serde(default)]
+  pub post: Vec<PostStep>,
+
+  // 각 그룹을 출력과 내보내기에서 연속으로 N번 내보낸다. 도크 하나를 지게차 두 대가 맡는 경우처럼 라벨이 여러 장 필요할 때 사용한다.
+  // --repeat-first, --repeat-second가 지정되었다면 1차, 2차 그룹은 그 값을 따른다. 예외 그룹은 3차 그룹과 같다.
+  /// Emit each group this many times in a row in the output and exports
+  #[arg(long = "repeat-groups", required = false)]
+  #[serde(default)]
+  pub repeat_groups: Option<u16>,
+
+  /// Emit each 1st priority group this many times in a row (default: --repeat-groups)
+  #[arg(long = "repeat-first", required = false)]
+  #[serde(default)]
+  pub repeat_first: Option<u16>,
+
+  /// Emit each 2nd priority group this many times in a row (default: --repeat-groups)
+  #[arg(long = "repeat-second", required = false)]
+  #[serde(default)]
+  pub repeat_second: Option<u16>,
+
+  // 1차 도크 주변의 도크들도 함께 바빠지는 것을 반영하여, 1차 도크로부터 N 이내의 도크들을 2차 도크로 올린다.
+  // 예외 도크, 제외된 도크, 이미 1차/2차인 도크는 바뀌지 않는다.
+  /// Promote docks within this distance of a 1st priority dock to 2nd priority (0 disables)
+  #[arg(long = "spread", default_value_t = 0)]
+  #[serde(default)]
+  pub spread: u32,
+
+  // 그룹들을 나누어 맡을 작업조 수. 출력의 각 그룹에 작업조가 표시되고, 구조화된 출력에는 그룹의 crew 필드로 담긴다.
+  /// Assign groups to this many crews and show the crew on each output line
+  #[arg(long = "crews", required = false)]
+  #[serde(default)]
+  pub crews: Option<u16>,
+
+  /// How groups are assigned to crews (used with --crews)
+  #[arg(long = "crew-assignment", value_enum, default_value_t = CrewAssignment::RoundRobin, requires = "crews")]
+  #[serde(default)]
+  pub crew_assignment: CrewAssignment,
+
+  // 그룹들을 나누어 넣을 시간대들의 시작 시각. 출력 순서대로 시간대를 채워 plan을 대략적인 일정으로 보여준다.
+  /// Start times of time slots (e.g. "06:00,08:00,10:00"); groups fill the slots in output order
+  #[arg(long = "slots", required = false, value_delimiter = ',', value_parser = parse_slot_time)]
+  #[serde(default)]
+  pub slots: Vec<String>,
+
+  // 도크 하나를 처리하는 데 걸리는 예상 시간(분). 지정되면 --slots는 그룹 수 대신 예상 작업 시간으로 시간대를 채운다.
+  /// Estimated minutes to serve one dock; --slots then fills slots by estimated group duration
+  #[arg(long = "dock-minutes", required = false, requires = "slots")]
+  #[serde(default)]
+  pub dock_minutes: Option<u32>,
+
   // 1차, 2차 도크에 marker를 출력하는지 여부의 플래그
   /// Print markers ('@' for 1st, '*' for 2nd) next to priority dock numbers.
   #[arg(long = "mark", short = 'm', action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
   pub print_marker: bool,
+
+  // 출력 순서의 도크 번호들을 그룹(줄)을 넘어 열 단위로 맞춰 출력하는 플래그.
+  // 모든 그룹의 폭을 알아야 하므로 출력 전에 그룹들을 모두 모은다.
+  /// Align dock numbers into columns across groups in the output order
+  #[arg(long = "align", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub align: bool,
+
+  // 출력 순서를 보여주는 형식. table은 그룹 번호, 도크 목록, 우선순위 구성, 크기를 열로 맞춘 표를 출력한다.
+  /// Output format of the group list
+  #[arg(long = "format", value_enum, default_value_t = OutputFormat::Text)]
+  #[serde(default)]
+  pub format: OutputFormat,
+
+  // 출력하지 않을 머리글 부분들. 예를들어 --no-header range,per-page라면 처리 범위와 그룹당 도크 수 줄을 생략한다.
+  /// Header parts to leave out of the text and table output (comma-separated)
+  #[arg(long = "no-header", value_enum, value_delimiter = ',', required = false)]
+  #[serde(default)]
+  pub no_header: Vec<HeaderSection>,
+
+  // 출력 순서 위의 구분선 template. {legend} 자리는 --mark가 지정되었다면 기호 설명("1st: @, 2nd: *")으로, 아니라면 빈 문자열로 바뀌고, {date}는 --date로 바뀐다.
+  /// Template of the banner above the output order, e.g. '=== Labels {legend} ===' ({legend} is the marker legend with --mark, {date} is --date)
+  #[arg(long = "banner", required = false)]
+  #[serde(default)]
+  pub banner: Option<String>,
+
+  // 텍스트 출력의 구분자들. 기존 라벨 병합 도구가 읽는 형식에 맞추기 위해 사용하며, `\n`, `\t`, `\\`를 이스케이프로 쓸 수 있다.
+  // 예를들어 --dock-separator ';' --group-separator '\n\n'이라면 도크를 ;로 잇고 그룹 사이에 빈 줄을 둔다.
+  /// Text between the docks of a group in the text output (default ", "; \n, \t and \\ are escapes)
+  #[arg(long = "dock-separator", required = false, value_parser = parse_separator)]
+  #[serde(default)]
+  pub dock_separator: Option<String>,
+
+  /// Text between groups in the text output instead of a line break (e.g. '\n\n' for a blank line)
+  #[arg(long = "group-separator", required = false, value_parser = parse_separator)]
+  #[serde(default)]
+  pub group_separator: Option<String>,
+
+  // 텍스트 출력의 그룹 줄마다 앞에 붙이는 template. 도크마다 붙여두는 출력물이 어느 날짜, 현장, 그룹의 것인지 알 수 있게 한다.
+  // 자리표시자는 --exec-per-group과 같다 (placeholders::GROUP_PLACEHOLDERS).
+  /// Template put before each group line, e.g. '{date} {site} G{index}: ' (same placeholders as --exec-per-group)
+  #[arg(long = "line-prefix", required = false)]
+  #[serde(default)]
+  pub line_prefix: Option<String>,
+
+  // 출력 머리글과 내보내는 plan JSON에 실행 ID(UUID)와 시각을 붙이는 플래그.
+  // 출력된 라벨 목록을 보고 어느 계산에서 나온 것인지 추적할 수 있다.
+  /// Add a run ID (UUID) and an ISO-8601 timestamp to the header and to exported plans
+  #[arg(long = "stamp", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub stamp: bool,
+
+  // 여러 현장, 여러 근무조가 함께 운영되는 곳에서 출력된 plan이 어디의 것인지 알 수 있도록 머리글, 명령 template,
+  // 내보내는 plan JSON에 함께 남기는 현장 이름과 근무조.
+  /// Facility name shown in the header and available as {site} in command templates (e.g., "Busan DC")
+  #[arg(long = "site", required = false)]
+  #[serde(default)]
+  pub site: Option<String>,
+
+  // 현장별 도크 번호(`[sites.이름] docks = [...]`)와 우선순위 묶음(`[tiers.이름]`)을 정의한 설정 파일.
+  // --site의 도크 번호들이 --site-docks가 되고, --tier로 선택된 묶음의 도크들이 -f, -s에 더해진다.
+  /// Config file (TOML) whose `[sites.NAME]` table for --site lists the valid dock numbers (see --site-docks) and whose `[tiers.NAME]` tables define --tier
+  #[arg(long = "config", required = false)]
+  #[serde(default)]
+  pub config: Option<PathBuf>,
+
+  // 설정 파일의 `[tiers.이름]`에 우선순위(priority)와 도크들(docks)로 정의한 묶음. 묶음의 도크들은 우선순위에 따라
+  // -f 또는 -s로 입력한 것처럼 처리되며, "third" 묶음의 도크들은 -f, -s에서 빠진다.
+  /// Named dock tiers from the config file's `[tiers.NAME]` tables, added to -f or -s by the tier's priority (e.g., --tier reefer hazmat)
+  #[arg(long = "tier", value_delimiter = ' ', num_args = 0.., required = false, action = clap::ArgAction::Append)]
+  #[serde(default)]
+  pub tiers: Vec<String>,
+
+  /// Shift name shown in the header and available as {shift} in command templates (e.g., night)
+  #[arg(long = "shift", required = false)]
+  #[serde(default)]
+  pub shift: Option<String>,
+
+  // plan의 작업 날짜. 머리글에 표시되고, --save-plan 파일 이름, --banner, --exec-per-group의 {date} 자리에 들어간다.
+  /// Date of the plan (YYYY-MM-DD), shown in the header and available as {date} in --save-plan, --banner and command templates
+  #[arg(long = "date", required = false, value_parser = parse_date, conflicts_with = "dates")]
+  #[serde(default)]
+  pub date: Option<String>,
+
+  // 여러 날의 plan을 한 번에 계산할 날짜 범위. 날짜마다 --date를 지정한 것처럼 plan을 따로 계산하고 출력, 내보내기를 수행한다.
+  /// Plan every date in this range (e.g. 2024-07-01..2024-07-05), one plan section per date
+  #[arg(long = "dates", required = false, value_parser = parse_date_span, value_name = "FIRST..LAST")]
+  #[serde(default)]
+  pub dates: Option<String>,
+
+  // 날짜별로 -f, -s, -e, --skip 목록을 바꾸는 TOML 파일. 해당 날짜의 항목에 있는 목록만 바뀐다.
+  /// TOML file with per-date overrides of the priority, exception and skip lists (used with --date or --dates)
+  #[arg(long = "date-manifest", required = false)]
+  #[serde(default)]
+  pub date_manifest: Option<PathBuf>,
+
+  /// Border style of `--format table`
+  #[arg(long = "style", value_enum, default_value_t = TableStyle::Plain)]
+  #[serde(default)]
+  pub style: TableStyle,
+
+  // 텍스트 출력을 보낼 인코딩. 한글 현장 이름 등이 UTF-8로는 깨지는 오래된 도트 프린터 펌웨어를 위해 사용한다.
+  // 인코딩에 없는 문자는 `?`로 바뀐다.
+  /// Character encoding of the text output
+  #[arg(long = "encoding", value_enum, default_value_t = OutputEncoding::Utf8)]
+  #[serde(default)]
+  pub encoding: OutputEncoding,
+
+  // 라벨 프린터가 연결된 시리얼 포트 또는 블루투스 RFCOMM 주소. 지정되었다면 plan을 출력한 뒤
+  // 출력 순서대로 모든 라벨을 --printer-language의 명령으로 보낸다. 작업 책임자의 이동식 벨트 프린터를 위해 사용한다.
+  /// Send the labels to a printer on this serial port (e.g. /dev/ttyUSB0, COM3) or Bluetooth address (rfcomm:AA:BB:CC:DD:EE:FF[/CHANNEL])
+  #[arg(long = "serial", value_name = "DEVICE", required = false)]
+  #[serde(default)]
+  pub serial: Option<String>,
+
+  /// Baud rate of the --serial port (not used for RFCOMM addresses)
+  #[arg(long = "baud", required = false, default_value_t = DEFAULT_BAUD)]
+  #[serde(default = "default_baud")]
+  pub baud: u32,
+
+  /// Command language of the printer used with --serial
+  #[arg(long = "printer-language", value_enum, default_value_t = PrinterLanguage::Zpl)]
+  #[serde(default)]
+  pub printer_language: PrinterLanguage,
+
+  /// Label size in millimeters (WIDTHxHEIGHT) for printers driven with --serial
+  #[arg(long = "label-size", default_value = "100x60", value_parser = parse_label_dimensions)]
+  #[serde(default)]
+  pub label_size: LabelDimensions,
+
+  // 프린터마다 라벨이 찍히는 위치가 조금씩 어긋나는 것을 보정한다. `calibrate`로 찾은 값을 설정 파일의 프로필에 저장해 둔다.
+  /// Print position correction in millimeters, X,Y (positive moves right and down), as found with `calibrate`
+  #[arg(long = "label-offset", value_name = "X,Y", default_value = "0,0", value_parser = parse_label_offset, allow_hyphen_values = true)]
+  #[serde(default)]
+  pub label_offset: LabelOffset,
+
+  // 사이트마다 라벨 모양을 코드 수정 없이 바꿀 수 있도록 필드의 위치와 크기를 정한 파일. 형식은 template.rs의 LabelTemplate이다.
+  /// Label template (TOML or YAML) placing the dock, group, marker, barcode and text fields on labels sent with --serial
+  #[arg(long = "label-template", value_name = "PATH", requires = "serial")]
+  #[serde(default)]
+  pub label_template: Option<PathBuf>,
+
+  /// Resolution of the printer used with --serial, in dots per inch
+  #[arg(long = "printer-dpi", required = false, default_value_t = DEFAULT_PRINTER_DPI)]
+  #[serde(default = "default_printer_dpi")]
+  pub printer_dpi: u32,
+
+  // 그룹마다 인쇄하기 전에 작업자가 맞는 라벨 용지를 넣을 수 있도록 Enter를 기다리는 플래그.
+  /// Ask for confirmation on the terminal before sending each group to the --serial printer
+  #[arg(long = "confirm-each-group", action = clap::ArgAction::SetTrue, requires = "serial")]
+  #[serde(default)]
+  pub confirm_each_group: bool,
+
+  // 느린 프린터의 버퍼가 넘치지 않도록 그룹을 보낸 뒤 기다리는 시간.
+  /// Wait this many milliseconds after sending each group to the --serial printer
+  #[arg(long = "delay-ms", required = false, requires = "serial")]
+  #[serde(default)]
+  pub delay_ms: Option<u64>,
+
+  // --serial로 보내는 그룹마다 인쇄 작업을 기록하는 파일. 프린터가 멈췄을 때 `queue retry`로 남은 그룹을 다시 보낸다.
+  /// State file recording the --serial print jobs for `queue list/cancel/retry`
+  #[arg(long = "print-queue", value_name = "PATH", default_value = DEFAULT_QUEUE_FILE)]
+  #[serde(default = "default_print_queue")]
+  pub print_queue: PathBuf,
+
+  // 셸 파이프라인에서 안전하게 읽을 수 있는 기계용 출력. 머리글 없이 그룹들만 출력하며,
+  // 그룹은 NUL(\0)로 끝나고 그룹 안의 도크들은 unit separator(\x1f)로 구분된다. --format보다 우선한다.
+  /// Print only the groups, each terminated by NUL with docks separated by the unit separator (0x1F)
+  #[arg(long = "print0", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub print0: bool,
+
+  // 그룹 목록 대신 그룹 수, 우선순위별 도크 수, 출력할 라벨 수 같은 요약만 출력하는 플래그.
+  /// Print only summary metrics (group counts, docks per priority, expected label count) instead of the groups
+  #[arg(long = "stats-only", action = clap::ArgAction::SetTrue, conflicts_with = "print0")]
+  #[serde(default)]
+  pub stats_only: bool,
+
+  // 라벨 용지 한 장에 들어가는 라벨 수(예: 2x7 칸이라면 14). 지정되면 필요한 페이지 수를 그룹 수 대신 용지 장 수로 센다.
+  /// Labels per physical sheet of label stock (e.g. 14 for 2x7 sheets); the page count is then the number of sheets
+  #[arg(long = "labels-per-sheet", required = false)]
+  #[serde(default)]
+  pub labels_per_sheet: Option<u32>,
+
+  // 그룹마다 "page X of Y"를 붙이는 플래그. 떨어뜨린 라벨 묶음을 순서대로 다시 모을 수 있도록 텍스트 출력의 그룹 줄과
+  // 이 plan에서 내보낸 라벨(export png/pdf/svg)의 그룹 번호에 전체 그룹 수를 함께 표시한다.
+  /// Show "page X of Y" on each group line and the total group count on exported labels (e.g. G3/12)
+  #[arg(long = "page-numbers", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub page_numbers: bool,
+
+  // 그룹 줄 끝에 우선순위 구성을 "[1st×2, 3rd×1]"처럼 붙이는 플래그. strict mode가 아닐 때 우선순위가 섞인 그룹을 찾기 쉽게 한다.
+  /// Append the priority mix of each group to its line (e.g. [1st×2, 3rd×1])
+  #[arg(long = "composition", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub composition: bool,
+
+  // 그룹 목록 대신 그룹마다 실제 라벨처럼 큰 도크 번호, 기호, 그룹 번호가 들어간 상자를 터미널에 그리는 옵션.
+  // 프린터로 보내기 전에 라벨 배치를 눈으로 확인하기 위해 사용한다.
+  /// Draw a mock-up of each physical label (big dock number, marker, group) instead of the group list
+  #[arg(long = "preview", value_enum, num_args = 0..=1, default_missing_value = "unicode", conflicts_with_all = ["print0", "stats_only"])]
+  #[serde(default)]
+  pub preview: Option<PreviewStyle>,
+
+  // --preview와 같은 라벨들을 이미지로 그려 터미널에 바로 보여주는 옵션. 그룹마다 이미지 한 장이다.
+  // 터미널의 그래픽 프로토콜을 알아낼 수 없다면 --preview의 텍스트 라벨로 대신 출력한다.
+  /// Show the labels as inline images in terminals with Kitty, iTerm2 or Sixel graphics
+  #[arg(long = "preview-image", value_enum, num_args = 0..=1, default_missing_value = "auto", conflicts_with_all = ["print0", "stats_only", "preview"])]
+  #[serde(default)]
+  pub preview_image: Option<ImageProtocol>,
+
+  // 그룹마다 실행할 명령 template. {group_index}({index}), {docks}, {size}, {tier}, {kind} 자리표시자가 그룹의 값으로,
+  // {site}, {shift}, {date}는 --site, --shift, --date(--dates)의 값을 셸 인용한 하나의 단어로 바뀐다.
+  // 현장의 라벨 프린터나 호출 시스템 등 어떤 외부 시스템이든 명령으로 연결할 수 있다.
+  /// Run this shell command once per group, e.g. 'print-labels {group_index} {docks}'
+  ///
+  /// Placeholders: {group_index} or {index} (from 1), {docks} (comma-separated), {size}, {tier}, {kind}, {site}, {shift}, {date}.
+  /// {site}, {shift} and {date} are inserted already quoted; they are also passed as the DOCK_SITE, DOCK_SHIFT and DOCK_DATE environment variables.
+  #[arg(long = "exec-per-group", required = false)]
+  #[serde(default)]
+  pub exec_per_group: Option<String>,
+
+  // plan 계산과 모든 내보내기가 성공한 뒤 실행할 명령. plan JSON({"args": ..., "result": ...})을 표준 입력으로 받는다.
+  /// Run this shell command after a successful run, with the plan JSON on its stdin
+  #[arg(long = "on-complete", required = false)]
+  #[serde(default)]
+  pub on_complete: Option<String>,
+
+  // 도크 면(dock face)의 한 줄에 있는 도크 수. 지정되면 지게차가 실제로 주행하는 방향처럼
+  // 한 줄은 왼쪽에서 오른쪽, 다음 줄은 오른쪽에서 왼쪽으로 번갈아가며 도크를 순회한다.
+  /// Number of docks per physical row. When set, docks are traversed in serpentine order
+  /// (left-to-right, then right-to-left on the next row, alternating).
+  #[arg(long = "row-size", required = false)]
+  #[serde(default)]
+  pub row_size: Option<u32>,
+
+  // 통로 건너편 도크와의 번호 차이. 예를들어 13이 입력되면 도크 N은 도크 N+13과 항상 같은 그룹으로 묶인다.
+  /// Offset of the bay directly across the aisle. Dock N is grouped together with dock N+offset.
+  #[arg(long = "pair-offset", required = false)]
+  #[serde(default)]
+  pub pair_offset: Option<u32>,
+
+  // 도크별 출고 건수가 담긴 CSV 파일 경로. 각 줄은 `dock,count` 형식이다.
+  // 임계값에 따라 1차, 2차 도크가 자동으로 지정되며, -f, -s로 입력한 도크들과 합쳐진다.
+  /// CSV file of per-dock shipment counts (`dock,count` per line) used to assign priorities automatically.
+  #[arg(long = "auto-priority", required = false)]
+  #[serde(default)]
+  pub auto_priority: Option<PathBuf>,
+
+  /// Docks with at least this many shipments become 1st priority (used with --auto-priority)
+  #[arg(long = "first-threshold", required = false, requires = "auto_priority")]
+  #[serde(default)]
+  pub first_threshold: Option<u32>,
+
+  /// Docks with at least this many shipments become 2nd priority (used with --auto-priority)
+  #[arg(long = "second-threshold", required = false, requires = "auto_priority")]
+  #[serde(default)]
+  pub second_threshold: Option<u32>,
+
+  // 창고 관리 시스템(WMS)에서 오늘의 바쁜 도크들을 받아올 URL. 응답에서 --wms-first, --wms-second 경로의 도크들이
+  // 1차, 2차 도크가 되며, -f, -s로 입력한 도크들과 합쳐진다.
+  /// WMS URL to query for today's hot docks (`{date}` becomes --date or today as YYYY-MM-DD); adds 1st/2nd priority docks
+  #[arg(long = "wms-url", required = false)]
+  #[serde(default)]
+  pub wms_url: Option<String>,
+
+  /// Bearer token for --wms-url, read from env:NAME or file:PATH
+  #[arg(long = "wms-token", required = false, requires = "wms_url")]
+  #[serde(default)]
+  pub wms_token: Option<String>,
+
+  /// JSON path selecting the 1st priority docks (numbers or "60-61" ranges) in the --wms-url response, e.g. `$.docks[?(@.level == 'hot')].dock`
+  #[arg(long = "wms-first", default_value = DEFAULT_WMS_FIRST)]
+  #[serde(default = "default_wms_first")]
+  pub wms_first: String,
+
+  /// JSON path selecting the 2nd priority docks in the --wms-url response
+  #[arg(long = "wms-second", default_value = DEFAULT_WMS_SECOND)]
+  #[serde(default = "default_wms_second")]
+  pub wms_second: String,
+
+  // 트럭 도착 스케줄 CSV 파일 경로. 각 줄은 `dock,HH:MM` 형식이다.
+  // 지정되면 도크 번호 대신 도착 시간대(window) 순으로 도크를 정렬하고, 그룹은 시간대를 넘어 확장되지 않는다.
+  /// Truck arrival schedule CSV (`dock,HH:MM` per line). Orders and groups docks by arrival window.
+  #[arg(long = "arrivals", required = false, conflicts_with = "row_size")]
+  #[serde(default)]
+  pub arrivals: Option<PathBuf>,
+
+  /// Length of one arrival window in minutes (used with --arrivals)
+  #[arg(long = "arrival-window", required = false, default_value_t = DEFAULT_ARRIVAL_WINDOW)]
+  #[serde(default = "default_arrival_window")]
+  pub arrival_window: u32,
+
+  // 계산된 plan(입력 인자 + 결과 그룹)을 저장할 파일 경로. `dock_sorter load`로 다시 출력할 수 있다.
+  // 경로의 {date}는 --date로 바뀌므로 --dates와 함께 날짜마다 다른 파일로 저장할 수 있다.
+  /// Save the computed plan (inputs and resulting groups) to this file (e.g. plan-{date}.dockplan)
+  #[arg(long = "save-plan", required = false)]
+  #[serde(default)]
+  pub save_plan: Option<PathBuf>,
+
+  // 이전에 저장한 plan 파일. 지정되면 이전 plan 대비 다시 출력해야 하는 그룹 수를 함께 보여준다.
+  /// Previously saved plan to compare against (reports how many groups must be reprinted)
+  #[arg(long = "previous", required = false)]
+  #[serde(default)]
+  pub previous: Option<PathBuf>,
+
+  // 이전 plan의 그룹 경계를 최대한 유지하여 라벨 재출력을 최소화하는 플래그.
+  /// Keep docks in their previous groups where constraints allow, minimizing label reprints.
+  #[arg(long = "stable", action = clap::ArgAction::SetTrue, requires = "previous")]
+  #[serde(default)]
+  pub stable: bool,
+
+  // 현장별 그룹핑 규칙을 정의한 Rhai 스크립트 경로. 스크립트는 should_break(current_group, next_dock)와
+  // target_size(first_dock) 중 필요한 함수만 정의하면 되며, 기본 규칙에 더해 적용된다.
+  /// Rhai script with site-specific grouping rules (`should_break(current_group, next_dock)`, `target_size(first_dock)`)
+  #[arg(long = "rules", required = false)]
+  #[serde(default)]
+  pub rules: Option<PathBuf>,
+
+  // 샌드박스 안에서 실행되는 WASM 플러그인 경로. 플러그인은 그룹핑 규칙(should_break, target_size)과
+  // 출력 형식(format) 중 필요한 것을 export하며, 그룹핑 규칙은 --rules와 함께 사용할 수 없다.
+  /// Sandboxed WASM plugin providing grouping rules and/or an output formatter
+  #[arg(long = "plugin", required = false)]
+  #[serde(default)]
+  pub plugin: Option<PathBuf>,
+
+  // 실행 기록을 남길 SQLite 데이터베이스. 경로 없이 --history만 입력하면 기본 파일에 기록한다.
+  /// Record this run (timestamp, args, resulting groups) into a local SQLite history database
+  #[arg(long = "history", required = false, num_args = 0..=1, default_missing_value = DEFAULT_HISTORY_DB)]
+  #[serde(default)]
+  pub history: Option<PathBuf>,
+
+  // 계산이 끝난 뒤 plan JSON을 POST로 보낼 webhook URL. 라벨 프린터, 대시보드 등이 polling 없이 결과를 받을 수 있다.
+  /// POST the plan JSON to this webhook URL after each run
+  #[arg(long = "notify-url", required = false)]
+  #[serde(default)]
+  pub notify_url: Option<String>,
+
+  // 계산이 끝난 뒤 plan 요약과 출력 순서를 보낼 채팅 webhook들. 배차 채널에서 바로 확인할 수 있다.
+  /// Post the summary stats and output order to this Slack incoming webhook URL after each run
+  #[arg(long = "slack-webhook", required = false)]
+  #[serde(default)]
+  pub slack_webhook: Option<String>,
+
+  /// Post the summary stats and output order to this Microsoft Teams webhook URL (as an Adaptive Card) after each run
+  #[arg(long = "teams-webhook", required = false)]
+  #[serde(default)]
+  pub teams_webhook: Option<String>,
+
+  // 계산이 끝난 뒤 plan JSON을 올릴 위치. 야간 자동 실행의 결과를 운영 대시보드가 읽는 공용 저장소로 바로 보낸다.
+  /// Upload the plan JSON after each run to s3://BUCKET/PREFIX/ (credentials from the AWS_* environment variables) or with HTTP PUT to an http(s):// URL; a destination ending in `/` gets the --save-plan file name (or plan.json) appended
+  #[arg(long = "upload", required = false)]
+  #[serde(default)]
+  pub upload: Option<String>,
+
+  // 계산이 끝난 뒤 plan을 메일로 보낼 주소들. 야간 배차 담당자가 출력 결과를 손으로 전달하던 일을 대신한다.
+  /// Email the text plan to these addresses after each run (comma-separated), with the --email-attach files attached
+  #[arg(long = "email", value_delimiter = ',', required = false, requires = "smtp")]
+  #[serde(default)]
+  pub email: Vec<String>,
+
+  /// SMTP server (HOST or HOST:PORT) that sends the --email message
+  #[arg(long = "smtp", value_name = "HOST[:PORT]", required = false)]
+  #[serde(default)]
+  pub smtp: Option<String>,
+
+  /// How the connection to the --smtp server is secured
+  #[arg(long = "smtp-tls", value_enum, default_value_t = SmtpTls::Starttls)]
+  #[serde(default)]
+  pub smtp_tls: SmtpTls,
+
+  /// User name to log in to the --smtp server with (no login when omitted)
+  #[arg(long = "smtp-user", required = false, requires = "smtp_password")]
+  #[serde(default)]
+  pub smtp_user: Option<String>,
+
+  /// Password of --smtp-user, read from env:NAME or file:PATH
+  #[arg(long = "smtp-password", required = false, requires = "smtp_user")]
+  #[serde(default)]
+  pub smtp_password: Option<String>,
+
+  /// Sender address of the --email message
+  #[arg(long = "email-from", default_value = DEFAULT_EMAIL_FROM)]
+  #[serde(default = "default_email_from")]
+  pub email_from: String,
+
+  /// Files attached to the --email message (comma-separated; `--email-attach` with no value for none)
+  #[arg(long = "email-attach", value_enum, value_delimiter = ',', num_args = 0.., default_value = "csv,pdf")]
+  #[serde(default = "default_email_attach")]
+  pub email_attach: Vec<EmailAttachment>,
+
+  // plan JSON을 발행할 MQTT 브로커 주소(host:port). 도크 전광판들이 구독하고 있는 공장 MQTT 버스로 보낸다.
+  /// Publish the plan JSON to this MQTT broker (host:port) after each run
+  #[arg(long = "mqtt", required = false)]
+  #[serde(default)]
+  pub mqtt: Option<String>,
+
+  /// MQTT topic the plan is published to (used with --mqtt)
+  #[arg(long = "topic", required = false, default_value = DEFAULT_MQTT_TOPIC, requires = "mqtt")]
+  #[serde(default = "default_mqtt_topic")]
+  pub mqtt_topic: String,
+
+  /// Ask the broker to retain the published plan for boards that subscribe later (used with --mqtt)
+  #[arg(long = "mqtt-retain", action = clap::ArgAction::SetTrue, requires = "mqtt")]
+  #[serde(default)]
+  pub mqtt_retain: bool,
+
+  // 내보내는 plan JSON(--save-plan, --notify-url, --mqtt, --on-complete)에 HMAC-SHA256 서명을 붙일 공유 key의 위치.
+  // key 자체가 아니라 `env:NAME` 또는 `file:PATH`를 받으므로 실행 기록이나 plan 파일에 key가 남지 않는다.
+  /// Sign exported plan JSON with HMAC-SHA256 using the shared key at env:NAME or file:PATH
+  #[arg(long = "sign-key", required = false, value_name = "SOURCE")]
+  #[serde(default)]
+  pub sign_key: Option<String>,
+
+  // 감시할 입력 파일(예: --auto-priority나 --arrivals로 지정한 manifest). 파일이 바뀔 때마다 plan을 다시 계산하고
+  // --save-plan, --notify-url, --mqtt 등의 내보내기도 다시 수행한다. Ctrl-C로 종료한다.
+  /// Re-run planning (and exports) whenever this file changes, until interrupted
+  #[arg(long = "watch", required = false)]
+  #[serde(default)]
+  pub watch: Option<PathBuf>,
+
+  // 출력된 plan을 시스템 클립보드에도 복사하는 플래그. 메일이나 메신저에 바로 붙여넣을 수 있다.
+  /// Also copy the formatted plan to the system clipboard
+  #[arg(long = "clipboard", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub clipboard: bool,
+
+  // 처리 범위(min..max)와 -f, -s, -e 범위를 펼쳤을 때의 최대 도크 수. 잘못된 입력으로 메모리를 모두 써버리지 않도록 한다.
+  /// Refuse to plan when the dock range or the -f/-s/-e ranges would expand to more docks than this
+  #[arg(long = "max-range-docks", required = false)]
+  #[serde(default)]
+  pub max_range_docks: Option<u64>,
+
+  // 계산된 plan이 규칙(모든 도크가 한 번씩, 예외 그룹 유지, per-page 준수)을 지키는지 확인하는 플래그.
+  // 위반이 있다면 plan을 출력하지 않고 에러로 종료한다.
+  /// Check the computed plan against its invariants before printing and fail if any is violated
+  #[arg(long = "self-check", action = clap::ArgAction::SetTrue)]
+  #[serde(default)]
+  pub self_check: bool,
+
+  // 기대하는 plan 파일(golden plan). 계산된 plan의 그룹, 그룹 순서, 우선순위가 이 파일과 다르다면
+  // 차이를 출력하고 에러로 종료한다. 도구를 업그레이드할 때 동작이 바뀌지 않았는지 확인하는 데 사용한다.
+  /// Compare the computed plan against this saved plan and fail with a diff if they differ
+  #[arg(long = "expect", required = false)]
+  #[serde(default)]
+  pub expect: Option<PathBuf>,
 }
 
 impl Args {
   pub fn validate_input(&self) -> Result<(), String> {
     if self.per_page == 0 {
-      return Err(
-        "Error: Number of docks per group must be 1 or greater for all per-page settings."
-          .to_string(),
-      );
+      return Err(tr("error.per_page", &[]));
     }
     if self.first_priority_per_page == Some(0) {
-      return Err("Number of docks for 1st priority (`--fpp`) must be 1 or greater.".to_string());
+      return Err(tr("error.first_per_page", &[]));
     }
     if self.second_priority_per_page == Some(0) {
-      return Err("Number of docks for 2nd priority (`--spp`) must be 1 or greater.".to_string());
+      return Err(tr("error.second_per_page", &[]));
+    }
+    // --pp-range 구간들이 겹친다면 겹친 도크의 per-page가 모호하므로 에러로 처리한다.
+    for (i, a) in self.per_page_ranges.iter().enumerate() {
+      if let Some(b) = self.per_page_ranges[i + 1..]
+        .iter()
+        .find(|b| a.range.start <= b.range.end && b.range.start <= a.range.end)
+      {
+        return Err(tr("error.per_page_range_overlap", &[("first", &a.range), ("second", &b.range)]));
+      }
+    }
+
+    if self.row_size == Some(0) {
+      return Err(tr("error.row_size", &[]));
+    }
+
+    if self.pair_offset == Some(0) {
+      return Err(tr("error.pair_offset", &[]));
+    }
+
+    // 그룹 줄 template에 알 수 없는 자리표시자가 있다면 글자 그대로 출력되지 않도록 에러로 처리한다.
+    if let Some(template) = &self.line_prefix {
+      check_group_template("--line-prefix", template)?;
+    }
+    if let Some(template) = &self.exec_per_group {
+      check_group_template("--exec-per-group", template)?;
+    }
+
+    if self.arrival_window == 0 {
+      return Err(tr("error.arrival_window", &[]));
+    }
+
+    if self.crews == Some(0) {
+      return Err(tr("error.crews", &[]));
+    }
+
+    if self.labels_per_sheet == Some(0) {
+      return Err(tr("error.labels_per_sheet", &[]));
+    }
+
+    if [self.repeat_groups, self.repeat_first, self.repeat_second].contains(&Some(0)) {
+      return Err(tr("error.repeat_groups", &[]));
+    }
+
+    // 시간대는 HH:MM 형식이어야 하며, 차례로 채우므로 시작 시각이 늘어나는 순서여야 한다.
+    let mut last_slot: Option<u32> = None;
+    for slot in &self.slots {
+      let start = parse_slot_time(slot).ok().and_then(|slot| parse_time_of_day(&slot));
+      let Some(start) = start else {
+        return Err(tr("error.slot_time", &[("slot", slot)]));
+      };
+      if last_slot.is_some_and(|last| last >= start) {
+        return Err(tr("error.slot_order", &[("slot", slot)]));
+      }
+      last_slot = Some(start);
+    }
+    if self.dock_minutes == Some(0) {
+      return Err(tr("error.dock_minutes", &[]));
+    }
+
+    // --serial로 보낼 라벨은 프린터 해상도에서 크기를 가져야 한다.
+    if self.serial.is_some() {
+      if self.baud == 0 {
+        return Err(tr("error.baud", &[]));
+      }
+      self.label_size.to_pixels(self.printer_dpi)?;
+    }
+
+    if self.wms_url.is_some() {
+      parse_json_path(&self.wms_first)?;
+      parse_json_path(&self.wms_second)?;
+    }
+
+    if let Some(destination) = &self.upload {
+      parse_upload_target(destination)?;
+    }
+
+    // 메일 주소는 메일 머리글에 그대로 쓰이므로 줄바꿈이나 꺾쇠가 들어갈 수 없다.
+    if !self.email.is_empty() && self.smtp.is_none() {
+      return Err(tr("error.email_smtp", &[]));
+    }
+    if self.smtp_user.is_some() != self.smtp_password.is_some() {
+      return Err(tr("error.smtp_login", &[]));
+    }
+    for address in self.email.iter().chain(std::iter::once(&self.email_from)) {
+      let (local, domain) = address.split_once('@').unwrap_or_default();
+      if local.is_empty() || domain.is_empty() || address.chars().any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ',')) {
+        return Err(tr("error.email_address", &[("address", address)]));
+      }
+    }
+
+    // JSON으로 들어온 날짜는 clap의 value_parser를 거치지 않으므로 여기서 확인한다.
+    if let Some(date) = &self.date {
+      parse_date(date)?;
+    }
+    if let Some(dates) = &self.dates {
+      parse_date_span(dates)?;
+    }
+    if self.date_manifest.is_some() && self.date.is_none() && self.dates.is_none() {
+      return Err(tr("error.date_manifest", &[]));
+    }
+    // --tier의 묶음은 설정 파일을 읽을 때 -f, -s로 펼쳐지므로, 남아있다면 묶음을 정의한 설정 파일이 없는 것이다.
+    if !self.tiers.is_empty() {
+      return Err(tr("error.tier_without_config", &[("tiers", &self.tiers.join(", "))]));
+    }
+
+    // --auto-priority가 입력되었다면 최소 하나의 임계값이 있어야 한다.
+    if self.auto_priority.is_some() && self.first_threshold.is_none() && self.second_threshold.is_none() {
+      return Err(tr("error.auto_priority_threshold", &[]));
+    }
+    // 1차 임계값이 2차 임계값보다 작다면 2차 도크가 1차 도크보다 바쁜 셈이 되므로 에러로 처리한다.
+    if let (Some(first), Some(second)) = (self.first_threshold, self.second_threshold)
+      && first < second
+    {
+      return Err(tr("error.threshold_order", &[("first", &first), ("second", &second)]));
     }
 
     // min과 max를 비교하여 min이 max보다 큰 경우
     if self.min > self.max {
-      return Err(format!(
-        "Minimum dock number ({}) cannot be greater than maximum dock number ({}).",
-        self.min, self.max
+      return Err(tr("error.min_max", &[("min", &self.min), ("max", &self.max)]));
+    }
+    self.check_site_docks()?;
+
+    self.check_range_cap(self.max_range_docks.unwrap_or(DEFAULT_MAX_RANGE_DOCKS))
+  }
+
+  /// 처리 범위와, 처리 범위로 잘라낸 -f, -s, -e, --skip 범위가 펼쳐졌을 때의 도크 수가 cap을 넘는지 확인한다.
+  /// 서버는 --max-range-docks 대신 서버의 상한으로 확인한다.
+  pub fn check_range_cap(&self, cap: u64) -> Result<(), String> {
+    // 서버는 validate_input보다 먼저 상한을 확인하므로 뒤집힌 범위를 여기서도 거른다.
+    if self.min > self.max {
+      return Err(tr("error.min_max", &[("min", &self.min), ("max", &self.max)]));
+    }
+    let range_docks = self.processing_range().dock_count();
+    if range_docks > cap {
+      return Err(tr(
+        "error.range_cap",
+        &[("min", &self.min), ("max", &self.max), ("count", &range_docks), ("cap", &cap)],
+      ));
+    }
+    // 제외 범위(!)는 도크를 펼치지 않으므로 세지 않는다.
+    let priority_ranges = self.first_priority.iter().chain(&self.second_priority);
+    let input_docks: u64 = priority_ranges
+      .filter(|range| !range.excluded)
+      .map(|range| &range.range)
+      .chain(self.exception_groups_raw.iter().flat_map(|group| &group.ranges))
+      .chain(&self.skip)
+      .chain(&self.solo)
+      .map(|range| {
+        let (start, end) = (range.start.max(self.min), range.end.min(self.max));
+        if start <= end { DockRange { start, end }.dock_count() } else { 0 }
+      })
+      .sum();
+    if input_docks > cap {
+      return Err(tr(
+        "error.input_cap",
+        &[("min", &self.min), ("max", &self.max), ("count", &input_docks), ("cap", &cap)],
       ));
     }
+    Ok(())
+  }
 
+  /// 처리 범위(min..max).
+  pub fn processing_range(&self) -> DockRange {
+    DockRange { start: self.min, end: self.max }
+  }
+
+  /// --site-docks가 지정되었다면 --min, --max와 입력된 모든 도크 범위가 현장의 도크 범위들 안에 있는지 확인한다.
+  /// 범위를 벗어난 입력이 있다면 그 입력에서 현장에 없는 첫 도크와, 그 대신 입력하려던 것일 수 있는 가까운 도크들을 알려준다.
+  fn check_site_docks(&self) -> Result<(), String> {
+    if self.site_docks.is_empty() {
+      return Ok(());
+    }
+    let priority_ranges = self.first_priority.iter().chain(&self.second_priority).map(|range| &range.range);
+    let inputs = [DockRange::single(self.min), DockRange::single(self.max)]
+      .into_iter()
+      .chain(priority_ranges.copied())
+      .chain(self.exception_groups_raw.iter().flat_map(|group| group.ranges.iter().copied()))
+      .chain(self.skip.iter().copied())
+      .chain(self.solo.iter().copied())
+      .chain(self.per_page_ranges.iter().map(|section| section.range));
+    for range in inputs {
+      if let Some(invalid) = first_outside(&self.site_docks, range) {
+        let site: Vec<String> = self.site_docks.iter().map(DockRange::to_string).collect();
+        let nearest: Vec<String> = nearest_site_docks(&self.site_docks, invalid).iter().map(DockId::to_string).collect();
+        return Err(tr(
+          "error.site_dock",
+          &[("dock", &invalid), ("site", &site.join(", ")), ("nearest", &nearest.join(", "))],
+        ));
+      }
+    }
     Ok(())
   }
+
+  /// 처리 범위(min..max) 중 --site-docks의 범위들 사이에 있어 현장에 없는 도크 구간들.
+  /// --site-docks가 지정되지 않았다면 비어있다.
+  pub fn site_gaps(&self) -> Vec<DockRange> {
+    if self.site_docks.is_empty() {
+      return Vec::new();
+    }
+    let mut site = IntervalSet::new();
+    for range in &self.site_docks {
+      site.insert_range(range.start, range.end);
+    }
+    let mut gaps = Vec::new();
+    let mut next = Some(self.min);
+    for range in site.iter().filter(|r| r.end >= self.min && r.start <= self.max) {
+      if let Some(start) = next
+        && start < range.start
+      {
+        gaps.push(DockRange { start, end: range.start.saturating_sub(1) });
+      }
+      next = range.end.checked_add(1);
+    }
+    if let Some(start) = next
+      && start <= self.max
+    {
+      gaps.push(DockRange { start, end: self.max });
+    }
+    gaps
+  }
+
+  /// 어떤 그룹에도 들어가지 않는 도크 구간들. --skip으로 제외된 구간과 현장에 없는 도크 구간(site_gaps)이다.
+  pub fn skipped_ranges(&self) -> Vec<DockRange> {
+    self.skip.iter().copied().chain(self.site_gaps()).collect()
+  }
+
+  /// dock이 속한 --pp-range 구간의 index. 어느 구간에도 속하지 않는다면 None이다.
+  pub fn per_page_section(&self, dock: DockId) -> Option<usize> {
+    self.per_page_ranges.iter().position(|section| section.range.contains(dock))
+  }
+
+  /// 우선순위가 tier인 그룹을 연속으로 내보내는 횟수. 예외 그룹은 3차 그룹과 같다.
+  pub fn repeats_of(&self, tier: Priority) -> u16 {
+    let repeats = self.repeat_groups.unwrap_or(1);
+    match tier {
+      Priority::First => self.repeat_first.unwrap_or(repeats),
+      Priority::Second => self.repeat_second.unwrap_or(repeats),
+      Priority::Third => repeats,
+    }
+  }
+
+  /// dock으로 시작하는 그룹의 우선순위별 그룹당 도크 수 (1차, 2차, 3차).
+  /// dock이 --pp-range 구간에 속한다면 구간의 값이 -p를 대신한다.
+  pub fn per_pages_at(&self, dock: DockId) -> (u16, u16, u16) {
+    let per_page = match self.per_page_section(dock) {
+      Some(i) => self.per_page_ranges[i].per_page,
+      None => self.per_page,
+    };
+    (
+      self.first_priority_per_page.unwrap_or(per_page),
+      self.second_priority_per_page.unwrap_or(per_page),
+      per_page,
+    )
+  }
 }
 
-/// 입력된 문자열(단일 숫자 또는 "숫자-숫자" 범위)을 파싱하여 u32의 Vec으로 변환하는 함수.
+// range에서 site의 어느 범위에도 속하지 않는 첫 도크. 모두 속한다면 None이다.
+fn first_outside(site: &[DockRange], range: DockRange) -> Option<DockId> {
+  let mut dock = range.start;
+  while let Some(covering) = site.iter().find(|site_range| site_range.contains(dock)) {
+    dock = covering.end.checked_add(1).filter(|&next| next <= range.end)?;
+  }
+  Some(dock)
+}
+
+// 현장에 없는 도크 dock 대신 입력하려던 것일 수 있는 현장의 도크들.
+// 이웃한 두 자리를 바꾼 번호(85 -> 58)를 먼저, 그 다음 dock보다 작은 쪽과 큰 쪽에서 가장 가까운 도크를 놓는다.
+fn nearest_site_docks(site: &[DockRange], dock: DockId) -> Vec<DockId> {
+  let valid = |dock: &DockId| site.iter().any(|range| range.contains(*dock));
+  let digits: Vec<char> = dock.to_string().chars().collect();
+  let swapped = (1..digits.len()).filter_map(|i| {
+    let mut digits = digits.clone();
+    digits.swap(i - 1, i);
+    match digits[0] {
+      '0' => None,
+      _ => digits.into_iter().collect::<String>().parse::<DockId>().ok(),
+    }
+  });
+  let below = site.iter().filter(|range| range.start < dock).map(|range| range.end.min(dock)).max();
+  let above = site.iter().filter(|range| range.end > dock).map(|range| range.start.max(dock)).min();
+  let mut nearest: Vec<DockId> = Vec::new();
+  for candidate in swapped.filter(valid).chain(below).chain(above) {
+    if candidate != dock && !nearest.contains(&candidate) {
+      nearest.push(candidate);
+    }
+  }
+  nearest
+}
+
+/// "51-60=2"처럼 도크 범위와 그룹당 도크 수를 `=`로 이은 문자열을 파싱하는 함수.
+/// clap의 value_parser로 사용됩니다.
+pub fn parse_per_page_range(s: &str) -> Result<PerPageRange, String> {
+  let Some((range, per_page)) = s.split_once('=') else {
+    return Err(tr("error.per_page_range_format", &[("value", &s)]));
+  };
+  let range = parse_dock_ranges(range)?;
+  match per_page.trim().parse::<u16>() {
+    Ok(per_page) if per_page >= 1 => Ok(PerPageRange { range, per_page }),
+    _ => Err(tr("error.per_page_range_count", &[("value", &s)])),
+  }
+}
+
+/// --dock-separator, --group-separator의 값에서 `\n`, `\t`, `\\` 이스케이프를 실제 문자로 바꾸는 함수.
+/// clap의 value_parser로 사용됩니다.
+pub fn parse_separator(s: &str) -> Result<String, String> {
+  let mut separator = String::new();
+  let mut chars = s.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      separator.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('n') => separator.push('\n'),
+      Some('t') => separator.push('\t'),
+      Some('\\') => separator.push('\\'),
+      Some(other) => return Err(tr("error.separator_escape", &[("escape", &other), ("value", &s)])),
+      None => return Err(tr("error.separator_trailing", &[("value", &s)])),
+    }
+  }
+  Ok(separator)
+}
+
+/// --slots의 시각 하나("6:00", "06:00")를 "HH:MM" 형식으로 맞추는 함수.
+/// clap의 value_parser로 사용됩니다.
+pub fn parse_slot_time(s: &str) -> Result<String, String> {
+  parse_time_of_day(s.trim())
+    .map(format_time_of_day)
+    .ok_or_else(|| tr("error.slot_time", &[("slot", &s)]))
+}
+
+/// -f, -s의 입력 문자열을 파싱하는 함수. `!`로 시작한다면 제외 범위, 아니라면 parse_dock_ranges와 같은 범위이다.
 /// clap의 value_parser로 사용됩니다.
-pub fn parse_dock_ranges(s: &str) -> Result<Vec<u32>, String> {
-  // 파싱된 도크 숫자들이 저장될 Vec
-  let mut docks = Vec::new();
+pub fn parse_priority_range(s: &str) -> Result<PriorityRange, String> {
+  match s.trim().strip_prefix('!') {
+    Some(excluded) => Ok(PriorityRange { range: parse_dock_ranges(excluded)?, excluded: true }),
+    None => parse_dock_ranges(s).map(PriorityRange::from),
+  }
+}
 
+/// -e의 입력 문자열을 파싱하는 함수. 쉼표로 이은 범위들("52,57-58,61")은 하나의 예외 그룹이 된다.
+/// clap의 value_parser로 사용됩니다.
+pub fn parse_exception_group(s: &str) -> Result<ExceptionGroup, String> {
+  let ranges = s.split(',').map(parse_dock_ranges).collect::<Result<Vec<_>, _>>()?;
+  Ok(ExceptionGroup { ranges })
+}
+
+/// 입력된 문자열(단일 숫자 또는 "숫자-숫자" 범위)을 파싱하여 DockRange로 변환하는 함수.
+/// 범위는 펼치지 않고 양 끝만 저장하므로 아주 큰 범위도 메모리를 사용하지 않는다.
+/// clap의 value_parser로 사용됩니다.
+pub fn parse_dock_ranges(s: &str) -> Result<DockRange, String> {
   if s.contains('-') {
     // 만약 arg가 `-`를 포함한다면
     // 두 개의 숫자로 split 한다.
@@ -100,40 +1807,30 @@ pub fn parse_dock_ranges(s: &str) -> Result<Vec<u32>, String> {
       let start_str = parts[0].trim(); // parts[0]을 trim하여 start_str에 저장한다.
       let end_str = parts[1].trim(); // 마찬가지로 parts[1]을 trim하여 end_str에 저장한다.
       // 만약 start_str와 end_str를 u32로 파싱하는게 Ok라면 파싱된 값을 start와 end에 할당한다.
-      if let (Ok(start), Ok(end)) = (start_str.parse::<u32>(), end_str.parse::<u32>()) {
+      if let (Ok(start), Ok(end)) = (start_str.parse::<DockId>(), end_str.parse::<DockId>()) {
         if start <= end {
-          // start가 end보다 작거나 같다면
-          for i in start..=end {
-            // start에서 시작하여 end를 포함하여 범위를 생성하고
-            docks.push(i); // 범위에서 생성된 숫자 i를 docks에 push한다.
-          }
+          // start가 end보다 작거나 같다면 start에서 시작하여 end를 포함하는 범위를 반환한다.
+          Ok(DockRange { start, end })
         } else {
           // start가 end보다 큰 경우
-          return Err(format!(
-            // 에러 메세지를 내뱉는다.
-            "Invalid range: start ({start}) must be less than or equal to end ({end}) in '{s}'"
-          ));
+          // 에러 메세지를 내뱉는다.
+          Err(tr("error.range_order", &[("start", &start), ("end", &end), ("value", &s)]))
         }
       } else {
         // u32 파싱에 실패한경우. 입력된 문자열이 숫자 형식이 아니라서 발생할 수 있음.
-        return Err(format!(
-          "Invalid range format: '{s}'. Both parts must be numbers."
-        ));
+        Err(tr("error.range_numbers", &[("value", &s)]))
       }
     } else {
       // 이 경우는 splitn(2, ..) 로 인해 발생하지 않지만, 완전성을 위해
-      return Err(format!("Invalid range format: '{s}'"));
+      Err(tr("error.range_format", &[("value", &s)]))
     }
   }
   // 만약 `-`가 포함되지 않은 일반 숫자라서 arg s를 trim한뒤 parsing에 성공했다면
-  // 파싱된 수를 dock_num에 할당하고
-  else if let Ok(dock_num) = s.trim().parse::<u32>() {
-    // docks에 push 한다.
-    docks.push(dock_num);
+  // 파싱된 수 하나로 이루어진 범위를 반환한다.
+  else if let Ok(dock_num) = s.trim().parse::<DockId>() {
+    Ok(DockRange::single(dock_num))
   } else {
     // 그외의 경우. 즉, '-'도 없고, 단일 숫자 파싱도 실패한 경우
-    return Err(format!("Invalid number or range format: '{s}'"));
+    Err(tr("error.dock_format", &[("value", &s)]))
   }
-  // 에러가 없다면 docks를 Result로 return 한다.
-  Ok(docks)
 }
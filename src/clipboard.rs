@@ -0,0 +1,24 @@
+use arboard::Clipboard;
+
+/// 텍스트를 시스템 클립보드에 복사하는 함수.
+/// 클립보드를 사용할 수 없는 환경(디스플레이가 없는 서버 등)이라면 에러를 반환한다.
+pub fn copy_text(text: &str) -> Result<(), String> {
+  let mut clipboard = Clipboard::new().map_err(|e| format!("Clipboard is not available: {e}"))?;
+  set_text(&mut clipboard, text).map_err(|e| format!("Failed to copy to clipboard: {e}"))
+}
+
+/// X11/Wayland에서는 클립보드 내용을 복사한 프로세스가 들고 있어야 하므로,
+/// 프로그램이 바로 종료되면 내용이 사라진다. 클립보드 관리자가 내용을 가져갈 때까지 잠시 기다린다.
+#[cfg(target_os = "linux")]
+fn set_text(clipboard: &mut Clipboard, text: &str) -> Result<(), arboard::Error> {
+  use std::time::{Duration, Instant};
+
+  use arboard::SetExtLinux;
+
+  clipboard.set().wait_until(Instant::now() + Duration::from_secs(1)).text(text)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_text(clipboard: &mut Clipboard, text: &str) -> Result<(), arboard::Error> {
+  clipboard.set_text(text)
+}
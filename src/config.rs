@@ -0,0 +1,267 @@
+use std::{
+  collections::BTreeMap,
+  fs,
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+use serde::Deserialize;
+use toml_edit::{DocumentMut, Item};
+
+use crate::{
+  cli::Args,
+  i18n::tr,
+  models::{DockRange, Priority, PriorityRange},
+  raster::LabelOffset,
+};
+
+/// 설정 파일(TOML)의 내용.
+///
+/// ```toml
+/// [server]
+/// tokens = ["dispatch-system-token"]
+/// cors_origins = ["https://dispatch.example.com"]
+///
+/// [profiles.morning]
+/// per_page = 3
+/// first_priority = ["65-66", 71]
+/// save_plan = "morning.dockplan"
+///
+/// [sites."Busan DC"]
+/// docks = ["51-78", "101-110"]
+///
+/// [tiers.reefer]
+/// priority = "first"
+/// docks = ["65-66", 71]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+  #[serde(default)]
+  pub server: ServerConfig,
+  // 이름으로 선택하는 plan 인자 묶음(프로필). 필드는 plan 파일이나 HTTP 요청의 args와 같다.
+  #[serde(default)]
+  pub profiles: BTreeMap<String, Args>,
+  // --site 이름별 현장 설정
+  #[serde(default)]
+  pub sites: BTreeMap<String, SiteConfig>,
+  // --tier 이름별 우선순위 묶음
+  #[serde(default)]
+  pub tiers: BTreeMap<String, TierConfig>,
+}
+
+impl Config {
+  /// 이름에 해당하는 프로필의 plan 인자를 반환하는 함수.
+  pub fn profile(&self, name: &str) -> Result<&Args, String> {
+    self.profiles.get(name).ok_or_else(|| {
+      let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+      format!("Unknown profile '{name}'. Defined profiles: {}", if known.is_empty() { "(none)".to_string() } else { known.join(", ") })
+    })
+  }
+
+  /// args의 --site가 설정 파일에 정의된 현장이고 --site-docks가 지정되지 않았다면 현장의 도크 번호들을 --site-docks로 지정하는 함수.
+  pub fn apply_site(&self, args: &mut Args) {
+    if let Some(site) = args.site.as_ref().and_then(|name| self.sites.get(name))
+      && args.site_docks.is_empty()
+    {
+      args.site_docks = site.docks.clone();
+    }
+  }
+
+  /// args의 --tier로 선택된 묶음들의 도크를 묶음의 우선순위에 따라 -f, -s에 더하는 함수.
+  /// 3차(third) 묶음의 도크는 -f, -s에서 빠져 일반 도크가 된다.
+  pub fn apply_tiers(&self, args: &mut Args) -> Result<(), String> {
+    for name in std::mem::take(&mut args.tiers) {
+      let Some(tier) = self.tiers.get(&name) else {
+        let known: Vec<&str> = self.tiers.keys().map(String::as_str).collect();
+        let known = if known.is_empty() { tr("config.none", &[]) } else { known.join(", ") };
+        return Err(tr("error.unknown_tier", &[("name", &name), ("known", &known)]));
+      };
+      match tier.priority {
+        Priority::First => args.first_priority.extend(tier.docks.iter().copied()),
+        Priority::Second => args.second_priority.extend(tier.docks.iter().copied()),
+        Priority::Third => {
+          let removed = tier.docks.iter().filter(|range| !range.excluded).map(|range| PriorityRange { range: range.range, excluded: true });
+          for range in removed {
+            args.first_priority.push(range);
+            args.second_priority.push(range);
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+/// 설정 파일의 `[sites.NAME]` 섹션. NAME은 --site의 값이다.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteConfig {
+  // 현장에 있는 도크 번호들. --min, --max와 입력된 도크들은 이 범위들 안에 있어야 한다.
+  #[serde(default)]
+  pub docks: Vec<DockRange>,
+}
+
+/// 설정 파일의 `[tiers.NAME]` 섹션. NAME은 --tier의 값이다.
+///
+/// 자주 쓰는 도크 묶음(예: 냉동 도크)에 이름과 우선순위를 붙여 두고 -f, -s 대신 이름으로 지정하게 한다.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TierConfig {
+  // 묶음의 도크들이 받는 우선순위 ("first", "second", "third")
+  pub priority: Priority,
+  // 묶음의 도크 번호들. -f, -s와 같이 '!'로 시작하는 범위는 도크를 뺀다.
+  #[serde(default)]
+  pub docks: Vec<PriorityRange>,
+}
+
+/// 설정 파일의 `[server]` 섹션.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerConfig {
+  // plan 요청에 허용되는 bearer token 목록. 비어있다면 인증을 하지 않는다.
+  #[serde(default)]
+  pub tokens: Vec<String>,
+  // 브라우저에서 API를 호출할 수 있는 origin 목록 (--cors-origin에 더해진다)
+  #[serde(default)]
+  pub cors_origins: Vec<String>,
+}
+
+/// 설정 파일을 읽어 Config로 파싱하는 함수.
+pub fn load_config(path: &Path) -> Result<Config, String> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read config file '{}': {e}", path.display()))?;
+  toml::from_str(&content).map_err(|e| format!("Invalid config file '{}': {e}", path.display()))
+}
+
+/// 새로 읽은 설정을 쓰기 전에 확인하는 함수.
+type ConfigCheck = Box<dyn Fn(&Config) -> Result<(), String> + Send>;
+
+/// 수정 시각이 바뀌면 다시 읽는 설정 파일. 데몬과 서버가 재시작하지 않고(인쇄 대기 중인 작업을 잃지 않고)
+/// 프로필, 기호, 프린터 설정의 변경을 반영하도록 한다. 파일이 없다면(None) 빈 설정이다.
+pub struct ReloadableConfig {
+  path: Option<PathBuf>,
+  modified: Option<SystemTime>,
+  config: Config,
+  // 새로 읽은 설정을 쓰기 전에 확인하는 함수 (예: 필요한 프로필이 있는지)
+  check: ConfigCheck,
+}
+
+impl ReloadableConfig {
+  /// 설정 파일을 읽고 check로 확인하는 함수. check는 다시 읽을 때마다 다시 확인한다.
+  pub fn load(path: Option<&Path>, check: impl Fn(&Config) -> Result<(), String> + Send + 'static) -> Result<Self, String> {
+    let config = match path {
+      Some(path) => load_config(path)?,
+      None => Config::default(),
+    };
+    check(&config)?;
+    Ok(ReloadableConfig {
+      path: path.map(Path::to_path_buf),
+      modified: path.and_then(modified_time),
+      config,
+      check: Box::new(check),
+    })
+  }
+
+  /// 현재 설정.
+  pub fn get(&self) -> &Config {
+    &self.config
+  }
+
+  /// 파일의 수정 시각이 바뀌었다면 다시 읽고, 다시 읽었다면 true를 반환하는 함수.
+  /// 새 파일을 읽지 못했거나 확인에 실패하면 경고를 출력하고 이전 설정을 그대로 쓴다. 같은 내용으로 다시 경고하지 않도록
+  /// 수정 시각은 기억해 두므로, 파일을 고쳐 저장하면 그때 다시 읽는다.
+  pub fn reload_if_changed(&mut self) -> bool {
+    let Some(path) = &self.path else {
+      return false;
+    };
+    // 파일이 교체되는 도중에는 잠시 존재하지 않을 수 있으므로 다음 확인 때 다시 본다.
+    let Some(modified) = modified_time(path) else {
+      return false;
+    };
+    if self.modified == Some(modified) {
+      return false;
+    }
+    self.modified = Some(modified);
+    match load_config(path).and_then(|config| (self.check)(&config).map(|()| config)) {
+      Ok(config) => {
+        self.config = config;
+        eprintln!("Reloaded config file '{}'.", path.display());
+        true
+      }
+      Err(e) => {
+        eprintln!("Warning: Keeping the previous config: {e}");
+        false
+      }
+    }
+  }
+}
+
+// 파일의 마지막 수정 시각.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+  fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// 설정 파일의 프로필에 인쇄 위치 보정값(label_offset)을 기록하는 함수. 파일의 다른 내용과 주석은 그대로 둔다.
+pub fn save_profile_offset(path: &Path, profile: &str, offset: LabelOffset) -> Result<(), String> {
+  // 없는 프로필이라면 정의된 프로필들을 알려준다.
+  load_config(path)?.profile(profile)?;
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read config file '{}': {e}", path.display()))?;
+  let mut document: DocumentMut = content.parse().map_err(|e| format!("Invalid config file '{}': {e}", path.display()))?;
+  let table = document
+    .get_mut("profiles")
+    .and_then(|profiles| profiles.get_mut(profile))
+    .and_then(Item::as_table_like_mut)
+    .ok_or_else(|| format!("Profile '{profile}' in config file '{}' is not a table.", path.display()))?;
+  table.insert("label_offset", toml_edit::value(offset.to_string()));
+  fs::write(path, document.to_string()).map_err(|e| format!("Failed to write config file '{}': {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::Parser;
+
+  use super::*;
+  use crate::cli::ReplayOverrides;
+
+  fn config() -> Config {
+    toml::from_str(
+      r#"
+      [tiers.reefer]
+      priority = "first"
+      docks = ["52-53", 60]
+
+      [tiers.dry]
+      priority = "second"
+      docks = ["55-56", "!56"]
+
+      [tiers.general]
+      priority = "third"
+      docks = [53]
+      "#,
+    )
+    .expect("invalid test config")
+  }
+
+  fn args(input: &str) -> Args {
+    ReplayOverrides::try_parse_from(input.split_whitespace()).expect("invalid test arguments").args
+  }
+
+  #[test]
+  fn applies_tiers_by_priority() {
+    let mut args = args("-p 3 --min 51 --max 62 -f 58 --tier reefer dry general");
+    config().apply_tiers(&mut args).unwrap();
+    let ranges = |ranges: &[PriorityRange]| ranges.iter().map(PriorityRange::to_string).collect::<Vec<_>>();
+    assert_eq!(ranges(&args.first_priority), ["58", "52-53", "60", "!53"]);
+    assert_eq!(ranges(&args.second_priority), ["55-56", "!56", "!53"]);
+    assert!(args.tiers.is_empty());
+  }
+
+  #[test]
+  fn rejects_unknown_tier() {
+    let mut args = args("-p 3 --min 51 --max 62 --tier frozen");
+    let error = config().apply_tiers(&mut args).unwrap_err();
+    assert!(error.contains("'frozen'") && error.contains("dry, general, reefer"), "{error}");
+  }
+}
@@ -0,0 +1,69 @@
+use std::{collections::BTreeMap, fs};
+
+use crate::{cli::Args, models::{DockId, DockRange, PriorityRange}};
+
+/// 도크별 출고 건수 CSV 파일을 읽어 도크 번호를 key로, 출고 건수를 value로 갖는 BTreeMap을 반환하는 함수.
+/// 각 줄은 `dock,count` 형식이며, 빈 줄과 `#`으로 시작하는 주석 줄은 무시한다.
+/// 첫 줄이 숫자가 아닌 헤더(예: `dock,count`)라면 건너뛴다.
+pub fn load_shipment_counts(path: &std::path::Path) -> Result<BTreeMap<DockId, u32>, String> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read shipment count file '{}': {e}", path.display()))?;
+
+  let mut counts: BTreeMap<DockId, u32> = BTreeMap::new();
+  for (line_idx, line) in content.lines().enumerate() {
+    let line = line.trim();
+    // 빈 줄이나 주석 줄은 건너뛴다.
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let mut fields = line.split(',').map(str::trim);
+    let dock_field = fields.next().unwrap_or_default();
+    let count_field = fields.next().unwrap_or_default();
+
+    // 첫 줄의 도크 필드가 숫자가 아니라면 헤더로 간주하고 건너뛴다.
+    let Ok(dock) = dock_field.parse::<DockId>() else {
+      if line_idx == 0 {
+        continue;
+      }
+      return Err(format!(
+        "Invalid dock number '{dock_field}' on line {} of '{}'.",
+        line_idx + 1,
+        path.display()
+      ));
+    };
+    let count = count_field.parse::<u32>().map_err(|_| {
+      format!(
+        "Invalid shipment count '{count_field}' on line {} of '{}'.",
+        line_idx + 1,
+        path.display()
+      )
+    })?;
+    // 같은 도크가 여러 번 나온다면 출고 건수를 합산한다.
+    *counts.entry(dock).or_insert(0) += count;
+  }
+  Ok(counts)
+}
+
+/// --auto-priority가 지정되었다면 출고 건수 파일을 읽어 임계값 이상인 도크들을 args의 1차, 2차 도크 목록에 추가하는 함수.
+/// 1차 임계값을 만족하는 도크는 2차 목록에는 추가되지 않는다.
+pub fn apply_auto_priority(args: &mut Args) -> Result<(), String> {
+  let Some(path) = &args.auto_priority else {
+    return Ok(());
+  };
+  let counts = load_shipment_counts(path)?;
+
+  let mut auto_first: Vec<DockId> = Vec::new();
+  let mut auto_second: Vec<DockId> = Vec::new();
+  for (&dock, &count) in &counts {
+    if args.first_threshold.is_some_and(|t| count >= t) {
+      auto_first.push(dock);
+    } else if args.second_threshold.is_some_and(|t| count >= t) {
+      auto_second.push(dock);
+    }
+  }
+
+  // -f, -s로 직접 입력한 도크들 뒤에 자동 지정된 도크들을 덧붙인다.
+  args.first_priority.extend(auto_first.into_iter().map(|dock| PriorityRange::from(DockRange::single(dock))));
+  args.second_priority.extend(auto_second.into_iter().map(|dock| PriorityRange::from(DockRange::single(dock))));
+  Ok(())
+}
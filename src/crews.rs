@@ -0,0 +1,35 @@
+use crate::cli::{Args, CrewAssignment};
+
+/// --crews로 지정된 작업조들에 그룹을 출력 순서대로 배정하는 함수 객체.
+/// 그룹이 만들어지는 즉시 배정하므로 그룹을 모두 모아둘 필요가 없다.
+pub struct CrewAssigner {
+  assignment: CrewAssignment,
+  // 작업조별로 지금까지 배정된 도크 수. 그룹의 예상 작업 시간은 도크 수에 비례한다고 본다.
+  loads: Vec<usize>,
+  // round-robin에서 다음 그룹을 받을 작업조 (0부터)
+  next: usize,
+}
+
+impl CrewAssigner {
+  /// --crews가 지정되지 않았다면 None을 반환한다.
+  pub fn new(args: &Args) -> Option<Self> {
+    let crews = usize::from(args.crews?);
+    Some(CrewAssigner { assignment: args.crew_assignment, loads: vec![0; crews], next: 0 })
+  }
+
+  /// 도크 수가 size인 다음 그룹을 받을 작업조 번호(1부터)를 반환하는 함수.
+  /// balanced라면 지금까지 배정된 도크 수가 가장 적은 작업조이며, 같다면 번호가 작은 작업조이다.
+  pub fn assign(&mut self, size: usize) -> u16 {
+    let crew = match self.assignment {
+      CrewAssignment::RoundRobin => {
+        let crew = self.next;
+        self.next = (self.next + 1) % self.loads.len();
+        crew
+      }
+      CrewAssignment::Balanced => (0..self.loads.len()).min_by_key(|&crew| self.loads[crew]).unwrap_or(0),
+    };
+    self.loads[crew] += size;
+    // 작업조 수는 u16이므로 번호도 u16 안에 들어간다.
+    (crew + 1) as u16
+  }
+}
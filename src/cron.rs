@@ -0,0 +1,112 @@
+use chrono::{Datelike, Timelike};
+
+/// 5개 필드(분 시 일 월 요일)로 이루어진 cron 표현식.
+/// 각 필드는 `*`, 숫자, 범위(`1-5`), 목록(`1,15`), 간격(`*/15`, `0-30/10`)을 지원한다.
+/// 요일은 0(일요일)부터 6(토요일)까지이며, 7도 일요일로 취급한다.
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+  // 원래의 cron 표현식 문자열
+  expr: String,
+  // 각 필드에서 허용되는 값들을 비트로 표시한 mask
+  minutes: u64,
+  hours: u32,
+  days_of_month: u32,
+  months: u16,
+  days_of_week: u8,
+  // 일(day of month)과 요일 필드가 `*`가 아닌지 여부. 둘 다 제한되어 있다면 둘 중 하나만 맞아도 실행한다.
+  dom_restricted: bool,
+  dow_restricted: bool,
+}
+
+impl CronSchedule {
+  /// "0 5 * * *" 같은 cron 표현식을 파싱하는 함수.
+  pub fn parse(expr: &str) -> Result<Self, String> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let [minute, hour, dom, month, dow] = fields[..] else {
+      return Err(format!(
+        "Invalid cron expression '{expr}': expected 5 fields (minute hour day-of-month month day-of-week)."
+      ));
+    };
+    let field_err = |name: &str, e: String| format!("Invalid {name} field in cron expression '{expr}': {e}");
+
+    // 요일의 7은 일요일(0)과 같으므로 mask를 합친다.
+    let dow_mask = parse_field(dow, 0, 7).map_err(|e| field_err("day-of-week", e))?;
+    let days_of_week = ((dow_mask | (dow_mask >> 7)) & 0x7F) as u8;
+
+    Ok(CronSchedule {
+      expr: expr.to_string(),
+      minutes: parse_field(minute, 0, 59).map_err(|e| field_err("minute", e))?,
+      hours: parse_field(hour, 0, 23).map_err(|e| field_err("hour", e))? as u32,
+      days_of_month: parse_field(dom, 1, 31).map_err(|e| field_err("day-of-month", e))? as u32,
+      months: parse_field(month, 1, 12).map_err(|e| field_err("month", e))? as u16,
+      days_of_week,
+      dom_restricted: dom != "*",
+      dow_restricted: dow != "*",
+    })
+  }
+
+  /// 파싱하기 전의 cron 표현식 문자열.
+  pub fn expr(&self) -> &str {
+    &self.expr
+  }
+
+  /// 주어진 시각(분 단위)이 스케줄에 해당하는지 확인하는 함수.
+  pub fn matches<T: Datelike + Timelike>(&self, time: &T) -> bool {
+    let bit = |mask: u64, value: u32| mask & (1u64 << value) != 0;
+    let dom_ok = bit(self.days_of_month.into(), time.day());
+    let dow_ok = bit(self.days_of_week.into(), time.weekday().num_days_from_sunday());
+    // cron의 규칙: 일과 요일이 모두 제한되어 있다면 둘 중 하나만 맞으면 되고, 그렇지 않다면 둘 다 맞아야 한다.
+    let day_ok = if self.dom_restricted && self.dow_restricted { dom_ok || dow_ok } else { dom_ok && dow_ok };
+
+    bit(self.minutes, time.minute())
+      && bit(self.hours.into(), time.hour())
+      && bit(self.months.into(), time.month())
+      && day_ok
+  }
+}
+
+/// cron 필드 하나를 파싱하여 허용되는 값들의 bit mask를 반환하는 함수. 값은 min..=max 범위여야 한다.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+  let mut mask = 0u64;
+  for item in field.split(',') {
+    // "범위/간격" 형식이라면 간격을 분리한다.
+    let (range, step) = match item.split_once('/') {
+      Some((range, step)) => {
+        let step = step.parse::<u32>().map_err(|_| format!("invalid step '{step}'"))?;
+        if step == 0 {
+          return Err("step must be 1 or greater".to_string());
+        }
+        (range, step)
+      }
+      None => (item, 1),
+    };
+    let (start, end) = match range {
+      "*" => (min, max),
+      _ => match range.split_once('-') {
+        Some((a, b)) => (parse_value(a, min, max)?, parse_value(b, min, max)?),
+        // "5/10"처럼 시작값만 있고 간격이 있다면 최대값까지 반복한다.
+        None if step > 1 => (parse_value(range, min, max)?, max),
+        None => {
+          let value = parse_value(range, min, max)?;
+          (value, value)
+        }
+      },
+    };
+    if start > end {
+      return Err(format!("range '{range}' is reversed"));
+    }
+    for value in (start..=end).step_by(step as usize) {
+      mask |= 1 << value;
+    }
+  }
+  Ok(mask)
+}
+
+/// cron 필드의 숫자 값 하나를 파싱하고 범위를 확인하는 함수.
+fn parse_value(s: &str, min: u32, max: u32) -> Result<u32, String> {
+  let value = s.parse::<u32>().map_err(|_| format!("invalid value '{s}'"))?;
+  if value < min || value > max {
+    return Err(format!("value {value} is outside {min}-{max}"));
+  }
+  Ok(value)
+}
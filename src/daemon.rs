@@ -0,0 +1,125 @@
+use std::{
+  fs,
+  io::{BufRead, BufReader, Write},
+  os::unix::{
+    fs::FileTypeExt,
+    net::{UnixListener, UnixStream},
+  },
+  path::Path,
+  thread,
+  time::Duration,
+};
+
+use chrono::{Local, Timelike};
+
+use crate::{cli::Args, config::ReloadableConfig, cron::CronSchedule, plan::PlanFileRef, server::compute_api_plan};
+
+/// 데몬이 정해진 시각마다 실행하는 plan. 설정 프로필의 인자를 그대로 사용하므로
+/// 프로필에 지정된 --save-plan, --notify-url, --mqtt 등의 내보내기도 함께 수행된다.
+/// 설정 파일은 매 분 다시 확인하므로, 프로필을 고치면 데몬을 재시작하지 않아도 다음 실행부터 반영된다.
+pub struct ScheduledRun {
+  pub schedule: CronSchedule,
+  pub config: ReloadableConfig,
+  pub profile: String,
+}
+
+/// Unix domain socket에서 planning 요청을 받는 데몬을 실행하는 함수. 종료되지 않는 한 반환하지 않는다.
+///
+/// 프로토콜은 줄 단위 JSON이다. 클라이언트가 `POST /plan`과 같은 형식의 요청을 한 줄로 보내면,
+/// 데몬은 plan(`{"args": ..., "result": ...}`) 또는 `{"error": ...}`를 한 줄로 응답한다.
+/// 한 연결에서 여러 요청을 차례로 보낼 수 있다.
+///
+/// scheduled가 있다면 별도의 스레드에서 스케줄에 맞춰 run으로 plan을 실행한다.
+pub fn run_daemon(
+  socket_path: &Path,
+  scheduled: Option<ScheduledRun>,
+  run: fn(Args) -> Result<(), String>,
+) -> Result<(), String> {
+  // 이전 실행에서 남은 소켓 파일이 있다면 지운다. 일반 파일은 실수로 지우지 않도록 건드리지 않는다.
+  if let Ok(meta) = fs::symlink_metadata(socket_path)
+    && meta.file_type().is_socket()
+  {
+    fs::remove_file(socket_path)
+      .map_err(|e| format!("Failed to remove stale socket '{}': {e}", socket_path.display()))?;
+  }
+  let listener = UnixListener::bind(socket_path)
+    .map_err(|e| format!("Failed to listen on socket '{}': {e}", socket_path.display()))?;
+  eprintln!("Listening on unix socket '{}'", socket_path.display());
+
+  if let Some(scheduled) = scheduled {
+    thread::spawn(move || run_schedule(scheduled, run));
+  }
+
+  for stream in listener.incoming() {
+    match stream {
+      // 연결마다 스레드를 만들어 느린 클라이언트가 다른 요청을 막지 않도록 한다.
+      Ok(stream) => {
+        thread::spawn(move || handle_connection(stream));
+      }
+      Err(e) => eprintln!("Warning: Failed to accept connection: {e}"),
+    }
+  }
+  Ok(())
+}
+
+/// 연결 하나에서 들어오는 요청 줄들을 차례로 처리하는 함수.
+fn handle_connection(stream: UnixStream) {
+  let mut writer = match stream.try_clone() {
+    Ok(writer) => writer,
+    Err(e) => {
+      eprintln!("Warning: Failed to set up connection: {e}");
+      return;
+    }
+  };
+  for line in BufReader::new(stream).lines() {
+    let Ok(line) = line else { break };
+    if line.trim().is_empty() {
+      continue;
+    }
+    let response = respond_line(&line);
+    if writeln!(writer, "{response}").is_err() {
+      break;
+    }
+  }
+}
+
+/// 요청 한 줄을 plan으로 계산하여 응답 한 줄(JSON)을 만든다.
+fn respond_line(line: &str) -> String {
+  let plan = serde_json::from_str::<Args>(line)
+    .map_err(|e| format!("Invalid request: {e}"))
+    .and_then(|args| {
+      let result = compute_api_plan(&args)?;
+      serde_json::to_string(&PlanFileRef::new(&args, &result))
+        .map_err(|e| format!("Failed to serialize plan: {e}"))
+    });
+  match plan {
+    Ok(json) => json,
+    Err(e) => serde_json::json!({ "error": e }).to_string(),
+  }
+}
+
+/// 매 분이 시작될 때마다 스케줄을 확인하여, 해당하는 분이라면 plan을 실행하는 함수. 반환하지 않는다.
+/// 실행 중 발생한 에러는 출력만 하고 다음 스케줄을 기다린다.
+fn run_schedule(mut scheduled: ScheduledRun, run: fn(Args) -> Result<(), String>) {
+  loop {
+    // 다음 분의 시작(0초)까지 기다린다.
+    let now = Local::now();
+    let until_next_minute = 60 - u64::from(now.second());
+    thread::sleep(Duration::from_secs(until_next_minute));
+    scheduled.config.reload_if_changed();
+
+    let now = Local::now();
+    if scheduled.schedule.matches(&now) {
+      eprintln!("\n--- Scheduled run at {} ---", now.format("%Y-%m-%d %H:%M"));
+      // 다시 읽을 때 프로필이 있는지 확인했으므로 항상 찾을 수 있다.
+      let config = scheduled.config.get();
+      let args = config.profile(&scheduled.profile).cloned().and_then(|mut args| {
+        config.apply_site(&mut args);
+        config.apply_tiers(&mut args).map(|()| args)
+      });
+      if let Err(e) = args.and_then(run) {
+        eprintln!("Error: Scheduled run failed: {e}");
+      }
+    }
+  }
+}
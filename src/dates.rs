@@ -0,0 +1,107 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::{
+  cli::Args,
+  models::{DockRange, ExceptionGroup, PriorityRange},
+};
+
+// --dates 하나로 계산할 수 있는 최대 날짜 수. 잘못 입력한 연도 때문에 수천 개의 plan을 계산하지 않도록 한다.
+const MAX_DATES: usize = 366;
+
+// 날짜 형식 (예: 2024-07-01)
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 날짜 하나를 "YYYY-MM-DD" 형식으로 맞추는 함수. clap의 value_parser로 사용됩니다.
+pub fn parse_date(s: &str) -> Result<String, String> {
+  NaiveDate::parse_from_str(s.trim(), DATE_FORMAT)
+    .map(|date| date.format(DATE_FORMAT).to_string())
+    .map_err(|_| format!("Invalid date: '{s}'. Expected YYYY-MM-DD (e.g., 2024-07-01)."))
+}
+
+/// "2024-07-01..2024-07-05" 형식의 날짜 범위를 확인하여 맞춘 문자열로 반환하는 함수. clap의 value_parser로 사용됩니다.
+pub fn parse_date_span(s: &str) -> Result<String, String> {
+  let dates = expand_dates(s)?;
+  match (dates.first(), dates.last()) {
+    (Some(first), Some(last)) => Ok(format!("{first}..{last}")),
+    _ => Err(format!("Invalid date range: '{s}'.")),
+  }
+}
+
+/// 날짜 범위의 첫 날부터 마지막 날까지(마지막 날 포함) 각 날짜를 "YYYY-MM-DD" 문자열로 펼치는 함수.
+pub fn expand_dates(span: &str) -> Result<Vec<String>, String> {
+  let Some((first, last)) = span.split_once("..") else {
+    return Err(format!("Invalid date range: '{span}'. Expected FIRST..LAST (e.g., 2024-07-01..2024-07-05)."));
+  };
+  let parse = |s: &str| {
+    NaiveDate::parse_from_str(s.trim(), DATE_FORMAT)
+      .map_err(|_| format!("Invalid date: '{s}'. Expected YYYY-MM-DD (e.g., 2024-07-01)."))
+  };
+  let (first, last) = (parse(first)?, parse(last)?);
+  if first > last {
+    return Err(format!("Invalid date range: '{span}'. The first date must not be after the last date."));
+  }
+  let dates: Vec<String> = first
+    .iter_days()
+    .take_while(|date| *date <= last)
+    .take(MAX_DATES + 1)
+    .map(|date| date.format(DATE_FORMAT).to_string())
+    .collect();
+  if dates.len() > MAX_DATES {
+    return Err(format!("Date range '{span}' covers more than {MAX_DATES} days."));
+  }
+  Ok(dates)
+}
+
+/// 날짜별 입력 변경 파일(TOML)의 한 날짜 항목. 지정된 목록만 해당 날짜의 목록으로 바꾸며, 나머지는 명령줄 입력을 따른다.
+///
+/// ```toml
+/// ["2024-07-02"]
+/// first_priority = ["60-62", "!61"]
+/// second_priority = [70]
+///
+/// ["2024-07-04"]
+/// exception_groups_raw = ["65-66", "52,57,61"]
+/// skip = ["75-78"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DateOverride {
+  pub first_priority: Option<Vec<PriorityRange>>,
+  pub second_priority: Option<Vec<PriorityRange>>,
+  pub exception_groups_raw: Option<Vec<ExceptionGroup>>,
+  pub skip: Option<Vec<DockRange>>,
+}
+
+impl DateOverride {
+  /// 지정된 목록들로 args의 목록들을 바꾸는 함수. 덧붙이지 않고 바꾸므로 같은 args에 여러 번 적용해도 결과가 같다.
+  pub fn apply(&self, args: &mut Args) {
+    if let Some(first) = &self.first_priority {
+      args.first_priority = first.clone();
+    }
+    if let Some(second) = &self.second_priority {
+      args.second_priority = second.clone();
+    }
+    if let Some(exceptions) = &self.exception_groups_raw {
+      args.exception_groups_raw = exceptions.clone();
+    }
+    if let Some(skip) = &self.skip {
+      args.skip = skip.clone();
+    }
+  }
+}
+
+/// 날짜별 입력 변경 파일을 읽는 함수. key는 "YYYY-MM-DD" 형식의 날짜이다.
+pub fn load_date_manifest(path: &Path) -> Result<BTreeMap<String, DateOverride>, String> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read date manifest '{}': {e}", path.display()))?;
+  let invalid = |e: String| format!("Invalid date manifest '{}': {e}", path.display());
+  let manifest: BTreeMap<String, DateOverride> = toml::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+  // 날짜 형식이 다른 key("2024-7-2" 등)는 어느 날짜와도 맞지 않으므로 맞춘 형식으로 바꾼다.
+  manifest
+    .into_iter()
+    .map(|(date, entry)| parse_date(&date).map(|date| (date, entry)).map_err(invalid))
+    .collect()
+}
@@ -0,0 +1,165 @@
+use std::{collections::BTreeSet, fs};
+
+use crate::{
+  cli::{Args, GenOptions, ScenarioOptions},
+  models::{DockId, DockRange, ExceptionGroup, PriorityRange},
+};
+
+// 한 시나리오가 다룰 수 있는 최대 도크 수. 도크를 하나씩 섞어 고르므로 교육과 연동 테스트에 필요한 정도로 제한한다.
+const MAX_SCENARIO_DOCKS: u64 = 100_000;
+
+// --manifest로 만드는 출고 건수 파일의 임계값. 1차 도크는 이 이상, 2차 도크는 SECOND_THRESHOLD 이상의 건수를 받는다.
+const FIRST_THRESHOLD: u32 = 80;
+const SECOND_THRESHOLD: u32 = 40;
+
+/// seed가 같으면 항상 같은 수열을 만드는 작은 난수 생성기 (splitmix64).
+/// 외부 crate 없이 플랫폼과 버전에 관계없이 같은 결과를 재현하기 위해 사용한다.
+pub struct SeededRng(u64);
+
+impl SeededRng {
+  pub fn new(seed: u64) -> Self {
+    SeededRng(seed)
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+  }
+
+  /// 0 이상 bound 미만의 수. bound가 0이라면 0을 반환한다.
+  pub fn below(&mut self, bound: u64) -> u64 {
+    if bound == 0 {
+      return 0;
+    }
+    self.next_u64() % bound
+  }
+}
+
+/// 무작위로 만든 plan 입력. 도크들은 오름차순이다.
+pub struct Scenario {
+  pub docks: DockRange,
+  pub first: Vec<DockId>,
+  pub second: Vec<DockId>,
+  // 두 도크짜리 예외 그룹들
+  pub exceptions: Vec<DockRange>,
+}
+
+/// --docks 범위 안에서 1차 도크 --first개, 2차 도크 --second개, 이웃한 두 도크로 된 예외 그룹 --exceptions개를
+/// 겹치지 않게 고르는 함수. 범위의 도크 수가 부족하다면 에러를 반환한다.
+pub fn random_scenario(rng: &mut SeededRng, options: &ScenarioOptions) -> Result<Scenario, String> {
+  let ScenarioOptions { docks, first, second, exceptions, .. } = *options;
+  let size = docks.dock_count();
+  if size > MAX_SCENARIO_DOCKS {
+    return Err(format!("Dock range {docks} is too large for a generated scenario (at most {MAX_SCENARIO_DOCKS} docks)."));
+  }
+  let needed = u64::from(first) + u64::from(second) + 2 * u64::from(exceptions);
+  if needed > size {
+    return Err(format!(
+      "Dock range {docks} has {size} docks, but {first} first, {second} second and {exceptions} exception groups need {needed}."
+    ));
+  }
+
+  // 예외 그룹을 먼저 고른다. 이웃한 두 도크가 비어있어야 하므로 뒤에 고르면 자리가 없을 수 있다.
+  let mut used: BTreeSet<DockId> = BTreeSet::new();
+  let mut exception_groups: Vec<DockRange> = Vec::new();
+  // 남은 빈 자리가 흩어져 있어 이웃한 두 도크를 찾기 어려울 때 무한히 반복하지 않도록 시도 횟수를 제한한다.
+  let mut attempts = 0;
+  while exception_groups.len() < exceptions as usize {
+    attempts += 1;
+    if attempts > 100 * (exceptions as usize + 1) || size < 2 {
+      return Err(format!("Could not place {exceptions} exception groups of two adjacent docks in {docks}."));
+    }
+    let start = DockId(docks.start.get() + rng.below(size - 1) as u32);
+    let end = DockId(start.get() + 1);
+    if used.contains(&start) || used.contains(&end) {
+      continue;
+    }
+    used.extend([start, end]);
+    exception_groups.push(DockRange { start, end });
+  }
+  exception_groups.sort_by_key(|range| range.start);
+
+  // 남은 도크들을 섞은 뒤 앞에서부터 1차, 2차 도크로 나눈다.
+  let mut free: Vec<DockId> = docks.docks().filter(|dock| !used.contains(dock)).collect();
+  for i in (1..free.len()).rev() {
+    free.swap(i, rng.below(i as u64 + 1) as usize);
+  }
+  let mut first_docks = free[..first as usize].to_vec();
+  let mut second_docks = free[first as usize..(first + second) as usize].to_vec();
+  first_docks.sort_unstable();
+  second_docks.sort_unstable();
+  Ok(Scenario { docks, first: first_docks, second: second_docks, exceptions: exception_groups })
+}
+
+impl Scenario {
+  /// 시나리오를 args의 처리 범위와 -f, -s, -e 목록에 넣는 함수. 기존 목록은 바뀐다.
+  pub fn apply(&self, args: &mut Args) {
+    args.min = self.docks.start;
+    args.max = self.docks.end;
+    let single = |&dock: &DockId| PriorityRange::from(DockRange::single(dock));
+    args.first_priority = self.first.iter().map(single).collect();
+    args.second_priority = self.second.iter().map(single).collect();
+    args.exception_groups_raw = self.exceptions.iter().copied().map(ExceptionGroup::from).collect();
+  }
+}
+
+/// --seed의 난수 생성기를 만드는 함수.
+/// seed가 지정되지 않았다면 현재 시각을 사용하고, 같은 입력을 다시 만들 수 있도록 seed를 stderr로 알려준다.
+pub fn seeded_rng(options: &ScenarioOptions) -> SeededRng {
+  let seed = options.seed.unwrap_or_else(|| chrono::Utc::now().timestamp_micros() as u64);
+  if options.seed.is_none() {
+    eprintln!("Seed: {seed}");
+  }
+  SeededRng::new(seed)
+}
+
+/// `gen` 서브커맨드를 실행하는 함수. seed로 재현할 수 있는 무작위 입력을 명령줄 인자로 출력한다.
+/// --manifest가 지정되었다면 1차, 2차 도크 대신 --auto-priority로 읽을 출고 건수 파일을 만든다.
+pub fn run_gen(options: &GenOptions) -> Result<(), String> {
+  let mut rng = seeded_rng(&options.scenario);
+  let scenario = random_scenario(&mut rng, &options.scenario)?;
+
+  let mut line = vec![
+    format!("-p {}", options.per_page),
+    format!("--min {} --max {}", scenario.docks.start, scenario.docks.end),
+  ];
+  match &options.manifest {
+    Some(path) => {
+      // 1차, 2차, 나머지 도크가 각 임계값 구간 안에서 무작위 건수를 갖도록 한다. 예외 도크도 건수는 있다.
+      let mut csv = String::from("dock,count\n");
+      for dock in scenario.docks.docks() {
+        let count = if scenario.first.contains(&dock) {
+          FIRST_THRESHOLD + rng.below(u64::from(FIRST_THRESHOLD) / 2) as u32
+        } else if scenario.second.contains(&dock) {
+          SECOND_THRESHOLD + rng.below(u64::from(FIRST_THRESHOLD - SECOND_THRESHOLD)) as u32
+        } else {
+          rng.below(u64::from(SECOND_THRESHOLD)) as u32
+        };
+        csv.push_str(&format!("{dock},{count}\n"));
+      }
+      fs::write(path, csv).map_err(|e| format!("Failed to write manifest '{}': {e}", path.display()))?;
+      line.push(format!(
+        "--auto-priority {} --first-threshold {FIRST_THRESHOLD} --second-threshold {SECOND_THRESHOLD}",
+        path.display()
+      ));
+    }
+    None => {
+      let join = |docks: &[DockId]| docks.iter().map(|dock| dock.to_string()).collect::<Vec<_>>().join(" ");
+      if !scenario.first.is_empty() {
+        line.push(format!("-f {}", join(&scenario.first)));
+      }
+      if !scenario.second.is_empty() {
+        line.push(format!("-s {}", join(&scenario.second)));
+      }
+    }
+  }
+  if !scenario.exceptions.is_empty() {
+    let groups: Vec<String> = scenario.exceptions.iter().map(|range| range.to_string()).collect();
+    line.push(format!("-e {}", groups.join(" ")));
+  }
+  println!("{}", line.join(" "));
+  Ok(())
+}
@@ -0,0 +1,93 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+  models::{DockId, Group, Priority},
+  processor::ProcessingResult,
+};
+
+/// 두 plan 사이에서 그룹이 바뀐 도크 하나의 정보.
+/// 그룹 번호는 출력 순서 기준 1부터 시작하며, 해당 plan에 도크가 없다면 None이다.
+pub struct DockMove {
+  pub dock: DockId,
+  pub old_group: Option<(usize, Vec<DockId>)>,
+  pub new_group: Option<(usize, Vec<DockId>)>,
+}
+
+/// 두 plan을 비교한 결과.
+pub struct PlanDiff {
+  // 이전 plan에만 있는 그룹들
+  pub removed_groups: Vec<Vec<DockId>>,
+  // 새 plan에만 있는 그룹들
+  pub added_groups: Vec<Vec<DockId>>,
+  // 같은 그룹 구성원을 유지하지 못한 도크들
+  pub moved_docks: Vec<DockMove>,
+  // (도크, 이전 우선순위, 새 우선순위)
+  pub priority_changes: Vec<(DockId, Priority, Priority)>,
+}
+
+impl PlanDiff {
+  /// 두 plan 사이에 아무런 차이가 없는지 여부.
+  pub fn is_empty(&self) -> bool {
+    self.removed_groups.is_empty()
+      && self.added_groups.is_empty()
+      && self.moved_docks.is_empty()
+      && self.priority_changes.is_empty()
+  }
+}
+
+/// 이전 plan(old)과 새 plan(new)을 비교하여 PlanDiff를 만드는 함수.
+/// 그룹은 도크 구성이 같다면 위치(순서)가 바뀌어도 같은 그룹으로 본다.
+pub fn diff_plans(old: &ProcessingResult, new: &ProcessingResult) -> PlanDiff {
+  // 그룹 추가/삭제: 도크 구성이 완전히 같은 그룹이 반대편 plan에 없다면 추가 또는 삭제된 것이다.
+  let removed_groups = groups_missing_from(&old.result_groups, &new.result_groups);
+  let added_groups = groups_missing_from(&new.result_groups, &old.result_groups);
+
+  // 각 plan에서 도크 -> 그룹 index로 찾아가기 위한 HashMap을 만든다.
+  let old_index = group_index_of_docks(&old.result_groups);
+  let new_index = group_index_of_docks(&new.result_groups);
+
+  // 두 plan의 모든 도크를 오름차순으로 순회하며 속한 그룹의 구성이 달라졌는지 확인한다.
+  let all_docks: BTreeSet<DockId> = old_index.keys().chain(new_index.keys()).copied().collect();
+  let mut moved_docks: Vec<DockMove> = Vec::new();
+  for dock in all_docks {
+    let old_group = old_index.get(&dock).map(|&i| (i + 1, old.result_groups[i].docks.clone()));
+    let new_group = new_index.get(&dock).map(|&i| (i + 1, new.result_groups[i].docks.clone()));
+    // 그룹 번호가 아니라 그룹 구성(도크 목록)을 비교한다.
+    let old_docks = old_group.as_ref().map(|(_, g)| g);
+    let new_docks = new_group.as_ref().map(|(_, g)| g);
+    if old_docks != new_docks {
+      moved_docks.push(DockMove { dock, old_group, new_group });
+    }
+  }
+
+  // 우선순위 변경: priorities에 없는 도크는 3차(일반) 도크로 취급한다.
+  let prio_docks: BTreeSet<DockId> = old.priorities.keys().chain(new.priorities.keys()).copied().collect();
+  let priority_changes: Vec<(DockId, Priority, Priority)> = prio_docks
+    .into_iter()
+    .filter_map(|dock| {
+      let old_prio = *old.priorities.get(&dock).unwrap_or(&Priority::Third);
+      let new_prio = *new.priorities.get(&dock).unwrap_or(&Priority::Third);
+      (old_prio != new_prio).then_some((dock, old_prio, new_prio))
+    })
+    .collect();
+
+  PlanDiff { removed_groups, added_groups, moved_docks, priority_changes }
+}
+
+/// groups 중 도크 구성이 완전히 같은 그룹이 others에 없는 그룹들의 도크 목록을 반환하는 함수.
+fn groups_missing_from(groups: &[Group], others: &[Group]) -> Vec<Vec<DockId>> {
+  groups
+    .iter()
+    .filter(|g| !others.iter().any(|o| o.docks == g.docks))
+    .map(|g| g.docks.clone())
+    .collect()
+}
+
+/// 결과 그룹들로부터 도크 번호를 key로, 그 도크가 속한 그룹의 index를 value로 갖는 HashMap을 만드는 함수.
+fn group_index_of_docks(groups: &[Group]) -> HashMap<DockId, usize> {
+  groups
+    .iter()
+    .enumerate()
+    .flat_map(|(i, g)| g.docks.iter().map(move |&d| (d, i)))
+    .collect()
+}
@@ -0,0 +1,245 @@
+use std::{
+  io::{Read, Write},
+  net::TcpStream,
+  sync::Arc,
+  time::Duration,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::Utc;
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned, pki_types::ServerName};
+
+use crate::{
+  cli::{Args, EmailAttachment, Orientation, SmtpTls},
+  export::labels_pdf,
+  notify::plan_title,
+  pdf::{DEFAULT_COLUMNS, DEFAULT_PAPER, DEFAULT_ROWS, DEFAULT_SHEET_DPI, SheetLayout, parse_paper},
+  preview::label_marker,
+  processor::ProcessingResult,
+  raster::LabelFont,
+  signing::read_secret,
+};
+
+/// SMTP 서버에 연결할 때와 응답을 기다릴 때의 제한 시간.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+// 첨부 파일의 base64 줄 길이. 메일 한 줄은 CRLF를 빼고 78자를 넘지 않아야 한다.
+const BASE64_LINE: usize = 76;
+
+/// 메일에 붙이는 파일 하나.
+pub struct Attachment {
+  pub name: String,
+  pub content_type: &'static str,
+  pub data: Vec<u8>,
+}
+
+/// 계산된 plan을 출력 순서대로 `group,dock,marker` 줄들의 CSV로 만드는 함수. 그룹 번호는 1부터이다.
+pub fn plan_csv(result: &ProcessingResult) -> String {
+  let mut csv = String::from("group,dock,marker\r\n");
+  for (i, group) in result.result_groups.iter().enumerate() {
+    for &dock in &group.docks {
+      csv.push_str(&format!("{},{dock},{}\r\n", i + 1, label_marker(group, dock, result)));
+    }
+  }
+  csv
+}
+
+/// --email-attach에 지정된 첨부 파일들을 만드는 함수. plan_json은 --save-plan과 같은 plan JSON이다.
+/// 파일 이름에는 --date의 작업 날짜를 붙인다 (예: plan-2024-05-01.csv).
+pub fn plan_attachments(args: &Args, result: &ProcessingResult, plan_json: &str) -> Result<Vec<Attachment>, String> {
+  let suffix = args.date.as_ref().map(|date| format!("-{date}")).unwrap_or_default();
+  let mut attachments = Vec::new();
+  for kind in &args.email_attach {
+    let attachment = match kind {
+      EmailAttachment::Csv => Attachment { name: format!("plan{suffix}.csv"), content_type: "text/csv", data: plan_csv(result).into_bytes() },
+      EmailAttachment::Pdf => {
+        let layout = SheetLayout::new(parse_paper(DEFAULT_PAPER)?, Orientation::Portrait, DEFAULT_COLUMNS, DEFAULT_ROWS, 0.0, 0.0)?;
+        let data = labels_pdf(result, args.page_numbers, layout, DEFAULT_SHEET_DPI, None, &LabelFont::block(), None)?;
+        Attachment { name: format!("labels{suffix}.pdf"), content_type: "application/pdf", data }
+      }
+      EmailAttachment::Json => Attachment { name: format!("plan{suffix}.json"), content_type: "application/json", data: plan_json.as_bytes().to_vec() },
+    };
+    attachments.push(attachment);
+  }
+  Ok(attachments)
+}
+
+/// --email 주소들로 plan 메일을 --smtp 서버를 통해 보내는 함수. body는 메일 본문(출력한 plan)이다.
+pub fn send_plan_email(args: &Args, body: &str, attachments: &[Attachment]) -> Result<(), String> {
+  let Some(server) = &args.smtp else {
+    return Err("`--email` requires --smtp.".to_string());
+  };
+  let login = match (&args.smtp_user, &args.smtp_password) {
+    (Some(user), Some(source)) => Some((user, read_secret(source, "SMTP password")?)),
+    _ => None,
+  };
+  let message = mime_message(&args.email_from, &args.email, &plan_title(args), body, attachments);
+
+  let mut smtp = Smtp::connect(server, args.smtp_tls)?;
+  smtp.reply(2, "connect")?;
+  smtp.command("EHLO localhost", 2)?;
+  if args.smtp_tls == SmtpTls::Starttls {
+    smtp.command("STARTTLS", 2)?;
+    smtp.start_tls()?;
+    smtp.command("EHLO localhost", 2)?;
+  }
+  if let Some((user, password)) = login {
+    let mut credentials = vec![0];
+    credentials.extend_from_slice(user.as_bytes());
+    credentials.push(0);
+    credentials.extend(password);
+    // 비밀번호가 에러 메세지에 나오지 않도록 보여주는 명령은 따로 둔다.
+    smtp.send(&format!("AUTH PLAIN {}", BASE64.encode(credentials)))?;
+    smtp.reply(2, "AUTH PLAIN")?;
+  }
+  smtp.command(&format!("MAIL FROM:<{}>", args.email_from), 2)?;
+  for to in &args.email {
+    smtp.command(&format!("RCPT TO:<{to}>"), 2)?;
+  }
+  smtp.command("DATA", 3)?;
+  // 본문에서 '.'으로 시작하는 줄은 '.'을 하나 더 붙여 메세지의 끝(".")과 구분한다.
+  let stuffed: Vec<String> = message.split("\r\n").map(|line| if line.starts_with('.') { format!(".{line}") } else { line.to_string() }).collect();
+  smtp.send(&format!("{}\r\n.", stuffed.join("\r\n")))?;
+  smtp.reply(2, "DATA")?;
+  // 메일은 이미 받아들여졌으므로 QUIT의 실패는 무시한다.
+  let _ = smtp.command("QUIT", 2);
+  Ok(())
+}
+
+// 본문과 첨부 파일들로 multipart/mixed 메일을 만든다. 본문과 첨부 파일은 모두 base64로 넣는다.
+fn mime_message(from: &str, to: &[String], subject: &str, body: &str, attachments: &[Attachment]) -> String {
+  let boundary = format!("dock_sorter-{}", uuid::Uuid::new_v4().simple());
+  let mut message = vec![
+    format!("From: <{from}>"),
+    format!("To: {}", to.iter().map(|address| format!("<{address}>")).collect::<Vec<_>>().join(", ")),
+    format!("Subject: {}", header_text(subject)),
+    format!("Date: {}", Utc::now().to_rfc2822()),
+    format!("Message-ID: <{}@dock_sorter>", uuid::Uuid::new_v4()),
+    "MIME-Version: 1.0".to_string(),
+    format!("Content-Type: multipart/mixed; boundary=\"{boundary}\""),
+    String::new(),
+  ];
+  let mut part = |headers: &[String], data: &[u8]| {
+    message.push(format!("--{boundary}"));
+    message.extend_from_slice(headers);
+    message.push("Content-Transfer-Encoding: base64".to_string());
+    message.push(String::new());
+    let encoded = BASE64.encode(data);
+    // base64 문자열은 ASCII이므로 어디서 잘라도 올바른 UTF-8이다.
+    message.extend(encoded.as_bytes().chunks(BASE64_LINE).map(|line| String::from_utf8_lossy(line).into_owned()));
+  };
+  part(&["Content-Type: text/plain; charset=utf-8".to_string()], body.as_bytes());
+  for attachment in attachments {
+    part(
+      &[
+        format!("Content-Type: {}; name=\"{}\"", attachment.content_type, attachment.name),
+        format!("Content-Disposition: attachment; filename=\"{}\"", attachment.name),
+      ],
+      &attachment.data,
+    );
+  }
+  message.push(format!("--{boundary}--"));
+  message.join("\r\n")
+}
+
+// 머리글 값. ASCII가 아닌 글자(한글 현장 이름 등)나 제어 문자가 있다면 RFC 2047 base64 형식으로 쓴다.
+fn header_text(text: &str) -> String {
+  if text.chars().all(|c| c.is_ascii() && !c.is_control()) {
+    text.to_string()
+  } else {
+    format!("=?UTF-8?B?{}?=", BASE64.encode(text))
+  }
+}
+
+// SMTP 연결. STARTTLS 전에는 TCP 연결을, 그 뒤에는 TLS 연결을 사용한다.
+trait Connection: Read + Write {}
+impl<T: Read + Write> Connection for T {}
+
+struct Smtp {
+  server: String,
+  host: String,
+  tcp: TcpStream,
+  stream: Box<dyn Connection>,
+}
+
+impl Smtp {
+  // server(HOST 또는 HOST:PORT)에 연결한다. 포트를 생략하면 보안 방식에 따른 기본 포트이다.
+  fn connect(server: &str, security: SmtpTls) -> Result<Self, String> {
+    let default_port = match security {
+      SmtpTls::Starttls => 587,
+      SmtpTls::Tls => 465,
+      SmtpTls::None => 25,
+    };
+    let (host, port) = match server.rsplit_once(':').map(|(host, port)| (host, port.parse::<u16>())) {
+      Some((host, Ok(port))) => (host, port),
+      Some((_, Err(_))) => return Err(format!("Invalid SMTP server '{server}'. Expected HOST or HOST:PORT.")),
+      None => (server, default_port),
+    };
+    let tcp = TcpStream::connect((host, port)).map_err(|e| format!("Failed to connect to SMTP server '{server}': {e}"))?;
+    tcp.set_read_timeout(Some(TIMEOUT)).map_err(|e| e.to_string())?;
+    tcp.set_write_timeout(Some(TIMEOUT)).map_err(|e| e.to_string())?;
+    let plain = tcp.try_clone().map_err(|e| e.to_string())?;
+    let mut smtp = Smtp { server: server.to_string(), host: host.to_string(), tcp, stream: Box::new(plain) };
+    if security == SmtpTls::Tls {
+      smtp.start_tls()?;
+    }
+    Ok(smtp)
+  }
+
+  // 연결을 TLS로 바꾼다. 서버 인증서는 webpki의 루트 인증서들로 확인한다.
+  fn start_tls(&mut self) -> Result<(), String> {
+    let roots = RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+    let config = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+      .with_safe_default_protocol_versions()
+      .map_err(|e| e.to_string())?
+      .with_root_certificates(roots)
+      .with_no_client_auth();
+    let name = ServerName::try_from(self.host.clone()).map_err(|_| format!("Invalid SMTP server name '{}'.", self.host))?;
+    let connection = ClientConnection::new(Arc::new(config), name).map_err(|e| format!("Failed to start TLS with SMTP server '{}': {e}", self.server))?;
+    let tcp = self.tcp.try_clone().map_err(|e| e.to_string())?;
+    self.stream = Box::new(StreamOwned::new(connection, tcp));
+    Ok(())
+  }
+
+  // 명령 한 줄을 보낸다.
+  fn send(&mut self, line: &str) -> Result<(), String> {
+    self
+      .stream
+      .write_all(format!("{line}\r\n").as_bytes())
+      .and_then(|()| self.stream.flush())
+      .map_err(|e| format!("Failed to send to SMTP server '{}': {e}", self.server))
+  }
+
+  // 응답을 읽어 상태 코드의 첫 자리가 class인지 확인한다. 여러 줄 응답은 "250-"처럼 코드 뒤에 '-'가 붙는다.
+  // TLS로 바꾸기 전에 서버가 보낸 것보다 더 읽지 않도록 한 바이트씩 읽는다.
+  fn reply(&mut self, class: u8, command: &str) -> Result<(), String> {
+    loop {
+      let mut line = Vec::new();
+      let mut byte = [0u8];
+      while line.last() != Some(&b'\n') {
+        match self.stream.read(&mut byte) {
+          Ok(0) => return Err(format!("SMTP server '{}' closed the connection after `{command}`.", self.server)),
+          Ok(_) => line.push(byte[0]),
+          Err(e) => return Err(format!("Failed to read from SMTP server '{}': {e}", self.server)),
+        }
+      }
+      let line = String::from_utf8_lossy(&line).trim_end().to_string();
+      if line.len() < 3 || !line.as_bytes()[..3].iter().all(u8::is_ascii_digit) {
+        return Err(format!("Unexpected reply from SMTP server '{}': {line}", self.server));
+      }
+      if line.as_bytes().get(3) == Some(&b'-') {
+        continue;
+      }
+      if line.as_bytes()[0] != b'0' + class {
+        return Err(format!("SMTP server '{}' rejected `{command}`: {line}", self.server));
+      }
+      return Ok(());
+    }
+  }
+
+  // 명령을 보내고 응답을 확인한다.
+  fn command(&mut self, line: &str, class: u8) -> Result<(), String> {
+    self.send(line)?;
+    self.reply(class, line)
+  }
+}
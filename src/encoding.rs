@@ -0,0 +1,33 @@
+use std::borrow::Cow;
+
+use encoding_rs::EUC_KR;
+
+use crate::cli::OutputEncoding;
+
+/// 텍스트를 encoding의 바이트로 바꾸는 함수. UTF-8이라면 복사하지 않는다.
+/// 인코딩에 없는 문자는 `?`로 바뀐다. encoding_rs의 EUC-KR은 CP949(확장 완성형)이므로,
+/// EUC-KR로 지정된 경우 KS X 1001에 없는 확장 글자(예: 똠)도 `?`로 바꿔 오래된 펌웨어가 받을 수 있는 바이트만 보낸다.
+pub fn encode_text(text: &str, encoding: OutputEncoding) -> Cow<'_, [u8]> {
+  if encoding == OutputEncoding::Utf8 {
+    return Cow::Borrowed(text.as_bytes());
+  }
+  if encoding == OutputEncoding::Cp949 {
+    let (bytes, _, had_errors) = EUC_KR.encode(text);
+    if !had_errors {
+      return Cow::Owned(bytes.into_owned());
+    }
+  }
+  // 글자 하나씩 바꾼다. encoding_rs는 바꿀 수 없는 글자를 HTML 숫자 참조(&#...;)로 바꾸기 때문이다.
+  let mut encoded = Vec::with_capacity(text.len());
+  let mut buffer = [0; 4];
+  for c in text.chars() {
+    let (bytes, _, had_errors) = EUC_KR.encode(c.encode_utf8(&mut buffer));
+    // KS X 1001의 글자는 두 바이트 모두 0xA1 이상이다.
+    let in_ks_x_1001 = bytes.len() == 1 || bytes.iter().all(|&b| b >= 0xA1);
+    match had_errors || (encoding == OutputEncoding::EucKr && !in_ks_x_1001) {
+      true => encoded.push(b'?'),
+      false => encoded.extend_from_slice(&bytes),
+    }
+  }
+  Cow::Owned(encoded)
+}
@@ -0,0 +1,185 @@
+use std::{fs, path::Path};
+
+use crate::{
+  cli::{ExportTarget, LabelTextOptions, MapFormat, MapOptions, PdfOptions, PngOptions, SvgOptions},
+  map::{map_cells, map_text},
+  models::DockId,
+  pdf::{SheetLayout, SheetPdf},
+  plan::{PlanFile, load_plan},
+  preview::label_marker,
+  processor::ProcessingResult,
+  raster::{LabelDimensions, LabelFont, MM_PER_INCH, render_label, render_template},
+  svg::{label_svg, map_svg, overview_svg, template_svg},
+  template::{GroupNumber, LabelTemplate, load_template},
+  upload::upload,
+};
+
+/// `export` 서브커맨드를 실행하는 함수.
+pub fn run_export(target: &ExportTarget) -> Result<(), String> {
+  match target {
+    ExportTarget::Png(options) => export_png(options),
+    ExportTarget::Pdf(options) => export_pdf(options),
+    ExportTarget::Svg(options) => export_svg(options),
+    ExportTarget::Map(options) => export_map(options),
+  }
+}
+
+/// 저장된 plan의 라벨을 도크마다 PNG 파일 하나로 그리는 함수.
+/// 파일 이름은 출력 순서(1부터)와 도크 번호로 정해지므로(예: 0001-dock52.png) 이름순으로 인쇄하면 plan의 순서가 된다.
+fn export_png(options: &PngOptions) -> Result<(), String> {
+  if options.dpi == 0 {
+    return Err("`--dpi` must be 1 or greater.".to_string());
+  }
+  let template = options.template.as_deref().map(load_template).transpose()?;
+  let size = template.as_ref().and_then(|template| template.size).unwrap_or(options.size).to_pixels(options.dpi)?;
+  let (font, small_height) = label_font(&options.text, options.size, options.dpi)?;
+  let plan = load_plan(&options.plan)?;
+  let written = write_labels(&plan, &options.out, "png", |dock, group, marker| {
+    let label = match &template {
+      Some(template) => render_template(template, dock, group, marker, size, options.dpi, &font)?,
+      None => render_label(dock, group, marker, size, small_height, &font),
+    };
+    label.to_png(Some(options.dpi))
+  })?;
+  eprintln!("Wrote {written} labels ({}x{} pixels) to {}", size.width, size.height, options.out.display());
+  Ok(())
+}
+
+/// 저장된 plan의 라벨들을 용지의 칸에 출력 순서대로 놓은 PDF 파일 하나로 만드는 함수.
+/// 라벨은 칸 크기의 이미지로 그리므로 --font의 글꼴(한글 글꼴 포함)이 그대로 인쇄된다.
+fn export_pdf(options: &PdfOptions) -> Result<(), String> {
+  if options.dpi == 0 {
+    return Err("`--dpi` must be 1 or greater.".to_string());
+  }
+  let layout = SheetLayout::new(options.paper, options.orientation, options.columns, options.rows, options.margin, options.gap)?;
+  let cell = layout.cell();
+  let template = options.template.as_deref().map(load_template).transpose()?;
+  let (font, small_height) = label_font(&options.text, cell, options.dpi)?;
+  let plan = load_plan(&options.plan)?;
+  let pdf = labels_pdf(&plan.result, plan.args.page_numbers, layout, options.dpi, template.as_ref(), &font, small_height)?;
+  let labels: usize = plan.result.result_groups.iter().map(|group| group.docks.len()).sum();
+  let pages = labels.div_ceil(layout.cells_per_page()).max(1);
+  fs::write(&options.out, &pdf).map_err(|e| format!("Failed to write '{}': {e}", options.out.display()))?;
+  eprintln!(
+    "Wrote {labels} labels on {pages} pages ({}x{} mm cells) to {}",
+    (cell.width_mm * 10.0).round() / 10.0,
+    (cell.height_mm * 10.0).round() / 10.0,
+    options.out.display()
+  );
+  // --upload가 지정되었다면 같은 파일 이름으로 올린다.
+  if let Some(target) = &options.upload {
+    let name = options.out.file_name().map_or_else(|| "labels.pdf".into(), |name| name.to_string_lossy());
+    let location = upload(target, &name, &pdf, "application/pdf")?;
+    eprintln!("Uploaded {name} to {location}");
+  }
+  Ok(())
+}
+
+/// plan의 라벨들을 출력 순서대로 용지의 칸에 놓은 PDF 파일 내용을 만드는 함수.
+/// 라벨은 dpi 해상도에서 칸 크기의 이미지로 그린다. small_height는 render_label의 위쪽 줄 글자 높이이다.
+/// page_numbers라면 라벨의 그룹 번호에 전체 그룹 수를 함께 표시한다 (--page-numbers).
+pub fn labels_pdf(
+  result: &ProcessingResult,
+  page_numbers: bool,
+  layout: SheetLayout,
+  dpi: u32,
+  template: Option<&LabelTemplate>,
+  font: &LabelFont,
+  small_height: Option<u32>,
+) -> Result<Vec<u8>, String> {
+  let size = layout.cell().to_pixels(dpi)?;
+  let mut pdf = SheetPdf::new(layout);
+  let of = page_numbers.then_some(result.result_groups.len());
+  for (i, group) in result.result_groups.iter().enumerate() {
+    for &dock in &group.docks {
+      let marker = label_marker(group, dock, result);
+      let number = GroupNumber { index: i + 1, of };
+      let label = match template {
+        Some(template) => render_template(template, dock, number, marker, size, dpi, font)?,
+        None => render_label(dock, number, marker, size, small_height, font),
+      };
+      pdf.add_label(&label)?;
+    }
+  }
+  Ok(pdf.finish())
+}
+
+/// 저장된 plan의 라벨을 도크마다 SVG 파일 하나로 만들고, 도크 전체의 배치도를 overview.svg로 만드는 함수.
+/// 라벨 파일의 이름은 export png와 같은 규칙이다.
+fn export_svg(options: &SvgOptions) -> Result<(), String> {
+  let plan = load_plan(&options.plan)?;
+  // 배치도를 먼저 만들어 범위가 너무 큰 plan이라면 라벨 파일을 쓰기 전에 실패한다.
+  let overview = overview_svg(&plan, &options.font_family)?;
+  let template = options.template.as_deref().map(load_template).transpose()?;
+  let size = template.as_ref().and_then(|template| template.size).unwrap_or(options.size);
+  let written = write_labels(&plan, &options.out, "svg", |dock, group, marker| {
+    let svg = match &template {
+      Some(template) => template_svg(template, dock, group, marker, size, &options.font_family)?,
+      None => label_svg(dock, group, marker, size, &options.font_family),
+    };
+    Ok(svg.into_bytes())
+  })?;
+  let overview_path = options.out.join("overview.svg");
+  fs::write(&overview_path, overview).map_err(|e| format!("Failed to write '{}': {e}", overview_path.display()))?;
+  eprintln!("Wrote {written} labels and overview.svg to {}", options.out.display());
+  Ok(())
+}
+
+/// 저장된 plan의 처리 범위를 가로 띠 모양의 배치도(텍스트 또는 SVG)로 그리는 함수.
+/// --out이 없다면 표준 출력으로 내보낸다.
+fn export_map(options: &MapOptions) -> Result<(), String> {
+  let plan = load_plan(&options.plan)?;
+  let cells = map_cells(&plan)?;
+  let map = match options.format {
+    MapFormat::Text => map_text(&cells, options.width),
+    MapFormat::Svg => map_svg(&cells, &options.font_family),
+  };
+  match &options.out {
+    Some(path) => fs::write(path, map).map_err(|e| format!("Failed to write '{}': {e}", path.display())),
+    None => {
+      print!("{map}");
+      Ok(())
+    }
+  }
+}
+
+// --font, --bold, --italic로 글꼴을 읽고, --font-size를 라벨 size의 dpi 해상도에서의 픽셀 높이로 바꾼다.
+fn label_font(options: &LabelTextOptions, size: LabelDimensions, dpi: u32) -> Result<(LabelFont, Option<u32>), String> {
+  // 위쪽 줄의 글자는 도크 번호가 들어갈 자리가 남도록 라벨 높이의 절반보다 작아야 한다.
+  let small_height = match options.font_size {
+    Some(mm) if !mm.is_finite() || mm <= 0.0 || mm * 2.0 >= size.height_mm => {
+      return Err(format!("`--font-size` must be greater than 0 and less than half the label height ({} mm).", size.height_mm));
+    }
+    Some(mm) => Some((mm * f64::from(dpi) / MM_PER_INCH).round().max(1.0) as u32),
+    None => None,
+  };
+  let mut font = match &options.font {
+    Some(path) => LabelFont::load(path)?,
+    None => LabelFont::block(),
+  };
+  font.bold = options.bold;
+  font.italic = options.italic;
+  Ok((font, small_height))
+}
+
+// 출력 순서대로 도크마다 render(도크, 그룹 번호, 기호)의 내용을 "순서-dock도크.확장자" 파일로 쓴다. 쓴 파일 수를 반환한다.
+// plan이 --page-numbers로 저장되었다면 그룹 번호에 전체 그룹 수가 함께 표시된다.
+fn write_labels(
+  plan: &PlanFile,
+  out: &Path,
+  extension: &str,
+  mut render: impl FnMut(DockId, GroupNumber, &str) -> Result<Vec<u8>, String>,
+) -> Result<usize, String> {
+  fs::create_dir_all(out).map_err(|e| format!("Failed to create directory '{}': {e}", out.display()))?;
+  let mut written = 0;
+  let of = plan.args.page_numbers.then_some(plan.result.result_groups.len());
+  for (i, group) in plan.result.result_groups.iter().enumerate() {
+    for &dock in &group.docks {
+      written += 1;
+      let content = render(dock, GroupNumber { index: i + 1, of }, label_marker(group, dock, &plan.result))?;
+      let path = out.join(format!("{written:04}-dock{dock}.{extension}"));
+      fs::write(&path, content).map_err(|e| format!("Failed to write '{}': {e}", path.display()))?;
+    }
+  }
+  Ok(written)
+}
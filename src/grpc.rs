@@ -0,0 +1,210 @@
+use std::{
+  net::ToSocketAddrs,
+  sync::{Arc, Mutex, MutexGuard, PoisonError},
+  thread,
+};
+
+use serde_json::Value;
+use tonic::{
+  Request, Response, Status,
+  transport::{Server, server::TcpIncoming},
+};
+
+use crate::{
+  models::{GroupKind, Priority},
+  plan::PlanFile,
+  server::{PlanCall, ServerState},
+};
+
+/// proto/dock_sorter.proto로 만든 메세지와 서비스 타입들.
+pub mod proto {
+  tonic::include_proto!("dock_sorter.v1");
+}
+
+use proto::{
+  BatchReply, BatchRequest, Group, PlanReply, PlanRequest, Tier, VersionReply, VersionRequest, batch_result,
+  dock_planner_server::{DockPlanner, DockPlannerServer},
+};
+
+// 감사 기록에 남기는 gRPC 메서드 경로
+const PLAN_ENDPOINT: &str = "/dock_sorter.v1.DockPlanner/CreatePlan";
+const BATCH_ENDPOINT: &str = "/dock_sorter.v1.DockPlanner/CreatePlanBatch";
+
+/// host:port에서 gRPC 서버를 띄우는 함수. 서버는 별도의 스레드에서 돌며, HTTP 서버와 상태를 함께 쓴다.
+/// 포트를 열지 못했다면 바로 에러를 반환한다.
+pub fn spawn(host: &str, port: u16, state: Arc<Mutex<ServerState>>) -> Result<(), String> {
+  let addr = (host, port)
+    .to_socket_addrs()
+    .ok()
+    .and_then(|mut addrs| addrs.next())
+    .ok_or_else(|| format!("Failed to resolve {host}:{port}"))?;
+  let runtime = tokio::runtime::Builder::new_multi_thread()
+    .enable_all()
+    .build()
+    .map_err(|e| format!("Failed to start the gRPC server: {e}"))?;
+  let incoming = {
+    let _guard = runtime.enter();
+    TcpIncoming::bind(addr).map_err(|e| format!("Failed to listen on {host}:{port}: {e}"))?
+  };
+  eprintln!("Listening for gRPC on {host}:{port}");
+  thread::spawn(move || {
+    let server = Server::builder().add_service(DockPlannerServer::new(Planner { state })).serve_with_incoming(incoming);
+    if let Err(e) = runtime.block_on(server) {
+      eprintln!("Error: gRPC server stopped: {e}");
+    }
+  });
+  Ok(())
+}
+
+/// DockPlanner 서비스. HTTP 서버와 같은 ServerState로 요청을 처리한다.
+struct Planner {
+  state: Arc<Mutex<ServerState>>,
+}
+
+#[tonic::async_trait]
+impl DockPlanner for Planner {
+  async fn create_plan(&self, request: Request<PlanRequest>) -> Result<Response<PlanReply>, Status> {
+    let (token, caller) = call_info(&request);
+    let PlanRequest { args_json, profile } = request.into_inner();
+    let json = self.plan(PLAN_ENDPOINT, false, profile, token, caller, args_json).await?;
+    let plan: PlanFile = serde_json::from_str(&json).map_err(|e| Status::internal(e.to_string()))?;
+    Ok(Response::new(plan_reply(&plan, json)))
+  }
+
+  async fn create_plan_batch(&self, request: Request<BatchRequest>) -> Result<Response<BatchReply>, Status> {
+    let (token, caller) = call_info(&request);
+    let BatchRequest { jobs, profile } = request.into_inner();
+    // HTTP의 `POST /plan/batch`와 같은 본문으로 바꾼다. 이름은 각 작업의 인자 객체에 넣는다.
+    let requests = jobs
+      .into_iter()
+      .map(|job| {
+        let mut args: Value = serde_json::from_str(&job.args_json).map_err(|e| Status::invalid_argument(format!("Invalid request: {e}")))?;
+        let Some(object) = args.as_object_mut() else {
+          return Err(Status::invalid_argument("Invalid request: args_json must be a JSON object."));
+        };
+        if !job.name.is_empty() {
+          object.insert("name".to_string(), Value::String(job.name));
+        }
+        Ok(args)
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    let json = self.plan(BATCH_ENDPOINT, true, profile, token, caller, Value::Array(requests).to_string()).await?;
+
+    let items: Vec<Value> = serde_json::from_str(&json).map_err(|e| Status::internal(e.to_string()))?;
+    let results = items
+      .into_iter()
+      .map(|mut item| {
+        let name = item.as_object_mut().and_then(|object| object.remove("name")).and_then(|name| name.as_str().map(str::to_string));
+        let outcome = match item.get("error").and_then(Value::as_str) {
+          Some(error) => batch_result::Outcome::Error(error.to_string()),
+          None => {
+            let plan: PlanFile = serde_json::from_value(item.clone()).map_err(|e| Status::internal(e.to_string()))?;
+            let plan_json = serde_json::to_string_pretty(&item).map_err(|e| Status::internal(e.to_string()))?;
+            batch_result::Outcome::Plan(plan_reply(&plan, plan_json))
+          }
+        };
+        Ok(proto::BatchResult { name: name.unwrap_or_default(), outcome: Some(outcome) })
+      })
+      .collect::<Result<Vec<_>, Status>>()?;
+    Ok(Response::new(BatchReply { results }))
+  }
+
+  async fn get_version(&self, _request: Request<VersionRequest>) -> Result<Response<VersionReply>, Status> {
+    let version = lock(&self.state).version();
+    Ok(Response::new(VersionReply {
+      version: version.version.to_string(),
+      profile: version.profile.unwrap_or_default(),
+      schema_version: version.schema_version,
+      profiles: version.profiles,
+    }))
+  }
+}
+
+impl Planner {
+  // HTTP의 plan 요청과 같이 인증과 요청 한도를 확인한 뒤 plan을 계산한다. 성공하면 HTTP 응답과 같은 JSON을 반환한다.
+  // 계산과 감사 기록은 파일을 읽고 쓰므로 blocking 스레드에서 한다.
+  async fn plan(
+    &self,
+    endpoint: &'static str,
+    batch: bool,
+    profile: String,
+    token: Option<String>,
+    caller: String,
+    body: String,
+  ) -> Result<String, Status> {
+    let state = Arc::clone(&self.state);
+    let (status, response) = tokio::task::spawn_blocking(move || {
+      let mut state = lock(&state);
+      state.reload_config();
+      if !state.accepts_token(token.as_deref()) {
+        return Err(Status::unauthenticated("Missing or invalid bearer token."));
+      }
+      if let Err(wait) = state.check_rate_limit(token.as_deref(), &caller) {
+        let retry_after = wait.as_secs_f64().ceil() as u64;
+        return Err(Status::resource_exhausted(format!("Rate limit exceeded. Retry in {retry_after} seconds.")));
+      }
+      let call = PlanCall {
+        endpoint,
+        batch,
+        profile: (!profile.is_empty()).then_some(profile.as_str()),
+        token: token.as_deref(),
+        caller,
+        body: &body,
+      };
+      Ok(state.plan(&call))
+    })
+    .await
+    .map_err(|e| Status::internal(e.to_string()))??;
+
+    if status == 200 {
+      return Ok(response);
+    }
+    // HTTP 에러 본문은 `{"error": "..."}`이다.
+    let message = serde_json::from_str::<Value>(&response)
+      .ok()
+      .and_then(|body| body["error"].as_str().map(str::to_string))
+      .unwrap_or(response);
+    Err(match status {
+      400 => Status::invalid_argument(message),
+      404 => Status::not_found(message),
+      _ => Status::internal(message),
+    })
+  }
+}
+
+// 요청의 bearer token(`authorization` 메타데이터)과 호출자의 IP 주소.
+fn call_info<T>(request: &Request<T>) -> (Option<String>, String) {
+  let token = request
+    .metadata()
+    .get("authorization")
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.strip_prefix("Bearer "))
+    .map(|token| token.trim().to_string());
+  let caller = request.remote_addr().map(|addr| addr.ip().to_string()).unwrap_or_default();
+  (token, caller)
+}
+
+// plan을 gRPC 응답으로 바꾼다. plan_json은 HTTP 응답과 같은 전체 plan이다.
+fn plan_reply(plan: &PlanFile, plan_json: String) -> PlanReply {
+  let result = &plan.result;
+  let groups = result
+    .result_groups
+    .iter()
+    .map(|group| Group {
+      docks: group.docks.iter().map(|dock| dock.get()).collect(),
+      tier: match group.tier {
+        Priority::First => Tier::First,
+        Priority::Second => Tier::Second,
+        Priority::Third => Tier::Third,
+      }
+      .into(),
+      exception: group.kind == GroupKind::Exception,
+    })
+    .collect();
+  PlanReply { groups, warnings: result.warnings.clone(), checksum: result.checksum.clone().unwrap_or_default(), plan_json }
+}
+
+// 다른 스레드가 panic했더라도 상태는 그대로 쓸 수 있다.
+fn lock(state: &Mutex<ServerState>) -> MutexGuard<'_, ServerState> {
+  state.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use eframe::egui;
+
+use crate::{
+  cli::{Args, ReplayOverrides, parse_exception_group, parse_priority_range},
+  plan::{plan_to_json, save_plan},
+  printer::format_results,
+  processor::{PlanRequest, ProcessingResult, process_docks},
+};
+
+/// 슬라이더로 선택할 수 있는 가장 큰 도크 번호.
+const MAX_DOCK: u32 = 200;
+
+/// 도크 정렬 GUI 창을 띄우는 함수. 창이 닫힐 때까지 반환하지 않는다.
+pub fn run_gui() -> Result<(), String> {
+  let app = GuiApp::new()?;
+  let options = eframe::NativeOptions {
+    viewport: egui::ViewportBuilder::default().with_inner_size([760.0, 560.0]),
+    ..Default::default()
+  };
+  eframe::run_native("Dock Sorter", options, Box::new(|_| Ok(Box::new(app))))
+    .map_err(|e| format!("Failed to open the GUI window: {e}"))
+}
+
+/// GUI의 입력 상태. 도크 목록 입력칸은 CLI와 같은 형식(예: `65-66 71`)의 문자열로 보관한다.
+struct GuiApp {
+  args: Args,
+  first: String,
+  second: String,
+  exceptions: String,
+  export_path: String,
+  // 마지막으로 계산된 plan 또는 입력 오류
+  plan: Result<ProcessingResult, String>,
+  // 내보내기 결과 메세지
+  status: String,
+}
+
+impl GuiApp {
+  fn new() -> Result<Self, String> {
+    let args = ReplayOverrides::try_parse_from(["-p", "3"]).map_err(|e| e.to_string())?.args;
+    let mut app = GuiApp {
+      args,
+      first: String::new(),
+      second: String::new(),
+      exceptions: String::new(),
+      export_path: "plan.dockplan".to_string(),
+      plan: Err(String::new()),
+      status: String::new(),
+    };
+    app.recompute();
+    Ok(app)
+  }
+
+  /// 입력칸의 내용을 args에 반영하고 plan을 다시 계산한다.
+  fn recompute(&mut self) {
+    self.plan = self.read_inputs().map(|()| process_docks(PlanRequest::new(&self.args)));
+  }
+
+  /// 도크 목록 입력칸들을 파싱하여 args에 넣고 입력을 검사한다.
+  fn read_inputs(&mut self) -> Result<(), String> {
+    self.args.first_priority = parse_docks(&self.first, parse_priority_range)?;
+    self.args.second_priority = parse_docks(&self.second, parse_priority_range)?;
+    self.args.exception_groups_raw = parse_docks(&self.exceptions, parse_exception_group)?;
+    self.args.validate_input()
+  }
+
+  /// 현재 plan을 --save-plan과 같은 형식의 파일로 저장한다.
+  fn export(&mut self) {
+    self.status = match &self.plan {
+      Ok(result) => {
+        let path = PathBuf::from(&self.export_path);
+        match plan_to_json(&self.args, result).and_then(|json| save_plan(&path, &json)) {
+          Ok(()) => format!("Plan saved to '{}'.", path.display()),
+          Err(e) => format!("Error: {e}"),
+        }
+      }
+      Err(e) => format!("Error: {e}"),
+    };
+  }
+}
+
+impl eframe::App for GuiApp {
+  fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut eframe::Frame) {
+    let mut changed = false;
+    egui::Panel::left("inputs").resizable(false).show(ui, |ui| {
+      ui.heading("Docks");
+      changed |= ui.add(egui::Slider::new(&mut self.args.min.0, 1..=MAX_DOCK).text("min")).changed();
+      changed |= ui.add(egui::Slider::new(&mut self.args.max.0, 1..=MAX_DOCK).text("max")).changed();
+      changed |= ui.add(egui::Slider::new(&mut self.args.per_page, 1..=20).text("docks per group")).changed();
+
+      ui.separator();
+      ui.label("1st priority (e.g. 65-66 71)");
+      changed |= ui.text_edit_singleline(&mut self.first).changed();
+      ui.label("2nd priority");
+      changed |= ui.text_edit_singleline(&mut self.second).changed();
+      ui.label("Exception groups (e.g. 51-53 60,64)");
+      changed |= ui.text_edit_singleline(&mut self.exceptions).changed();
+
+      ui.separator();
+      changed |= ui.checkbox(&mut self.args.strict_first, "Strict 1st priority groups").changed();
+      changed |= ui.checkbox(&mut self.args.strict_second, "Strict 2nd priority groups").changed();
+      changed |= ui.checkbox(&mut self.args.print_marker, "Mark priority docks (@, *)").changed();
+
+      ui.separator();
+      ui.label("Export to");
+      ui.text_edit_singleline(&mut self.export_path);
+      if ui.add_enabled(self.plan.is_ok(), egui::Button::new("Export plan")).clicked() {
+        self.export();
+      }
+      ui.label(&self.status);
+    });
+
+    if changed {
+      self.recompute();
+    }
+
+    // 계산된 plan을 CLI 출력과 같은 형식으로 미리 보여준다.
+    egui::CentralPanel::default().show(ui, |ui| {
+      egui::ScrollArea::vertical().show(ui, |ui| match &self.plan {
+        Ok(result) => {
+          for warning in &result.warnings {
+            ui.colored_label(egui::Color32::YELLOW, warning);
+          }
+          ui.monospace(format_results(&self.args, result));
+        }
+        Err(e) => {
+          ui.colored_label(egui::Color32::RED, e);
+        }
+      });
+    });
+  }
+}
+
+/// 공백으로 구분된 도크 목록을 CLI의 -f, -s, -e와 같은 방식으로 파싱한다.
+fn parse_docks<T>(input: &str, parse: fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+  input.split_whitespace().map(parse).collect()
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use rusqlite::{Connection, params};
+
+use crate::{
+  cli::Args,
+  plan::{PlanFile, SCHEMA_VERSION, result_from_json},
+  processor::ProcessingResult,
+};
+
+/// --history에 경로를 지정하지 않았을 때 사용하는 기본 SQLite 데이터베이스 파일.
+pub const DEFAULT_HISTORY_DB: &str = "dock_history.sqlite3";
+
+/// `history list`에 출력되는 실행 기록 한 건의 요약.
+pub struct RunSummary {
+  pub id: i64,
+  pub created_at: String,
+  pub command_line: String,
+  pub group_count: usize,
+}
+
+/// 실행 기록 데이터베이스를 열고, runs 테이블이 없다면 만드는 함수.
+fn open_history(path: &Path) -> Result<Connection, String> {
+  let conn = Connection::open(path)
+    .map_err(|e| format!("Failed to open history database '{}': {e}", path.display()))?;
+  conn
+    .execute_batch(
+      "CREATE TABLE IF NOT EXISTS runs (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+        command_line TEXT NOT NULL,
+        args TEXT NOT NULL,
+        result TEXT NOT NULL
+      );",
+    )
+    .map_err(|e| format!("Failed to initialize history database: {e}"))?;
+  Ok(conn)
+}
+
+/// 한 번의 실행(입력 인자와 결과 그룹)을 실행 기록 데이터베이스에 저장하고, 저장된 기록의 id를 반환하는 함수.
+pub fn record_run(path: &Path, args: &Args, result: &ProcessingResult) -> Result<i64, String> {
+  let conn = open_history(path)?;
+  // 실행한 명령줄을 그대로 남겨두어 list에서 어떤 실행이었는지 알아볼 수 있도록 한다.
+  let command_line = std::env::args().collect::<Vec<_>>().join(" ");
+  let args_json = serde_json::to_string(args).map_err(|e| format!("Failed to serialize args: {e}"))?;
+  let result_json =
+    serde_json::to_string(result).map_err(|e| format!("Failed to serialize result: {e}"))?;
+  conn
+    .execute(
+      "INSERT INTO runs (command_line, args, result) VALUES (?1, ?2, ?3)",
+      params![command_line, args_json, result_json],
+    )
+    .map_err(|e| format!("Failed to record run in history: {e}"))?;
+  Ok(conn.last_insert_rowid())
+}
+
+/// 저장된 모든 실행 기록의 요약을 id 오름차순으로 반환하는 함수.
+pub fn list_runs(path: &Path) -> Result<Vec<RunSummary>, String> {
+  let conn = open_history(path)?;
+  let mut stmt = conn
+    .prepare("SELECT id, created_at, command_line, result FROM runs ORDER BY id")
+    .map_err(|e| format!("Failed to query history: {e}"))?;
+  let rows = stmt
+    .query_map([], |row| {
+      Ok((
+        row.get::<_, i64>(0)?,
+        row.get::<_, String>(1)?,
+        row.get::<_, String>(2)?,
+        row.get::<_, String>(3)?,
+      ))
+    })
+    .map_err(|e| format!("Failed to query history: {e}"))?;
+
+  let mut runs = Vec::new();
+  for row in rows {
+    let (id, created_at, command_line, result_json) =
+      row.map_err(|e| format!("Failed to read history row: {e}"))?;
+    // 그룹 수만 필요하므로 결과 JSON을 ProcessingResult로 복원해 그룹 수를 센다.
+    let result = result_from_json(&result_json).map_err(|e| format!("Corrupted history entry #{id}: {e}"))?;
+    runs.push(RunSummary { id, created_at, command_line, group_count: result.result_groups.len() });
+  }
+  Ok(runs)
+}
+
+/// id에 해당하는 실행 기록을 PlanFile로 복원하는 함수.
+pub fn load_run(path: &Path, id: i64) -> Result<PlanFile, String> {
+  let conn = open_history(path)?;
+  let (args_json, result_json): (String, String) = conn
+    .query_row("SELECT args, result FROM runs WHERE id = ?1", params![id], |row| {
+      Ok((row.get(0)?, row.get(1)?))
+    })
+    .map_err(|e| match e {
+      rusqlite::Error::QueryReturnedNoRows => format!("No run with id {id} in history."),
+      e => format!("Failed to query history: {e}"),
+    })?;
+  let args: Args =
+    serde_json::from_str(&args_json).map_err(|e| format!("Corrupted history entry #{id}: {e}"))?;
+  let result = result_from_json(&result_json).map_err(|e| format!("Corrupted history entry #{id}: {e}"))?;
+  Ok(PlanFile { schema_version: SCHEMA_VERSION, args, result, signature: None })
+}
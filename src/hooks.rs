@@ -0,0 +1,76 @@
+use std::{
+  io::Write,
+  process::{Command, Stdio},
+};
+
+use crate::{cli::Args, placeholders::GroupPlaceholders, processor::ProcessingResult};
+
+/// 그룹마다 사용자 명령을 실행하는 함수. 명령 template의 자리표시자(`placeholders::GROUP_PLACEHOLDERS`)는 그룹의 값으로 바뀐다.
+///
+/// 사용자가 입력한 `{site}`, `{shift}`, `{date}`는 셸 인용한 하나의 단어로 바뀌므로 값에 셸 문법이 있어도 실행되지 않는다.
+/// 같은 값은 `DOCK_SITE`, `DOCK_SHIFT`, `DOCK_DATE` 환경 변수로도 넘긴다.
+///
+/// 명령은 셸(Unix는 `sh -c`, Windows는 `cmd /C`)로 실행되며, 실패한 명령이 있다면 남은 그룹은 실행하지 않고 에러를 반환한다.
+pub fn run_per_group(template: &str, args: &Args, result: &ProcessingResult) -> Result<(), String> {
+  for (i, group) in result.result_groups.iter().enumerate() {
+    let values = GroupPlaceholders::new(args, group, i + 1);
+    let (site, shift, date) = (shell_quote(values.site)?, shell_quote(values.shift)?, shell_quote(values.date)?);
+    let command = GroupPlaceholders { site: &site, shift: &shift, date: &date, ..values }.render(template);
+    let status = shell(&command)
+      .env("DOCK_SITE", values.site)
+      .env("DOCK_SHIFT", values.shift)
+      .env("DOCK_DATE", values.date)
+      .status()
+      .map_err(|e| format!("Failed to run command for group {}: {e}", i + 1))?;
+    if !status.success() {
+      return Err(format!("Command for group {} ({command}) failed with {status}.", i + 1));
+    }
+  }
+  Ok(())
+}
+
+/// plan 계산이 끝난 뒤 사용자 명령을 실행하고 plan JSON을 표준 입력으로 넘기는 함수.
+/// 명령이 표준 입력을 읽지 않고 끝나더라도 에러로 보지 않으며, 명령이 실패했다면 에러를 반환한다.
+pub fn run_on_complete(command: &str, plan_json: &str) -> Result<(), String> {
+  let mut child = shell(command)
+    .stdin(Stdio::piped())
+    .spawn()
+    .map_err(|e| format!("Failed to run on-complete command '{command}': {e}"))?;
+  if let Some(mut stdin) = child.stdin.take() {
+    // 명령이 입력을 모두 읽지 않고 끝나면 broken pipe가 나므로 쓰기 에러는 무시한다.
+    let _ = stdin.write_all(plan_json.as_bytes());
+  }
+  let status = child
+    .wait()
+    .map_err(|e| format!("Failed to wait for on-complete command '{command}': {e}"))?;
+  if !status.success() {
+    return Err(format!("On-complete command '{command}' failed with {status}."));
+  }
+  Ok(())
+}
+
+// 값을 셸이 하나의 단어로 읽도록 인용한다.
+// Unix는 작은따옴표로 감싸고, cmd는 큰따옴표 안에서도 `"`와 `%`를 해석하므로 이 문자가 있는 값은 거부한다.
+fn shell_quote(value: &str) -> Result<String, String> {
+  if cfg!(windows) {
+    if value.contains(['"', '%']) {
+      return Err(format!("Value '{value}' cannot be passed to a command: it contains '\"' or '%'."));
+    }
+    Ok(format!("\"{value}\""))
+  } else {
+    Ok(format!("'{}'", value.replace('\'', r"'\''")))
+  }
+}
+
+// 명령줄 문자열을 운영체제의 셸로 실행하는 Command를 만든다.
+fn shell(command: &str) -> Command {
+  if cfg!(windows) {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+  } else {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+  }
+}
@@ -0,0 +1,423 @@
+use std::{env, fmt, sync::OnceLock};
+
+use clap::ValueEnum;
+
+/// 사용자에게 보여주는 메세지의 언어.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+  En,
+  Ko,
+}
+
+// 프로세스 전체에서 사용하는 메세지 언어. 처음 사용할 때 정해지며 이후에는 바뀌지 않는다.
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// 메세지 언어를 지정하는 함수. 메세지가 하나라도 만들어지기 전에 호출해야 한다.
+pub fn set_lang(lang: Lang) {
+  let _ = LANG.set(lang);
+}
+
+/// 현재 메세지 언어. 지정되지 않았다면 locale 환경 변수로부터 정한다.
+pub fn lang() -> Lang {
+  *LANG.get_or_init(detect_lang)
+}
+
+/// LC_ALL, LC_MESSAGES, LANG 순으로 처음 설정된 locale이 한국어(ko_KR.UTF-8 등)라면 Ko, 아니라면 En을 반환한다.
+pub fn detect_lang() -> Lang {
+  for var in ["LC_ALL", "LC_MESSAGES", "LANG"] {
+    if let Ok(locale) = env::var(var)
+      && !locale.is_empty()
+    {
+      return match locale.starts_with("ko") {
+        true => Lang::Ko,
+        false => Lang::En,
+      };
+    }
+  }
+  Lang::En
+}
+
+// (key, 영어, 한국어) 메세지 표. 메세지의 `{name}` 자리는 tr에 넘긴 값으로 채워진다.
+const MESSAGES: &[(&str, &str, &str)] = &[
+  // plan 출력
+  ("header.stamp", "Run {run_id} at {timestamp}", "실행 {run_id} ({timestamp})"),
+  ("header.date", "Date: {date}", "날짜: {date}"),
+  ("header.site_shift", "Site: {site}, shift: {shift}", "현장: {site}, 근무조: {shift}"),
+  ("header.site", "Site: {site}", "현장: {site}"),
+  ("header.shift", "Shift: {shift}", "근무조: {shift}"),
+  ("header.range", "\nProcessing dock range: {min} - {max}\n", "\n처리 도크 범위: {min} - {max}\n"),
+  ("header.first_per_page", "Docks per group (1st priority): {count}", "그룹당 도크 수 (1차): {count}"),
+  ("header.second_per_page", "Docks per group (2nd priority): {count}", "그룹당 도크 수 (2차): {count}"),
+  ("header.third_per_page", "Docks per group (3rd priority/general): {count}", "그룹당 도크 수 (3차/일반): {count}"),
+  ("header.range_per_page", "Docks per group in {range}: {count}", "{range} 구간의 그룹당 도크 수: {count}"),
+  ("header.spread", "Spread: docks within {distance} of a 1st priority dock are 2nd priority", "주변 확산: 1차 도크로부터 {distance} 이내의 도크는 2차로 처리"),
+  ("header.serpentine", "Serpentine order: {row_size} docks per row", "지그재그 순서: 한 줄에 도크 {row_size}개"),
+  ("header.arrival_windows", "Arrival windows: {minutes} minutes", "도착 시간대: {minutes}분"),
+  ("header.pairs", "Cross-aisle pairs: dock N is grouped with dock N+{offset}", "통로 건너편 짝: 도크 N과 짝 도크(N+{offset})를 함께 묶음"),
+  ("header.crews_round_robin", "Crews: {count}, taking groups in turn", "작업조: {count}개, 그룹을 차례로 배정"),
+  ("header.crews_balanced", "Crews: {count}, balanced by group size", "작업조: {count}개, 그룹 크기에 따라 균등 배정"),
+  ("header.slots", "Time slots: {slots}", "작업 시간대: {slots}"),
+  ("header.slots_timed", "Time slots: {slots} ({minutes} minutes per dock)", "작업 시간대: {slots} (도크당 {minutes}분)"),
+  ("header.pad_exceptions", "Exception groups are filled up to the per-page with the following docks", "예외 그룹은 뒤따르는 도크들로 per-page까지 채움"),
+  ("header.strict_first", "\nStrict mode applyed for 1st priority groups.", "\n1차 그룹에 strict 모드가 적용되었습니다."),
+  ("header.strict_second", "\nStrict mode applyed for 2nd priority groups.", "\n2차 그룹에 strict 모드가 적용되었습니다."),
+  ("header.post", "Post-processing: {steps}", "후처리: {steps}"),
+  ("header.repeat_groups", "Groups repeated: 1st x{first}, 2nd x{second}, 3rd x{third}", "그룹 반복: 1차 {first}번, 2차 {second}번, 3차 {third}번"),
+  (
+    "header.exception_groups",
+    "Exception groups (printed together, in order of their first dock):",
+    "예외 그룹 (함께 출력되며, 첫 도크 순서대로):",
+  ),
+  ("header.docks", "\n--- Docks ---", "\n--- 도크 ---"),
+  ("header.first_docks", "first: {docks}", "1차: {docks}"),
+  ("header.second_docks", "second: {docks}", "2차: {docks}"),
+  ("header.skipped_docks", "skipped: {docks}", "제외: {docks}"),
+  ("header.solo_docks", "solo: {docks}", "단독: {docks}"),
+  ("header.output_order_marked", "\n--- Output Order (1st: @, 2nd: *) ---", "\n--- 출력 순서 (1차: @, 2차: *) ---"),
+  ("header.output_order", "\n--- Output Order ---", "\n--- 출력 순서 ---"),
+  ("slot.header", "=== {start} ===", "=== {start} ==="),
+  ("crew.tag", "[crew {crew}]", "[{crew}조]"),
+  ("page.tag", "(page {page} of {pages})", "({pages}쪽 중 {page}쪽)"),
+  ("footer.labels", "\nLabels: {labels} on {pages} pages", "\n라벨: {pages}페이지에 {labels}장"),
+  ("footer.labels_sheets", "\nLabels: {labels} on {pages} sheets of {per_sheet}", "\n라벨: {per_sheet}장짜리 용지 {pages}장에 {labels}장"),
+  ("footer.checksum", "\nChecksum: {checksum}", "\nchecksum: {checksum}"),
+  ("legend.markers", "1st: @, 2nd: *", "1차: @, 2차: *"),
+  ("window.unscheduled", "unscheduled", "도착 예정 없음"),
+  ("table.group", "#", "#"),
+  ("table.window", "Window", "시간대"),
+  ("table.crew", "Crew", "작업조"),
+  ("table.slot", "Slot", "작업 시간대"),
+  ("table.docks", "Docks", "도크"),
+  ("table.composition", "Composition", "구성"),
+  ("table.size", "Size", "크기"),
+  ("table.exception", "exception", "예외"),
+  // plan 비교와 실행 기록
+  ("diff.header", "\n--- Plan diff ---", "\n--- plan 비교 ---"),
+  ("diff.none", "No differences.", "차이가 없습니다."),
+  ("diff.removed", "Removed groups:", "삭제된 그룹:"),
+  ("diff.added", "Added groups:", "추가된 그룹:"),
+  ("diff.moved", "Docks that changed group:", "그룹이 바뀐 도크:"),
+  ("diff.group", "group {index} {docks}", "{index}번 그룹 {docks}"),
+  ("diff.not_planned", "(not planned)", "(계획에 없음)"),
+  ("diff.priority_changes", "Priority changes:", "우선순위 변경:"),
+  ("diff.order_changed", "Same groups, but printed in a different order.", "그룹은 같지만 출력 순서가 다릅니다."),
+  (
+    "diff.reprint_summary",
+    "\nUnchanged from previous plan: {unchanged} of {total} groups ({reprint} to reprint)",
+    "\n이전 plan과 같은 그룹: {total}개 중 {unchanged}개 (다시 출력할 그룹 {reprint}개)",
+  ),
+  ("overlaps.header", "\n--- Docks listed more than once ---", "\n--- 중복 입력된 도크 ---"),
+  ("overlaps.none", "No dock is listed more than once.", "중복 입력된 도크가 없습니다."),
+  ("overlaps.line", "{docks}: {sources} -> {resolution}", "{docks}: {sources} -> {resolution}"),
+  (
+    "stats.groups",
+    "Groups: {total} (1st: {first}, 2nd: {second}, 3rd: {third}, exception: {exception})",
+    "그룹: {total}개 (1차: {first}, 2차: {second}, 3차: {third}, 예외: {exception})",
+  ),
+  (
+    "stats.docks",
+    "Docks: {total} (1st: {first}, 2nd: {second}, 3rd: {third}, exception: {exception}, skipped: {skipped})",
+    "도크: {total}개 (1차: {first}, 2차: {second}, 3차: {third}, 예외: {exception}, 제외: {skipped})",
+  ),
+  ("stats.labels", "Expected labels: {labels} on {pages} pages", "출력할 라벨: {pages}페이지에 {labels}장"),
+  ("stats.labels_sheets", "Expected labels: {labels} on {pages} sheets of {per_sheet}", "출력할 라벨: {per_sheet}장짜리 용지 {pages}장에 {labels}장"),
+  ("history.empty", "No runs recorded.", "기록된 실행이 없습니다."),
+  ("queue.empty", "No print jobs in the queue.", "인쇄 대기열에 작업이 없습니다."),
+  ("queue.nothing", "No pending or failed print jobs to re-send.", "다시 보낼 대기 중이거나 실패한 인쇄 작업이 없습니다."),
+  ("reconcile.header", "\n--- Plan vs. scans ---", "\n--- plan과 스캔 비교 ---"),
+  ("reconcile.summary", "{scans} scans, {planned} planned docks", "스캔 {scans}건, 계획된 도크 {planned}개"),
+  ("reconcile.clean", "Every planned dock was scanned once, in plan order.", "계획된 모든 도크가 plan 순서대로 한 번씩 스캔되었습니다."),
+  ("reconcile.out_of_order", "Docks served out of order:", "순서를 어긴 도크:"),
+  (
+    "reconcile.out_of_order_line",
+    "{dock} (scan #{scan}): group {group}, scanned after group {after}",
+    "{dock} ({scan}번째 스캔): {group}번 그룹, {after}번 그룹 이후에 스캔됨",
+  ),
+  ("reconcile.missed", "Planned docks never scanned: {docks}", "스캔되지 않은 도크: {docks}"),
+  ("reconcile.unplanned", "Scanned docks not in the plan: {docks}", "plan에 없는 스캔 도크: {docks}"),
+  ("reconcile.rescanned", "Docks scanned more than once: {docks}", "두 번 이상 스캔된 도크: {docks}"),
+  // 처리 중 경고
+  ("label.first", "First priority", "1차 우선순위"),
+  ("label.second", "Second priority", "2차 우선순위"),
+  ("label.third", "Third priority", "3차 우선순위"),
+  ("label.exception", "Exception", "예외"),
+  ("label.skip", "Skipped", "제외"),
+  ("label.solo", "Solo", "단독"),
+  (
+    "warning.out_of_range_one",
+    "Warning: {label} dock {start} is outside the specified range [{min}-{max}] and will be ignored.",
+    "경고: {label} 도크 {start}: 지정된 범위 [{min}-{max}] 밖이므로 무시합니다.",
+  ),
+  (
+    "warning.out_of_range_many",
+    "Warning: {label} docks {start}-{end} are outside the specified range [{min}-{max}] and will be ignored.",
+    "경고: {label} 도크 {start}-{end}: 지정된 범위 [{min}-{max}] 밖이므로 무시합니다.",
+  ),
+  (
+    "warning.duplicate_exception",
+    "Warning: Dock {dock} in exception group already part of another exception group. Ignoring.",
+    "경고: 예외 그룹의 도크 {dock}: 이미 다른 예외 그룹에 속해 있으므로 무시합니다.",
+  ),
+  (
+    "warning.solo_exception",
+    "Warning: Dock {dock} in exception group is a solo dock and will be printed alone.",
+    "경고: 예외 그룹의 도크 {dock}: 단독 도크이므로 혼자 출력합니다.",
+  ),
+  (
+    "warning.break_on_gap_unused",
+    "Warning: --break-on-gap has no effect because no docks in the range are skipped, closed or off-site.",
+    "경고: 처리 범위에 제외되었거나 사용 중지되었거나 현장에 없는 도크가 없으므로 --break-on-gap은 효과가 없습니다.",
+  ),
+  (
+    "warning.preview_image",
+    "Warning: Could not detect the terminal's graphics protocol. Showing text labels instead; pass kitty, iterm or sixel to --preview-image.",
+    "경고: 터미널의 그래픽 프로토콜을 알 수 없어 텍스트 라벨로 대신 출력합니다. --preview-image에 kitty, iterm, sixel 중 하나를 지정하세요.",
+  ),
+  // 입력 오류
+  ("error.prefix", "Error: {message}", "오류: {message}"),
+  ("error.no_arguments", "No arguments given. Try '--help'.", "인자가 없습니다. '--help'를 참고하세요."),
+  (
+    "error.per_page",
+    "Error: Number of docks per group must be 1 or greater for all per-page settings.",
+    "모든 그룹당 도크 수 설정은 1 이상이어야 합니다.",
+  ),
+  (
+    "error.first_per_page",
+    "Number of docks for 1st priority (`--fpp`) must be 1 or greater.",
+    "1차 그룹당 도크 수(`--fpp`)는 1 이상이어야 합니다.",
+  ),
+  (
+    "error.second_per_page",
+    "Number of docks for 2nd priority (`--spp`) must be 1 or greater.",
+    "2차 그룹당 도크 수(`--spp`)는 1 이상이어야 합니다.",
+  ),
+  (
+    "error.per_page_range_overlap",
+    "Per-page ranges {first} and {second} (`--pp-range`) overlap.",
+    "그룹당 도크 수 구간 {first}, {second}(`--pp-range`)가 겹칩니다.",
+  ),
+  (
+    "error.row_size",
+    "Number of docks per row (`--row-size`) must be 1 or greater.",
+    "한 줄의 도크 수(`--row-size`)는 1 이상이어야 합니다.",
+  ),
+  ("error.pair_offset", "Pair offset (`--pair-offset`) must be 1 or greater.", "짝 도크 간격(`--pair-offset`)은 1 이상이어야 합니다."),
+  (
+    "error.arrival_window",
+    "Arrival window (`--arrival-window`) must be 1 minute or longer.",
+    "도착 시간대 길이(`--arrival-window`)는 1분 이상이어야 합니다.",
+  ),
+  ("error.slot_time", "Invalid time slot '{slot}'. Expected HH:MM.", "잘못된 작업 시간대 '{slot}'입니다. HH:MM 형식이어야 합니다."),
+  (
+    "error.slot_order",
+    "Time slot {slot} must start later than the slot before it.",
+    "작업 시간대 {slot}은 앞의 작업 시간대보다 늦게 시작해야 합니다.",
+  ),
+  ("error.dock_minutes", "Minutes per dock (`--dock-minutes`) must be 1 or greater.", "도크당 작업 시간(`--dock-minutes`)은 1분 이상이어야 합니다."),
+  ("error.email_smtp", "`--email` requires --smtp.", "`--email`은 --smtp와 함께 사용해야 합니다."),
+  ("error.smtp_login", "`--smtp-user` and `--smtp-password` must be given together.", "`--smtp-user`와 `--smtp-password`는 함께 지정해야 합니다."),
+  ("error.email_address", "Invalid email address: '{address}'.", "잘못된 메일 주소입니다: '{address}'."),
+  ("error.date_manifest", "`--date-manifest` requires --date or --dates.", "`--date-manifest`는 --date 또는 --dates와 함께 사용해야 합니다."),
+  ("error.baud", "Baud rate (`--baud`) must be 1 or greater.", "통신 속도(`--baud`)는 1 이상이어야 합니다."),
+  ("error.crews", "Number of crews (`--crews`) must be 1 or greater.", "작업조 수(`--crews`)는 1 이상이어야 합니다."),
+  ("error.labels_per_sheet", "Labels per sheet (`--labels-per-sheet`) must be 1 or greater.", "용지당 라벨 수(`--labels-per-sheet`)는 1 이상이어야 합니다."),
+  (
+    "error.repeat_groups",
+    "Group repeats (`--repeat-groups`, `--repeat-first`, `--repeat-second`) must be 1 or greater.",
+    "그룹 반복 횟수(`--repeat-groups`, `--repeat-first`, `--repeat-second`)는 1 이상이어야 합니다.",
+  ),
+  (
+    "error.auto_priority_threshold",
+    "`--auto-priority` requires `--first-threshold` and/or `--second-threshold`.",
+    "`--auto-priority`에는 `--first-threshold` 또는 `--second-threshold`가 필요합니다.",
+  ),
+  (
+    "error.threshold_order",
+    "First threshold ({first}) cannot be lower than second threshold ({second}).",
+    "1차 임계값({first})은 2차 임계값({second})보다 작을 수 없습니다.",
+  ),
+  (
+    "error.unknown_placeholder",
+    "Unknown placeholder {placeholder} in {option}. Available placeholders: {names}.",
+    "{option}에 알 수 없는 자리표시자 {placeholder}이(가) 있습니다. 사용할 수 있는 자리표시자: {names}.",
+  ),
+  (
+    "error.unknown_tier",
+    "Unknown tier '{name}'. Tiers defined in the config file: {known}",
+    "알 수 없는 우선순위 묶음 '{name}'입니다. 설정 파일에 정의된 묶음: {known}",
+  ),
+  ("config.none", "(none)", "(없음)"),
+  (
+    "error.tier_without_config",
+    "--tier {tiers} requires a config file (--config) that defines the tiers.",
+    "--tier {tiers}에는 묶음을 정의한 설정 파일(--config)이 필요합니다.",
+  ),
+  (
+    "error.site_dock",
+    "Dock {dock} is not valid at this site: docks are {site}. Nearest valid docks: {nearest}.",
+    "도크 {dock}은(는) 이 현장에 없는 도크입니다: 도크는 {site}입니다. 가까운 도크: {nearest}.",
+  ),
+  (
+    "error.min_max",
+    "Minimum dock number ({min}) cannot be greater than maximum dock number ({max}).",
+    "최소 도크 번호({min})는 최대 도크 번호({max})보다 클 수 없습니다.",
+  ),
+  (
+    "error.range_cap",
+    "Dock range {min}-{max} spans {count} docks, more than the limit of {cap}. Raise it with `--max-range-docks` if this is intended.",
+    "도크 범위 {min}-{max}의 도크 수({count}개)가 상한({cap}개)을 넘습니다. 의도한 것이라면 `--max-range-docks`로 상한을 올리세요.",
+  ),
+  (
+    "error.batch_cap",
+    "The {jobs} jobs of the batch span {count} docks in total, more than the server limit of {cap}. Split the batch into smaller requests.",
+    "배치의 작업 {jobs}개의 도크 수 합계({count}개)가 서버의 상한({cap}개)을 넘습니다. 배치를 더 작은 요청들로 나누세요.",
+  ),
+  (
+    "error.input_cap",
+    "The -f, -s, -e and --skip ranges cover {count} docks within {min}-{max}, more than the limit of {cap}. Raise it with `--max-range-docks` if this is intended.",
+    "-f, -s, -e, --skip 범위가 {min}-{max} 안에서 차지하는 도크 수({count}개)가 상한({cap}개)을 넘습니다. 의도한 것이라면 `--max-range-docks`로 상한을 올리세요.",
+  ),
+  // 실행 결과 안내
+  ("status.closed_docks", "Fetched {count} closed dock ranges from '{url}'.", "'{url}'에서 사용 중지된 도크 범위 {count}개를 받았습니다."),
+  (
+    "status.wms",
+    "Fetched {first} 1st and {second} 2nd priority dock ranges from the WMS.",
+    "WMS에서 1차 도크 범위 {first}개, 2차 도크 범위 {second}개를 받았습니다.",
+  ),
+  ("status.clipboard", "Plan copied to clipboard.", "plan을 클립보드에 복사했습니다."),
+  ("status.saved", "Plan saved to '{path}'.", "plan을 '{path}'에 저장했습니다."),
+  ("status.history", "Run recorded in history as #{id}.", "실행 기록에 저장했습니다 (#{id})."),
+  ("status.webhook", "Plan sent to webhook '{url}'.", "plan을 webhook '{url}'에 보냈습니다."),
+  ("status.emailed", "Plan emailed to {to}.", "plan을 {to}에 메일로 보냈습니다."),
+  ("status.chat", "Plan posted to the {service} webhook.", "plan을 {service} webhook에 보냈습니다."),
+  ("status.uploaded", "Plan uploaded to {location}.", "plan을 {location}에 올렸습니다."),
+  ("status.mqtt", "Plan published to MQTT topic '{topic}' on '{broker}'.", "plan을 '{broker}'의 MQTT topic '{topic}'에 발행했습니다."),
+  (
+    "printing.confirm",
+    "Group {group} ({docks}): {labels} labels. Press Enter to print, 's' to skip, 'q' to stop: ",
+    "그룹 {group} ({docks}): 라벨 {labels}장. 인쇄하려면 Enter, 건너뛰려면 's', 멈추려면 'q'를 입력하세요: ",
+  ),
+  ("printing.skipped", "Skipped group {group}.", "그룹 {group}: 건너뛰었습니다."),
+  (
+    "printing.queued",
+    "Unsent groups are kept in '{path}'; re-send them with `dock_sorter queue retry`.",
+    "보내지 못한 그룹은 '{path}'에 남아 있습니다. `dock_sorter queue retry`로 다시 보낼 수 있습니다.",
+  ),
+  ("printing.stopped", "Printing stopped before group {group}.", "그룹 {group}부터 인쇄를 멈췄습니다."),
+  ("status.printed", "Sent {labels} labels to printer '{device}'.", "라벨 {labels}장을 프린터 '{device}'로 보냈습니다."),
+  (
+    "status.calibration",
+    "Calibration label sent to '{device}' with offset {offset} mm. If the border is not on the label edges, read the shift on the rulers and print again with --offset.",
+    "보정 라벨을 '{device}'로 보냈습니다 (보정값 {offset} mm). 테두리가 라벨 가장자리에 맞지 않다면 눈금에서 어긋난 만큼을 읽어 --offset으로 다시 인쇄하세요.",
+  ),
+  ("status.offset_saved", "Offset {offset} mm saved to profile '{profile}' in '{path}'.", "보정값 {offset} mm를 '{path}'의 프로필 '{profile}'에 저장했습니다."),
+  ("status.queue_cancelled", "Cancelled {jobs} print jobs.", "인쇄 작업 {jobs}개를 취소했습니다."),
+  ("status.queue_retried", "Re-sent {labels} labels from the print queue.", "인쇄 대기열의 라벨 {labels}장을 다시 보냈습니다."),
+  ("status.signature_ok", "Plan '{path}' has a valid signature.", "plan '{path}'의 서명이 올바릅니다."),
+  ("status.checksum_ok", "Plan '{path}' matches its checksum {checksum}.", "plan '{path}'의 내용이 checksum {checksum}과 일치합니다."),
+  ("status.expect_match", "Plan matches expected plan '{path}'.", "plan이 기대하는 plan 파일 '{path}'의 내용과 일치합니다."),
+  ("error.expect_mismatch", "Plan does not match expected plan '{path}'.", "plan이 기대하는 plan 파일 '{path}'의 내용과 다릅니다."),
+  ("error.read_plan_file", "Failed to read plan file '{path}': {error}", "plan 파일 '{path}'을(를) 읽지 못했습니다: {error}"),
+  ("error.plan_file", "Plan file '{path}': {error}", "plan 파일 '{path}': {error}"),
+  ("error.job_finished", "Print job #{id} is already {status}.", "인쇄 작업 #{id}은(는) 이미 {status} 상태입니다."),
+  ("status.replay_run", "Replaying run #{id} from '{db}'.", "'{db}'의 실행 기록 #{id}을(를) 다시 실행합니다."),
+  ("status.replay_plan", "Replaying plan file '{path}'.", "plan 파일 '{path}'을(를) 다시 실행합니다."),
+  ("batch.job", "=== Job {index} of {total}: {name} ===", "=== 작업 {index}/{total}: {name} ==="),
+  ("batch.unnamed", "unnamed", "이름 없음"),
+  ("error.batch_failed", "{failed} of {total} jobs failed.", "작업 {total}개 중 {failed}개가 실패했습니다."),
+  ("status.scheduled", "Scheduled profile '{profile}' at '{schedule}'.", "프로필 '{profile}'을(를) '{schedule}'에 실행하도록 예약했습니다."),
+  ("verify.header", "Plan self-check found {count} problem(s):", "plan 자체 검사에서 문제 {count}개를 찾았습니다:"),
+  ("verify.more", "... and {count} more", "... 외 {count}개"),
+  ("verify.skipped_dock", "Group #{group} contains skipped dock {dock}.", "그룹 #{group}에 제외된 도크 {dock}이(가) 있습니다."),
+  (
+    "verify.outside_range",
+    "Group #{group} contains dock {dock}, outside the range {min}-{max}.",
+    "그룹 #{group}에 범위 {min}-{max} 밖의 도크 {dock}이(가) 있습니다.",
+  ),
+  ("verify.duplicate", "Dock {dock} appears more than once (again in group #{group}).", "도크 {dock}이(가) 두 번 이상 나타납니다 (그룹 #{group}에서 다시 나타남)."),
+  ("verify.missing_one", "Dock {dock} does not appear in any group.", "도크 {dock}이(가) 어떤 그룹에도 없습니다."),
+  ("verify.missing_many", "Docks {start}-{end} do not appear in any group.", "도크 {start}-{end}이(가) 어떤 그룹에도 없습니다."),
+  ("verify.split_exception", "Exception group {docks} does not appear as one intact group.", "예외 그룹 {docks}이(가) 하나의 온전한 그룹으로 나타나지 않습니다."),
+  ("verify.exception_in_regular", "Regular group #{group} contains exception dock {dock}.", "일반 그룹 #{group}에 예외 도크 {dock}이(가) 있습니다."),
+  (
+    "verify.oversized",
+    "Group #{group} ({tier} priority) has {size} docks, more than its per-page of {per_page}.",
+    "그룹 #{group}({tier} 우선순위)의 도크 {size}개가 그룹당 도크 수 {per_page}보다 많습니다.",
+  ),
+  ("verify.solo", "Group #{group} contains solo dock {dock} together with other docks.", "그룹 #{group}에 단독 도크 {dock}이(가) 다른 도크와 함께 있습니다."),
+  ("error.invalid_request", "Invalid request: {error}", "잘못된 요청입니다: {error}"),
+  ("error.request_body", "Failed to read request body: {error}", "요청 본문을 읽지 못했습니다: {error}"),
+  ("error.serialize_results", "Failed to serialize results: {error}", "결과를 JSON으로 만들지 못했습니다: {error}"),
+  ("error.cli_only_field", "Field `{field}` is only supported on the command line, not over HTTP.", "`{field}` 필드는 HTTP가 아닌 명령줄에서만 사용할 수 있습니다."),
+  (
+    "error.profile_mismatch",
+    "Profile '{path}' in the path does not match '{header}' in the {name} header.",
+    "경로의 프로필 '{path}'이(가) {name} 헤더의 '{header}'와(과) 다릅니다.",
+  ),
+  ("error.audit_failed", "Failed to record the request in the audit log.", "요청을 감사 기록에 남기지 못했습니다."),
+  ("error.not_found", "Not found.", "찾을 수 없습니다."),
+  ("error.unauthorized", "Missing or invalid bearer token.", "bearer token이 없거나 올바르지 않습니다."),
+  ("error.rate_limited", "Rate limit exceeded. Retry in {seconds} seconds.", "요청 한도를 넘었습니다. {seconds}초 뒤에 다시 시도하세요."),
+  ("error.method_not_allowed", "Method not allowed. Use {methods}.", "허용되지 않는 method입니다. {methods}을(를) 사용하세요."),
+  ("error.origin_not_allowed", "Origin not allowed.", "허용되지 않는 origin입니다."),
+  ("error.websocket_upgrade", "Expected a WebSocket upgrade request (version 13).", "WebSocket 업그레이드 요청(version 13)이어야 합니다."),
+  (
+    "error.audit_disabled",
+    "The audit log is not enabled on this server. Start it with --audit-log.",
+    "이 서버에는 감사 기록이 켜져 있지 않습니다. --audit-log로 시작하세요.",
+  ),
+  ("error.invalid_limit", "Invalid limit '{value}'.", "잘못된 limit '{value}'입니다."),
+  ("error.unknown_query", "Unknown query parameter '{pair}'. Only `limit` is supported.", "알 수 없는 query parameter '{pair}'입니다. `limit`만 사용할 수 있습니다."),
+  (
+    "error.dock_db_disabled",
+    "Dock metadata is not enabled on this server. Start it with --dock-db.",
+    "이 서버에는 도크 메타데이터가 켜져 있지 않습니다. --dock-db로 시작하세요.",
+  ),
+  ("error.invalid_dock", "Invalid dock number '{number}'.", "잘못된 도크 번호 '{number}'입니다."),
+  ("error.no_dock_metadata", "No metadata for dock {dock}.", "도크 {dock}의 메타데이터가 없습니다."),
+  (
+    "error.per_page_range_format",
+    "Invalid per-page range: '{value}'. Expected RANGE=COUNT (e.g., 51-60=2).",
+    "잘못된 구간별 그룹당 도크 수 '{value}'입니다. 범위=개수 형식이어야 합니다 (예: 51-60=2).",
+  ),
+  (
+    "error.per_page_range_count",
+    "Invalid per-page range: '{value}'. The number of docks per group must be 1 or greater.",
+    "잘못된 구간별 그룹당 도크 수 '{value}'입니다. 그룹당 도크 수는 1 이상이어야 합니다.",
+  ),
+  (
+    "error.separator_escape",
+    "Unknown escape '\\{escape}' in separator '{value}'. Use \\n, \\t or \\\\.",
+    "구분자 '{value}'에 알 수 없는 이스케이프 '\\{escape}'이(가) 있습니다. \\n, \\t, \\\\를 사용하세요.",
+  ),
+  ("error.separator_trailing", "Separator '{value}' ends with a lone '\\'.", "구분자 '{value}'이(가) 짝이 없는 '\\'로 끝납니다."),
+  (
+    "error.range_order",
+    "Invalid range: start ({start}) must be less than or equal to end ({end}) in '{value}'",
+    "잘못된 범위 '{value}'입니다: 시작({start})은 끝({end})보다 작거나 같아야 합니다.",
+  ),
+  ("error.range_numbers", "Invalid range format: '{value}'. Both parts must be numbers.", "잘못된 범위 형식 '{value}'입니다. 양쪽 모두 숫자여야 합니다."),
+  ("error.range_format", "Invalid range format: '{value}'", "잘못된 범위 형식 '{value}'입니다."),
+  ("error.dock_format", "Invalid number or range format: '{value}'", "잘못된 숫자 또는 범위 형식 '{value}'입니다."),
+  (
+    "error.rules_with_plugin",
+    "`--rules` cannot be used with a plugin that provides grouping rules.",
+    "`--rules`는 그룹핑 규칙을 제공하는 plugin과 함께 사용할 수 없습니다.",
+  ),
+];
+
+/// key에 해당하는 메세지를 현재 언어로 만드는 함수. 메세지의 `{name}` 자리는 params의 같은 이름의 값으로 채운다.
+/// 표에 없는 key라면 key를 그대로 반환한다.
+pub fn tr(key: &str, params: &[(&str, &dyn fmt::Display)]) -> String {
+  let Some(&(_, en, ko)) = MESSAGES.iter().find(|(k, _, _)| *k == key) else {
+    return key.to_string();
+  };
+  let mut message = match lang() {
+    Lang::En => en.to_string(),
+    Lang::Ko => ko.to_string(),
+  };
+  for (name, value) in params {
+    message = message.replace(&format!("{{{name}}}"), &value.to_string());
+  }
+  message
+}
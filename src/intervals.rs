@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use crate::models::{DockId, DockRange};
+
+/// 겹치지 않는 닫힌 구간들로 도크 번호를 저장하는 집합.
+/// 연속된 도크들은 구간 하나로 합쳐지므로, 처리 범위가 매우 크더라도 메모리는 구간 수에만 비례한다.
+#[derive(Debug, Default, Clone)]
+pub struct IntervalSet {
+  // 구간 시작 -> 구간 끝(포함). 구간들은 서로 겹치거나 맞닿지 않는다.
+  ranges: BTreeMap<DockId, DockId>,
+}
+
+impl IntervalSet {
+  pub fn new() -> Self {
+    IntervalSet::default()
+  }
+
+  /// dock이 집합에 포함되어 있는지 여부.
+  pub fn contains(&self, dock: DockId) -> bool {
+    self.ranges.range(..=dock).next_back().is_some_and(|(_, &end)| dock <= end)
+  }
+
+  /// 도크 하나를 추가한다.
+  pub fn insert(&mut self, dock: DockId) {
+    self.insert_range(dock, dock);
+  }
+
+  /// 구간들을 오름차순으로 반환한다.
+  pub fn iter(&self) -> impl Iterator<Item = DockRange> + '_ {
+    self.ranges.iter().map(|(&start, &end)| DockRange { start, end })
+  }
+
+  /// start부터 end까지(포함)의 구간을 추가한다. 겹치거나 맞닿는 구간들은 하나로 합친다.
+  pub fn insert_range(&mut self, mut start: DockId, mut end: DockId) {
+    if start > end {
+      return;
+    }
+    // 앞쪽 구간이 새 구간과 겹치거나 맞닿는다면 앞쪽 구간의 시작부터 합친다.
+    if let Some((&prev_start, &prev_end)) = self.ranges.range(..=start).next_back()
+      && prev_end.saturating_add(1) >= start
+    {
+      start = prev_start;
+      end = end.max(prev_end);
+    }
+    // 새 구간 안에서 시작하거나 바로 뒤에 맞닿는 구간들을 흡수한다.
+    let absorbed: Vec<(DockId, DockId)> = self
+      .ranges
+      .range(start..=end.saturating_add(1))
+      .map(|(&s, &e)| (s, e))
+      .collect();
+    for (s, e) in absorbed {
+      self.ranges.remove(&s);
+      end = end.max(e);
+    }
+    self.ranges.insert(start, end);
+  }
+}
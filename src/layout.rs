@@ -0,0 +1,148 @@
+use unicode_width::UnicodeWidthStr;
+
+/// 열 안에서 셀을 정렬하는 방향.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+  Left,
+  Right,
+}
+
+// Columns의 한 줄. 셀들로 이루어진 행이거나, 열 폭 계산에 참여하지 않는 한 줄짜리 문자열(예: 시간대 헤더)이다.
+enum Line {
+  Row(Vec<String>),
+  Text(String),
+}
+
+/// 셀들을 열 단위로 맞춰 출력하는 레이아웃.
+/// 열 폭은 문자 수가 아니라 터미널에 표시되는 폭으로 계산하므로, 한글처럼 두 칸을 차지하는 문자가 섞여도 열이 어긋나지 않는다.
+pub struct Columns {
+  lines: Vec<Line>,
+  // 열별 정렬 방향. 지정되지 않은 열은 왼쪽 정렬이다.
+  aligns: Vec<Align>,
+  separator: String,
+}
+
+impl Columns {
+  /// 열 사이에 separator를 넣는 빈 레이아웃을 만든다.
+  pub fn new(separator: &str) -> Self {
+    Columns { lines: Vec::new(), aligns: Vec::new(), separator: separator.to_string() }
+  }
+
+  /// column번째 열의 정렬 방향을 지정한다.
+  pub fn align(mut self, column: usize, align: Align) -> Self {
+    if self.aligns.len() <= column {
+      self.aligns.resize(column + 1, Align::Left);
+    }
+    self.aligns[column] = align;
+    self
+  }
+
+  /// 셀들로 이루어진 행을 추가한다. 행마다 셀 수가 달라도 된다.
+  pub fn push_row(&mut self, cells: Vec<String>) {
+    self.lines.push(Line::Row(cells));
+  }
+
+  /// 열 폭과 상관없이 그대로 출력할 한 줄을 추가한다.
+  pub fn push_text(&mut self, text: String) {
+    self.lines.push(Line::Text(text));
+  }
+
+  /// 각 열의 표시 폭(가장 넓은 셀의 폭).
+  pub fn widths(&self) -> Vec<usize> {
+    let mut widths: Vec<usize> = Vec::new();
+    for line in &self.lines {
+      if let Line::Row(cells) = line {
+        if widths.len() < cells.len() {
+          widths.resize(cells.len(), 0);
+        }
+        for (width, cell) in widths.iter_mut().zip(cells) {
+          *width = (*width).max(display_width(cell));
+        }
+      }
+    }
+    widths
+  }
+
+  /// 레이아웃을 줄 단위 문자열들로 만든다. 줄 끝의 공백은 남기지 않는다.
+  pub fn render(&self) -> Vec<String> {
+    let widths = self.widths();
+    self
+      .lines
+      .iter()
+      .map(|line| match line {
+        Line::Text(text) => text.clone(),
+        Line::Row(cells) => {
+          let padded: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| pad(cell, widths[i], self.aligns.get(i).copied().unwrap_or(Align::Left)))
+            .collect();
+          padded.join(&self.separator).trim_end().to_string()
+        }
+      })
+      .collect()
+  }
+
+  /// 행들을 머리글이 있는 표로 만든다. 첫 행을 머리글로 보고 그 아래에 구분선을 넣는다.
+  /// boxed라면 표 전체를 상자 선(┌─┐)으로 두르고, 아니라면 머리글 아래에만 `-` 구분선을 넣는다.
+  /// 한 줄짜리 문자열(push_text)은 표에 넣지 않는다.
+  pub fn render_table(&self, boxed: bool) -> Vec<String> {
+    let widths = self.widths();
+    let rows: Vec<&Vec<String>> = self
+      .lines
+      .iter()
+      .filter_map(|line| match line {
+        Line::Row(cells) => Some(cells),
+        Line::Text(_) => None,
+      })
+      .collect();
+    // 셀 수가 모자란 행은 빈 셀로 채워 모든 행이 같은 열을 갖도록 한다.
+    let padded_row = |cells: &Vec<String>| -> Vec<String> {
+      (0..widths.len())
+        .map(|i| {
+          let cell = cells.get(i).map_or("", String::as_str);
+          pad(cell, widths[i], self.aligns.get(i).copied().unwrap_or(Align::Left))
+        })
+        .collect()
+    };
+
+    let mut lines = Vec::new();
+    if boxed {
+      let border = |left: &str, middle: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{left}{}{right}", segments.join(middle))
+      };
+      lines.push(border("┌", "┬", "┐"));
+      for (i, cells) in rows.iter().enumerate() {
+        lines.push(format!("│ {} │", padded_row(cells).join(" │ ")));
+        if i == 0 {
+          lines.push(border("├", "┼", "┤"));
+        }
+      }
+      lines.push(border("└", "┴", "┘"));
+    } else {
+      for (i, cells) in rows.iter().enumerate() {
+        lines.push(padded_row(cells).join(&self.separator).trim_end().to_string());
+        if i == 0 {
+          let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+          lines.push(rule.join(&self.separator));
+        }
+      }
+    }
+    lines
+  }
+}
+
+/// 문자열이 터미널에서 차지하는 폭. 한글 등 전각 문자는 두 칸으로 센다.
+pub fn display_width(text: &str) -> usize {
+  UnicodeWidthStr::width(text)
+}
+
+/// 문자열을 표시 폭 기준으로 width칸이 되도록 공백으로 채운다. 이미 width보다 넓다면 그대로 둔다.
+pub fn pad(text: &str, width: usize, align: Align) -> String {
+  let fill = " ".repeat(width.saturating_sub(display_width(text)));
+  match align {
+    Align::Left => format!("{text}{fill}"),
+    Align::Right => format!("{fill}{text}"),
+  }
+}
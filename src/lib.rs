@@ -0,0 +1,89 @@
+use clap::Parser;
+
+use crate::{
+  cli::ReplayOverrides,
+  processor::{PlanRequest, ProcessingResult, process_docks},
+  server::reject_cli_only_fields,
+};
+
+pub mod audit;
+pub mod barcode;
+pub mod batch;
+pub mod bench;
+pub mod calibrate;
+pub mod cli;
+pub mod clipboard;
+pub mod config;
+pub mod counts;
+pub mod crews;
+pub mod cron;
+#[cfg(unix)]
+pub mod daemon;
+pub mod dates;
+pub mod demo;
+pub mod diff;
+pub mod email;
+pub mod encoding;
+pub mod export;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod intervals;
+pub mod layout;
+pub mod lint;
+pub mod map;
+pub mod merge;
+pub mod metadata;
+pub mod models;
+pub mod mqtt;
+pub mod notify;
+pub mod openapi;
+pub mod pdf;
+pub mod placeholders;
+pub mod plugin;
+pub mod plan;
+pub mod post;
+pub mod preview;
+pub mod printer;
+pub mod printing;
+pub mod processor;
+pub mod queue;
+pub mod ratelimit;
+pub mod raster;
+pub mod reconcile;
+pub mod repl;
+pub mod rules;
+pub mod schedule;
+pub mod server;
+pub mod signing;
+pub mod simulate;
+pub mod slots;
+pub mod svg;
+pub mod template;
+pub mod tls;
+pub mod upload;
+pub mod verify;
+pub mod watch;
+pub mod websocket;
+pub mod wms;
+
+// plan_from_str에서 입력 하나가 다룰 수 있는 최대 도크 수. fuzzer가 입력마다 오래 머무르지 않도록 CLI 기본값보다 훨씬 작다.
+const FUZZ_MAX_RANGE_DOCKS: u64 = 100_000;
+
+/// CLI와 같은 형식의 인자 문자열(예: "-p 3 --min 51 --max 60 -e 55-56")로 plan을 계산하는 함수.
+/// cargo-fuzz 같은 fuzzer의 진입점으로 쓰기 위한 것으로, 어떤 입력에도 panic하지 않고 잘못된 입력은 에러로 반환한다.
+///
+/// HTTP API와 마찬가지로 파일을 읽거나 쓰고 외부로 보내는 인자는 허용하지 않으며,
+/// 범위 상한(`--max-range-docks`)은 FUZZ_MAX_RANGE_DOCKS로 고정된다.
+/// 계산된 plan이 규칙을 지키는지는 `verify::verify`로 함께 확인할 수 있다.
+pub fn plan_from_str(input: &str) -> Result<ProcessingResult, String> {
+  let mut args = ReplayOverrides::try_parse_from(input.split_whitespace()).map_err(|e| e.to_string())?.args;
+  reject_cli_only_fields(&args)?;
+  args.max_range_docks = Some(FUZZ_MAX_RANGE_DOCKS);
+  args.validate_input()?;
+  Ok(process_docks(PlanRequest::new(&args)))
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeSet;
+
+use crate::{
+  cli::Args,
+  models::{DockId, DockRange, Priority},
+  processor::ProcessingResult,
+};
+
+/// 여러 입력 목록(-f, -s, -e, --skip)에 함께 나타난 도크들이 최종적으로 어떻게 처리되었는지.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+  Priority(Priority),
+  Exception,
+  Skipped,
+}
+
+impl Resolution {
+  /// 보고서에 쓰이는 메세지 key.
+  pub fn label_key(self) -> &'static str {
+    match self {
+      Resolution::Priority(Priority::First) => "label.first",
+      Resolution::Priority(Priority::Second) => "label.second",
+      Resolution::Priority(Priority::Third) => "label.third",
+      Resolution::Exception => "label.exception",
+      Resolution::Skipped => "label.skip",
+    }
+  }
+}
+
+/// 둘 이상의 입력 범위에 함께 나타난 연속된 도크들.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap {
+  pub docks: DockRange,
+  // 도크들을 포함한 입력 범위들 (예: "-f 60-70", "-s 65-66")
+  pub sources: Vec<String>,
+  pub resolution: Resolution,
+}
+
+/// -f, -s, -e, --skip의 입력 범위 중 둘 이상에 나타난 도크들을 찾는 함수.
+/// 같은 입력 범위들에 속하고 처리 결과도 같은 연속된 도크들은 하나의 Overlap으로 묶인다.
+/// 제외 범위(!)는 도크를 빼는 입력이므로 겹침으로 보지 않는다.
+pub fn find_overlaps(args: &Args, result: &ProcessingResult) -> Vec<Overlap> {
+  // (입력 이름, 처리 범위로 잘라낸 범위)
+  let mut inputs: Vec<(&str, DockRange)> = Vec::new();
+  let priority_inputs = [("-f", &args.first_priority), ("-s", &args.second_priority)];
+  for (flag, ranges) in priority_inputs {
+    inputs.extend(ranges.iter().filter(|range| !range.excluded).map(|range| (flag, range.range)));
+  }
+  inputs.extend(args.exception_groups_raw.iter().flat_map(|group| &group.ranges).map(|&range| ("-e", range)));
+  inputs.extend(args.skip.iter().map(|&range| ("--skip", range)));
+  let inputs: Vec<(&str, DockRange)> = inputs
+    .into_iter()
+    .filter(|(_, range)| range.start <= args.max && range.end >= args.min)
+    .map(|(flag, range)| (flag, DockRange { start: range.start.max(args.min), end: range.end.min(args.max) }))
+    .collect();
+
+  // 입력 범위들의 경계로 나눈 구간마다 그 구간을 포함하는 입력들이 같다.
+  let mut bounds: BTreeSet<u64> = BTreeSet::new();
+  for (_, range) in &inputs {
+    bounds.insert(u64::from(range.start.get()));
+    bounds.insert(u64::from(range.end.get()) + 1);
+  }
+  let bounds: Vec<u64> = bounds.into_iter().collect();
+
+  let mut overlaps: Vec<Overlap> = Vec::new();
+  for window in bounds.windows(2) {
+    let range = DockRange { start: DockId(window[0] as u32), end: DockId((window[1] - 1) as u32) };
+    let covering: Vec<String> = inputs
+      .iter()
+      .filter(|(_, input)| input.start <= range.start && range.end <= input.end)
+      .map(|(flag, range)| format!("{flag} {range}"))
+      .collect();
+    if covering.len() < 2 {
+      continue;
+    }
+    // 구간 안에서도 제외 범위나 중복된 예외 도크 처리에 따라 결과가 달라질 수 있으므로 도크마다 확인한다.
+    for dock in range.docks() {
+      let resolution = resolve(args, result, dock);
+      match overlaps.last_mut() {
+        Some(last)
+          if last.sources == covering && last.resolution == resolution && last.docks.end.checked_add(1) == Some(dock) =>
+        {
+          last.docks.end = dock;
+        }
+        _ => overlaps.push(Overlap { docks: DockRange::single(dock), sources: covering.clone(), resolution }),
+      }
+    }
+  }
+  overlaps
+}
+
+// 도크가 최종적으로 어떻게 처리되었는지.
+fn resolve(args: &Args, result: &ProcessingResult, dock: DockId) -> Resolution {
+  if args.skip.iter().any(|range| range.contains(dock)) {
+    Resolution::Skipped
+  } else if result.all_exception_docks.contains(&dock) {
+    Resolution::Exception
+  } else {
+    Resolution::Priority(result.priorities.get(&dock).copied().unwrap_or(Priority::Third))
+  }
+}
@@ -1,24 +1,576 @@
-use clap::Parser;
+use std::path::PathBuf;
 
-use crate::{cli::Args, printer::print_results, processor::process_docks};
+use clap::{CommandFactory, FromArgMatches, Parser, parser::ValueSource};
 
-mod models;
-mod cli;
-mod processor;
-mod printer;
+#[cfg(unix)]
+use dock_sorter::daemon;
+#[cfg(feature = "gui")]
+use dock_sorter::gui;
+use dock_sorter::{
+  batch::load_jobs,
+  bench,
+  calibrate,
+  cli::{Args, Cli, Command, HistoryAction, QueueAction, ReplayOverrides, ReplaySource, ServeOptions},
+  clipboard,
+  config::load_config,
+  counts::apply_auto_priority,
+  dates::{expand_dates, load_date_manifest},
+  demo,
+  diff::diff_plans,
+  email::{plan_attachments, send_plan_email},
+  export,
+  history::{list_runs, load_run, record_run},
+  hooks::{run_on_complete, run_per_group},
+  i18n::{self, tr},
+  lint::find_overlaps,
+  models::{PriorityRange, RunStamp},
+  mqtt,
+  notify::{ChatService, chat_message, plan_title, post_chat_webhook, post_plan_webhook},
+  openapi::openapi_document,
+  plan::{load_plan, plan_schema, plan_to_json, save_plan, verify_plan_checksum},
+  plugin::WasmPlugin,
+  printer::{StreamingPrinter, format_results, format_stats, print_formatted_results, print_history_list, print_overlap_report, print_plan_diff, print_queue_list, print_reconcile_report, print_reprint_summary, print_results},
+  printing::{Pacing, print_labels, send_jobs},
+  processor::{PlanRequest, ProcessingResult, process_docks, process_docks_with},
+  queue::{JobStatus, PrintQueue},
+  reconcile::{load_scans, reconcile},
+  repl,
+  rules::{GroupingRules, RhaiRules},
+  schedule::load_arrivals,
+  server::serve,
+  simulate,
+  signing::{read_sign_key, sign_plan_json, verify_plan_signature},
+  upload::{parse_upload_target, upload},
+  verify::verify,
+  watch,
+  wms::{fetch_closed_docks, fetch_wms_priorities},
+};
 
 fn main() {
-  let args_raw = Args::parse();
-  
-  // 입력 유효성 검사
-  if let Err(e) = args_raw.validate_input() {
-    eprintln!("Error: {e}");
+  let cli = Cli::parse();
+  if let Some(lang) = cli.lang {
+    i18n::set_lang(lang);
+  }
+
+  let result = match (cli.command, cli.args) {
+    // 저장된 plan 파일을 다시 출력한다.
+    (Some(Command::Load { path }), _) => run_load(&path),
+    // plan 파일의 checksum을 다시 계산하여 비교한다.
+    (Some(Command::VerifyPlan { path, sign_key }), _) => run_verify_plan(&path, sign_key.as_deref()),
+    // 두 plan 파일을 비교한다.
+    (Some(Command::Diff { old, new }), _) => run_diff(&old, &new),
+    // plan의 그룹 순서와 실제 스캔 순서를 비교한다.
+    (Some(Command::Reconcile { plan, scans }), _) => run_reconcile(&plan, &scans),
+    // 저장된 plan의 라벨을 파일로 내보낸다.
+    (Some(Command::Export { target }), _) => export::run_export(&target),
+    // 라벨 프린터의 인쇄 위치를 맞추기 위한 보정 라벨을 인쇄한다.
+    (Some(Command::Calibrate(options)), _) => calibrate::run_calibrate(&options),
+    // 실행 기록을 조회한다.
+    (Some(Command::History { action, db }), _) => run_history(&action, &db),
+    // --serial로 보낸 인쇄 작업을 조회하거나 취소하거나 다시 보낸다.
+    (Some(Command::Queue { action, file }), _) => run_queue(&action, &file),
+    // 저장된 실행을 같은 인자로 다시 계산한다.
+    (Some(Command::Replay { source, db, overrides }), _) => run_replay(source, db, &overrides),
+    // 대화형으로 인자를 바꿔가며 plan을 확인한다.
+    (Some(Command::Repl { initial }), _) => repl::run_repl(&initial),
+    // 도크 정렬 GUI 창을 띄운다.
+    #[cfg(feature = "gui")]
+    (Some(Command::Gui), _) => gui::run_gui(),
+    // plan 형식의 JSON Schema를 출력한다.
+    (Some(Command::Schema), _) => run_schema(),
+    // 합성 입력으로 plan 계산 성능을 측정한다.
+    (Some(Command::Bench(options)), _) => bench::run_bench(&options),
+    // 재현 가능한 무작위 plan 인자를 만든다.
+    (Some(Command::Gen(options)), _) => demo::run_gen(&options),
+    // 무작위 시나리오들을 계산하여 그룹 통계를 출력한다.
+    (Some(Command::Simulate(options)), _) => simulate::run_simulate(&options),
+    // HTTP API 서버를 실행한다.
+    (Some(Command::Serve(options)), _) => run_serve(&options),
+    // 작업 목록 파일의 작업들을 차례로 계산한다.
+    (Some(Command::Batch { path }), _) => run_batch(&path),
+    // Unix 소켓으로 요청을 받는 데몬을 실행한다.
+    #[cfg(unix)]
+    (Some(Command::Daemon(options)), _) => run_daemon(&options),
+    // 서브커맨드가 없다면 기본 도크 정렬을 수행한다.
+    (None, Some(args_raw)) => run_plan(args_raw),
+    // clap이 per_page를 필수로 요구하므로 이 경우는 발생하지 않는다.
+    (None, None) => Err(tr("error.no_arguments", &[])),
+  };
+
+  if let Err(e) = result {
+    eprintln!("{}", tr("error.prefix", &[("message", &e)]));
     std::process::exit(1);
   }
+}
+
+/// 입력 인자로부터 plan을 계산하고 출력하는 기본 실행 흐름.
+/// --watch가 지정되었다면 파일이 바뀔 때마다 다시 계산한다.
+fn run_plan(args_raw: Args) -> Result<(), String> {
+  match args_raw.watch.clone() {
+    Some(path) => watch::watch_file(&path, || plan_once(args_raw.clone())),
+    None => plan_once(args_raw),
+  }
+}
+
+/// 입력 인자로부터 plan을 한 번 계산하고 출력, 저장, 전송까지 수행하는 함수.
+fn plan_once(mut args_raw: Args) -> Result<(), String> {
+  // 설정 파일에 --site의 도크 번호들이 정의되어 있다면 입력을 그 번호들로 확인하고, --tier의 묶음들을 -f, -s에 더한다.
+  if let Some(path) = &args_raw.config {
+    let config = load_config(path)?;
+    config.apply_site(&mut args_raw);
+    config.apply_tiers(&mut args_raw)?;
+  }
+  // 입력 유효성 검사
+  args_raw.validate_input()?;
+
+  // --dates가 지정되었다면 날짜마다 --date를 지정한 것처럼 plan을 따로 계산한다. 각 plan 사이에는 빈 줄을 둔다.
+  if let Some(span) = args_raw.dates.take() {
+    for (i, date) in expand_dates(&span)?.into_iter().enumerate() {
+      if i > 0 {
+        println!();
+      }
+      let mut day_args = args_raw.clone();
+      day_args.date = Some(date);
+      plan_once(day_args)?;
+    }
+    return Ok(());
+  }
+
+  // 날짜별 입력 변경 파일에 이 날짜의 항목이 있다면 목록들을 바꾼다.
+  if let (Some(path), Some(date)) = (&args_raw.date_manifest, &args_raw.date)
+    && let Some(entry) = load_date_manifest(path)?.get(date)
+  {
+    entry.apply(&mut args_raw);
+    args_raw.validate_input()?;
+  }
+
+  // 서명 key는 계산하기 전에 읽어 key가 없다면 바로 실패하도록 한다.
+  let sign_key = match &args_raw.sign_key {
+    Some(source) => Some(read_sign_key(source)?),
+    None => None,
+  };
+
+  // 출고 건수 파일로부터 1차, 2차 도크 자동 지정. 자동 지정된 도크들도 현장의 도크인지 다시 확인한다.
+  apply_auto_priority(&mut args_raw)?;
+  if args_raw.auto_priority.is_some() {
+    args_raw.validate_input()?;
+  }
+
+  // 사용 중지된 도크 목록을 받아 --skip에 추가한다. 범위 상한을 넘지 않는지 다시 확인한다.
+  if let Some(url) = args_raw.closed_docks_url.clone() {
+    let closed = fetch_closed_docks(&url)?;
+    eprintln!("{}", tr("status.closed_docks", &[("count", &closed.len()), ("url", &url)]));
+    args_raw.skip.extend(closed);
+    args_raw.validate_input()?;
+  }
+
+  // WMS에서 오늘의 바쁜 도크들을 받아 1차, 2차 도크에 추가한다.
+  if args_raw.wms_url.is_some() {
+    let hot = fetch_wms_priorities(&args_raw)?;
+    eprintln!("{}", tr("status.wms", &[("first", &hot.first.len()), ("second", &hot.second.len())]));
+    args_raw.first_priority.extend(hot.first.into_iter().map(PriorityRange::from));
+    args_raw.second_priority.extend(hot.second.into_iter().map(PriorityRange::from));
+    args_raw.validate_input()?;
+  }
+
+  // 트럭 도착 스케줄 로딩
+  let arrivals = match &args_raw.arrivals {
+    Some(path) => Some(load_arrivals(path)?),
+    None => None,
+  };
+
+  // 이전 plan 로딩. --stable이라면 이전 그룹들을 processor에 넘겨 그룹 경계를 유지하도록 한다.
+  let previous_plan = match &args_raw.previous {
+    Some(path) => Some(load_plan(path)?),
+    None => None,
+  };
+  let previous_groups = match &previous_plan {
+    Some(plan) if args_raw.stable => plan.result.result_groups.as_slice(),
+    _ => &[],
+  };
+
+  // 현장별 그룹핑 규칙 스크립트 로딩
+  let rules = match &args_raw.rules {
+    Some(path) => Some(RhaiRules::load(path)?),
+    None => None,
+  };
+
+  // WASM 플러그인 로딩. 그룹핑 규칙은 --rules 스크립트와 플러그인 중 하나만 사용할 수 있다.
+  let plugin = match &args_raw.plugin {
+    Some(path) => Some(WasmPlugin::load(path)?),
+    None => None,
+  };
+  let plugin_rules = plugin.as_ref().filter(|plugin| plugin.has_rules());
+  let grouping_rules: Option<&dyn GroupingRules> = match (&rules, plugin_rules) {
+    (Some(_), Some(_)) => return Err(tr("error.rules_with_plugin", &[])),
+    (Some(rules), None) => Some(rules),
+    (None, Some(plugin)) => Some(plugin),
+    (None, None) => None,
+  };
 
   // dock sorting 및 로직 processing
-  let processing_result = process_docks(&args_raw);
+  let request = PlanRequest { args: &args_raw, arrivals: arrivals.as_ref(), previous_groups, rules: grouping_rules };
+  // 그룹들을 텍스트 출력(또는 요약) 외에 쓰지 않는다면 그룹을 모으지 않고 계산되는 즉시 출력한다.
+  // 매우 큰 범위를 출력하더라도 메모리 사용량이 그룹 수에 비례하지 않는다.
+  if grouping_rules.is_none() && plugin.is_none() && previous_plan.is_none() && streams_output(&args_raw) {
+    let mut printer = StreamingPrinter::new(&args_raw);
+    let result = process_docks_with(request, |result, group| printer.push(result, group));
+    printer.finish(&result);
+    return Ok(());
+  }
+  let mut processing_result = process_docks(request);
+  // 규칙이 실행 중 실패했다면 규칙이 일부 적용되지 않은 plan이므로 출력하지 않는다.
+  let rules_error = rules.as_ref().and_then(RhaiRules::error).or_else(|| plugin_rules.and_then(WasmPlugin::error));
+  if let Some(error) = rules_error {
+    return Err(error);
+  }
+
+  // --stamp가 지정되었다면 출력과 내보내기에 함께 쓰일 실행 ID와 시각을 붙인다.
+  if args_raw.stamp {
+    processing_result.stamp = Some(RunStamp::now());
+  }
+
+  // --self-check가 지정되었다면 출력하기 전에 plan이 규칙을 지키는지 확인한다.
+  if args_raw.self_check {
+    verify(&processing_result, &request)?;
+  }
+
+  // 내보내는 plan JSON. --sign-key가 지정되었다면 서명을 붙인다.
+  let export_json = || -> Result<String, String> {
+    let json = plan_to_json(&args_raw, &processing_result)?;
+    match &sign_key {
+      Some(key) => sign_plan_json(&json, key),
+      None => Ok(json),
+    }
+  };
 
   // print final results
-  print_results(&args_raw, &processing_result);
+  // 플러그인이 출력 형식을 제공한다면 plan JSON을 넘겨 받은 내용을 대신 출력한다.
+  let plugin_output = match plugin.as_ref().filter(|plugin| plugin.has_formatter()) {
+    Some(plugin) => Some(plugin.format(&plan_to_json(&args_raw, &processing_result)?)?),
+    None => None,
+  };
+  match &plugin_output {
+    Some(text) => print_formatted_results(&processing_result, text, args_raw.encoding),
+    None => print_results(&args_raw, &processing_result),
+  }
+
+  // --report-overlaps가 지정되었다면 여러 목록에 중복 입력된 도크들을 보고한다.
+  if args_raw.report_overlaps {
+    print_overlap_report(&find_overlaps(&args_raw, &processing_result));
+  }
+
+  // 이전 plan이 있다면 다시 출력해야 하는 그룹 수를 출력한다.
+  if let Some(plan) = &previous_plan {
+    print_reprint_summary(&diff_plans(&plan.result, &processing_result), &processing_result);
+  }
+
+  // --expect가 지정되었다면 기대하는 plan과 비교하여 다르다면 내보내기 전에 에러로 종료한다.
+  if let Some(path) = &args_raw.expect {
+    check_expected_plan(path, &processing_result)?;
+  }
+
+  // --clipboard가 지정되었다면 출력한 plan을 클립보드에도 복사한다.
+  if args_raw.clipboard {
+    let text = plugin_output.clone().unwrap_or_else(|| format_results(&args_raw, &processing_result));
+    clipboard::copy_text(&text)?;
+    eprintln!("{}", tr("status.clipboard", &[]));
+  }
+
+  // --exec-per-group이 지정되었다면 그룹마다 사용자 명령을 실행한다.
+  if let Some(template) = &args_raw.exec_per_group {
+    run_per_group(template, &args_raw, &processing_result)?;
+  }
+
+  // --serial이 지정되었다면 라벨들을 프린터로 보낸다.
+  if let Some(device) = &args_raw.serial {
+    let labels = print_labels(&args_raw, &processing_result)?;
+    eprintln!("{}", tr("status.printed", &[("labels", &labels), ("device", device)]));
+  }
+
+  // --save-plan이 지정되었다면 plan을 파일로 저장한다. 파일 이름의 {date}는 작업 날짜로 바꾼다.
+  let save_path = args_raw.save_plan.as_ref().map(|path| match &args_raw.date {
+    Some(date) => PathBuf::from(path.to_string_lossy().replace("{date}", date)),
+    None => path.clone(),
+  });
+  if let Some(path) = &save_path {
+    save_plan(path, &export_json()?)?;
+    eprintln!("{}", tr("status.saved", &[("path", &path.display())]));
+  }
+
+  // --history가 지정되었다면 실행 기록을 데이터베이스에 남긴다.
+  if let Some(db) = &args_raw.history {
+    let id = record_run(db, &args_raw, &processing_result)?;
+    eprintln!("{}", tr("status.history", &[("id", &id)]));
+  }
+
+  // --notify-url이 지정되었다면 plan JSON을 webhook으로 보낸다.
+  if let Some(url) = &args_raw.notify_url {
+    post_plan_webhook(url, &export_json()?)?;
+    eprintln!("{}", tr("status.webhook", &[("url", url)]));
+  }
+
+  // --slack-webhook, --teams-webhook이 지정되었다면 plan 요약과 출력 순서를 채팅 메세지로 보낸다.
+  let chats = [(ChatService::Slack, &args_raw.slack_webhook), (ChatService::Teams, &args_raw.teams_webhook)];
+  for (service, url) in chats.into_iter().filter_map(|(service, url)| Some((service, url.as_ref()?))) {
+    let message = chat_message(service, &plan_title(&args_raw), &format_stats(&args_raw, &processing_result), &processing_result);
+    post_chat_webhook(service, url, &message)?;
+    eprintln!("{}", tr("status.chat", &[("service", &service.name())]));
+  }
+
+  // --upload가 지정되었다면 plan JSON을 공용 저장소에 올린다. 이름은 --save-plan의 파일 이름, 없다면 plan.json이다.
+  if let Some(destination) = &args_raw.upload {
+    let name = save_path
+      .as_ref()
+      .and_then(|path| path.file_name())
+      .map_or_else(|| "plan.json".to_string(), |name| name.to_string_lossy().into_owned());
+    let location = upload(&parse_upload_target(destination)?, &name, export_json()?.as_bytes(), "application/json")?;
+    eprintln!("{}", tr("status.uploaded", &[("location", &location)]));
+  }
+
+  // --email이 지정되었다면 출력한 plan을 본문으로, --email-attach의 파일들을 첨부하여 메일로 보낸다.
+  if !args_raw.email.is_empty() {
+    let body = plugin_output.unwrap_or_else(|| format_results(&args_raw, &processing_result));
+    let attachments = plan_attachments(&args_raw, &processing_result, &export_json()?)?;
+    send_plan_email(&args_raw, &body, &attachments)?;
+    eprintln!("{}", tr("status.emailed", &[("to", &args_raw.email.join(", "))]));
+  }
+
+  // --mqtt가 지정되었다면 plan JSON을 MQTT topic으로 발행한다.
+  if let Some(broker) = &args_raw.mqtt {
+    let json = export_json()?;
+    mqtt::publish(broker, &args_raw.mqtt_topic, json.as_bytes(), args_raw.mqtt_retain)?;
+    eprintln!("{}", tr("status.mqtt", &[("topic", &args_raw.mqtt_topic), ("broker", broker)]));
+  }
+
+  // --on-complete가 지정되었다면 마지막으로 plan JSON을 표준 입력으로 넘겨 사용자 명령을 실행한다.
+  if let Some(command) = &args_raw.on_complete {
+    run_on_complete(command, &export_json()?)?;
+  }
+  Ok(())
+}
+
+/// 계산된 그룹들을 텍스트 출력(또는 --stats-only 요약)에만 쓰는 실행인지 여부.
+/// 그렇다면 그룹들을 모으지 않고 계산되는 즉시 출력할 수 있다.
+fn streams_output(args: &Args) -> bool {
+  StreamingPrinter::supports(args)
+    // 작업 시간대는 전체 그룹 수를 알아야 나눌 수 있고, 실행 ID는 계산이 끝난 뒤 붙인다.
+    && args.slots.is_empty()
+    && !args.stamp
+    // 출력 뒤에 그룹들을 다시 쓰는 확인, 내보내기, 전송들
+    && !args.self_check
+    && !args.report_overlaps
+    && args.expect.is_none()
+    && !args.clipboard
+    && args.exec_per_group.is_none()
+    && args.serial.is_none()
+    && args.save_plan.is_none()
+    && args.history.is_none()
+    && args.notify_url.is_none()
+    && args.slack_webhook.is_none()
+    && args.teams_webhook.is_none()
+    && args.upload.is_none()
+    && args.email.is_empty()
+    && args.mqtt.is_none()
+    && args.on_complete.is_none()
+}
+
+/// 계산된 plan을 기대하는 plan 파일과 비교하는 함수.
+/// 그룹 구성, 우선순위, 그룹의 출력 순서 중 하나라도 다르다면 차이를 출력하고 에러를 반환한다.
+fn check_expected_plan(path: &std::path::Path, result: &ProcessingResult) -> Result<(), String> {
+  let expected = load_plan(path)?;
+  let diff = diff_plans(&expected.result, result);
+  let same_order = expected.result.result_groups.iter().map(|g| &g.docks).eq(result.result_groups.iter().map(|g| &g.docks));
+  if diff.is_empty() && same_order {
+    eprintln!("{}", tr("status.expect_match", &[("path", &path.display())]));
+    return Ok(());
+  }
+  print_plan_diff(&diff);
+  if diff.is_empty() {
+    println!("{}", tr("diff.order_changed", &[]));
+  }
+  Err(tr("error.expect_mismatch", &[("path", &path.display())]))
+}
+
+/// 저장된 plan 파일을 읽어 다시 계산하지 않고 그대로 출력하는 함수.
+fn run_load(path: &std::path::Path) -> Result<(), String> {
+  let plan = load_plan(path)?;
+  print_results(&plan.args, &plan.result);
+  Ok(())
+}
+
+/// plan 파일의 checksum이 그룹 구성과 일치하는지 확인하는 함수.
+/// 서명 key가 지정되었다면 먼저 파일 전체의 서명을 확인한다.
+fn run_verify_plan(path: &std::path::Path, sign_key: Option<&str>) -> Result<(), String> {
+  if let Some(source) = sign_key {
+    let key = read_sign_key(source)?;
+    let content = std::fs::read_to_string(path)
+      .map_err(|e| tr("error.read_plan_file", &[("path", &path.display()), ("error", &e)]))?;
+    verify_plan_signature(&content, &key).map_err(|e| tr("error.plan_file", &[("path", &path.display()), ("error", &e)]))?;
+    println!("{}", tr("status.signature_ok", &[("path", &path.display())]));
+  }
+  let plan = load_plan(path)?;
+  let checksum = verify_plan_checksum(&plan).map_err(|e| tr("error.plan_file", &[("path", &path.display()), ("error", &e)]))?;
+  println!("{}", tr("status.checksum_ok", &[("path", &path.display()), ("checksum", &checksum)]));
+  Ok(())
+}
+
+/// 두 plan 파일을 읽어 차이점을 출력하는 함수.
+fn run_diff(old_path: &std::path::Path, new_path: &std::path::Path) -> Result<(), String> {
+  let old_plan = load_plan(old_path)?;
+  let new_plan = load_plan(new_path)?;
+  print_plan_diff(&diff_plans(&old_plan.result, &new_plan.result));
+  Ok(())
+}
+
+/// plan 파일의 그룹 순서와 스캔 파일의 실제 처리 순서를 비교하여 출력하는 함수.
+fn run_reconcile(plan_path: &std::path::Path, scans_path: &std::path::Path) -> Result<(), String> {
+  let plan = load_plan(plan_path)?;
+  let scans = load_scans(scans_path)?;
+  let planned = plan.result.result_groups.iter().map(|group| group.docks.len()).sum();
+  print_reconcile_report(&reconcile(&plan.result, &scans), planned);
+  Ok(())
+}
+
+/// `history list/show` 서브커맨드를 실행하는 함수.
+fn run_history(action: &HistoryAction, db: &std::path::Path) -> Result<(), String> {
+  match action {
+    HistoryAction::List => print_history_list(&list_runs(db)?),
+    HistoryAction::Show { id } => {
+      let plan = load_run(db, *id)?;
+      print_results(&plan.args, &plan.result);
+    }
+  }
+  Ok(())
+}
+
+/// `queue list/cancel/retry` 서브커맨드를 실행하는 함수.
+fn run_queue(action: &QueueAction, file: &std::path::Path) -> Result<(), String> {
+  let mut queue = PrintQueue::load(file)?;
+  match action {
+    QueueAction::List => print_queue_list(&queue.jobs),
+    QueueAction::Cancel { ids, all } => {
+      let ids = if *all { queue.unfinished_ids() } else { ids.clone() };
+      for &id in &ids {
+        let job = queue.job_mut(id)?;
+        if !job.status.is_unfinished() {
+          return Err(tr("error.job_finished", &[("id", &id), ("status", &job.status.name())]));
+        }
+        job.status = JobStatus::Cancelled;
+      }
+      queue.save(file)?;
+      eprintln!("{}", tr("status.queue_cancelled", &[("jobs", &ids.len())]));
+    }
+    QueueAction::Retry { ids, device, confirm_each_group, delay_ms } => {
+      let ids = queue.retry_ids(ids)?;
+      if ids.is_empty() {
+        eprintln!("{}", tr("queue.nothing", &[]));
+        return Ok(());
+      }
+      // 다른 프린터로 보낸다면 작업의 프린터도 바꿔 기록하여 이후의 retry도 그 프린터로 보내게 한다.
+      if let Some(device) = device {
+        for &id in &ids {
+          queue.job_mut(id)?.printer.device = device.clone();
+        }
+      }
+      let pacing = Pacing { confirm_each_group: *confirm_each_group, delay_ms: *delay_ms };
+      let labels = send_jobs(&mut queue, file, &ids, &pacing)?;
+      eprintln!("{}", tr("status.queue_retried", &[("labels", &labels)]));
+    }
+  }
+  Ok(())
+}
+
+/// 실행 기록 또는 plan 파일에 저장된 인자로 plan을 다시 계산하는 함수.
+/// overrides에 입력된 플래그만 저장된 인자를 덮어쓴다.
+fn run_replay(source: ReplaySource, db: PathBuf, overrides: &[String]) -> Result<(), String> {
+  let plan = match &source {
+    ReplaySource::History(id) => {
+      eprintln!("{}", tr("status.replay_run", &[("id", &id), ("db", &db.display())]));
+      load_run(&db, *id)?
+    }
+    ReplaySource::PlanFile(path) => {
+      eprintln!("{}", tr("status.replay_plan", &[("path", &path.display())]));
+      load_plan(path)?
+    }
+  };
+
+  // 저장된 plan을 덮어쓰거나 기록을 중복으로 남기지 않도록 출력 관련 인자는 비운다.
+  let mut stored = plan.args;
+  stored.save_plan = None;
+  stored.history = None;
+  let stored_json = serde_json::to_value(&stored).map_err(|e| e.to_string())?;
+
+  // 덮어쓰기 플래그들을 파싱하여 저장된 인자에 적용한다.
+  // update_from_arg_matches는 기본값이 있는 인자(--min 등)까지 덮어쓰므로,
+  // 명령줄에 직접 입력되지 않은 인자들은 다시 저장된 값으로 되돌린다.
+  let matches = ReplayOverrides::command_for_update()
+    .try_get_matches_from(overrides)
+    .map_err(|e| e.to_string())?;
+  let mut replay = ReplayOverrides { args: stored };
+  replay.update_from_arg_matches(&matches).map_err(|e| e.to_string())?;
+  let mut merged = serde_json::to_value(&replay.args).map_err(|e| e.to_string())?;
+  if let (Some(stored_fields), Some(merged_fields)) = (stored_json.as_object(), merged.as_object_mut()) {
+    for (field, value) in stored_fields {
+      if matches.value_source(field) != Some(ValueSource::CommandLine) {
+        merged_fields.insert(field.clone(), value.clone());
+      }
+    }
+  }
+  let args: Args = serde_json::from_value(merged).map_err(|e| e.to_string())?;
+
+  run_plan(args)
+}
+
+/// plan 형식의 JSON Schema를 출력하는 함수.
+fn run_schema() -> Result<(), String> {
+  let schema = serde_json::to_string_pretty(&plan_schema()).map_err(|e| e.to_string())?;
+  println!("{schema}");
+  Ok(())
+}
+
+/// HTTP API 서버를 실행하는 함수. --openapi가 지정되었다면 OpenAPI 문서만 출력하고 종료한다.
+fn run_serve(options: &ServeOptions) -> Result<(), String> {
+  if options.openapi {
+    let doc = serde_json::to_string_pretty(&openapi_document()).map_err(|e| e.to_string())?;
+    println!("{doc}");
+    return Ok(());
+  }
+  serve(options)
+}
+
+/// 작업 목록 파일의 작업들을 하나씩 계산하고 출력하는 함수.
+/// 실패한 작업이 있더라도 나머지 작업은 계속 처리하고, 마지막에 실패한 작업 수를 에러로 반환한다.
+fn run_batch(path: &std::path::Path) -> Result<(), String> {
+  let jobs = load_jobs(path)?;
+  let total = jobs.len();
+  let mut failed = 0;
+  for (i, job) in jobs.into_iter().enumerate() {
+    let name = job.name.clone().unwrap_or_else(|| tr("batch.unnamed", &[]));
+    println!("\n{}", tr("batch.job", &[("index", &(i + 1)), ("total", &total), ("name", &name)]));
+    if let Err(e) = run_plan(job.args) {
+      eprintln!("{}", tr("error.prefix", &[("message", &e)]));
+      failed += 1;
+    }
+  }
+  if failed > 0 {
+    return Err(tr("error.batch_failed", &[("failed", &failed), ("total", &total)]));
+  }
+  Ok(())
+}
+
+/// Unix 소켓 데몬을 실행하는 함수. --schedule이 지정되었다면 설정 프로필의 plan을 정해진 시각마다 실행한다.
+#[cfg(unix)]
+fn run_daemon(options: &dock_sorter::cli::DaemonOptions) -> Result<(), String> {
+  let scheduled = match (&options.schedule, &options.profile, &options.config) {
+    (Some(schedule), Some(profile), Some(config_path)) => {
+      // 시작할 때(그리고 설정 파일을 다시 읽을 때) 프로필이 올바른지 미리 확인하여, 새벽에 실행될 때가 되어서야 실패하지 않도록 한다.
+      let name = profile.clone();
+      let config = dock_sorter::config::ReloadableConfig::load(Some(config_path), move |config| config.profile(&name)?.validate_input())?;
+      eprintln!("{}", tr("status.scheduled", &[("profile", &profile), ("schedule", &schedule.expr())]));
+      Some(daemon::ScheduledRun { schedule: schedule.clone(), config, profile: profile.clone() })
+    }
+    _ => None,
+  };
+  daemon::run_daemon(&options.socket, scheduled, plan_once)
 }
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::{
+  models::{DockId, GroupKind, Priority},
+  plan::PlanFile,
+};
+
+// 배치도에 그릴 수 있는 최대 도크 수. 눈으로 확인하는 용도이므로 그 이상은 읽을 수 없다.
+const MAX_MAP_DOCKS: u64 = 10_000;
+
+/// 배치도에서 도크 한 칸의 표시 종류.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapShade {
+  First,
+  Second,
+  Third,
+  // 예외 그룹의 도크
+  Exception,
+  // --skip으로 제외된 도크
+  Skipped,
+  // 범위 안에 있지만 어느 그룹에도 속하지 않은 도크
+  Unplanned,
+}
+
+impl MapShade {
+  /// 텍스트 배치도에서 사용하는 문자.
+  pub fn symbol(self) -> char {
+    match self {
+      MapShade::First => '#',
+      MapShade::Second => '+',
+      MapShade::Third => '.',
+      MapShade::Exception => 'E',
+      MapShade::Skipped => 'x',
+      MapShade::Unplanned => ' ',
+    }
+  }
+}
+
+/// 배치도의 도크 한 칸.
+pub struct MapCell {
+  pub dock: DockId,
+  // 출력 순서상 그룹 번호 (1부터). 그룹에 속하지 않은 도크는 None이다.
+  pub group: Option<usize>,
+  pub shade: MapShade,
+}
+
+/// plan의 처리 범위(min..max)를 도크 순서대로 배치도 칸으로 만드는 함수.
+pub fn map_cells(plan: &PlanFile) -> Result<Vec<MapCell>, String> {
+  let (min, max) = (plan.args.min, plan.args.max);
+  if plan.args.processing_range().dock_count() > MAX_MAP_DOCKS {
+    return Err(format!("Dock range {min}-{max} is too large for a map (at most {MAX_MAP_DOCKS} docks)."));
+  }
+  // 어떤 그룹에도 들어가지 않는 도크 구간들 (--skip과 현장에 없는 도크)
+  let skipped = plan.args.skipped_ranges();
+  // 도크 -> (출력 순서상 그룹 번호, 그룹 종류)
+  let group_of: HashMap<DockId, (usize, GroupKind)> = plan
+    .result
+    .result_groups
+    .iter()
+    .enumerate()
+    .flat_map(|(i, group)| group.docks.iter().map(move |&dock| (dock, (i + 1, group.kind))))
+    .collect();
+
+  Ok(
+    plan
+      .args
+      .processing_range()
+      .docks()
+      .map(|dock| {
+        let (group, shade) = match group_of.get(&dock) {
+          Some(&(group, GroupKind::Exception)) => (Some(group), MapShade::Exception),
+          Some(&(group, GroupKind::Regular)) => {
+            let shade = match plan.result.priorities.get(&dock) {
+              Some(Priority::First) => MapShade::First,
+              Some(Priority::Second) => MapShade::Second,
+              _ => MapShade::Third,
+            };
+            (Some(group), shade)
+          }
+          None if skipped.iter().any(|range| range.contains(dock)) => (None, MapShade::Skipped),
+          None => (None, MapShade::Unplanned),
+        };
+        MapCell { dock, group, shade }
+      })
+      .collect(),
+  )
+}
+
+/// 배치도를 텍스트로 그리는 함수. 줄마다 width 문자 이내의 도크들을 세 줄(그룹 번호, 도크 번호, 표시 문자)로 그리며,
+/// 그룹 경계는 `|`로 나눈다. 마지막에 표시 문자의 범례를 붙인다.
+pub fn map_text(cells: &[MapCell], width: usize) -> String {
+  let cell_width = cells.iter().map(|cell| cell.dock.to_string().len()).max().unwrap_or(1);
+  // 칸 하나는 cell_width 문자와 뒤따르는 구분 문자 하나이다.
+  let per_line = ((width + 1) / (cell_width + 1)).max(1);
+
+  let mut lines: Vec<String> = Vec::new();
+  let mut previous_group: Option<Option<usize>> = None;
+  for chunk in cells.chunks(per_line) {
+    let mut groups = String::new();
+    let mut docks = String::new();
+    let mut shades = String::new();
+    for (i, cell) in chunk.iter().enumerate() {
+      // 그룹이 바뀌는 칸 앞에는 경계를, 그 외에는 공백을 둔다. 줄의 첫 칸 앞에는 두지 않는다.
+      if i > 0 {
+        let separator = if previous_group == Some(cell.group) { ' ' } else { '|' };
+        docks.push(separator);
+        shades.push(separator);
+        groups.push(' ');
+      }
+      // 그룹 번호는 그룹이 시작되는 칸(또는 줄의 첫 칸)에 쓰며, 이전 그룹 번호와 겹치는 부분은 잘린다.
+      if let Some(group) = cell.group
+        && (i == 0 || previous_group != Some(cell.group))
+      {
+        let label = format!("G{group}");
+        groups.truncate(docks.len());
+        groups.push_str(&label);
+      }
+      docks.push_str(&format!("{:>cell_width$}", cell.dock));
+      shades.push_str(&cell.shade.symbol().to_string().repeat(cell_width));
+      if groups.len() < docks.len() {
+        groups.push_str(&" ".repeat(docks.len() - groups.len()));
+      }
+      previous_group = Some(cell.group);
+    }
+    if !lines.is_empty() {
+      lines.push(String::new());
+    }
+    lines.extend([groups.trim_end().to_string(), docks, shades.trim_end().to_string()]);
+  }
+  lines.push(String::new());
+  lines.push("# first  + second  . general  E exception  x skipped  | group boundary".to_string());
+  lines.join("\n") + "\n"
+}
@@ -0,0 +1,82 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::{
+  cli::Args,
+  models::{Group, GroupKind, Priority},
+  post::{GroupTransform, per_page},
+};
+
+/// --post merge-singles(또는 --merge-singles)로 도크 하나짜리 일반 그룹들을 같은 우선순위끼리 다시 per-page 크기의 그룹으로 합치는 함수 객체.
+///
+/// strict mode에서는 1차 도크 하나짜리 그룹들 사이에 3차 그룹들이 끼므로, 출력 순서상 사이에 다른 그룹이 있더라도
+/// 같은 우선순위의 다음 한 도크 그룹을 합친다. 합친 그룹은 첫 도크의 자리에 출력되며, 그 뒤의 그룹들은 합치던 그룹이
+/// per-page만큼 차거나 더 합칠 수 없게 될 때까지 기다렸다가 넘어간다.
+/// 합친 그룹은 첫 도크의 per-page를 넘지 않고 --pp-range 구간 경계를 넘어 합치지 않으며, --solo 도크는 혼자 남긴다.
+pub struct SingleMerger<'a> {
+  args: &'a Args,
+  // 아직 넘기지 않은 그룹들. 맨 앞은 출력 순서상 position번째 그룹이다.
+  queue: VecDeque<Group>,
+  position: usize,
+  // 우선순위별로 한 도크 그룹들을 합치고 있는 그룹의 출력 순서상 위치
+  open: BTreeMap<Priority, usize>,
+}
+
+impl<'a> SingleMerger<'a> {
+  pub fn new(args: &'a Args) -> Self {
+    SingleMerger { args, queue: VecDeque::new(), position: 0, open: BTreeMap::new() }
+  }
+
+  // 맨 앞부터 합치고 있는 그룹 직전까지의 그룹들을 넘긴다.
+  fn drain(&mut self, emit: &mut dyn FnMut(Group)) {
+    while !self.open.values().any(|&at| at == self.position)
+      && let Some(group) = self.queue.pop_front()
+    {
+      self.position += 1;
+      emit(group);
+    }
+  }
+
+  // 합칠 수 있는 그룹인지 여부. --solo 도크의 그룹은 합치지 않는다.
+  fn mergeable(&self, group: &Group) -> bool {
+    group.kind == GroupKind::Regular
+      && group.docks.len() == 1
+      && !self.args.solo.iter().any(|range| range.contains(group.docks[0]))
+  }
+
+  // 한 도크 그룹 group을 합치고 있는 그룹 merged 뒤에 붙일 수 있는지 여부.
+  fn fits(&self, merged: &Group, group: &Group) -> bool {
+    merged.docks.len() < per_page(self.args, merged)
+      && self.args.per_page_section(group.docks[0]) == self.args.per_page_section(merged.docks[0])
+  }
+}
+
+impl GroupTransform for SingleMerger<'_> {
+  fn push(&mut self, group: Group, emit: &mut dyn FnMut(Group)) {
+    if self.mergeable(&group) {
+      let tier = group.tier;
+      let target = self.open.get(&tier).map(|&at| at - self.position);
+      match target {
+        Some(target) if self.fits(&self.queue[target], &group) => {
+          let merged = &mut self.queue[target];
+          merged.docks.push(group.docks[0]);
+          if merged.docks.len() >= per_page(self.args, merged) {
+            self.open.remove(&tier);
+          }
+        }
+        // 합칠 수 없다면 합치던 그룹을 닫고 이 그룹부터 새로 합친다.
+        _ => {
+          self.open.insert(tier, self.position + self.queue.len());
+          self.queue.push_back(group);
+        }
+      }
+    } else {
+      self.queue.push_back(group);
+    }
+    self.drain(emit);
+  }
+
+  fn flush(&mut self, emit: &mut dyn FnMut(Group)) {
+    self.open.clear();
+    self.drain(emit);
+  }
+}
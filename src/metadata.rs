@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{DockId, DockRange};
+
+/// `serve --dock-db`에 경로를 지정하지 않았을 때 사용하는 기본 SQLite 데이터베이스 파일.
+pub const DEFAULT_DOCK_DB: &str = "dock_metadata.sqlite3";
+
+/// 도크 하나의 메타데이터. planner와 배차 화면이 같은 데이터베이스를 보고 도크 상태를 공유한다.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[schemars(description = "Metadata of one dock")]
+pub struct DockMetadata {
+  /// Dock number
+  pub dock: DockId,
+  /// Display name of the dock (e.g. `North 3`)
+  pub name: Option<String>,
+  /// Relative weight of the dock for the dispatch UI
+  pub weight: Option<f64>,
+  /// Out of service; closed docks are skipped by the planning endpoints
+  pub closed: bool,
+  /// Time of the last change (UTC, RFC 3339)
+  pub updated_at: String,
+}
+
+/// `PUT /docks/{dock}`의 요청 본문. 저장된 메타데이터를 통째로 바꾼다.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(description = "New metadata of a dock; replaces the stored values")]
+pub struct DockMetadataUpdate {
+  /// Display name of the dock
+  #[serde(default)]
+  pub name: Option<String>,
+  /// Relative weight of the dock (0 or more)
+  #[serde(default)]
+  pub weight: Option<f64>,
+  /// Out of service
+  #[serde(default)]
+  pub closed: bool,
+}
+
+impl DockMetadataUpdate {
+  /// 저장할 수 있는 값인지 확인한다.
+  pub fn validate(&self) -> Result<(), String> {
+    if self.weight.is_some_and(|weight| !weight.is_finite() || weight < 0.0) {
+      return Err("Dock weight must be 0 or more.".to_string());
+    }
+    Ok(())
+  }
+}
+
+/// 도크 메타데이터 데이터베이스를 열고, docks 테이블이 없다면 만드는 함수.
+fn open_dock_db(path: &Path) -> Result<Connection, String> {
+  let conn = Connection::open(path)
+    .map_err(|e| format!("Failed to open dock metadata database '{}': {e}", path.display()))?;
+  conn
+    .execute_batch(
+      "CREATE TABLE IF NOT EXISTS docks (
+        dock INTEGER PRIMARY KEY,
+        name TEXT,
+        weight REAL,
+        closed INTEGER NOT NULL DEFAULT 0,
+        updated_at TEXT NOT NULL
+      );",
+    )
+    .map_err(|e| format!("Failed to initialize dock metadata database: {e}"))?;
+  Ok(conn)
+}
+
+/// 데이터베이스를 미리 열어 경로가 잘못되었다면 서버를 띄우기 전에 실패하도록 하는 함수.
+pub fn check_dock_db(path: &Path) -> Result<(), String> {
+  open_dock_db(path).map(|_| ())
+}
+
+// SELECT dock, name, weight, closed, updated_at 한 줄을 DockMetadata로 읽는다.
+fn metadata_from_row(row: &Row) -> rusqlite::Result<DockMetadata> {
+  Ok(DockMetadata {
+    dock: DockId(row.get(0)?),
+    name: row.get(1)?,
+    weight: row.get(2)?,
+    closed: row.get(3)?,
+    updated_at: row.get(4)?,
+  })
+}
+
+/// 저장된 모든 도크의 메타데이터를 도크 번호 순서로 반환하는 함수.
+pub fn list_docks(path: &Path) -> Result<Vec<DockMetadata>, String> {
+  let conn = open_dock_db(path)?;
+  let mut stmt = conn
+    .prepare("SELECT dock, name, weight, closed, updated_at FROM docks ORDER BY dock")
+    .map_err(|e| format!("Failed to query dock metadata: {e}"))?;
+  let rows = stmt.query_map([], metadata_from_row).map_err(|e| format!("Failed to query dock metadata: {e}"))?;
+  rows.collect::<Result<_, _>>().map_err(|e| format!("Failed to read dock metadata: {e}"))
+}
+
+/// 도크 하나의 메타데이터를 반환하는 함수. 저장되지 않은 도크라면 None이다.
+pub fn get_dock(path: &Path, dock: DockId) -> Result<Option<DockMetadata>, String> {
+  let conn = open_dock_db(path)?;
+  conn
+    .query_row("SELECT dock, name, weight, closed, updated_at FROM docks WHERE dock = ?1", params![dock.get()], metadata_from_row)
+    .optional()
+    .map_err(|e| format!("Failed to read metadata of dock {dock}: {e}"))
+}
+
+/// 도크 하나의 메타데이터를 저장(없다면 추가, 있다면 교체)하고 저장된 값을 반환하는 함수.
+pub fn put_dock(path: &Path, dock: DockId, update: &DockMetadataUpdate) -> Result<DockMetadata, String> {
+  let conn = open_dock_db(path)?;
+  conn
+    .execute(
+      "INSERT INTO docks (dock, name, weight, closed, updated_at) VALUES (?1, ?2, ?3, ?4, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+       ON CONFLICT (dock) DO UPDATE SET name = excluded.name, weight = excluded.weight, closed = excluded.closed, updated_at = excluded.updated_at",
+      params![dock.get(), update.name, update.weight, update.closed],
+    )
+    .map_err(|e| format!("Failed to save metadata of dock {dock}: {e}"))?;
+  get_dock(path, dock)?.ok_or_else(|| format!("Failed to save metadata of dock {dock}."))
+}
+
+/// 도크 하나의 메타데이터를 지우고, 지운 값을 반환하는 함수. 저장되지 않은 도크라면 None이다.
+pub fn delete_dock(path: &Path, dock: DockId) -> Result<Option<DockMetadata>, String> {
+  let Some(metadata) = get_dock(path, dock)? else {
+    return Ok(None);
+  };
+  let conn = open_dock_db(path)?;
+  conn
+    .execute("DELETE FROM docks WHERE dock = ?1", params![dock.get()])
+    .map_err(|e| format!("Failed to delete metadata of dock {dock}: {e}"))?;
+  Ok(Some(metadata))
+}
+
+/// 사용 중지(closed)로 표시된 도크들. plan 요청의 --skip 목록에 더해진다.
+pub fn closed_docks(path: &Path) -> Result<Vec<DockRange>, String> {
+  Ok(list_docks(path)?.into_iter().filter(|metadata| metadata.closed).map(|metadata| DockRange::single(metadata.dock)).collect())
+}
@@ -1,7 +1,443 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+use std::{borrow::Cow, collections::BTreeMap, fmt, str::FromStr};
+
+use chrono::{Local, SecondsFormat};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// JSON에서는 "first", "second", "third"로 표현한다. 이전 plan 파일의 "First" 형식도 읽을 수 있다.
+// 설정 파일의 우선순위 묶음(`[tiers.이름]`, config::TierConfig)도 이 이름으로 묶음의 우선순위를 지정한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum Priority { // 우선순위
+  #[serde(alias = "First")]
   First,     // 1: 1차
+  #[serde(alias = "Second")]
   Second,    // 2: 2차
+  #[serde(alias = "Third")]
   Third,     // 3: 3차 (일반)
 }
 
+impl Priority {
+  /// 모든 우선순위. 높은 순서대로 나열한다.
+  pub const ALL: [Priority; 3] = [Priority::First, Priority::Second, Priority::Third];
+
+  /// 설정 파일, JSON, 템플릿에서 사용하는 우선순위 이름. serde의 표현과 같다.
+  pub fn name(self) -> &'static str {
+    match self {
+      Priority::First => "first",
+      Priority::Second => "second",
+      Priority::Third => "third",
+    }
+  }
+
+  /// 사람이 읽는 출력에 쓰는 짧은 이름 ("1st", "2nd", "3rd").
+  pub fn short_name(self) -> &'static str {
+    match self {
+      Priority::First => "1st",
+      Priority::Second => "2nd",
+      Priority::Third => "3rd",
+    }
+  }
+
+  /// 1부터 시작하는 우선순위 번호.
+  pub fn rank(self) -> u8 {
+    self as u8 + 1
+  }
+}
+
+impl fmt::Display for Priority {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+/// 우선순위의 이름, 짧은 이름, 번호(대소문자 무시)와 일반 우선순위의 별칭 "general"을 파싱한다.
+impl FromStr for Priority {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let key = s.trim().to_ascii_lowercase();
+    if key == "general" {
+      return Ok(Priority::Third);
+    }
+    Priority::ALL
+      .into_iter()
+      .find(|tier| key == tier.name() || key == tier.short_name() || key == tier.rank().to_string())
+      .ok_or_else(|| format!("Unknown priority '{s}'. Expected first, second or third."))
+  }
+}
+
+/// 도크 번호.
+///
+/// JSON에서는 숫자(`71`)로 표현한다.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct DockId(pub u32);
+
+impl DockId {
+  /// 가장 큰 도크 번호.
+  pub const MAX: DockId = DockId(u32::MAX);
+
+  /// 도크 번호의 값.
+  pub fn get(self) -> u32 {
+    self.0
+  }
+
+  /// n만큼 뒤의 도크. 도크 번호가 넘친다면 None이다.
+  pub fn checked_add(self, n: u32) -> Option<Self> {
+    self.0.checked_add(n).map(DockId)
+  }
+
+  /// n만큼 뒤의 도크. 도크 번호의 최댓값을 넘지 않는다.
+  pub fn saturating_add(self, n: u32) -> Self {
+    DockId(self.0.saturating_add(n))
+  }
+
+  /// n만큼 앞의 도크. 0보다 작아지지 않는다.
+  pub fn saturating_sub(self, n: u32) -> Self {
+    DockId(self.0.saturating_sub(n))
+  }
+}
+
+// 에러 메세지의 도크 목록이 `[52, 57]`처럼 보이도록 숫자만 출력한다.
+impl fmt::Debug for DockId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.0.fmt(f)
+  }
+}
+
+impl fmt::Display for DockId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.0.fmt(f)
+  }
+}
+
+impl FromStr for DockId {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    s.trim().parse::<u32>().map(DockId).map_err(|_| format!("Invalid dock number: '{s}'"))
+  }
+}
+
+/// -f, -s, -e로 입력된 도크 범위 하나("65-66" 또는 "71").
+/// 범위를 도크 목록으로 펼치지 않고 양 끝만 저장하므로, `1-4294967295` 같은 입력도 처리 범위로 잘라낸 뒤에만 펼쳐진다.
+///
+/// JSON에서는 단일 도크는 숫자(`71`), 범위는 문자열(`"65-66"`)로 표현한다.
+/// 이전 형식인 연속된 도크 목록(`[65, 66]`)도 읽을 수 있다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "DockRangeRepr", into = "DockRangeRepr")]
+pub struct DockRange {
+  pub start: DockId,
+  pub end: DockId,
+}
+
+impl DockRange {
+  /// 도크 하나로 이루어진 범위.
+  pub fn single(dock: DockId) -> Self {
+    DockRange { start: dock, end: dock }
+  }
+
+  /// 범위에 속한 도크들.
+  pub fn docks(&self) -> impl DoubleEndedIterator<Item = DockId> + use<> {
+    (self.start.0..=self.end.0).map(DockId)
+  }
+
+  /// 범위에 속한 도크 수. 시작이 끝보다 큰 범위는 도크가 없다.
+  pub fn dock_count(&self) -> u64 {
+    self.end.0.checked_sub(self.start.0).map_or(0, |span| u64::from(span) + 1)
+  }
+
+  /// dock이 범위에 속하는지 여부.
+  pub fn contains(&self, dock: DockId) -> bool {
+    self.start <= dock && dock <= self.end
+  }
+}
+
+impl fmt::Display for DockRange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.start == self.end {
+      true => write!(f, "{}", self.start),
+      false => write!(f, "{}-{}", self.start, self.end),
+    }
+  }
+}
+
+// DockRange의 JSON 표현들.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum DockRangeRepr {
+  Single(DockId),
+  Text(String),
+  Docks(Vec<DockId>),
+}
+
+impl TryFrom<DockRangeRepr> for DockRange {
+  type Error = String;
+
+  fn try_from(repr: DockRangeRepr) -> Result<Self, Self::Error> {
+    match repr {
+      DockRangeRepr::Single(dock) => Ok(DockRange::single(dock)),
+      DockRangeRepr::Text(text) => crate::cli::parse_dock_ranges(&text),
+      DockRangeRepr::Docks(docks) => {
+        // 이전 형식의 도크 목록은 항상 연속된 오름차순 범위였다.
+        let (Some(&start), Some(&end)) = (docks.first(), docks.last()) else {
+          return Err("Dock list must not be empty.".to_string());
+        };
+        if docks.windows(2).any(|w| w[0].checked_add(1) != Some(w[1])) {
+          return Err(format!("Dock list {docks:?} must be a contiguous ascending range."));
+        }
+        Ok(DockRange { start, end })
+      }
+    }
+  }
+}
+
+impl From<DockRange> for DockRangeRepr {
+  fn from(range: DockRange) -> Self {
+    match range.start == range.end {
+      true => DockRangeRepr::Single(range.start),
+      false => DockRangeRepr::Text(range.to_string()),
+    }
+  }
+}
+
+impl JsonSchema for DockRange {
+  fn schema_name() -> Cow<'static, str> {
+    "DockRange".into()
+  }
+
+  fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "description": "A single dock number (71) or an inclusive range (\"65-66\")",
+      "anyOf": [
+        { "type": "integer", "minimum": 0 },
+        { "type": "string", "pattern": "^\\s*\\d+(\\s*-\\s*\\d+)?\\s*$" },
+        { "type": "array", "items": { "type": "integer", "minimum": 0 } }
+      ]
+    })
+  }
+}
+
+/// -f, -s로 입력된 도크 범위 하나. `!`로 시작하는 범위("!65", "!67-68")는 같은 목록의 다른 범위에서 도크들을 뺀다.
+/// 목록 안의 순서와 관계없이 포함 범위들의 합에서 제외 범위들의 합을 뺀 도크들이 해당 우선순위가 된다.
+///
+/// JSON에서는 DockRange와 같이 표현하며, 제외 범위는 `"!67-68"`처럼 `!`로 시작하는 문자열이다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "DockRangeRepr", into = "DockRangeRepr")]
+pub struct PriorityRange {
+  pub range: DockRange,
+  pub excluded: bool,
+}
+
+impl From<DockRange> for PriorityRange {
+  fn from(range: DockRange) -> Self {
+    PriorityRange { range, excluded: false }
+  }
+}
+
+impl fmt::Display for PriorityRange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.excluded {
+      true => write!(f, "!{}", self.range),
+      false => write!(f, "{}", self.range),
+    }
+  }
+}
+
+impl TryFrom<DockRangeRepr> for PriorityRange {
+  type Error = String;
+
+  fn try_from(repr: DockRangeRepr) -> Result<Self, Self::Error> {
+    match repr {
+      DockRangeRepr::Text(text) => crate::cli::parse_priority_range(&text),
+      repr => DockRange::try_from(repr).map(PriorityRange::from),
+    }
+  }
+}
+
+impl From<PriorityRange> for DockRangeRepr {
+  fn from(range: PriorityRange) -> Self {
+    match range.excluded {
+      true => DockRangeRepr::Text(range.to_string()),
+      false => DockRangeRepr::from(range.range),
+    }
+  }
+}
+
+impl JsonSchema for PriorityRange {
+  fn schema_name() -> Cow<'static, str> {
+    "PriorityRange".into()
+  }
+
+  fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "description": "A single dock number (71) or an inclusive range (\"65-66\"); a string starting with '!' (\"!67-68\") removes docks from the list",
+      "anyOf": [
+        { "type": "integer", "minimum": 0 },
+        { "type": "string", "pattern": "^\\s*!?\\s*\\d+(\\s*-\\s*\\d+)?\\s*$" },
+        { "type": "array", "items": { "type": "integer", "minimum": 0 } }
+      ]
+    })
+  }
+}
+
+/// -e로 입력된 예외 그룹 하나. 쉼표로 이은 여러 범위("52,57,61", "52-53,60")는 서로 이웃하지 않더라도
+/// 한 트레일러로 나가는 도크들이므로 하나의 그룹으로 함께 출력된다.
+///
+/// JSON에서는 범위가 하나라면 DockRange와 같이, 여러 개라면 `"52,57,61"`처럼 쉼표로 이은 문자열로 표현한다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "DockRangeRepr", into = "DockRangeRepr")]
+pub struct ExceptionGroup {
+  pub ranges: Vec<DockRange>,
+}
+
+impl From<DockRange> for ExceptionGroup {
+  fn from(range: DockRange) -> Self {
+    ExceptionGroup { ranges: vec![range] }
+  }
+}
+
+impl fmt::Display for ExceptionGroup {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let ranges: Vec<String> = self.ranges.iter().map(DockRange::to_string).collect();
+    write!(f, "{}", ranges.join(","))
+  }
+}
+
+impl TryFrom<DockRangeRepr> for ExceptionGroup {
+  type Error = String;
+
+  fn try_from(repr: DockRangeRepr) -> Result<Self, Self::Error> {
+    match repr {
+      DockRangeRepr::Text(text) => crate::cli::parse_exception_group(&text),
+      repr => DockRange::try_from(repr).map(ExceptionGroup::from),
+    }
+  }
+}
+
+impl From<ExceptionGroup> for DockRangeRepr {
+  fn from(group: ExceptionGroup) -> Self {
+    match group.ranges.as_slice() {
+      [range] => DockRangeRepr::from(*range),
+      _ => DockRangeRepr::Text(group.to_string()),
+    }
+  }
+}
+
+impl JsonSchema for ExceptionGroup {
+  fn schema_name() -> Cow<'static, str> {
+    "ExceptionGroup".into()
+  }
+
+  fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "description": "A single dock number (71), an inclusive range (\"65-66\"), or comma-separated docks and ranges printed together even if not adjacent (\"52,57,61\")",
+      "anyOf": [
+        { "type": "integer", "minimum": 0 },
+        { "type": "string", "pattern": "^\\s*\\d+(\\s*-\\s*\\d+)?(\\s*,\\s*\\d+(\\s*-\\s*\\d+)?)*\\s*$" },
+        { "type": "array", "items": { "type": "integer", "minimum": 0 } }
+      ]
+    })
+  }
+}
+
+/// --pp-range로 입력된 구간 하나와 그 구간의 그룹당 도크 수("51-60=2").
+/// JSON에서도 같은 문자열로 표현한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PerPageRange {
+  pub range: DockRange,
+  pub per_page: u16,
+}
+
+impl fmt::Display for PerPageRange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}={}", self.range, self.per_page)
+  }
+}
+
+impl TryFrom<String> for PerPageRange {
+  type Error = String;
+
+  fn try_from(text: String) -> Result<Self, Self::Error> {
+    crate::cli::parse_per_page_range(&text)
+  }
+}
+
+impl From<PerPageRange> for String {
+  fn from(range: PerPageRange) -> Self {
+    range.to_string()
+  }
+}
+
+impl JsonSchema for PerPageRange {
+  fn schema_name() -> Cow<'static, str> {
+    "PerPageRange".into()
+  }
+
+  fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "description": "A dock or inclusive dock range and its number of docks per group (\"51-60=2\")",
+      "type": "string",
+      "pattern": "^\\s*\\d+(\\s*-\\s*\\d+)?\\s*=\\s*\\d+\\s*$"
+    })
+  }
+}
+
+/// plan 계산 한 번을 가리키는 실행 ID와 시각 (--stamp).
+/// 출력된 라벨 목록이나 내보낸 plan을 정확히 어느 계산에서 나온 것인지 추적하기 위해 사용한다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RunStamp {
+  /// Randomly generated UUID of the planning run
+  pub run_id: String,
+  /// Local time of the planning run (ISO-8601 with UTC offset)
+  pub timestamp: String,
+}
+
+impl RunStamp {
+  /// 새 실행 ID와 현재 시각으로 만든다.
+  pub fn now() -> Self {
+    RunStamp {
+      run_id: Uuid::new_v4().to_string(),
+      timestamp: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+    }
+  }
+}
+
+/// 출력 그룹의 종류.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupKind {
+  // per-page와 우선순위 규칙에 따라 만들어진 일반 그룹
+  Regular,
+  // -e로 지정되어 per-page와 관계없이 함께 출력되는 예외 그룹
+  Exception,
+}
+
+/// 함께 출력되는 도크 묶음 하나.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Group {
+  /// Docks of the group, in printing order
+  pub docks: Vec<DockId>,
+  /// Whether the group was built by the grouping rules or given with -e
+  pub kind: GroupKind,
+  /// Priority tier of the group's first dock (exception groups are always `third`)
+  pub tier: Priority,
+  /// Position of the group in the output order, starting at 0
+  pub index: usize,
+  /// For exception groups: position (starting at 0) of the `-e` value the group was defined by
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub exception_source: Option<usize>,
+  /// Crew the group is assigned to with --crews, starting at 1
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub crew: Option<u16>,
+  /// Start time (`HH:MM`) of the --slots time slot the group is scheduled in
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub slot: Option<String>,
+  /// Number of docks of each priority tier in the group, recorded by `--post annotate`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub composition: Option<BTreeMap<Priority, usize>>,
+}
+
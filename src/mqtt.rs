@@ -0,0 +1,73 @@
+use std::{
+  io::{Read, Write},
+  net::TcpStream,
+  time::Duration,
+};
+
+/// MQTT 브로커에 연결할 때와 응답을 기다릴 때의 제한 시간.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// MQTT 3.1.1 브로커(host:port)에 접속하여 topic으로 payload를 QoS 0으로 발행하고 연결을 종료하는 함수.
+/// retain이 true라면 브로커가 마지막 메세지를 보관하여, 나중에 구독을 시작한 전광판도 최신 plan을 바로 받는다.
+pub fn publish(broker: &str, topic: &str, payload: &[u8], retain: bool) -> Result<(), String> {
+  let mut stream =
+    TcpStream::connect(broker).map_err(|e| format!("Failed to connect to MQTT broker '{broker}': {e}"))?;
+  stream.set_read_timeout(Some(TIMEOUT)).map_err(|e| e.to_string())?;
+  stream.set_write_timeout(Some(TIMEOUT)).map_err(|e| e.to_string())?;
+
+  // CONNECT: 프로토콜 이름 "MQTT", 레벨 4(3.1.1), clean session, keep alive 60초, client id
+  let client_id = format!("dock_sorter-{}", std::process::id());
+  let mut connect = Vec::new();
+  push_str(&mut connect, "MQTT");
+  connect.extend_from_slice(&[0x04, 0x02, 0x00, 0x3C]);
+  push_str(&mut connect, &client_id);
+  write_packet(&mut stream, 0x10, &connect)?;
+
+  // CONNACK: 0x20 0x02 <session present> <return code>. return code가 0이어야 접속이 허용된 것이다.
+  let mut connack = [0u8; 4];
+  stream
+    .read_exact(&mut connack)
+    .map_err(|e| format!("No CONNACK from MQTT broker '{broker}': {e}"))?;
+  if connack[0] != 0x20 || connack[3] != 0 {
+    return Err(format!("MQTT broker '{broker}' refused the connection (return code {}).", connack[3]));
+  }
+
+  // PUBLISH (QoS 0): topic 이름 뒤에 payload가 그대로 이어진다.
+  let mut publish = Vec::new();
+  push_str(&mut publish, topic);
+  publish.extend_from_slice(payload);
+  write_packet(&mut stream, 0x30 | u8::from(retain), &publish)?;
+
+  // DISCONNECT
+  write_packet(&mut stream, 0xE0, &[])
+}
+
+/// 길이(2바이트 big endian)가 앞에 붙은 MQTT 문자열을 buf에 추가한다.
+fn push_str(buf: &mut Vec<u8>, s: &str) {
+  let len = u16::try_from(s.len()).unwrap_or(u16::MAX);
+  buf.extend_from_slice(&len.to_be_bytes());
+  buf.extend_from_slice(&s.as_bytes()[..len as usize]);
+}
+
+/// 고정 헤더(패킷 종류 + 남은 길이)와 본문으로 이루어진 패킷 하나를 전송한다.
+/// 남은 길이는 7비트씩 나눠 쓰는 가변 길이 정수로 인코딩한다.
+fn write_packet(stream: &mut TcpStream, header: u8, body: &[u8]) -> Result<(), String> {
+  if body.len() > 268_435_455 {
+    return Err("MQTT payload is too large.".to_string());
+  }
+  let mut packet = vec![header];
+  let mut remaining = body.len();
+  loop {
+    let mut byte = (remaining % 128) as u8;
+    remaining /= 128;
+    if remaining > 0 {
+      byte |= 0x80;
+    }
+    packet.push(byte);
+    if remaining == 0 {
+      break;
+    }
+  }
+  packet.extend_from_slice(body);
+  stream.write_all(&packet).map_err(|e| format!("Failed to send MQTT packet: {e}"))
+}
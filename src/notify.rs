@@ -0,0 +1,121 @@
+use serde_json::{Value, json};
+
+use crate::{cli::Args, models::GroupKind, preview::label_marker, processor::ProcessingResult};
+
+// 채팅 메세지에 넣는 출력 순서의 최대 길이. Slack의 section 글은 3000자까지이다.
+const MAX_ORDER_TEXT: usize = 2800;
+
+/// 계산된 plan JSON을 webhook URL로 POST하는 함수.
+/// 응답 상태 코드가 2xx가 아니거나 연결에 실패하면 에러를 반환한다.
+pub fn post_plan_webhook(url: &str, plan_json: &str) -> Result<(), String> {
+  ureq::post(url)
+    .header("Content-Type", "application/json")
+    .send(plan_json)
+    .map_err(|e| format!("Failed to notify webhook '{url}': {e}"))?;
+  Ok(())
+}
+
+/// plan 알림의 제목. "Dock plan" 뒤에 --site와 --date가 붙는다. 메일 제목으로도 사용한다.
+pub fn plan_title(args: &Args) -> String {
+  ["Dock plan"].into_iter().chain(args.site.as_deref()).chain(args.date.as_deref()).collect::<Vec<_>>().join(" ")
+}
+
+/// 채팅 webhook의 메세지 형식.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatService {
+  // Slack incoming webhook (Block Kit)
+  Slack,
+  // Microsoft Teams webhook (Adaptive Card)
+  Teams,
+}
+
+impl ChatService {
+  /// 상태 메세지에 표시하는 이름.
+  pub fn name(self) -> &'static str {
+    match self {
+      ChatService::Slack => "Slack",
+      ChatService::Teams => "Teams",
+    }
+  }
+}
+
+// 그룹마다 "G1: 51@, 52, 53" 한 줄이며, 예외 그룹은 "G2 EX: 60, 61"이다. 너무 길면 남은 그룹 수로 줄인다.
+fn order_lines(result: &ProcessingResult) -> String {
+  let mut text = String::new();
+  for (i, group) in result.result_groups.iter().enumerate() {
+    let exception = group.kind == GroupKind::Exception;
+    let docks: Vec<String> = group
+      .docks
+      .iter()
+      .map(|&dock| if exception { dock.to_string() } else { format!("{dock}{}", label_marker(group, dock, result)) })
+      .collect();
+    let line = format!("G{}{}: {}\n", i + 1, if exception { " EX" } else { "" }, docks.join(", "));
+    if text.len() + line.len() > MAX_ORDER_TEXT {
+      text.push_str(&format!("... {} more groups\n", result.result_groups.len() - i));
+      break;
+    }
+    text.push_str(&line);
+  }
+  text
+}
+
+/// plan 요약(stats)과 출력 순서를 채팅 서비스의 메세지 JSON으로 만드는 함수.
+/// 출력 순서는 고정폭 글꼴로 보이도록 코드 블록에 넣고, checksum과 실행 ID는 아래에 작게 붙인다.
+pub fn chat_message(service: ChatService, title: &str, stats: &str, result: &ProcessingResult) -> Value {
+  let order = order_lines(result);
+  let footer: Vec<String> = result
+    .checksum
+    .iter()
+    .map(|checksum| format!("Checksum {checksum}"))
+    .chain(result.stamp.iter().map(|stamp| format!("Run {}", stamp.run_id)))
+    .collect();
+  match service {
+    ChatService::Slack => {
+      let mut blocks = vec![
+        json!({ "type": "header", "text": { "type": "plain_text", "text": title } }),
+        json!({ "type": "section", "text": { "type": "mrkdwn", "text": stats.trim_end() } }),
+        json!({ "type": "section", "text": { "type": "mrkdwn", "text": format!("```{}```", order.trim_end()) } }),
+      ];
+      if !footer.is_empty() {
+        blocks.push(json!({ "type": "context", "elements": [{ "type": "mrkdwn", "text": footer.join(" · ") }] }));
+      }
+      // text는 블록을 보여줄 수 없는 알림(모바일 푸시 등)에 표시된다.
+      json!({ "text": format!("{title}\n{}", stats.trim_end()), "blocks": blocks })
+    }
+    ChatService::Teams => {
+      // 줄바꿈 처리가 클라이언트마다 다르므로 한 줄을 TextBlock 하나로 만든다.
+      let mut body = vec![json!({ "type": "TextBlock", "text": title, "size": "Large", "weight": "Bolder", "wrap": true })];
+      body.extend(stats.lines().map(|line| json!({ "type": "TextBlock", "text": line, "spacing": "None", "wrap": true })));
+      body.extend(
+        order
+          .lines()
+          .enumerate()
+          .map(|(i, line)| json!({ "type": "TextBlock", "text": line, "fontType": "Monospace", "spacing": if i == 0 { "Medium" } else { "None" }, "wrap": true })),
+      );
+      if !footer.is_empty() {
+        body.push(json!({ "type": "TextBlock", "text": footer.join(" · "), "size": "Small", "isSubtle": true, "wrap": true }));
+      }
+      json!({
+        "type": "message",
+        "attachments": [{
+          "contentType": "application/vnd.microsoft.card.adaptive",
+          "content": {
+            "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+            "type": "AdaptiveCard",
+            "version": "1.4",
+            "body": body,
+          },
+        }],
+      })
+    }
+  }
+}
+
+/// 채팅 메세지를 webhook URL로 POST하는 함수.
+pub fn post_chat_webhook(service: ChatService, url: &str, message: &Value) -> Result<(), String> {
+  ureq::post(url)
+    .header("Content-Type", "application/json")
+    .send(message.to_string())
+    .map_err(|e| format!("Failed to post to {} webhook: {e}", service.name()))?;
+  Ok(())
+}
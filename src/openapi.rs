@@ -0,0 +1,290 @@
+use schemars::generate::SchemaSettings;
+use serde_json::{Value, json};
+
+use crate::{
+  audit::AuditEntry,
+  batch::{BatchJob, BatchResult},
+  cli::Args,
+  metadata::{DockMetadata, DockMetadataUpdate},
+  plan::PlanFile,
+  server::{ErrorBody, StatusBody, VersionBody},
+};
+
+/// HTTP API 서버의 OpenAPI 3.0 문서를 만드는 함수.
+/// 요청/응답 스키마는 schemars로 Args, PlanFile 등의 타입에서 직접 생성되므로 타입이 바뀌면 문서도 함께 바뀐다.
+pub fn openapi_document() -> Value {
+  let mut generator = SchemaSettings::openapi3().into_generator();
+  let request_schema = generator.subschema_for::<Args>();
+  let plan_schema = generator.subschema_for::<PlanFile>();
+  let batch_request_schema = generator.subschema_for::<Vec<BatchJob>>();
+  let batch_response_schema = generator.subschema_for::<Vec<BatchResult>>();
+  let error_schema = generator.subschema_for::<ErrorBody>();
+  let status_schema = generator.subschema_for::<StatusBody>();
+  let version_schema = generator.subschema_for::<VersionBody>();
+  let dock_schema = generator.subschema_for::<DockMetadata>();
+  let dock_list_schema = generator.subschema_for::<Vec<DockMetadata>>();
+  let dock_update_schema = generator.subschema_for::<DockMetadataUpdate>();
+  let audit_schema = generator.subschema_for::<Vec<AuditEntry>>();
+  let schemas = generator.take_definitions(true);
+
+  // 에러 응답은 모든 엔드포인트에서 같은 형식이므로 한 번만 만들어 재사용한다.
+  let error_response = |description: &str| {
+    json!({
+      "description": description,
+      "content": { "application/json": { "schema": error_schema } }
+    })
+  };
+
+  // 프로필을 고르는 경로 또는 헤더 파라미터.
+  let profile_parameter = |location: &str| {
+    json!({
+      "name": if location == "path" { "profile" } else { "X-Dock-Profile" },
+      "in": location,
+      "required": location == "path",
+      "description": "Config profile whose plan parameters are the defaults of the request; request fields override them",
+      "schema": { "type": "string" }
+    })
+  };
+  // plan 엔드포인트들은 프로필 경로(`/profiles/{profile}/...`) 아래에도 같은 형식으로 있다.
+  let plan_operation = |operation_id: &str, under_profile: bool| {
+    json!({
+      "summary": "Compute a dock plan",
+      "description": "Accepts the same parameters as the CLI and returns the inputs together with the resulting groups.",
+      "operationId": operation_id,
+      "parameters": if under_profile { json!([]) } else { json!([profile_parameter("header")]) },
+      "requestBody": {
+        "required": true,
+        "content": { "application/json": { "schema": request_schema } }
+      },
+      "responses": {
+        "200": {
+          "description": "Computed plan",
+          "content": { "application/json": { "schema": plan_schema } }
+        },
+        "400": error_response("Invalid request"),
+        "401": error_response("Missing or invalid bearer token"),
+        "404": error_response("Unknown profile"),
+        "429": error_response("Rate limit exceeded; see the Retry-After header")
+      },
+      "security": [{ "bearerAuth": [] }]
+    })
+  };
+  let batch_operation = |operation_id: &str, under_profile: bool| {
+    json!({
+      "summary": "Compute several independent dock plans",
+      "description": "Accepts a list of jobs (different ranges/priorities) and returns one result per job, in order. A failing job reports an error without failing the others.",
+      "operationId": operation_id,
+      "parameters": if under_profile { json!([]) } else { json!([profile_parameter("header")]) },
+      "requestBody": {
+        "required": true,
+        "content": { "application/json": { "schema": batch_request_schema } }
+      },
+      "responses": {
+        "200": {
+          "description": "Per-job results",
+          "content": { "application/json": { "schema": batch_response_schema } }
+        },
+        "400": error_response("Invalid request"),
+        "401": error_response("Missing or invalid bearer token"),
+        "404": error_response("Unknown profile"),
+        "429": error_response("Rate limit exceeded; see the Retry-After header")
+      },
+      "security": [{ "bearerAuth": [] }]
+    })
+  };
+
+  json!({
+    "openapi": "3.0.3",
+    "info": {
+      "title": "dock_sorter planning API",
+      "description": "Dock label output order and range calculator",
+      "version": env!("CARGO_PKG_VERSION")
+    },
+    "paths": {
+      "/plan": { "post": plan_operation("createPlan", false) },
+      "/plan/batch": { "post": batch_operation("createPlanBatch", false) },
+      "/profiles/{profile}/plan": {
+        "parameters": [profile_parameter("path")],
+        "post": plan_operation("createProfilePlan", true)
+      },
+      "/profiles/{profile}/plan/batch": {
+        "parameters": [profile_parameter("path")],
+        "post": batch_operation("createProfilePlanBatch", true)
+      },
+      "/docks": {
+        "get": {
+          "summary": "List dock metadata",
+          "description": "Returns the stored metadata of every dock, ordered by dock number. Available when the server runs with --dock-db.",
+          "operationId": "listDocks",
+          "responses": {
+            "200": {
+              "description": "Dock metadata",
+              "content": { "application/json": { "schema": dock_list_schema } }
+            },
+            "401": error_response("Missing or invalid bearer token"),
+            "404": error_response("Dock metadata is not enabled")
+          },
+          "security": [{ "bearerAuth": [] }]
+        }
+      },
+      "/docks/{dock}": {
+        "parameters": [
+          { "name": "dock", "in": "path", "required": true, "schema": { "type": "integer", "minimum": 0 } }
+        ],
+        "get": {
+          "summary": "Get the metadata of a dock",
+          "operationId": "getDock",
+          "responses": {
+            "200": {
+              "description": "Dock metadata",
+              "content": { "application/json": { "schema": dock_schema } }
+            },
+            "400": error_response("Invalid dock number"),
+            "401": error_response("Missing or invalid bearer token"),
+            "404": error_response("No metadata for the dock")
+          },
+          "security": [{ "bearerAuth": [] }]
+        },
+        "put": {
+          "summary": "Create or replace the metadata of a dock",
+          "description": "Closed docks are added to the skipped docks of every planning request.",
+          "operationId": "putDock",
+          "requestBody": {
+            "required": true,
+            "content": { "application/json": { "schema": dock_update_schema } }
+          },
+          "responses": {
+            "200": {
+              "description": "Stored dock metadata",
+              "content": { "application/json": { "schema": dock_schema } }
+            },
+            "400": error_response("Invalid request"),
+            "401": error_response("Missing or invalid bearer token"),
+            "404": error_response("Dock metadata is not enabled")
+          },
+          "security": [{ "bearerAuth": [] }]
+        },
+        "delete": {
+          "summary": "Delete the metadata of a dock",
+          "operationId": "deleteDock",
+          "responses": {
+            "200": {
+              "description": "Deleted dock metadata",
+              "content": { "application/json": { "schema": dock_schema } }
+            },
+            "400": error_response("Invalid dock number"),
+            "401": error_response("Missing or invalid bearer token"),
+            "404": error_response("No metadata for the dock")
+          },
+          "security": [{ "bearerAuth": [] }]
+        }
+      },
+      "/audit": {
+        "get": {
+          "summary": "List the audit log of planning requests",
+          "description": "Returns the audit records of `/plan` and `/plan/batch` requests, oldest first. Available when the server runs with --audit-log.",
+          "operationId": "listAudit",
+          "parameters": [
+            {
+              "name": "limit",
+              "in": "query",
+              "required": false,
+              "description": "Return only the last N records",
+              "schema": { "type": "integer", "minimum": 0 }
+            }
+          ],
+          "responses": {
+            "200": {
+              "description": "Audit records",
+              "content": { "application/json": { "schema": audit_schema } }
+            },
+            "400": error_response("Invalid query"),
+            "401": error_response("Missing or invalid bearer token"),
+            "404": error_response("The audit log is not enabled")
+          },
+          "security": [{ "bearerAuth": [] }]
+        }
+      },
+      "/ws": {
+        "get": {
+          "summary": "Receive newly computed plans over a WebSocket",
+          "description": "Upgrades to a WebSocket (RFC 6455). After every successful planning request the server sends a text message `{\"endpoint\", \"profile\", \"response\"}` with the returned JSON to every connected client. Messages from clients are ignored.",
+          "operationId": "watchPlans",
+          "parameters": [
+            {
+              "name": "token",
+              "in": "query",
+              "required": false,
+              "description": "Bearer token, for clients that cannot set the Authorization header",
+              "schema": { "type": "string" }
+            }
+          ],
+          "responses": {
+            "101": { "description": "Switched to the WebSocket protocol" },
+            "400": error_response("Not a WebSocket upgrade request"),
+            "401": error_response("Missing or invalid bearer token")
+          },
+          "security": [{ "bearerAuth": [] }]
+        }
+      },
+      "/healthz": {
+        "get": {
+          "summary": "Liveness check",
+          "operationId": "getHealth",
+          "responses": {
+            "200": {
+              "description": "Server is alive",
+              "content": { "application/json": { "schema": status_schema } }
+            }
+          }
+        }
+      },
+      "/readyz": {
+        "get": {
+          "summary": "Readiness check",
+          "operationId": "getReady",
+          "responses": {
+            "200": {
+              "description": "Server is ready to accept planning requests",
+              "content": { "application/json": { "schema": status_schema } }
+            }
+          }
+        }
+      },
+      "/version": {
+        "get": {
+          "summary": "Server version and active configuration profile",
+          "operationId": "getVersion",
+          "responses": {
+            "200": {
+              "description": "Version information",
+              "content": { "application/json": { "schema": version_schema } }
+            }
+          }
+        }
+      },
+      "/openapi.json": {
+        "get": {
+          "summary": "This OpenAPI document",
+          "operationId": "getOpenApi",
+          "responses": {
+            "200": {
+              "description": "OpenAPI document",
+              "content": { "application/json": { "schema": { "type": "object" } } }
+            }
+          }
+        }
+      }
+    },
+    "components": {
+      "schemas": schemas,
+      "securitySchemes": {
+        "bearerAuth": {
+          "type": "http",
+          "scheme": "bearer",
+          "description": "Required for planning, dock metadata and audit endpoints when the server has tokens configured"
+        }
+      }
+    }
+  })
+}
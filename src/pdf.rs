@@ -0,0 +1,193 @@
+use std::io::Write;
+
+use flate2::{Compression, write::ZlibEncoder};
+
+use crate::{
+  cli::Orientation,
+  raster::{Bitmap, LabelDimensions, MM_PER_INCH, parse_label_dimensions},
+};
+
+// PDF 좌표의 단위인 포인트(1/72인치)의 인치당 개수
+const POINTS_PER_INCH: f64 = 72.0;
+
+/// `export pdf`의 기본 용지, 칸 수, 해상도. --email로 첨부하는 PDF도 이 값으로 만든다.
+pub const DEFAULT_PAPER: &str = "a4";
+pub const DEFAULT_COLUMNS: u32 = 2;
+pub const DEFAULT_ROWS: u32 = 7;
+pub const DEFAULT_SHEET_DPI: u32 = 300;
+
+/// "a4", "letter" 또는 "210x297" 형식의 용지 크기(밀리미터, 폭x높이)를 파싱하는 함수. clap의 value_parser로 사용됩니다.
+pub fn parse_paper(s: &str) -> Result<LabelDimensions, String> {
+  match s.trim().to_ascii_lowercase().as_str() {
+    "a4" => Ok(LabelDimensions { width_mm: 210.0, height_mm: 297.0 }),
+    "letter" => Ok(LabelDimensions { width_mm: 215.9, height_mm: 279.4 }),
+    _ => parse_label_dimensions(s)
+      .map_err(|_| format!("Invalid paper size: '{s}'. Expected a4, letter or WIDTHxHEIGHT in millimeters (e.g., 210x297).")),
+  }
+}
+
+// 밀리미터를 포인트로 바꾼다.
+fn points(mm: f64) -> f64 {
+  mm * POINTS_PER_INCH / MM_PER_INCH
+}
+
+/// 용지 한 장에 라벨 칸을 놓는 방법. 칸은 왼쪽 위부터 한 줄씩 채운다.
+#[derive(Debug, Clone, Copy)]
+pub struct SheetLayout {
+  // 방향을 적용한 용지 크기
+  pub page: LabelDimensions,
+  pub columns: u32,
+  pub rows: u32,
+  // 용지 가장자리와 칸 사이, 칸과 칸 사이의 간격(밀리미터)
+  pub margin: f64,
+  pub gap: f64,
+}
+
+impl SheetLayout {
+  /// 용지 배치를 만드는 함수. 세로 방향은 긴 변을 세로로, 가로 방향은 긴 변을 가로로 돌린다.
+  /// 칸이 없거나 여백과 간격 때문에 칸 크기가 0 이하라면 에러를 반환한다.
+  pub fn new(paper: LabelDimensions, orientation: Orientation, columns: u32, rows: u32, margin: f64, gap: f64) -> Result<Self, String> {
+    let (short, long) = (paper.width_mm.min(paper.height_mm), paper.width_mm.max(paper.height_mm));
+    let page = match orientation {
+      Orientation::Portrait => LabelDimensions { width_mm: short, height_mm: long },
+      Orientation::Landscape => LabelDimensions { width_mm: long, height_mm: short },
+    };
+    if columns == 0 || rows == 0 {
+      return Err("`--columns` and `--rows` must be 1 or greater.".to_string());
+    }
+    if !margin.is_finite() || margin < 0.0 || !gap.is_finite() || gap < 0.0 {
+      return Err("`--margin` and `--gap` must be zero or more millimeters.".to_string());
+    }
+    let layout = SheetLayout { page, columns, rows, margin, gap };
+    let cell = layout.cell();
+    if cell.width_mm <= 0.0 || cell.height_mm <= 0.0 {
+      return Err(format!(
+        "{columns}x{rows} cells with a {margin} mm margin and {gap} mm gap do not fit on a {}x{} mm page.",
+        page.width_mm, page.height_mm
+      ));
+    }
+    Ok(layout)
+  }
+
+  /// 칸 하나(라벨 한 장)의 크기.
+  pub fn cell(&self) -> LabelDimensions {
+    let span = |length: f64, count: u32| (length - 2.0 * self.margin - f64::from(count - 1) * self.gap) / f64::from(count);
+    LabelDimensions { width_mm: span(self.page.width_mm, self.columns), height_mm: span(self.page.height_mm, self.rows) }
+  }
+
+  /// 용지 한 장의 칸 수.
+  pub fn cells_per_page(&self) -> usize {
+    self.columns as usize * self.rows as usize
+  }
+
+  // 용지의 index번째 칸의 왼쪽 위 위치(밀리미터, 용지의 왼쪽 위가 원점).
+  fn cell_origin(&self, index: usize) -> (f64, f64) {
+    let cell = self.cell();
+    let (column, row) = (index as u32 % self.columns, index as u32 / self.columns);
+    (
+      self.margin + f64::from(column) * (cell.width_mm + self.gap),
+      self.margin + f64::from(row) * (cell.height_mm + self.gap),
+    )
+  }
+}
+
+/// 라벨 이미지들을 용지의 칸에 차례로 놓아 PDF 문서를 만드는 것. 칸이 모두 차면 다음 쪽으로 넘어간다.
+/// 라벨은 추가할 때 바로 압축하므로 라벨이 많아도 원본 이미지를 모두 메모리에 두지 않는다.
+pub struct SheetPdf {
+  layout: SheetLayout,
+  // PDF 객체들. 번호는 (index + 1)이며, 1번은 문서 카탈로그, 2번은 쪽 목록이다.
+  objects: Vec<Vec<u8>>,
+  // 완성된 쪽 객체들의 번호
+  pages: Vec<usize>,
+  // 현재 쪽에 놓인 라벨 이미지 객체들의 번호
+  current: Vec<usize>,
+}
+
+impl SheetPdf {
+  pub fn new(layout: SheetLayout) -> Self {
+    SheetPdf { layout, objects: vec![Vec::new(), Vec::new()], pages: Vec::new(), current: Vec::new() }
+  }
+
+  /// 다음 칸에 라벨 이미지를 놓는 함수. 이미지는 칸 크기로 늘이거나 줄여 그려진다.
+  pub fn add_label(&mut self, label: &Bitmap) -> Result<(), String> {
+    // 한 픽셀이 1비트이며, 1이 검은색이다. 줄마다 바이트 경계까지 채운다.
+    let row_bytes = label.width.div_ceil(8) as usize;
+    let mut data = vec![0u8; row_bytes * label.height as usize];
+    for y in 0..label.height {
+      for x in (0..label.width).filter(|&x| label.ink(x, y)) {
+        data[y as usize * row_bytes + x as usize / 8] |= 0x80 >> (x % 8);
+      }
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let compressed = encoder.write_all(&data).and_then(|()| encoder.finish()).map_err(|e| format!("Failed to compress a label image: {e}"))?;
+    let mut image = format!(
+      "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent 1 /Decode [1 0] /Filter /FlateDecode /Length {} >>\nstream\n",
+      label.width,
+      label.height,
+      compressed.len()
+    )
+    .into_bytes();
+    image.extend(compressed);
+    image.extend_from_slice(b"\nendstream");
+    self.objects.push(image);
+    self.current.push(self.objects.len());
+    if self.current.len() == self.layout.cells_per_page() {
+      self.finish_page();
+    }
+    Ok(())
+  }
+
+  // 현재 쪽의 내용과 쪽 객체를 만든다.
+  fn finish_page(&mut self) {
+    let cell = self.layout.cell();
+    let page_height = points(self.layout.page.height_mm);
+    let mut content = String::new();
+    let mut resources = String::new();
+    for (index, &image) in self.current.iter().enumerate() {
+      // PDF의 원점은 왼쪽 아래이다.
+      let (left, top) = self.layout.cell_origin(index);
+      let (width, height) = (points(cell.width_mm), points(cell.height_mm));
+      content.push_str(&format!("q {width:.3} 0 0 {height:.3} {:.3} {:.3} cm /L{image} Do Q\n", points(left), page_height - points(top) - height));
+      resources.push_str(&format!("/L{image} {image} 0 R "));
+    }
+    self.objects.push(format!("<< /Length {} >>\nstream\n{content}endstream", content.len()).into_bytes());
+    let contents = self.objects.len();
+    self.objects.push(format!("<< /Type /Page /Parent 2 0 R /Resources << /XObject << {resources}>> >> /Contents {contents} 0 R >>").into_bytes());
+    self.pages.push(self.objects.len());
+    self.current.clear();
+  }
+
+  /// 남은 라벨로 마지막 쪽을 만들고 PDF 파일 내용을 반환하는 함수. 라벨이 하나도 없다면 빈 쪽 하나이다.
+  pub fn finish(mut self) -> Vec<u8> {
+    if !self.current.is_empty() || self.pages.is_empty() {
+      self.finish_page();
+    }
+    let kids: Vec<String> = self.pages.iter().map(|page| format!("{page} 0 R")).collect();
+    self.objects[0] = b"<< /Type /Catalog /Pages 2 0 R >>".to_vec();
+    self.objects[1] = format!(
+      "<< /Type /Pages /Kids [{}] /Count {} /MediaBox [0 0 {:.3} {:.3}] >>",
+      kids.join(" "),
+      self.pages.len(),
+      points(self.layout.page.width_mm),
+      points(self.layout.page.height_mm)
+    )
+    .into_bytes();
+
+    // 객체들 뒤에 각 객체의 위치(바이트)를 적은 상호 참조 표를 붙인다.
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(self.objects.len());
+    for (i, object) in self.objects.iter().enumerate() {
+      offsets.push(pdf.len());
+      pdf.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+      pdf.extend_from_slice(object);
+      pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", self.objects.len() + 1).into_bytes());
+    for offset in offsets {
+      pdf.extend(format!("{offset:010} 00000 n \n").into_bytes());
+    }
+    pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n", self.objects.len() + 1).into_bytes());
+    pdf
+  }
+}
@@ -0,0 +1,171 @@
+use crate::{
+  cli::Args,
+  i18n::tr,
+  models::{Group, GroupKind},
+};
+
+/// 그룹마다 채워지는 template(--line-prefix, --exec-per-group)에서 쓸 수 있는 자리표시자 이름.
+///
+/// - `{group_index}`: 출력 순서상 그룹 번호 (1부터). 줄여서 `{index}`로도 쓸 수 있다.
+/// - `{docks}`: 그룹의 도크들 (콤마로 구분, 예: `51,52,53`)
+/// - `{size}`: 그룹의 도크 수
+/// - `{tier}`: 그룹의 우선순위 (`first`, `second`, `third`)
+/// - `{kind}`: 그룹 종류 (`regular`, `exception`)
+/// - `{site}`, `{shift}`: --site, --shift로 지정한 현장과 근무조 (지정되지 않았다면 빈 문자열)
+/// - `{date}`: --date 또는 --dates의 작업 날짜 (지정되지 않았다면 빈 문자열)
+pub const GROUP_PLACEHOLDERS: [&str; 9] = ["group_index", "index", "docks", "size", "tier", "kind", "site", "shift", "date"];
+
+/// 그룹 하나의 자리표시자 값.
+///
+/// 사용자가 입력한 값(site, shift, date)은 필드를 바꿔 넣을 수 있다 (예: 셸 명령에 넣기 전에 인용한 값).
+pub struct GroupPlaceholders<'a> {
+  pub group_index: usize,
+  pub group: &'a Group,
+  pub site: &'a str,
+  pub shift: &'a str,
+  pub date: &'a str,
+}
+
+impl<'a> GroupPlaceholders<'a> {
+  /// args의 현장, 근무조, 날짜로 값을 채운다. --date가 없다면 --dates의 날짜 범위를 쓴다.
+  pub fn new(args: &'a Args, group: &'a Group, group_index: usize) -> Self {
+    GroupPlaceholders {
+      group_index,
+      group,
+      site: args.site.as_deref().unwrap_or(""),
+      shift: args.shift.as_deref().unwrap_or(""),
+      date: args.date.as_deref().or(args.dates.as_deref()).unwrap_or(""),
+    }
+  }
+
+  /// template의 자리표시자를 값으로 바꾼다. 값 안의 `{…}`는 다시 바뀌지 않는다.
+  /// 알 수 없는 자리표시자는 그대로 남으므로, template은 미리 check_group_template으로 확인해 둔다.
+  pub fn render(&self, template: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    for segment in segments(template) {
+      match segment {
+        Segment::Text(text) => out.push_str(text),
+        Segment::Placeholder(name) => match self.value(name) {
+          Some(value) => out.push_str(&value),
+          None => out.push_str(&format!("{{{name}}}")),
+        },
+      }
+    }
+    out
+  }
+
+  fn value(&self, name: &str) -> Option<String> {
+    let value = match name {
+      "group_index" | "index" => self.group_index.to_string(),
+      "docks" => self.group.docks.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(","),
+      "size" => self.group.docks.len().to_string(),
+      "tier" => self.group.tier.name().to_string(),
+      "kind" => match self.group.kind {
+        GroupKind::Regular => "regular".to_string(),
+        GroupKind::Exception => "exception".to_string(),
+      },
+      "site" => self.site.to_string(),
+      "shift" => self.shift.to_string(),
+      "date" => self.date.to_string(),
+      _ => return None,
+    };
+    Some(value)
+  }
+}
+
+/// template의 자리표시자가 모두 GROUP_PLACEHOLDERS에 있는지 확인하는 함수. option은 에러 메시지에 표시할 옵션 이름이다.
+pub fn check_group_template(option: &str, template: &str) -> Result<(), String> {
+  for segment in segments(template) {
+    if let Segment::Placeholder(name) = segment
+      && !GROUP_PLACEHOLDERS.contains(&name)
+    {
+      let names: Vec<String> = GROUP_PLACEHOLDERS.iter().map(|name| format!("{{{name}}}")).collect();
+      return Err(tr(
+        "error.unknown_placeholder",
+        &[("option", &option), ("names", &names.join(", ")), ("placeholder", &format!("{{{name}}}"))],
+      ));
+    }
+  }
+  Ok(())
+}
+
+// template을 나눈 조각. 글자 그대로인 부분이거나 자리표시자의 이름이다.
+enum Segment<'a> {
+  Text(&'a str),
+  Placeholder(&'a str),
+}
+
+// template을 조각으로 나눈다. 중괄호 안이 영문 소문자와 `_`로만 이루어졌을 때만 자리표시자로 보고,
+// 셸 명령의 `{a: 1}`이나 `{print $1}`처럼 그 밖의 중괄호는 글자 그대로 둔다.
+fn segments(template: &str) -> Vec<Segment<'_>> {
+  let mut segments = Vec::new();
+  let mut rest = template;
+  while let Some(open) = rest.find('{') {
+    let after = &rest[open + 1..];
+    match after.find('}') {
+      Some(close) if is_placeholder_name(&after[..close]) => {
+        segments.push(Segment::Text(&rest[..open]));
+        segments.push(Segment::Placeholder(&after[..close]));
+        rest = &after[close + 1..];
+      }
+      _ => {
+        segments.push(Segment::Text(&rest[..=open]));
+        rest = after;
+      }
+    }
+  }
+  segments.push(Segment::Text(rest));
+  segments
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+  !name.is_empty() && name.bytes().all(|b| b.is_ascii_lowercase() || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::Parser;
+
+  use super::*;
+  use crate::{
+    cli::ReplayOverrides,
+    processor::{PlanRequest, process_docks},
+  };
+
+  #[test]
+  fn renders_index_alias_in_line_prefix() {
+    let args = ReplayOverrides::try_parse_from([
+      "-p",
+      "3",
+      "--min",
+      "51",
+      "--max",
+      "56",
+      "--site",
+      "north",
+      "--date",
+      "2024-05-01",
+      "--line-prefix",
+      "{date} {site} G{index}: ",
+    ])
+    .expect("invalid test arguments")
+    .args;
+    args.validate_input().expect("invalid test arguments");
+    let template = args.line_prefix.as_deref().unwrap();
+    let result = process_docks(PlanRequest::new(&args));
+    let prefixes: Vec<String> = result
+      .result_groups
+      .iter()
+      .enumerate()
+      .map(|(i, group)| GroupPlaceholders::new(&args, group, i + 1).render(template))
+      .collect();
+    assert_eq!(prefixes, ["2024-05-01 north G1: ", "2024-05-01 north G2: "]);
+  }
+
+  #[test]
+  fn accepts_index_alias_in_templates() {
+    assert!(check_group_template("--line-prefix", "{date} {site} G{index}: ").is_ok());
+    assert!(check_group_template("--exec-per-group", "print-labels {index} {docks}").is_ok());
+    assert!(check_group_template("--line-prefix", "G{idx}: ").is_err());
+  }
+}
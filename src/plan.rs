@@ -0,0 +1,180 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fs,
+  path::Path,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{
+  cli::Args,
+  models::{DockId, Group, GroupKind, Priority},
+  processor::ProcessingResult,
+};
+
+/// plan 파일, HTTP API, 데몬 등 구조화된 출력 형식의 버전.
+/// 기존 필드의 의미가 바뀌거나 필드가 빠지는 등 호환되지 않는 변경이 있을 때 올린다.
+///
+/// - 1: result_groups가 도크 목록의 배열
+/// - 2: result_groups가 Group(docks, kind, tier, index)의 배열
+/// - 3: args의 도크 범위가 펼친 배열 대신 `71` 또는 `"65-66"`으로 저장됨 (이전 배열 형식도 읽을 수 있다)
+pub const SCHEMA_VERSION: u32 = 3;
+
+// schema_version이 없는 plan 파일은 버전이 붙기 전에 저장된 것이며 1번 형식과 호환된다.
+fn default_schema_version() -> u32 {
+  1
+}
+
+/// 파일로 저장되는 plan. 입력 인자와 계산된 결과를 함께 담아두어
+/// 나중에 다시 계산하지 않고도 그대로 출력할 수 있도록 한다.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(rename = "Plan", description = "Plan inputs together with the resulting groups")]
+pub struct PlanFile {
+  /// Version of the plan format
+  #[serde(default = "default_schema_version")]
+  pub schema_version: u32,
+  pub args: Args,
+  pub result: ProcessingResult,
+  /// HMAC-SHA256 signature (`hmac-sha256:<hex>`) of the plan without this field, serialized compactly with sorted keys.
+  /// Present when the plan was exported with --sign-key.
+  #[serde(default)]
+  pub signature: Option<String>,
+}
+
+/// PlanFile을 저장할 때 Args와 ProcessingResult를 복제하지 않기 위한 참조 버전.
+#[derive(Serialize)]
+pub struct PlanFileRef<'a> {
+  pub schema_version: u32,
+  pub args: &'a Args,
+  pub result: &'a ProcessingResult,
+}
+
+impl<'a> PlanFileRef<'a> {
+  /// 현재 schema 버전의 plan을 만든다.
+  pub fn new(args: &'a Args, result: &'a ProcessingResult) -> Self {
+    PlanFileRef { schema_version: SCHEMA_VERSION, args, result }
+  }
+}
+
+/// plan 형식의 JSON Schema를 만드는 함수. `dock_sorter schema`로 출력된다.
+pub fn plan_schema() -> schemars::Schema {
+  schemars::schema_for!(PlanFile)
+}
+
+/// 입력 인자와 계산 결과를 plan 파일 형식의 JSON 문자열로 변환하는 함수.
+/// HTTP API의 응답도 같은 형식을 사용하므로 응답을 그대로 plan 파일로 저장할 수 있다.
+pub fn plan_to_json(args: &Args, result: &ProcessingResult) -> Result<String, String> {
+  serde_json::to_string_pretty(&PlanFileRef::new(args, result))
+    .map_err(|e| format!("Failed to serialize plan: {e}"))
+}
+
+/// plan_to_json으로 만든(서명되었을 수도 있는) plan JSON을 plan 파일로 저장하는 함수.
+pub fn save_plan(path: &Path, json: &str) -> Result<(), String> {
+  fs::write(path, json).map_err(|e| format!("Failed to write plan file '{}': {e}", path.display()))
+}
+
+/// 저장된 plan 파일을 읽어 PlanFile로 복원하는 함수.
+/// 이 버전이 모르는 더 새로운 schema 버전의 파일이라면 잘못 해석하지 않도록 에러를 반환한다.
+pub fn load_plan(path: &Path) -> Result<PlanFile, String> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read plan file '{}': {e}", path.display()))?;
+  let invalid = |e: serde_json::Error| format!("Invalid plan file '{}': {e}", path.display());
+  let mut value: Value = serde_json::from_str(&content).map_err(invalid)?;
+  let version = value.get("schema_version").and_then(Value::as_u64).unwrap_or(1);
+  if version > u64::from(SCHEMA_VERSION) {
+    return Err(format!(
+      "Plan file '{}' uses schema version {version}, but this version of dock_sorter only understands up to {SCHEMA_VERSION}.",
+      path.display()
+    ));
+  }
+  // 이전 버전의 파일은 현재 형식으로 바꾼 뒤 읽는다.
+  if let Some(result) = value.get_mut("result") {
+    upgrade_result(result).map_err(invalid)?;
+  }
+  let mut plan: PlanFile = serde_json::from_value(value).map_err(invalid)?;
+  // 손으로 고친 파일의 잘못된 인자(예: per_page 0)가 다시 출력하거나 재계산할 때 문제를 일으키지 않도록 확인한다.
+  plan.args.validate_input().map_err(|e| format!("Invalid plan file '{}': {e}", path.display()))?;
+  plan.schema_version = SCHEMA_VERSION;
+  Ok(plan)
+}
+
+/// 그룹 구성(출력 순서, 그룹 종류, 우선순위, 도크)의 짧은 checksum(SHA-256의 앞 16자리 hex)을 만드는 함수.
+/// 다시 입력하거나 손으로 고친 plan은 그룹이 하나라도 다르면 checksum이 달라진다.
+pub fn plan_checksum(groups: &[Group]) -> String {
+  let mut checksum = PlanChecksum::default();
+  for group in groups {
+    checksum.add(group);
+  }
+  checksum.finish()
+}
+
+/// 그룹을 하나씩 더해 plan_checksum과 같은 값을 만드는 hasher. 그룹들을 모아두지 않고 출력하는 곳에서 사용한다.
+#[derive(Default)]
+pub struct PlanChecksum {
+  hasher: Sha256,
+}
+
+impl PlanChecksum {
+  /// 출력 순서상 다음 그룹을 더한다.
+  pub fn add(&mut self, group: &Group) {
+    let kind = match group.kind {
+      GroupKind::Regular => "regular",
+      GroupKind::Exception => "exception",
+    };
+    let docks: Vec<String> = group.docks.iter().map(|d| d.to_string()).collect();
+    self.hasher.update(format!("{kind}:{}:{}\n", group.tier.name(), docks.join(",")));
+  }
+
+  /// 지금까지 더한 그룹들의 checksum.
+  pub fn finish(self) -> String {
+    self.hasher.finalize().iter().take(8).map(|byte| format!("{byte:02x}")).collect()
+  }
+}
+
+/// plan 파일에 기록된 checksum을 그룹들로부터 다시 계산한 값과 비교하는 함수. 일치하면 checksum을 반환한다.
+pub fn verify_plan_checksum(plan: &PlanFile) -> Result<String, String> {
+  let Some(recorded) = &plan.result.checksum else {
+    return Err("Plan has no checksum (it was saved by an older version).".to_string());
+  };
+  let computed = plan_checksum(&plan.result.result_groups);
+  if *recorded != computed {
+    return Err(format!("Checksum mismatch: the plan records {recorded}, but its groups hash to {computed}. The plan was edited."));
+  }
+  Ok(computed)
+}
+
+/// ProcessingResult JSON을 읽는 함수. 실행 기록에 저장된 이전 형식의 결과도 현재 형식으로 바꿔 읽는다.
+pub fn result_from_json(json: &str) -> Result<ProcessingResult, serde_json::Error> {
+  let mut value: Value = serde_json::from_str(json)?;
+  upgrade_result(&mut value)?;
+  serde_json::from_value(value)
+}
+
+/// 1번 형식의 결과(result_groups가 도크 목록의 배열)를 현재 형식으로 바꾸는 함수. 이미 현재 형식이라면 그대로 둔다.
+/// 1번 형식에는 그룹 정보가 없으므로 첫 도크가 예외 도크인 그룹을 예외 그룹으로, 첫 도크의 우선순위를 tier로 삼는다.
+fn upgrade_result(result: &mut Value) -> Result<(), serde_json::Error> {
+  let Some(groups) = result.get("result_groups").and_then(Value::as_array) else {
+    return Ok(());
+  };
+  if !groups.iter().all(Value::is_array) {
+    return Ok(());
+  }
+  let exception_docks: BTreeSet<DockId> = serde_json::from_value(result["all_exception_docks"].clone())?;
+  let priorities: BTreeMap<DockId, Priority> = serde_json::from_value(result["priorities"].clone())?;
+  let mut upgraded = Vec::new();
+  for (index, group) in groups.iter().enumerate() {
+    let docks: Vec<DockId> = serde_json::from_value(group.clone())?;
+    let first = docks.first().copied().unwrap_or_default();
+    let (kind, tier) = match exception_docks.contains(&first) {
+      true => (GroupKind::Exception, Priority::Third),
+      false => (GroupKind::Regular, *priorities.get(&first).unwrap_or(&Priority::Third)),
+    };
+    // 1번 형식에는 예외 그룹이 몇 번째 -e 입력이었는지 남아있지 않다.
+    upgraded.push(Group { docks, kind, tier, index, exception_source: None, crew: None, slot: None, composition: None });
+  }
+  result["result_groups"] = serde_json::to_value(upgraded)?;
+  Ok(())
+}
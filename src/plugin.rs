@@ -0,0 +1,205 @@
+use std::{cell::RefCell, fs, path::Path};
+
+use wasmi::{Config, Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc, WasmParams, WasmResults};
+
+use crate::{models::DockId, rules::GroupingRules};
+
+// 플러그인 함수 호출 한 번에 허용하는 연료(실행 명령 수). 잘못된 플러그인이 무한 루프에 빠져도 plan 계산이 멈추지 않도록 한다.
+const FUEL_PER_CALL: u64 = 10_000_000;
+// 플러그인이 사용할 수 있는 최대 선형 메모리 크기.
+const MAX_MEMORY_BYTES: usize = 64 << 20;
+
+// 플러그인 Store에 붙는 상태. 메모리 사용량 제한만 담는다.
+struct PluginState {
+  limits: StoreLimits,
+}
+
+/// 샌드박스 안에서 실행되는 WASM 플러그인 (`--plugin strategy.wasm`).
+///
+/// 플러그인은 호스트 함수를 하나도 import할 수 없으며(파일, 네트워크, 시간 등에 접근할 수 없다),
+/// 아래 함수 중 필요한 것만 export하면 된다. 도크 번호는 u32 값을 i32로 그대로 넘긴다.
+///
+/// - 그룹핑 규칙 (`--rules`의 Rhai 스크립트와 같은 역할)
+///   - `should_break(group_ptr: i32, group_len: i32, next_dock: i32) -> i32`: 현재 그룹(u32 little-endian 배열)에
+///     next_dock을 붙이지 않고 새 그룹을 시작해야 한다면 0이 아닌 값
+///   - `target_size(first_dock: i32) -> i32`: first_dock으로 시작하는 그룹의 목표 크기. 0 이하라면 per-page를 따른다.
+/// - 출력 형식
+///   - `format(plan_ptr: i32, plan_len: i32) -> i64`: plan JSON(`{"args": ..., "result": ...}`)을 받아 출력할 UTF-8 문자열의
+///     위치를 `(ptr << 32) | len`으로 반환한다.
+///
+/// 호스트가 플러그인에 데이터를 넘길 때는 `alloc(len: i32) -> i32`로 받은 자리에 쓰고 해제하지 않으므로,
+/// 플러그인은 `memory`와 함께 `alloc`도 export해야 한다 (한 버퍼를 재사용해도 된다).
+pub struct WasmPlugin {
+  name: String,
+  store: RefCell<Store<PluginState>>,
+  memory: Option<Memory>,
+  alloc: Option<TypedFunc<i32, i32>>,
+  should_break: Option<TypedFunc<(i32, i32, i32), i32>>,
+  target_size: Option<TypedFunc<i32, i32>>,
+  format: Option<TypedFunc<(i32, i32), i64>>,
+  // 그룹핑 규칙 실행 중 처음 발생한 에러. 규칙 함수는 에러를 반환할 수 없으므로 여기에 남겨두고 계산이 끝난 뒤 확인한다.
+  error: RefCell<Option<String>>,
+}
+
+impl WasmPlugin {
+  /// WASM 모듈 파일을 읽어 인스턴스를 만드는 함수.
+  /// 호스트 함수를 import하거나, 알려진 함수를 하나도 export하지 않거나, export의 시그니처가 다르다면 에러를 반환한다.
+  pub fn load(path: &Path) -> Result<Self, String> {
+    let name = path.display().to_string();
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read plugin '{name}': {e}"))?;
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, &bytes).map_err(|e| format!("Invalid plugin '{name}': {e}"))?;
+    if let Some(import) = module.imports().next() {
+      return Err(format!(
+        "Plugin '{name}' imports `{}::{}`, but plugins cannot use host functions.",
+        import.module(),
+        import.name()
+      ));
+    }
+
+    let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).build();
+    let mut store = Store::new(&engine, PluginState { limits });
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+    let instance = Linker::<PluginState>::new(&engine)
+      .instantiate(&mut store, &module)
+      .and_then(|pre| pre.start(&mut store))
+      .map_err(|e| format!("Failed to start plugin '{name}': {e}"))?;
+
+    let alloc = typed_export(&instance, &store, &name, "alloc")?;
+    let should_break = typed_export(&instance, &store, &name, "should_break")?;
+    let target_size = typed_export(&instance, &store, &name, "target_size")?;
+    let format = typed_export(&instance, &store, &name, "format")?;
+    let memory = instance.get_memory(&store, "memory");
+
+    if should_break.is_none() && target_size.is_none() && format.is_none() {
+      return Err(format!("Plugin '{name}' exports none of `should_break`, `target_size` or `format`."));
+    }
+    if (should_break.is_some() || format.is_some()) && (memory.is_none() || alloc.is_none()) {
+      return Err(format!("Plugin '{name}' must export `memory` and `alloc` to receive data from the host."));
+    }
+    Ok(WasmPlugin {
+      name,
+      store: RefCell::new(store),
+      memory,
+      alloc,
+      should_break,
+      target_size,
+      format,
+      error: RefCell::new(None),
+    })
+  }
+
+  /// 플러그인이 그룹핑 규칙(`should_break` 또는 `target_size`)을 제공하는지 여부.
+  pub fn has_rules(&self) -> bool {
+    self.should_break.is_some() || self.target_size.is_some()
+  }
+
+  /// 플러그인이 출력 형식(`format`)을 제공하는지 여부.
+  pub fn has_formatter(&self) -> bool {
+    self.format.is_some()
+  }
+
+  /// 그룹핑 규칙 실행 중 발생한 첫 에러. 에러가 있었다면 계산된 plan은 규칙이 일부 적용되지 않은 것이다.
+  pub fn error(&self) -> Option<String> {
+    self.error.borrow().clone()
+  }
+
+  /// plan JSON을 플러그인의 `format`으로 넘겨 출력할 문자열을 받는 함수.
+  pub fn format(&self, plan_json: &str) -> Result<String, String> {
+    let Some(format) = self.format else {
+      return Err(format!("Plugin '{}' does not export `format`.", self.name));
+    };
+    let (ptr, len) = self.write_input(plan_json.as_bytes())?;
+    let packed = self.call(format, "format", (ptr, len))?;
+    // 상위 32비트는 위치, 하위 32비트는 길이이다.
+    let (out_ptr, out_len) = ((packed as u64 >> 32) as usize, (packed as u64 & 0xffff_ffff) as usize);
+    let store = self.store.borrow();
+    let memory = self.memory.expect("checked in load");
+    let bytes = memory
+      .data(&*store)
+      .get(out_ptr..out_ptr.saturating_add(out_len))
+      .ok_or_else(|| format!("Plugin '{}' returned output outside of its memory.", self.name))?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| format!("Plugin '{}' returned output that is not UTF-8.", self.name))
+  }
+
+  // bytes를 플러그인의 `alloc`으로 받은 자리에 쓰고 (위치, 길이)를 반환한다.
+  fn write_input(&self, bytes: &[u8]) -> Result<(i32, i32), String> {
+    let (Some(alloc), Some(memory)) = (self.alloc, self.memory) else {
+      return Err(format!("Plugin '{}' must export `memory` and `alloc`.", self.name));
+    };
+    let len = i32::try_from(bytes.len()).map_err(|_| format!("Input for plugin '{}' is too large.", self.name))?;
+    let ptr = self.call(alloc, "alloc", len)?;
+    memory
+      .write(&mut *self.store.borrow_mut(), ptr as u32 as usize, bytes)
+      .map_err(|e| format!("Failed to pass input to plugin '{}': {e}", self.name))?;
+    Ok((ptr, len))
+  }
+
+  // 연료를 채운 뒤 플러그인 함수를 호출한다.
+  fn call<Params: WasmParams, Results: WasmResults>(
+    &self,
+    func: TypedFunc<Params, Results>,
+    export: &str,
+    params: Params,
+  ) -> Result<Results, String> {
+    let mut store = self.store.borrow_mut();
+    store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+    func
+      .call(&mut *store, params)
+      .map_err(|e| format!("Plugin '{}' function `{export}` failed: {e}", self.name))
+  }
+
+  // 그룹핑 규칙 함수의 결과. 실패했다면 에러를 기록한 뒤 None을 반환하며, 이미 에러가 기록되었다면 더 이상 실행하지 않는다.
+  fn rule_result<T>(&self, run: impl FnOnce() -> Result<T, String>) -> Option<T> {
+    if self.error.borrow().is_some() {
+      return None;
+    }
+    match run() {
+      Ok(value) => Some(value),
+      Err(e) => {
+        *self.error.borrow_mut() = Some(e);
+        None
+      }
+    }
+  }
+}
+
+// 플러그인이 export한 함수를 찾는다. export가 없다면 None이고, 있지만 시그니처가 다르다면 에러를 반환한다.
+fn typed_export<Params: WasmParams, Results: WasmResults>(
+  instance: &Instance,
+  store: &Store<PluginState>,
+  name: &str,
+  export: &str,
+) -> Result<Option<TypedFunc<Params, Results>>, String> {
+  match instance.get_func(store, export) {
+    Some(func) => func
+      .typed::<Params, Results>(store)
+      .map(Some)
+      .map_err(|e| format!("Plugin '{name}' exports `{export}` with the wrong signature: {e}")),
+    None => Ok(None),
+  }
+}
+
+impl GroupingRules for WasmPlugin {
+  fn should_break(&self, current_group: &[DockId], next_dock: DockId) -> bool {
+    let Some(should_break) = self.should_break else {
+      return false;
+    };
+    let bytes: Vec<u8> = current_group.iter().flat_map(|dock| dock.get().to_le_bytes()).collect();
+    self
+      .rule_result(|| {
+        let (ptr, _) = self.write_input(&bytes)?;
+        self.call(should_break, "should_break", (ptr, current_group.len() as i32, next_dock.get() as i32))
+      })
+      .is_some_and(|result| result != 0)
+  }
+
+  fn target_size(&self, first_dock: DockId) -> Option<usize> {
+    let target_size = self.target_size?;
+    let size = self.rule_result(|| self.call(target_size, "target_size", first_dock.get() as i32))?;
+    usize::try_from(size).ok().filter(|&size| size > 0)
+  }
+}
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+
+use crate::{
+  cli::{Args, PostStep},
+  merge::SingleMerger,
+  models::{DockId, Group, GroupKind, Priority},
+  processor::PlanRequest,
+};
+
+/// 그룹들을 만든 뒤 출력 모양을 바꾸는 후처리 단계 하나 (--post).
+/// 그룹을 출력 순서대로 하나씩 받아, 순서가 정해진 그룹들을 emit에 넘긴다.
+/// 뒤의 그룹을 보아야 하는 단계는 그룹들을 들고 있다가 flush에서 모두 넘긴다.
+pub trait GroupTransform {
+  /// 만들어진 그룹 하나를 받는다.
+  fn push(&mut self, group: Group, emit: &mut dyn FnMut(Group));
+
+  /// 들고 있던 그룹들을 모두 넘긴다. 마지막 그룹을 받은 뒤 호출된다.
+  fn flush(&mut self, emit: &mut dyn FnMut(Group));
+}
+
+/// --post로 지정된 단계들과 --repeat-groups의 그룹 반복을 차례로 거쳐 그룹을 넘기는 후처리 pipeline.
+/// 넘기는 그룹들의 index는 출력 순서대로 다시 매긴다.
+pub struct PostPipeline<'a> {
+  steps: Vec<Box<dyn GroupTransform + 'a>>,
+  // 다음으로 넘길 그룹의 index
+  next_index: usize,
+}
+
+impl<'a> PostPipeline<'a> {
+  /// 후처리 단계가 없다면 None을 반환한다. priorities는 annotate 단계가 그룹의 우선순위 구성을 세는 데 사용한다.
+  pub fn new(request: PlanRequest<'a>, priorities: &'a BTreeMap<DockId, Priority>) -> Option<Self> {
+    let args = request.args;
+    let mut steps: Vec<Box<dyn GroupTransform + 'a>> = post_steps(args)
+      .into_iter()
+      .map(|step| -> Box<dyn GroupTransform + 'a> {
+        match step {
+          PostStep::MergeSingles => Box::new(SingleMerger::new(args)),
+          // 짝 도크가 나뉘거나 그룹핑 규칙이 정한 경계가 바뀌지 않도록, --pair-offset이나 --rules가 있다면 크기를 고르지 않는다.
+          PostStep::Balance => Box::new(Balancer { args, enabled: args.pair_offset.is_none() && request.rules.is_none(), pending: None }),
+          PostStep::Reorder => Box::new(Reorderer { groups: Vec::new() }),
+          PostStep::Annotate => Box::new(Annotator { priorities }),
+        }
+      })
+      .collect();
+    // 그룹 반복은 다른 단계들이 모두 끝난 뒤에 적용하므로, 합치거나 크기를 고르는 단계는 반복된 그룹을 보지 않는다.
+    if Priority::ALL.into_iter().any(|tier| args.repeats_of(tier) > 1) {
+      steps.push(Box::new(Repeater { args }));
+    }
+    (!steps.is_empty()).then_some(PostPipeline { steps, next_index: 0 })
+  }
+
+  /// 만들어진 그룹 하나를 첫 단계에 넘긴다.
+  pub fn push(&mut self, group: Group, emit: &mut dyn FnMut(Group)) {
+    let next_index = &mut self.next_index;
+    push_through(&mut self.steps, group, &mut |mut group: Group| {
+      group.index = *next_index;
+      *next_index += 1;
+      emit(group);
+    });
+  }
+
+  /// 앞 단계부터 차례로 들고 있던 그룹들을 넘긴다. 마지막 그룹을 받은 뒤 호출한다.
+  pub fn flush(&mut self, emit: &mut dyn FnMut(Group)) {
+    let next_index = &mut self.next_index;
+    flush_through(&mut self.steps, &mut |mut group: Group| {
+      group.index = *next_index;
+      *next_index += 1;
+      emit(group);
+    });
+  }
+}
+
+// group을 steps의 첫 단계에 넘기고, 그 단계가 넘기는 그룹들은 다음 단계들로 이어서 넘긴다.
+fn push_through(steps: &mut [Box<dyn GroupTransform + '_>], group: Group, emit: &mut dyn FnMut(Group)) {
+  match steps.split_first_mut() {
+    Some((step, rest)) => step.push(group, &mut |group| push_through(rest, group, emit)),
+    None => emit(group),
+  }
+}
+
+// 앞 단계가 flush하며 넘기는 그룹들을 뒤 단계들에 넘긴 뒤 뒤 단계들을 flush한다.
+fn flush_through(steps: &mut [Box<dyn GroupTransform + '_>], emit: &mut dyn FnMut(Group)) {
+  if let Some((step, rest)) = steps.split_first_mut() {
+    step.flush(&mut |group| push_through(rest, group, emit));
+    flush_through(rest, emit);
+  }
+}
+
+/// 실제로 적용되는 후처리 단계들. --merge-singles가 지정되었고 --post에 merge-singles가 없다면 맨 앞에 더한다.
+pub fn post_steps(args: &Args) -> Vec<PostStep> {
+  let mut steps = args.post.clone();
+  if args.merge_singles && !steps.contains(&PostStep::MergeSingles) {
+    steps.insert(0, PostStep::MergeSingles);
+  }
+  steps
+}
+
+/// group의 첫 도크와 우선순위에 따른 그룹당 도크 수.
+pub fn per_page(args: &Args, group: &Group) -> usize {
+  let (fpp, spp, gpp) = args.per_pages_at(group.docks[0]);
+  let per_page = match group.tier {
+    Priority::First => fpp,
+    Priority::Second => spp,
+    Priority::Third => gpp,
+  };
+  usize::from(per_page)
+}
+
+// --post balance: 출력 순서상 이어진 3차 일반 그룹 둘 중 앞 그룹이 2개 이상 크다면 앞 그룹 끝의 도크들을
+// 뒤 그룹 앞으로 옮겨 크기 차이를 1 이하로 만든다. 도크들의 출력 순서와 라벨 수는 바뀌지 않는다.
+struct Balancer<'a> {
+  args: &'a Args,
+  enabled: bool,
+  // 다음 그룹과 크기를 고르기 위해 들고 있는 그룹
+  pending: Option<Group>,
+}
+
+impl Balancer<'_> {
+  fn balance(&self, prev: &mut Group, next: &mut Group) {
+    let balanceable = |group: &Group| {
+      group.kind == GroupKind::Regular
+        && group.tier == Priority::Third
+        && !self.args.solo.iter().any(|range| group.docks.iter().any(|&dock| range.contains(dock)))
+    };
+    let moved = prev.docks.len().saturating_sub(next.docks.len()) / 2;
+    if !self.enabled || moved == 0 || !balanceable(prev) || !balanceable(next) {
+      return;
+    }
+    let split = prev.docks.len() - moved;
+    // 옮긴 뒤 뒤 그룹이 같은 per-page를 따르도록 --pp-range 구간 경계를 넘어 옮기지 않는다.
+    if self.args.per_page_section(prev.docks[split]) != self.args.per_page_section(next.docks[0]) {
+      return;
+    }
+    let tail = prev.docks.split_off(split);
+    next.docks.splice(0..0, tail);
+  }
+}
+
+impl GroupTransform for Balancer<'_> {
+  fn push(&mut self, mut group: Group, emit: &mut dyn FnMut(Group)) {
+    if let Some(mut prev) = self.pending.take() {
+      self.balance(&mut prev, &mut group);
+      emit(prev);
+    }
+    self.pending = Some(group);
+  }
+
+  fn flush(&mut self, emit: &mut dyn FnMut(Group)) {
+    if let Some(group) = self.pending.take() {
+      emit(group);
+    }
+  }
+}
+
+// --post reorder: 1차 그룹들을 맨 앞으로, 2차 그룹들을 그 뒤로 옮긴다. 같은 우선순위 안의 순서는 유지된다.
+// 모든 그룹을 보아야 하므로 그룹들을 모두 들고 있다가 flush에서 넘긴다.
+struct Reorderer {
+  groups: Vec<Group>,
+}
+
+impl GroupTransform for Reorderer {
+  fn push(&mut self, group: Group, _emit: &mut dyn FnMut(Group)) {
+    self.groups.push(group);
+  }
+
+  fn flush(&mut self, emit: &mut dyn FnMut(Group)) {
+    self.groups.sort_by_key(|group| group.tier);
+    for group in self.groups.drain(..) {
+      emit(group);
+    }
+  }
+}
+
+// --post annotate: 그룹의 우선순위별 도크 수를 composition에 기록한다. 예외 도크와 우선순위가 없는 도크는 3차로 센다.
+struct Annotator<'a> {
+  priorities: &'a BTreeMap<DockId, Priority>,
+}
+
+impl GroupTransform for Annotator<'_> {
+  fn push(&mut self, mut group: Group, emit: &mut dyn FnMut(Group)) {
+    let mut composition = BTreeMap::new();
+    for dock in &group.docks {
+      *composition.entry(*self.priorities.get(dock).unwrap_or(&Priority::Third)).or_insert(0) += 1;
+    }
+    group.composition = Some(composition);
+    emit(group);
+  }
+
+  fn flush(&mut self, _emit: &mut dyn FnMut(Group)) {}
+}
+
+// --repeat-groups: 각 그룹을 우선순위에 따른 횟수만큼 연속으로 넘긴다.
+struct Repeater<'a> {
+  args: &'a Args,
+}
+
+impl GroupTransform for Repeater<'_> {
+  fn push(&mut self, group: Group, emit: &mut dyn FnMut(Group)) {
+    for _ in 1..self.args.repeats_of(group.tier) {
+      emit(group.clone());
+    }
+    emit(group);
+  }
+
+  fn flush(&mut self, _emit: &mut dyn FnMut(Group)) {}
+}
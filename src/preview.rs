@@ -0,0 +1,240 @@
+use std::{env, fmt};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+  cli::{ImageProtocol, PreviewStyle},
+  models::{DockId, Group, GroupKind, Priority},
+  processor::ProcessingResult,
+  raster::{Bitmap, LabelFont, LabelSize, render_label},
+  template::GroupNumber,
+};
+
+// 큰 글자 글꼴. 글자마다 폭 3, 높이 5이며 '#'이 채워진 칸이다.
+// 숫자, 라벨의 기호(@, *), 라벨 템플릿의 글에 쓰이는 영어 대문자와 몇 가지 문장 부호가 있다.
+const GLYPHS: [(char, [&str; GLYPH_HEIGHT]); 42] = [
+  ('0', ["###", "# #", "# #", "# #", "###"]),
+  ('1', [" # ", "## ", " # ", " # ", "###"]),
+  ('2', ["###", "  #", "###", "#  ", "###"]),
+  ('3', ["###", "  #", "###", "  #", "###"]),
+  ('4', ["# #", "# #", "###", "  #", "  #"]),
+  ('5', ["###", "#  ", "###", "  #", "###"]),
+  ('6', ["###", "#  ", "###", "# #", "###"]),
+  ('7', ["###", "  #", "  #", "  #", "  #"]),
+  ('8', ["###", "# #", "###", "# #", "###"]),
+  ('9', ["###", "# #", "###", "  #", "###"]),
+  ('G', ["###", "#  ", "# #", "# #", "###"]),
+  ('E', ["###", "#  ", "###", "#  ", "###"]),
+  ('X', ["# #", "# #", " # ", "# #", "# #"]),
+  ('@', [" # ", "###", "###", "###", " # "]),
+  ('*', ["# #", " # ", "###", " # ", "# #"]),
+  ('A', [" # ", "# #", "###", "# #", "# #"]),
+  ('B', ["## ", "# #", "## ", "# #", "## "]),
+  ('C', ["###", "#  ", "#  ", "#  ", "###"]),
+  ('D', ["## ", "# #", "# #", "# #", "## "]),
+  ('F', ["###", "#  ", "## ", "#  ", "#  "]),
+  ('H', ["# #", "# #", "###", "# #", "# #"]),
+  ('I', ["###", " # ", " # ", " # ", "###"]),
+  ('J', ["  #", "  #", "  #", "# #", "###"]),
+  ('K', ["# #", "# #", "## ", "# #", "# #"]),
+  ('L', ["#  ", "#  ", "#  ", "#  ", "###"]),
+  ('M', ["# #", "###", "# #", "# #", "# #"]),
+  ('N', ["## ", "# #", "# #", "# #", "# #"]),
+  ('O', [" # ", "# #", "# #", "# #", " # "]),
+  ('P', ["###", "# #", "###", "#  ", "#  "]),
+  ('Q', [" # ", "# #", "# #", "## ", " ##"]),
+  ('R', ["## ", "# #", "## ", "# #", "# #"]),
+  ('S', [" ##", "#  ", " # ", "  #", "## "]),
+  ('T', ["###", " # ", " # ", " # ", " # "]),
+  ('U', ["# #", "# #", "# #", "# #", "###"]),
+  ('V', ["# #", "# #", "# #", "# #", " # "]),
+  ('W', ["# #", "# #", "# #", "###", "# #"]),
+  ('Y', ["# #", "# #", " # ", " # ", " # "]),
+  ('Z', ["###", "  #", " # ", "#  ", "###"]),
+  ('-', ["   ", "   ", "###", "   ", "   "]),
+  ('.', ["   ", "   ", "   ", "   ", " # "]),
+  (':', ["   ", " # ", "   ", " # ", "   "]),
+  ('/', ["  #", "  #", " # ", "#  ", "#  "]),
+];
+/// 큰 글자의 높이(칸 수). 폭은 GLYPH_WIDTH이다.
+pub const GLYPH_HEIGHT: usize = 5;
+pub const GLYPH_WIDTH: usize = 3;
+
+/// 큰 글자 글꼴에서 글자 하나의 모양을 찾는 함수. 위에서부터 한 줄씩이며 '#'이 채워진 칸이다.
+/// 영어 소문자는 대문자로 그리며, 글꼴에 없는 글자는 None이다.
+pub fn glyph(c: char) -> Option<&'static [&'static str; GLYPH_HEIGHT]> {
+  let c = c.to_ascii_uppercase();
+  GLYPHS.iter().find(|(glyph_char, _)| *glyph_char == c).map(|(_, rows)| rows)
+}
+
+// --preview-image에서 라벨 하나의 픽셀 크기와 라벨 사이 간격. 터미널 몇 줄 정도의 크기이다.
+const IMAGE_LABEL_SIZE: LabelSize = LabelSize { width: 240, height: 144 };
+const IMAGE_LABEL_GAP: u32 = 12;
+
+// Kitty 그래픽 프로토콜에서 escape 한 번에 보낼 수 있는 최대 base64 길이
+const KITTY_CHUNK: usize = 4096;
+
+// 라벨 안쪽의 최소 폭. 그룹 번호와 기호가 들어갈 자리를 남긴다.
+const MIN_INNER_WIDTH: usize = 12;
+
+// 라벨 테두리와 채운 칸에 쓰는 문자들: (모서리 ┌ ┐ └ ┘, 가로, 세로, 채운 칸)
+struct Glyphs {
+  corners: [char; 4],
+  horizontal: char,
+  vertical: char,
+  fill: char,
+}
+
+impl Glyphs {
+  fn of(style: PreviewStyle) -> Self {
+    match style {
+      PreviewStyle::Unicode => Glyphs { corners: ['┌', '┐', '└', '┘'], horizontal: '─', vertical: '│', fill: '█' },
+      PreviewStyle::Ascii => Glyphs { corners: ['+', '+', '+', '+'], horizontal: '-', vertical: '|', fill: '#' },
+    }
+  }
+}
+
+/// 그룹마다 한 줄로 라벨 모양을 나란히 그려 out에 쓰는 함수.
+/// 라벨에는 큰 도크 번호와 함께 그룹 번호(출력 순서, 1부터)와 1차(@), 2차(*), 예외(EX) 기호가 들어간다.
+pub fn write_preview(out: &mut impl fmt::Write, result: &ProcessingResult, style: PreviewStyle) -> fmt::Result {
+  let glyphs = Glyphs::of(style);
+  // 모든 라벨의 폭을 가장 긴 도크 번호에 맞춰 같게 한다.
+  let longest = result.result_groups.iter().flat_map(|group| &group.docks).map(|dock| dock.to_string().len()).max();
+  let inner_width = longest.map_or(0, |digits| digits * 4 + 1).max(MIN_INNER_WIDTH);
+
+  for (i, group) in result.result_groups.iter().enumerate() {
+    let labels: Vec<Vec<String>> =
+      group.docks.iter().map(|&dock| label_lines(dock, i + 1, label_marker(group, dock, result), inner_width, &glyphs)).collect();
+    let height = labels.first().map_or(0, Vec::len);
+    for row in 0..height {
+      let line: Vec<&str> = labels.iter().map(|label| label[row].as_str()).collect();
+      writeln!(out, "{}", line.join(" "))?;
+    }
+  }
+  Ok(())
+}
+
+/// 도크 라벨에 붙는 기호. 예외 그룹의 도크는 우선순위와 관계없이 EX이다.
+pub fn label_marker(group: &Group, dock: DockId, result: &ProcessingResult) -> &'static str {
+  match (group.kind, result.priorities.get(&dock)) {
+    (GroupKind::Exception, _) => "EX",
+    (GroupKind::Regular, Some(Priority::First)) => "@",
+    (GroupKind::Regular, Some(Priority::Second)) => "*",
+    _ => "",
+  }
+}
+
+// 라벨 하나를 줄들로 그린다. 첫 줄과 마지막 줄은 테두리이다.
+fn label_lines(dock: DockId, group_index: usize, marker: &str, inner_width: usize, glyphs: &Glyphs) -> Vec<String> {
+  let [top_left, top_right, bottom_left, bottom_right] = glyphs.corners;
+  let border = glyphs.horizontal.to_string().repeat(inner_width);
+  let side = glyphs.vertical;
+  let mut lines = vec![format!("{top_left}{border}{top_right}")];
+
+  // 첫 줄: 왼쪽에 그룹 번호, 오른쪽에 기호
+  let group_tag = format!(" G{group_index}");
+  let gap = inner_width.saturating_sub(group_tag.width() + marker.width() + 1);
+  lines.push(format!("{side}{group_tag}{}{marker} {side}", " ".repeat(gap)));
+
+  // 큰 숫자들을 가운데에 맞춘다.
+  let digits: Vec<&[&str; GLYPH_HEIGHT]> = dock.to_string().chars().filter_map(glyph).collect();
+  let fill = glyphs.fill.to_string();
+  lines.extend((0..GLYPH_HEIGHT).map(|row| {
+    let big: Vec<String> = digits.iter().map(|digit| digit[row].replace('#', &fill)).collect();
+    let big = big.join(" ");
+    let left = (inner_width - big.chars().count()) / 2;
+    let right = inner_width - big.chars().count() - left;
+    format!("{side}{}{big}{}{side}", " ".repeat(left), " ".repeat(right))
+  }));
+
+  lines.push(format!("{bottom_left}{border}{bottom_right}"));
+  lines
+}
+
+/// --preview-image의 프로토콜을 정하는 함수. auto라면 터미널이 설정하는 환경 변수로 알아내며,
+/// 알 수 없다면 None을 반환한다.
+pub fn resolve_protocol(protocol: ImageProtocol) -> Option<ImageProtocol> {
+  if protocol != ImageProtocol::Auto {
+    return Some(protocol);
+  }
+  let var = |name: &str| env::var(name).unwrap_or_default();
+  let (term, term_program) = (var("TERM"), var("TERM_PROGRAM"));
+  if env::var_os("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" || term == "xterm-ghostty" {
+    Some(ImageProtocol::Kitty)
+  } else if term_program == "iTerm.app" || term_program == "WezTerm" {
+    Some(ImageProtocol::Iterm)
+  } else if term.starts_with("foot") || term.starts_with("mlterm") || term.contains("sixel") {
+    Some(ImageProtocol::Sixel)
+  } else {
+    None
+  }
+}
+
+/// 그룹마다 라벨들을 나란히 붙인 이미지 한 장을 protocol의 escape 문자열로 out에 쓰는 함수.
+/// 라벨의 배치와 내용은 write_preview와 같다. protocol은 resolve_protocol로 정해진 값이어야 한다.
+pub fn write_preview_images(out: &mut impl fmt::Write, result: &ProcessingResult, protocol: ImageProtocol) -> fmt::Result {
+  for (i, group) in result.result_groups.iter().enumerate() {
+    let labels: Vec<Bitmap> =
+      group.docks.iter().map(|&dock| render_label(dock, GroupNumber::new(i + 1), label_marker(group, dock, result), IMAGE_LABEL_SIZE, None, &LabelFont::block())).collect();
+    let image = Bitmap::beside(&labels, IMAGE_LABEL_GAP);
+    if image.width == 0 || image.height == 0 {
+      continue;
+    }
+    match protocol {
+      ImageProtocol::Kitty => write_kitty(out, &image)?,
+      ImageProtocol::Iterm => write_iterm(out, &image)?,
+      ImageProtocol::Sixel | ImageProtocol::Auto => write_sixel(out, &image)?,
+    }
+    writeln!(out)?;
+  }
+  Ok(())
+}
+
+// Kitty 그래픽 프로토콜: PNG를 base64로 나누어 보낸다. 마지막 조각이 아닌 조각에는 m=1이 붙는다.
+fn write_kitty(out: &mut impl fmt::Write, image: &Bitmap) -> fmt::Result {
+  let encoded = BASE64.encode(image.to_png(None).map_err(|_| fmt::Error)?);
+  let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(KITTY_CHUNK).collect();
+  for (i, chunk) in chunks.iter().enumerate() {
+    let more = u8::from(i + 1 < chunks.len());
+    // base64 문자열은 ASCII이므로 조각도 항상 올바른 UTF-8이다.
+    let chunk = std::str::from_utf8(chunk).map_err(|_| fmt::Error)?;
+    match i {
+      0 => write!(out, "\x1b_Ga=T,f=100,m={more};{chunk}\x1b\\")?,
+      _ => write!(out, "\x1b_Gm={more};{chunk}\x1b\\")?,
+    }
+  }
+  Ok(())
+}
+
+// iTerm2 inline image: 파일 내용(PNG)을 base64로 한 번에 보낸다.
+fn write_iterm(out: &mut impl fmt::Write, image: &Bitmap) -> fmt::Result {
+  let png = image.to_png(None).map_err(|_| fmt::Error)?;
+  write!(out, "\x1b]1337;File=inline=1;size={}:{}\x07", png.len(), BASE64.encode(&png))
+}
+
+// Sixel: 위에서부터 6픽셀 높이의 띠마다 흰색(0번)과 검은색(1번) 픽셀을 차례로 칠한다.
+// 한 문자가 세로 6픽셀을 나타내며(63 + 비트), 같은 문자가 이어지면 "!개수문자"로 줄인다.
+fn write_sixel(out: &mut impl fmt::Write, image: &Bitmap) -> fmt::Result {
+  write!(out, "\x1bPq\"1;1;{};{}#0;2;100;100;100#1;2;0;0;0", image.width, image.height)?;
+  for band in (0..image.height).step_by(6) {
+    for (color, ink) in [(0, false), (1, true)] {
+      write!(out, "#{color}")?;
+      let sixels: Vec<char> = (0..image.width)
+        .map(|x| {
+          let bits = (0..6).filter(|dy| band + dy < image.height && image.ink(x, band + dy) == ink).fold(0, |bits, dy| bits | 1 << dy);
+          char::from(63 + bits as u8)
+        })
+        .collect();
+      for run in sixels.chunk_by(|a, b| a == b) {
+        match run.len() {
+          1..=3 => run.iter().try_for_each(|c| out.write_char(*c))?,
+          len => write!(out, "!{len}{}", run[0])?,
+        }
+      }
+      // $는 같은 띠의 처음으로, -는 다음 띠로 이동한다.
+      out.write_char(if ink { '-' } else { '$' })?;
+    }
+  }
+  write!(out, "\x1b\\")
+}
@@ -1,100 +1,905 @@
-use crate::{cli::Args, models::Priority, processor::ProcessingResult};
+use std::{
+  fmt,
+  io::{self, BufWriter, StdoutLock, Write},
+};
+
+use clap::ValueEnum;
+
+use crate::{
+  cli::{Args, CrewAssignment, HeaderSection, OutputEncoding, OutputFormat, PreviewStyle, TableStyle},
+  diff::PlanDiff,
+  encoding::encode_text,
+  history::RunSummary,
+  i18n::tr,
+  layout::{Align, Columns},
+  lint::Overlap,
+  models::{DockId, DockRange, Group, GroupKind, Priority, PriorityRange},
+  placeholders::GroupPlaceholders,
+  plan::PlanChecksum,
+  preview::{resolve_protocol, write_preview, write_preview_images},
+  post::post_steps,
+  processor::ProcessingResult,
+  queue::PrintJob,
+  reconcile::Reconciliation,
+  schedule::{arrival_window, format_window},
+};
+
+// 텍스트 출력에서 한 그룹의 도크들 사이에 쓰는 기본 구분자 (--dock-separator)
+const DEFAULT_DOCK_SEPARATOR: &str = ", ";
 
 pub fn print_results(args: &Args, result_data: &ProcessingResult) {
-  // 처리 도크의 min..max 도크 range를 출력한다.
-  println!("\nProcessing dock range: {} - {}\n", args.min, args.max);
-  // 1차, 2차 그룹, 일반 그룹의 각 처리당 per-page들을 출력한다.
-  println!("Docks per group (1st priority): {}", result_data.fpp);
-  println!("Docks per group (2nd priority): {}", result_data.spp);
-  println!(
-    "Docks per group (3rd priority/general): {}",
-    result_data.gpp
-  );
+  print_warnings(result_data);
+  // 범위가 매우 크면 출력 내용도 매우 커지므로 문자열로 모으지 않고 stdout으로 바로 쓴다.
+  // 출력이 중간에 끊긴 경우(예: `| head`)에는 조용히 출력을 멈춘다.
+  let mut out = StdoutWriter { out: BufWriter::new(io::stdout().lock()), encoding: args.encoding };
+  if write_results(&mut out, args, result_data).is_ok() {
+    let _ = out.out.flush();
+  }
+}
+
+/// 그룹이 계산되는 즉시 텍스트로 출력하는 출력기. process_docks_with의 emit으로 그룹을 넘긴다.
+/// 그룹을 모아두지 않으므로 매우 큰 범위를 출력하더라도 메모리 사용량이 그룹 수에 비례하지 않는다.
+/// 전체 그룹을 알아야 하는 출력 형식은 지원하지 않는다 (supports 참고).
+pub struct StreamingPrinter<'a> {
+  args: &'a Args,
+  out: StdoutWriter,
+  lines: GroupLines<'a>,
+  stats: PlanStats,
+  checksum: PlanChecksum,
+  // 머리글을 이미 썼는지 여부. 머리글은 첫 그룹과 함께 받는 계산 결과로 쓴다.
+  started: bool,
+  // 출력이 중간에 끊겼다면(예: `| head`) 나머지는 조용히 버린다.
+  closed: bool,
+}
+
+impl<'a> StreamingPrinter<'a> {
+  /// args의 출력 형식을 그룹을 모으지 않고 출력할 수 있는지 여부.
+  /// 텍스트 출력(--stats-only 포함)만 지원하며, 열 맞춤, 페이지 번호, 표, 미리보기처럼 전체 그룹이 필요한 것은 제외한다.
+  pub fn supports(args: &Args) -> bool {
+    args.stats_only
+      || args.format == OutputFormat::Text
+        && !args.print0
+        && !args.align
+        && !args.page_numbers
+        && args.preview.is_none()
+        && args.preview_image.is_none()
+  }
+
+  pub fn new(args: &'a Args) -> Self {
+    StreamingPrinter {
+      args,
+      out: StdoutWriter { out: BufWriter::new(io::stdout().lock()), encoding: args.encoding },
+      lines: GroupLines::new(args, None),
+      stats: PlanStats::default(),
+      checksum: PlanChecksum::default(),
+      started: false,
+      closed: false,
+    }
+  }
+
+  /// 계산된 그룹 하나를 출력한다. result_data는 그룹을 제외한 계산 결과이다.
+  pub fn push(&mut self, result_data: &ProcessingResult, group: Group) {
+    self.start(result_data);
+    self.stats.add(result_data, &group);
+    // --stats-only의 요약에는 checksum이 없다.
+    if !self.closed && !self.args.stats_only {
+      self.checksum.add(&group);
+      self.closed = self.lines.write(&mut self.out, result_data, &group).is_err();
+    }
+  }
+
+  /// 마지막 그룹 뒤의 요약이나 라벨 수, checksum을 출력한다.
+  pub fn finish(mut self, result_data: &ProcessingResult) {
+    self.start(result_data);
+    if self.closed {
+      return;
+    }
+    let written = match self.args.stats_only {
+      true => write_stats(&mut self.out, self.args, &self.stats),
+      false => self
+        .lines
+        .finish(&mut self.out)
+        .and_then(|()| write_footer(&mut self.out, self.args, &self.stats, Some(&self.checksum.finish()))),
+    };
+    if written.is_ok() {
+      let _ = self.out.out.flush();
+    }
+  }
+
+  // 처음 한 번 경고와 머리글을 출력한다.
+  fn start(&mut self, result_data: &ProcessingResult) {
+    if self.started {
+      return;
+    }
+    self.started = true;
+    print_warnings(result_data);
+    if !self.args.stats_only {
+      self.closed = write_header(&mut self.out, self.args, result_data).is_err();
+    }
+  }
+}
+
+/// 플러그인처럼 외부에서 만든 plan 출력 내용을 경고 메세지와 함께 encoding으로 출력하는 함수.
+pub fn print_formatted_results(result_data: &ProcessingResult, text: &str, encoding: OutputEncoding) {
+  print_warnings(result_data);
+  let mut out = io::stdout().lock();
+  // 출력이 중간에 끊긴 경우(예: `| head`)에는 조용히 출력을 멈춘다.
+  if out.write_all(&encode_text(text, encoding)).is_ok() {
+    let _ = out.flush();
+  }
+}
+
+// 처리 중 발생한 경고 메시지를 stderr로 출력한다.
+fn print_warnings(result_data: &ProcessingResult) {
+  for warning in &result_data.warnings {
+    eprintln!("{warning}");
+  }
+}
+
+/// fmt::Write로 쓰인 내용을 --encoding의 바이트로 바꾸고 버퍼를 거쳐 stdout으로 내보내는 어댑터.
+struct StdoutWriter {
+  out: BufWriter<StdoutLock<'static>>,
+  encoding: OutputEncoding,
+}
+
+impl fmt::Write for StdoutWriter {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.out.write_all(&encode_text(s, self.encoding)).map_err(|_| fmt::Error)
+  }
+}
+
+/// 최종 plan 출력 내용을 문자열로 만드는 함수. 화면 출력과 클립보드 복사에 같은 내용을 사용한다.
+pub fn format_results(args: &Args, result_data: &ProcessingResult) -> String {
+  let mut out = String::new();
+  // String에 쓰는 것은 실패하지 않는다.
+  let _ = write_results(&mut out, args, result_data);
+  out
+}
+
+/// 그룹 수, 우선순위별 도크 수, 라벨 수 요약(--stats-only의 출력)을 문자열로 만드는 함수. 채팅 알림에 함께 보낸다.
+pub fn format_stats(args: &Args, result_data: &ProcessingResult) -> String {
+  let mut out = String::new();
+  let _ = write_stats(&mut out, args, &PlanStats::of(result_data));
+  out
+}
+
+/// 적용된 옵션들(--spread, serpentine 순회, 도착 스케줄, 짝 도크, 예외 그룹 채우기, strict mode)을 설명하는 머리글 줄들을 out에 쓰는 함수.
+fn write_option_lines(out: &mut impl fmt::Write, args: &Args) -> fmt::Result {
+  // --spread가 적용되었다면 2차로 올리는 거리를 출력한다.
+  if args.spread > 0 {
+    writeln!(out, "{}", tr("header.spread", &[("distance", &args.spread)]))?;
+  }
+  // serpentine 순회가 적용되었다면 한 줄당 도크 수를 출력한다.
+  if let Some(row_size) = args.row_size {
+    writeln!(out, "{}", tr("header.serpentine", &[("row_size", &row_size)]))?;
+  }
+  // 도착 스케줄이 적용되었다면 시간대 길이를 출력한다.
+  if args.arrivals.is_some() {
+    writeln!(out, "{}", tr("header.arrival_windows", &[("minutes", &args.arrival_window)]))?;
+  }
+  // 작업조 배정이 적용되었다면 작업조 수와 배정 방식을 출력한다.
+  if let Some(crews) = args.crews {
+    let key = match args.crew_assignment {
+      CrewAssignment::RoundRobin => "header.crews_round_robin",
+      CrewAssignment::Balanced => "header.crews_balanced",
+    };
+    writeln!(out, "{}", tr(key, &[("count", &crews)]))?;
+  }
+  // 작업 시간대가 지정되었다면 시간대들과 도크당 예상 작업 시간을 출력한다.
+  if !args.slots.is_empty() {
+    let slots = args.slots.join(", ");
+    match args.dock_minutes {
+      Some(minutes) => writeln!(out, "{}", tr("header.slots_timed", &[("slots", &slots), ("minutes", &minutes)]))?,
+      None => writeln!(out, "{}", tr("header.slots", &[("slots", &slots)]))?,
+    }
+  }
+  // 짝 도크 그루핑이 적용되었다면 offset을 출력한다.
+  if let Some(offset) = args.pair_offset {
+    writeln!(out, "{}", tr("header.pairs", &[("offset", &offset)]))?;
+  }
+  // 예외 그룹을 채운다면 그렇다고 출력한다.
+  if args.pad_exceptions {
+    writeln!(out, "{}", tr("header.pad_exceptions", &[]))?;
+  }
   // 만약 strict mode가 적용되었다면 모드 적용이 됐음을 출력한다.
   if args.strict_first {
-    println!("\nStrict mode applyed for 1st priority groups.");
+    writeln!(out, "{}", tr("header.strict_first", &[]))?;
   }
   if args.strict_second {
-    println!("\nStrict mode applyed for 2nd priority groups.");
+    writeln!(out, "{}", tr("header.strict_second", &[]))?;
   }
+  // 후처리 단계가 있다면 적용 순서대로 출력한다.
+  let steps = post_steps(args);
+  if !steps.is_empty() {
+    let names: Vec<String> =
+      steps.iter().filter_map(|step| step.to_possible_value()).map(|value| value.get_name().to_string()).collect();
+    writeln!(out, "{}", tr("header.post", &[("steps", &names.join(", "))]))?;
+  }
+  // 그룹을 반복한다면 우선순위별 반복 횟수를 출력한다.
+  let repeats = Priority::ALL.map(|tier| args.repeats_of(tier));
+  if repeats.iter().any(|&count| count > 1) {
+    let [first, second, third] = repeats;
+    writeln!(out, "{}", tr("header.repeat_groups", &[("first", &first), ("second", &second), ("third", &third)]))?;
+  }
+
+  Ok(())
+}
+
+/// plan 출력 내용을 out에 쓰는 함수.
+fn write_results(out: &mut impl fmt::Write, args: &Args, result_data: &ProcessingResult) -> fmt::Result {
+  // --stats-only라면 머리글과 그룹 목록 없이 요약만 출력한다.
+  if args.stats_only {
+    return write_stats(out, args, &PlanStats::of(result_data));
+  }
+  // --print0이라면 머리글 없이 그룹들만 구분 문자로 나누어 출력한다.
+  if args.print0 {
+    for group in &result_data.result_groups {
+      let docks: Vec<String> = group.docks.iter().map(|d| d.to_string()).collect();
+      write!(out, "{}\0", docks.join("\x1f"))?;
+    }
+    return Ok(());
+  }
+  // --format lines라면 머리글 없이 한 줄에 도크 하나씩 "그룹 번호<TAB>도크<TAB>기호"로 출력한다.
+  // 그룹 번호는 1부터 시작하며, 기호는 --mark와 상관없이 1차는 @, 2차는 *, 그 외에는 비어있다.
+  // 작업조가 배정되었다면 "<TAB>작업조"가 이어진다.
+  if args.format == OutputFormat::Lines {
+    for (i, group) in result_data.result_groups.iter().enumerate() {
+      for &dock in &group.docks {
+        let marker = match (group.kind, result_data.priorities.get(&dock)) {
+          (GroupKind::Regular, Some(Priority::First)) => "@",
+          (GroupKind::Regular, Some(Priority::Second)) => "*",
+          _ => "",
+        };
+        match group.crew {
+          Some(crew) => writeln!(out, "{}\t{dock}\t{marker}\t{crew}", i + 1)?,
+          None => writeln!(out, "{}\t{dock}\t{marker}", i + 1)?,
+        }
+      }
+    }
+    return Ok(());
+  }
+  write_header(out, args, result_data)?;
+
+  // 5. 결과 출력
+  // --preview라면 그룹마다 라벨 모양을 나란히 그린다.
+  if let Some(style) = args.preview {
+    write_preview(out, result_data, style)?;
+    return write_footer(out, args, &PlanStats::of(result_data), result_data.checksum.as_deref());
+  }
+  // --preview-image라면 라벨들을 이미지로 그려 터미널 그래픽으로 출력한다.
+  if let Some(protocol) = args.preview_image {
+    match resolve_protocol(protocol) {
+      Some(protocol) => write_preview_images(out, result_data, protocol)?,
+      None => {
+        eprintln!("{}", tr("warning.preview_image", &[]));
+        write_preview(out, result_data, PreviewStyle::Unicode)?;
+      }
+    }
+    return write_footer(out, args, &PlanStats::of(result_data), result_data.checksum.as_deref());
+  }
+  // --format table이라면 그룹마다 한 행인 표로 출력한다.
+  if args.format == OutputFormat::Table {
+    for line in group_table(args, result_data).render_table(args.style == TableStyle::Box) {
+      writeln!(out, "{line}")?;
+    }
+    return write_footer(out, args, &PlanStats::of(result_data), result_data.checksum.as_deref());
+  }
+  let mut lines = GroupLines::new(args, Some(result_data.result_groups.len()));
+  for group in &result_data.result_groups {
+    lines.write(out, result_data, group)?;
+  }
+  lines.finish(out)?;
+  write_footer(out, args, &PlanStats::of(result_data), result_data.checksum.as_deref())
+}
+
+/// 텍스트 출력의 그룹 줄들을 차례로 쓰는 상태. 그룹을 모두 모아두지 않아도 그룹이 계산되는 대로 쓸 수 있다.
+struct GroupLines<'a> {
+  args: &'a Args,
+  // --align이라면 그룹들을 열 레이아웃에 모았다가 마지막에 열을 맞춰 출력한다.
+  columns: Option<Columns>,
+  // 직전에 출력한 그룹의 도착 시간대. 시간대가 바뀔 때마다 시간대 헤더를 출력하기 위해 사용한다.
+  last_window: Option<Option<u32>>,
+  // 직전에 출력한 그룹의 작업 시간대. 마찬가지로 작업 시간대가 바뀔 때마다 헤더를 출력한다.
+  last_slot: Option<String>,
+  // 지금까지 쓴 그룹 수
+  written: usize,
+  // 전체 그룹 수. 그룹을 계산되는 대로 쓸 때는 알 수 없다 (--page-numbers에 필요하다).
+  total_groups: Option<usize>,
+}
 
+impl<'a> GroupLines<'a> {
+  fn new(args: &'a Args, total_groups: Option<usize>) -> Self {
+    GroupLines { args, columns: args.align.then(|| Columns::new(" ")), last_window: None, last_slot: None, written: 0, total_groups }
+  }
+
+  /// 다음 그룹의 줄(과 시간대가 바뀌었다면 시간대 헤더)을 쓴다.
+  fn write(&mut self, out: &mut impl fmt::Write, result_data: &ProcessingResult, group: &Group) -> fmt::Result {
+    let args = self.args;
+    let i = self.written;
+    self.written += 1;
+    if let Some(slot) = group.slot.as_deref()
+      && self.last_slot.as_deref() != Some(slot)
+    {
+      let header = tr("slot.header", &[("start", &slot)]);
+      match &mut self.columns {
+        Some(columns) => columns.push_text(header),
+        None => writeln!(out, "{header}")?,
+      }
+      self.last_slot = Some(slot.to_string());
+    }
+    // 도착 스케줄이 있다면 그룹의 첫 도크가 속한 시간대를 구해 시간대가 바뀌었을 때 헤더를 출력한다.
+    // plan 파일에서 읽어온 결과에는 빈 그룹이 있을 수도 있으므로 첫 도크가 없다면 시간대가 없는 것으로 본다.
+    if !result_data.arrivals.is_empty() {
+      let window = group.docks.first().and_then(|&d| arrival_window(&result_data.arrivals, d, args.arrival_window));
+      if self.last_window != Some(window) {
+        let header = format!("[{}]", format_window(window, args.arrival_window));
+        match &mut self.columns {
+          Some(columns) => columns.push_text(header),
+          None => writeln!(out, "{header}")?,
+        }
+        self.last_window = Some(window);
+      }
+    }
+    // 각 그룹으로부터 1차 2차 기호가 포매팅된 String을 담는 그룹 Vec
+    let formatted_group = formatted_docks(group, args, result_data);
+    // 작업조가 배정되었다면 줄 앞에 작업조를 표시한다.
+    let crew_tag = group.crew.map(|crew| tr("crew.tag", &[("crew", &crew)]));
+    // --page-numbers라면 작업조 다음에 전체 그룹 중 몇 번째 페이지인지 표시한다.
+    let page_tag = self
+      .total_groups
+      .filter(|_| args.page_numbers)
+      .map(|total_groups| tr("page.tag", &[("page", &(i + 1)), ("pages", &total_groups)]));
+    // --composition이라면 줄 끝에 그룹의 우선순위 구성을 붙인다.
+    let composition_tag = args.composition.then(|| composition_tag(group, result_data));
+    let dock_separator = args.dock_separator.as_deref().unwrap_or(DEFAULT_DOCK_SEPARATOR);
+    // --line-prefix가 지정되었다면 자리표시자를 그룹의 값으로 채워 줄 맨 앞에 붙인다.
+    let prefix = args.line_prefix.as_deref().map(|template| GroupPlaceholders::new(args, group, i + 1).render(template));
+    match &mut self.columns {
+      // 열을 맞출 때는 마지막 도크를 제외한 각 도크 뒤에 구분자(뒤쪽 공백 제외)를 붙여 하나의 셀로 만든다.
+      // 그룹마다 도크 수가 다르므로 우선순위 구성은 도크 열이 밀리지 않도록 작업조와 페이지 다음에 놓는다.
+      Some(columns) => {
+        let last = formatted_group.len().saturating_sub(1);
+        let cells = prefix
+          .map(|prefix| prefix.trim_end().to_string())
+          .into_iter()
+          .chain(crew_tag)
+          .chain(page_tag)
+          .chain(composition_tag)
+          .chain(
+            formatted_group
+              .into_iter()
+              .enumerate()
+              .map(|(i, cell)| if i < last { format!("{cell}{}", dock_separator.trim_end()) } else { cell }),
+          )
+          .collect();
+        columns.push_row(cells);
+      }
+      // 최종적으로 formatted_group을 join을 이용하여 도크 구분자(기본값 ", ")로 구분하여 출력해준다.
+      None => {
+        let tags: Vec<String> = crew_tag.into_iter().chain(page_tag).collect();
+        let mut line = formatted_group.join(dock_separator);
+        if !tags.is_empty() {
+          line = format!("{} {line}", tags.join(" "));
+        }
+        if let Some(tag) = composition_tag {
+          line = format!("{line} {tag}");
+        }
+        if let Some(prefix) = prefix {
+          line = format!("{prefix}{line}");
+        }
+        // --group-separator가 지정되었다면 그룹 사이에 줄바꿈 대신 쓴다. 마지막 그룹 뒤에는 항상 줄바꿈이 온다.
+        // 다음 그룹이 있는지는 다음 그룹을 쓸 때 알 수 있으므로 구분자는 다음 그룹의 앞에 쓴다.
+        match args.group_separator.as_deref() {
+          Some(separator) if i > 0 => write!(out, "{separator}{line}")?,
+          Some(_) => write!(out, "{line}")?,
+          None => writeln!(out, "{line}")?,
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// 열을 맞추고 있었다면 모은 줄들을, --group-separator라면 마지막 그룹 뒤의 줄바꿈을 쓴다.
+  fn finish(self, out: &mut impl fmt::Write) -> fmt::Result {
+    match self.columns {
+      Some(columns) => {
+        for line in columns.render() {
+          writeln!(out, "{line}")?;
+        }
+      }
+      None if self.args.group_separator.is_some() && self.written > 0 => writeln!(out)?,
+      None => {}
+    }
+    Ok(())
+  }
+}
+
+/// 출력 순서 위의 머리글(실행 ID, 날짜, 현장, 범위, per-page, 옵션, 예외 그룹, 입력 도크, 구분선)을 out에 쓰는 함수.
+fn write_header(out: &mut impl fmt::Write, args: &Args, result_data: &ProcessingResult) -> fmt::Result {
+  // --no-header로 숨기지 않은 머리글 부분만 출력한다.
+  let show = |section: HeaderSection| !args.no_header.contains(&section);
+  // --stamp로 붙인 실행 ID와 시각을 가장 먼저 출력한다.
+  if show(HeaderSection::Stamp)
+    && let Some(stamp) = &result_data.stamp
+  {
+    writeln!(out, "{}", tr("header.stamp", &[("run_id", &stamp.run_id), ("timestamp", &stamp.timestamp)]))?;
+  }
+  // 작업 날짜가 지정되었다면 날짜를 출력한다.
+  if show(HeaderSection::Date)
+    && let Some(date) = &args.date
+  {
+    writeln!(out, "{}", tr("header.date", &[("date", date)]))?;
+  }
+  // --site, --shift가 지정되었다면 현장과 근무조를 출력한다.
+  if show(HeaderSection::Site) {
+    match (&args.site, &args.shift) {
+      (Some(site), Some(shift)) => writeln!(out, "{}", tr("header.site_shift", &[("site", site), ("shift", shift)]))?,
+      (Some(site), None) => writeln!(out, "{}", tr("header.site", &[("site", site)]))?,
+      (None, Some(shift)) => writeln!(out, "{}", tr("header.shift", &[("shift", shift)]))?,
+      (None, None) => {}
+    }
+  }
+  // 처리 도크의 min..max 도크 range를 출력한다.
+  if show(HeaderSection::Range) {
+    writeln!(out, "{}", tr("header.range", &[("min", &args.min), ("max", &args.max)]))?;
+  }
+  // 1차, 2차 그룹, 일반 그룹의 각 처리당 per-page들을 출력한다.
+  if show(HeaderSection::PerPage) {
+    writeln!(out, "{}", tr("header.first_per_page", &[("count", &result_data.fpp)]))?;
+    writeln!(out, "{}", tr("header.second_per_page", &[("count", &result_data.spp)]))?;
+    writeln!(out, "{}", tr("header.third_per_page", &[("count", &result_data.gpp)]))?;
+    // --pp-range 구간들의 per-page를 출력한다.
+    for section in &args.per_page_ranges {
+      writeln!(out, "{}", tr("header.range_per_page", &[("range", &section.range), ("count", &section.per_page)]))?;
+    }
+  }
+  if show(HeaderSection::Options) {
+    write_option_lines(out, args)?;
+  }
   // 만약 final_exception_groups이 있는 경우 해당 그룹들을 출력해준다.
-  if !result_data.final_exception_groups.is_empty() {
-    println!("Exception groups (printed together, in order of their first dock):");
+  if show(HeaderSection::Exceptions) && !result_data.final_exception_groups.is_empty() {
+    writeln!(out, "{}", tr("header.exception_groups", &[]))?;
     // final_exception_groups의 각 그룹들을 순회한다.
     for ex_group in &result_data.final_exception_groups {
       // 각 ex_group을 iter().map하여 각 dock인 d를 string으로 만든뒤 이것을 다시 Vec으로 collect한뒤 이 Vec을
       // join을 이용하여 하나의 콤마 separate된 문자열로 만든뒤 println!의 placeholder인 {}부분에 출력한다.
-      println!(
+      writeln!(
+        out,
         "  - [{}]",
         ex_group
           .iter()
           .map(|d| d.to_string())
           .collect::<Vec<_>>()
           .join(", ")
-      );
+      )?;
     }
   }
-  println!("\n--- Docks ---");
-  if !args.first_priority.is_empty() {
-    let first_input: Vec<String> = args
-      .first_priority
-      .clone()
-      .iter()
-      .flat_map(|d| d.to_owned())
-      .map(|d| d.to_string())
-      .collect();
-    println!("first: {}", first_input.join(", "));
-  }
-  if !args.second_priority.is_empty() {
-    let second_input: Vec<String> = args
-      .second_priority
-      .clone()
-      .iter()
-      .flat_map(|d| d.to_owned())
-      .map(|d| d.to_string())
-      .collect();
-    println!("second: {}", second_input.join(", "));
-  }
-  
-  if args.print_marker {
-    println!("\n--- Output Order (1st: @, 2nd: *) ---");
-  } else {
-    println!("\n--- Output Order ---");
+  if show(HeaderSection::Docks) {
+    writeln!(out, "{}", tr("header.docks", &[]))?;
+    if !args.first_priority.is_empty() {
+      let first_input = priority_input(&args.first_priority);
+      writeln!(out, "{}", tr("header.first_docks", &[("docks", &first_input)]))?;
+    }
+    if !args.second_priority.is_empty() {
+      let second_input = priority_input(&args.second_priority);
+      writeln!(out, "{}", tr("header.second_docks", &[("docks", &second_input)]))?;
+    }
+    if !args.skip.is_empty() {
+      let skipped_input: Vec<String> = args.skip.iter().map(|range| range.to_string()).collect();
+      writeln!(out, "{}", tr("header.skipped_docks", &[("docks", &skipped_input.join(", "))]))?;
+    }
+    if !args.solo.is_empty() {
+      let solo_input: Vec<String> = args.solo.iter().map(|range| range.to_string()).collect();
+      writeln!(out, "{}", tr("header.solo_docks", &[("docks", &solo_input.join(", "))]))?;
+    }
   }
 
-  // 5. 결과 출력
-  // 최종 결과물인 result_groups를 루핑하여 각 group을 얻는다.
-  for group in &result_data.result_groups {
-    // 각 그룹으로부터 1차 2차 기호가 포매팅된 String을 담는 그룹 Vec
-    let formatted_group: Vec<String> = group
-      .iter()
-      .map(|&d| {
-        // 현재 도크인 d가 all_exception_docks에 포함된 도크, 즉 예외 그룹이라면
-        if result_data.all_exception_docks.contains(&d) {
-          // 기호 없이 그대로 String으로 변환한다.
-          d.to_string()
-        }
-        // 예외 도크가 아니라면
-        else {
-          // print_marker flag가 설정되었다면
-          if args.print_marker {
-            // priorities에 도크 d를 키로 넣어서 해당 도크의 Priority를 match 시켜서
-            match result_data.priorities.get(&d) {
-              // 각 Priority에 맞는 기호를 붙여 출력한다.
-              Some(Priority::First) => format!("{d}@"),
-              Some(Priority::Second) => format!("{d}*"),
-              Some(Priority::Third) => d.to_string(),
-              None => d.to_string(),
-            }
-          // print_marker가 Set되지 않았다면 그냥 출력한다.
-          } else {
-            d.to_string()
+  // --banner가 지정되었다면 template의 {legend}를 기호 설명으로 채워 기본 구분선 대신 출력한다.
+  if show(HeaderSection::Banner) {
+    match (&args.banner, args.print_marker) {
+      (Some(template), print_marker) => {
+        let legend = match print_marker {
+          true => tr("legend.markers", &[]),
+          false => String::new(),
+        };
+        let banner = template.replace("{legend}", &legend).replace("{date}", args.date.as_deref().unwrap_or(""));
+        writeln!(out, "\n{banner}")?;
+      }
+      (None, true) => writeln!(out, "{}", tr("header.output_order_marked", &[]))?,
+      (None, false) => writeln!(out, "{}", tr("header.output_order", &[]))?,
+    }
+  }
+  Ok(())
+}
+
+/// plan 요약(--stats-only, 출력 아래의 라벨 수)에 쓰는 우선순위별 그룹 수와 도크 수. 그룹을 하나씩 더해 센다.
+#[derive(Default)]
+struct PlanStats {
+  // [1차, 2차, 3차, 예외]별 그룹 수와 도크 수
+  groups: [usize; 4],
+  docks: [usize; 4],
+}
+
+impl PlanStats {
+  fn of(result_data: &ProcessingResult) -> Self {
+    let mut stats = PlanStats::default();
+    for group in &result_data.result_groups {
+      stats.add(result_data, group);
+    }
+    stats
+  }
+
+  fn add(&mut self, result_data: &ProcessingResult, group: &Group) {
+    let column = |kind: GroupKind, priority: Priority| match (kind, priority) {
+      (GroupKind::Exception, _) => 3,
+      (GroupKind::Regular, Priority::First) => 0,
+      (GroupKind::Regular, Priority::Second) => 1,
+      (GroupKind::Regular, Priority::Third) => 2,
+    };
+    self.groups[column(group.kind, group.tier)] += 1;
+    for dock in &group.docks {
+      self.docks[column(group.kind, *result_data.priorities.get(dock).unwrap_or(&Priority::Third))] += 1;
+    }
+  }
+
+  /// 출력할 라벨 수와 필요한 페이지 수. label_pages를 참고.
+  fn label_pages(&self, args: &Args) -> (usize, usize) {
+    let labels: usize = self.docks.iter().sum();
+    let pages = match args.labels_per_sheet {
+      Some(per_sheet) => labels.div_ceil(per_sheet as usize),
+      None => self.groups.iter().sum(),
+    };
+    (labels, pages)
+  }
+}
+
+// 그룹 수, 우선순위별 도크 수, 출력할 라벨 수를 out에 쓴다. 라벨은 도크마다 한 장이고, 그룹마다 한 페이지이다.
+fn write_stats(out: &mut impl fmt::Write, args: &Args, stats: &PlanStats) -> fmt::Result {
+  let total_groups: usize = stats.groups.iter().sum();
+  let total_docks: usize = stats.docks.iter().sum();
+  // 처리 범위 중 어느 그룹에도 들어가지 않은 도크는 --skip으로 제외된 도크이다.
+  let skipped = args.processing_range().dock_count().saturating_sub(total_docks as u64);
+
+  let [first, second, third, exception] = stats.groups;
+  writeln!(
+    out,
+    "{}",
+    tr(
+      "stats.groups",
+      &[("total", &total_groups), ("first", &first), ("second", &second), ("third", &third), ("exception", &exception)]
+    )
+  )?;
+  let [first, second, third, exception] = stats.docks;
+  writeln!(
+    out,
+    "{}",
+    tr(
+      "stats.docks",
+      &[
+        ("total", &total_docks),
+        ("first", &first),
+        ("second", &second),
+        ("third", &third),
+        ("exception", &exception),
+        ("skipped", &skipped),
+      ]
+    )
+  )?;
+  let (labels, pages) = stats.label_pages(args);
+  match args.labels_per_sheet {
+    Some(per_sheet) => writeln!(out, "{}", tr("stats.labels_sheets", &[("labels", &labels), ("pages", &pages), ("per_sheet", &per_sheet)])),
+    None => writeln!(out, "{}", tr("stats.labels", &[("labels", &labels), ("pages", &pages)])),
+  }
+}
+
+/// 출력할 라벨 수와 필요한 페이지 수를 계산하는 함수. 라벨은 (--repeat-groups로 반복된 그룹을 포함하여) 그룹의 도크마다 한 장이다.
+/// 페이지는 그룹마다 한 페이지이며, --labels-per-sheet가 지정되었다면 라벨들을 출력 순서대로 채운 용지 장 수이다.
+pub fn label_pages(args: &Args, result_data: &ProcessingResult) -> (usize, usize) {
+  PlanStats::of(result_data).label_pages(args)
+}
+
+// 출력 순서 아래의 라벨 수와 checksum 줄을 out에 쓴다. checksum이 없는 이전 버전의 plan이라면 checksum 줄은 쓰지 않는다.
+fn write_footer(out: &mut impl fmt::Write, args: &Args, stats: &PlanStats, checksum: Option<&str>) -> fmt::Result {
+  // 인쇄 전에 충분한 라벨 용지를 넣을 수 있도록 필요한 페이지 수를 알려준다.
+  if !args.no_header.contains(&HeaderSection::Labels) {
+    let (labels, pages) = stats.label_pages(args);
+    match args.labels_per_sheet {
+      Some(per_sheet) => writeln!(out, "{}", tr("footer.labels_sheets", &[("labels", &labels), ("pages", &pages), ("per_sheet", &per_sheet)]))?,
+      None => writeln!(out, "{}", tr("footer.labels", &[("labels", &labels), ("pages", &pages)]))?,
+    }
+  }
+  if !args.no_header.contains(&HeaderSection::Checksum)
+    && let Some(checksum) = checksum
+  {
+    writeln!(out, "{}", tr("footer.checksum", &[("checksum", &checksum)]))?;
+  }
+  Ok(())
+}
+
+/// 그룹의 도크들을 출력할 문자열들로 만드는 함수. --mark가 지정되었다면 1차, 2차 도크에 기호를 붙인다.
+fn formatted_docks(group: &Group, args: &Args, result_data: &ProcessingResult) -> Vec<String> {
+  group
+    .docks
+    .iter()
+    .map(|&d| {
+      // 현재 그룹이 예외 그룹이라면
+      if group.kind == GroupKind::Exception {
+        // 기호 없이 그대로 String으로 변환한다.
+        d.to_string()
+      }
+      // 예외 도크가 아니라면
+      else {
+        // print_marker flag가 설정되었다면
+        if args.print_marker {
+          // priorities에 도크 d를 키로 넣어서 해당 도크의 Priority를 match 시켜서
+          match result_data.priorities.get(&d) {
+            // 각 Priority에 맞는 기호를 붙여 출력한다.
+            Some(Priority::First) => format!("{d}@"),
+            Some(Priority::Second) => format!("{d}*"),
+            Some(Priority::Third) => d.to_string(),
+            None => d.to_string(),
           }
+        // print_marker가 Set되지 않았다면 그냥 출력한다.
+        } else {
+          d.to_string()
         }
-      })
-      .collect();
-    // 최종적으로 formatted_group을 join을 이용하여 comma separator로 구분하여 출력해준다.
-    println!("{}", formatted_group.join(", "));
+      }
+    })
+    .collect()
+}
+
+/// `--format table`의 표를 만드는 함수. 그룹 번호, (도착 스케줄이 있다면) 시간대, 도크 목록, 우선순위 구성, 크기를 열로 갖는다.
+fn group_table(args: &Args, result_data: &ProcessingResult) -> Columns {
+  let with_windows = !result_data.arrivals.is_empty();
+  let with_crews = result_data.result_groups.iter().any(|group| group.crew.is_some());
+  let with_slots = result_data.result_groups.iter().any(|group| group.slot.is_some());
+  let mut header = vec![tr("table.group", &[])];
+  if with_slots {
+    header.push(tr("table.slot", &[]));
+  }
+  if with_windows {
+    header.push(tr("table.window", &[]));
+  }
+  if with_crews {
+    header.push(tr("table.crew", &[]));
+  }
+  header.extend([tr("table.docks", &[]), tr("table.composition", &[]), tr("table.size", &[])]);
+  let size_column = header.len() - 1;
+
+  let mut table = Columns::new("  ").align(0, Align::Right).align(size_column, Align::Right);
+  table.push_row(header);
+  for (i, group) in result_data.result_groups.iter().enumerate() {
+    let mut row = vec![(i + 1).to_string()];
+    if with_slots {
+      row.push(group.slot.clone().unwrap_or_default());
+    }
+    if with_windows {
+      let window = group.docks.first().and_then(|&d| arrival_window(&result_data.arrivals, d, args.arrival_window));
+      row.push(format_window(window, args.arrival_window));
+    }
+    if with_crews {
+      row.push(group.crew.map(|crew| crew.to_string()).unwrap_or_default());
+    }
+    row.extend([
+      formatted_docks(group, args, result_data).join(", "),
+      composition(group, result_data),
+      group.docks.len().to_string(),
+    ]);
+    table.push_row(row);
+  }
+  table
+}
+
+/// 그룹의 우선순위 구성을 "1st×2 3rd×1" 형식으로 만드는 함수. 예외 그룹은 예외 그룹임을 표시한다.
+fn composition(group: &Group, result_data: &ProcessingResult) -> String {
+  if group.kind == GroupKind::Exception {
+    return tr("table.exception", &[]);
+  }
+  composition_parts(group, result_data).join(" ")
+}
+
+// -f, -s로 입력된 범위들의 도크를 하나씩 콤마로 나열한다 (예: "65-66 71" → "65, 66, 71"). 제외 범위의 도크에는 `!`를 붙인다.
+// 입력된 도크 수는 validate_input에서 상한을 확인하므로 범위를 펼쳐도 된다.
+fn priority_input(ranges: &[PriorityRange]) -> String {
+  let docks: Vec<String> = ranges
+    .iter()
+    .flat_map(|range| {
+      let prefix = if range.excluded { "!" } else { "" };
+      range.range.docks().map(move |dock| format!("{prefix}{dock}"))
+    })
+    .collect();
+  docks.join(", ")
+}
+
+// --composition으로 그룹 줄 끝에 붙이는 우선순위 구성 표시 (예: "[1st×2, 3rd×1]").
+fn composition_tag(group: &Group, result_data: &ProcessingResult) -> String {
+  match group.kind {
+    GroupKind::Exception => format!("[{}]", tr("table.exception", &[])),
+    GroupKind::Regular => format!("[{}]", composition_parts(group, result_data).join(", ")),
+  }
+}
+
+// 그룹의 우선순위별 도크 수를 "1st×2"처럼 나타낸 것들. 도크가 없는 우선순위는 빠진다.
+fn composition_parts(group: &Group, result_data: &ProcessingResult) -> Vec<String> {
+  Priority::ALL
+    .into_iter()
+    .filter_map(|priority| {
+      let count = group
+        .docks
+        .iter()
+        .filter(|d| *result_data.priorities.get(d).unwrap_or(&Priority::Third) == priority)
+        .count();
+      (count > 0).then(|| format!("{}×{count}", priority.short_name()))
+    })
+    .collect()
+}
+
+/// 도크 목록을 "[51, 52, 53]" 형식의 문자열로 만든다.
+fn format_group(group: &[DockId]) -> String {
+  format!(
+    "[{}]",
+    group.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")
+  )
+}
+
+/// 두 plan의 비교 결과(PlanDiff)를 출력하는 함수.
+pub fn print_plan_diff(diff: &PlanDiff) {
+  println!("{}", tr("diff.header", &[]));
+  if diff.is_empty() {
+    println!("{}", tr("diff.none", &[]));
+    return;
+  }
+
+  // 삭제된 그룹과 추가된 그룹을 출력한다.
+  if !diff.removed_groups.is_empty() {
+    println!("{}", tr("diff.removed", &[]));
+    for group in &diff.removed_groups {
+      println!("  - {}", format_group(group));
+    }
+  }
+  if !diff.added_groups.is_empty() {
+    println!("{}", tr("diff.added", &[]));
+    for group in &diff.added_groups {
+      println!("  + {}", format_group(group));
+    }
+  }
+
+  // 그룹이 바뀐 도크들을 "도크: 이전 그룹 -> 새 그룹" 형식으로 출력한다.
+  if !diff.moved_docks.is_empty() {
+    println!("{}", tr("diff.moved", &[]));
+    for mv in &diff.moved_docks {
+      let describe = |group: &Option<(usize, Vec<DockId>)>| match group {
+        Some((index, docks)) => tr("diff.group", &[("index", index), ("docks", &format_group(docks))]),
+        None => tr("diff.not_planned", &[]),
+      };
+      println!("  {}: {} -> {}", mv.dock, describe(&mv.old_group), describe(&mv.new_group));
+    }
+  }
+
+  // 우선순위가 바뀐 도크들을 출력한다.
+  if !diff.priority_changes.is_empty() {
+    println!("{}", tr("diff.priority_changes", &[]));
+    for (dock, old_prio, new_prio) in &diff.priority_changes {
+      println!("  {dock}: {} -> {}", old_prio.short_name(), new_prio.short_name());
+    }
+  }
+}
+
+/// 이전 plan 대비 그대로 유지된 그룹 수와 다시 출력해야 하는 그룹 수를 출력하는 함수.
+pub fn print_reprint_summary(diff: &PlanDiff, result_data: &ProcessingResult) {
+  let total = result_data.result_groups.len();
+  let reprint = diff.added_groups.len();
+  println!(
+    "{}",
+    tr("diff.reprint_summary", &[("unchanged", &(total - reprint)), ("total", &total), ("reprint", &reprint)])
+  );
+}
+
+/// 여러 입력 범위에 함께 나타난 도크들과 그 처리 결과를 stderr로 출력하는 함수.
+pub fn print_overlap_report(overlaps: &[Overlap]) {
+  eprintln!("{}", tr("overlaps.header", &[]));
+  if overlaps.is_empty() {
+    eprintln!("{}", tr("overlaps.none", &[]));
+    return;
+  }
+  for overlap in overlaps {
+    let resolution = tr(overlap.resolution.label_key(), &[]);
+    eprintln!(
+      "{}",
+      tr(
+        "overlaps.line",
+        &[("docks", &overlap.docks), ("sources", &overlap.sources.join(", ")), ("resolution", &resolution)]
+      )
+    );
+  }
+}
+
+/// plan과 실제 스캔 순서를 비교한 결과(Reconciliation)를 출력하는 함수.
+pub fn print_reconcile_report(report: &Reconciliation, planned: usize) {
+  println!("{}", tr("reconcile.header", &[]));
+  println!("{}", tr("reconcile.summary", &[("scans", &report.scans), ("planned", &planned)]));
+  if report.is_clean() {
+    println!("{}", tr("reconcile.clean", &[]));
+    return;
+  }
+  if !report.out_of_order.is_empty() {
+    println!("{}", tr("reconcile.out_of_order", &[]));
+    for scan in &report.out_of_order {
+      let line = tr(
+        "reconcile.out_of_order_line",
+        &[("dock", &scan.dock), ("scan", &scan.scan_index), ("group", &scan.group), ("after", &scan.after_group)],
+      );
+      println!("  {line}");
+    }
+  }
+  let dock_lists = [
+    ("reconcile.missed", &report.missed),
+    ("reconcile.unplanned", &report.unplanned),
+    ("reconcile.rescanned", &report.rescanned),
+  ];
+  for (key, docks) in dock_lists {
+    if !docks.is_empty() {
+      println!("{}", tr(key, &[("docks", &format_dock_ranges(docks))]));
+    }
+  }
+}
+
+// 오름차순 도크 목록의 연속된 도크들을 "51-55, 60"처럼 범위로 줄여 쓴다.
+fn format_dock_ranges(docks: &[DockId]) -> String {
+  let mut ranges: Vec<DockRange> = Vec::new();
+  for &dock in docks {
+    match ranges.last_mut() {
+      Some(last) if last.end.checked_add(1) == Some(dock) => last.end = dock,
+      _ => ranges.push(DockRange::single(dock)),
+    }
+  }
+  ranges.iter().map(|range| range.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// `queue list`에서 인쇄 작업을 "id  상태  제출 시각  프린터  그룹  도크들" 형식으로 출력하는 함수.
+/// 실패한 작업은 다음 줄에 실패 이유를 출력한다.
+pub fn print_queue_list(jobs: &[PrintJob]) {
+  if jobs.is_empty() {
+    println!("{}", tr("queue.empty", &[]));
+    return;
+  }
+  let mut columns = Columns::new("  ").align(4, Align::Right);
+  for job in jobs {
+    let docks: Vec<String> = job.labels.iter().map(|label| label.dock.to_string()).collect();
+    columns.push_row(vec![
+      format!("#{}", job.id),
+      job.status.name().to_string(),
+      job.submitted_at.clone(),
+      job.printer.device.clone(),
+      format!("G{}", job.group_index),
+      docks.join(", "),
+    ]);
+    if let Some(error) = &job.error {
+      columns.push_text(format!("    {error}"));
+    }
+  }
+  for line in columns.render() {
+    println!("{line}");
+  }
+}
+
+/// 실행 기록 목록을 "id  시각  그룹 수  명령줄" 형식으로 출력하는 함수.
+pub fn print_history_list(runs: &[RunSummary]) {
+  if runs.is_empty() {
+    println!("{}", tr("history.empty", &[]));
+    return;
+  }
+  // 명령줄에 한글 등이 섞여도 열이 어긋나지 않도록 표시 폭 기준으로 맞춘다.
+  let mut columns = Columns::new("  ").align(2, Align::Right);
+  for run in runs {
+    columns.push_row(vec![
+      format!("#{}", run.id),
+      run.created_at.clone(),
+      format!("{} groups", run.group_count),
+      run.command_line.clone(),
+    ]);
+  }
+  for line in columns.render() {
+    println!("{line}");
   }
 }
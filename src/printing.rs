@@ -0,0 +1,389 @@
+use std::{
+  io::{self, BufRead, Write},
+  path::Path,
+  thread,
+  time::Duration,
+};
+
+use crate::{
+  barcode::code128,
+  cli::{Args, PrinterLanguage},
+  i18n::tr,
+  models::DockId,
+  preview::label_marker,
+  processor::ProcessingResult,
+  queue::{JobStatus, PrintJob, PrintQueue, PrinterSettings, QueuedLabel},
+  raster::{Bitmap, LabelFont, LabelSize, MM_PER_INCH, render_template},
+  template::{FieldAlign, FieldKind, GroupNumber, LabelTemplate, load_template},
+};
+
+// 블루투스 주소로 지정할 때의 접두사 (예: rfcomm:00:11:22:33:44:55/1)
+const RFCOMM_PREFIX: &str = "rfcomm:";
+// 채널을 지정하지 않았을 때의 RFCOMM 채널. 대부분의 이동식 프린터는 Serial Port Profile을 1번 채널에 둔다.
+const DEFAULT_RFCOMM_CHANNEL: u8 = 1;
+// 시리얼 포트에 쓰기를 기다리는 최대 시간. 프린터가 흐름 제어로 멈춘 경우 영원히 기다리지 않도록 한다.
+const SERIAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// --serial로 지정된 프린터 연결 대상.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PrinterTarget {
+  // 시리얼 포트 경로 (/dev/ttyUSB0, /dev/rfcomm0, COM3)
+  Serial(String),
+  // 블루투스 주소(표기 순서)와 RFCOMM 채널
+  Rfcomm { address: [u8; 6], channel: u8 },
+}
+
+/// --serial 값을 해석하는 함수. `rfcomm:`으로 시작하면 블루투스 주소, 그 외에는 시리얼 포트 경로이다.
+pub fn parse_printer_target(device: &str) -> Result<PrinterTarget, String> {
+  let Some(rest) = device.strip_prefix(RFCOMM_PREFIX) else {
+    return Ok(PrinterTarget::Serial(device.to_string()));
+  };
+  let invalid = || format!("Invalid Bluetooth address '{device}'. Expected rfcomm:AA:BB:CC:DD:EE:FF or rfcomm:AA:BB:CC:DD:EE:FF/CHANNEL.");
+  let (address, channel) = match rest.split_once('/') {
+    Some((address, channel)) => (address, channel.parse::<u8>().ok().filter(|c| (1..=30).contains(c)).ok_or_else(invalid)?),
+    None => (rest, DEFAULT_RFCOMM_CHANNEL),
+  };
+  let bytes: Vec<u8> = address.split(':').map(|byte| u8::from_str_radix(byte, 16)).collect::<Result<_, _>>().map_err(|_| invalid())?;
+  let address: [u8; 6] = bytes.try_into().map_err(|_| invalid())?;
+  Ok(PrinterTarget::Rfcomm { address, channel })
+}
+
+/// 프린터 연결을 여는 함수. 시리얼 포트는 baud 속도로 연다.
+pub fn open_printer(device: &str, baud: u32) -> Result<Box<dyn Write>, String> {
+  match parse_printer_target(device)? {
+    PrinterTarget::Serial(path) => serialport::new(&path, baud)
+      .timeout(SERIAL_TIMEOUT)
+      .open()
+      .map(|port| Box::new(port) as Box<dyn Write>)
+      .map_err(|e| format!("Failed to open serial port '{path}': {e}")),
+    PrinterTarget::Rfcomm { address, channel } => connect_rfcomm(address, channel)
+      .map(|socket| Box::new(socket) as Box<dyn Write>)
+      .map_err(|e| format!("Failed to connect to Bluetooth printer '{device}': {e}")),
+  }
+}
+
+// 블루투스 RFCOMM 소켓에 연결한다. 페어링은 미리 되어 있어야 한다.
+#[cfg(target_os = "linux")]
+fn connect_rfcomm(address: [u8; 6], channel: u8) -> io::Result<std::fs::File> {
+  use std::{mem::size_of, os::fd::FromRawFd};
+
+  // <bluetooth/rfcomm.h>의 struct sockaddr_rc
+  #[repr(C)]
+  struct SockaddrRc {
+    family: libc::sa_family_t,
+    bdaddr: [u8; 6],
+    channel: u8,
+  }
+  const BTPROTO_RFCOMM: libc::c_int = 3;
+
+  // bdaddr_t는 주소의 바이트 순서가 표기와 반대이다.
+  let mut bdaddr = address;
+  bdaddr.reverse();
+  let addr = SockaddrRc { family: libc::AF_BLUETOOTH as libc::sa_family_t, bdaddr, channel };
+  // SAFETY: socket이 돌려준 fd는 바로 File이 소유하여 에러가 나더라도 닫히며,
+  // connect에는 살아있는 sockaddr_rc와 그 크기를 넘긴다.
+  unsafe {
+    let fd = libc::socket(libc::AF_BLUETOOTH, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, BTPROTO_RFCOMM);
+    if fd < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    let socket = std::fs::File::from_raw_fd(fd);
+    let addr_ptr = (&raw const addr).cast::<libc::sockaddr>();
+    if libc::connect(fd, addr_ptr, size_of::<SockaddrRc>() as libc::socklen_t) < 0 {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(socket)
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn connect_rfcomm(_address: [u8; 6], _channel: u8) -> io::Result<std::fs::File> {
+  Err(io::Error::other("RFCOMM addresses are only supported on Linux; use the printer's paired serial port (e.g. COM5) instead"))
+}
+
+/// 인쇄 위치 보정값(dot, x와 y)을 프린터 명령으로 만드는 함수. 라벨 형식의 시작(ZPL ^XA, ESC/POS 초기화) 바로 뒤에 넣는다.
+/// ESC/POS는 왼쪽 여백과 용지 이송으로 옮기므로 왼쪽과 위쪽으로는 옮기지 못한다.
+pub fn shift_commands(language: PrinterLanguage, (x, y): (i32, i32)) -> Vec<u8> {
+  match language {
+    PrinterLanguage::Zpl if (x, y) == (0, 0) => Vec::new(),
+    // ^LS는 양수일 때 왼쪽으로 옮기며, ^LT는 최대 120 dot까지 위아래로 옮긴다.
+    PrinterLanguage::Zpl => format!("^LS{}\n^LT{}\n", -x.clamp(-9999, 9999), y.clamp(-120, 120)).into_bytes(),
+    PrinterLanguage::EscPos => {
+      let mut escpos = Vec::new();
+      if x > 0 {
+        let [low, high] = (x.min(i32::from(u16::MAX)) as u16).to_le_bytes();
+        escpos.extend_from_slice(&[0x1D, b'L', low, high]);
+      }
+      if y > 0 {
+        escpos.extend_from_slice(&[0x1B, b'J', y.min(255) as u8]);
+      }
+      escpos
+    }
+  }
+}
+
+/// 라벨 하나를 프린터 명령으로 만드는 함수. 배치는 PNG, SVG 라벨과 같다.
+/// size는 프린터 해상도에서의 라벨 크기(dot)이며 ZPL에서만 사용한다. shift는 dot 단위의 인쇄 위치 보정값이다.
+pub fn label_commands(language: PrinterLanguage, dock: DockId, group_index: usize, marker: &str, size: LabelSize, shift: (i32, i32)) -> Vec<u8> {
+  match language {
+    PrinterLanguage::Zpl => {
+      let LabelSize { width, height } = size;
+      let border = (height / 48).max(1);
+      let small = (height / 8).max(1);
+      let margin = border + height / 20;
+      let inner = width.saturating_sub(2 * margin);
+      let top = margin + small * 6 / 5;
+      let digits = dock.to_string();
+      // 도크 번호의 글자 높이. 숫자 하나의 폭을 글자 높이의 0.6배로 보고 라벨 폭을 넘지 않게 한다.
+      let big = height.saturating_sub(top + margin).min(inner * 5 / (3 * digits.len() as u32)).max(1);
+      let mut zpl = format!("^PW{width}\n^LL{height}\n^FO0,0^GB{width},{height},{border}^FS\n");
+      zpl.push_str(&format!("^FO{margin},{margin}^A0N,{small},{small}^FDG{group_index}^FS\n"));
+      if !marker.is_empty() {
+        zpl.push_str(&format!("^FO{margin},{margin}^FB{inner},1,0,R^A0N,{small},{small}^FD{marker}^FS\n"));
+      }
+      zpl.push_str(&format!("^FO{margin},{top}^FB{inner},1,0,C^A0N,{big},{big}^FD{digits}^FS\n^XZ\n"));
+      let mut commands = b"^XA\n".to_vec();
+      commands.extend(shift_commands(language, shift));
+      commands.extend(zpl.into_bytes());
+      commands
+    }
+    PrinterLanguage::EscPos => {
+      // 초기화, 왼쪽 정렬, 두 배 크기 글자로 그룹 번호와 기호
+      let mut escpos = vec![0x1B, b'@'];
+      escpos.extend(shift_commands(language, shift));
+      escpos.extend_from_slice(&[0x1B, b'a', 0, 0x1D, b'!', 0x11]);
+      escpos.extend_from_slice(format!("{}\n", format!("G{group_index}  {marker}").trim_end()).as_bytes());
+      // 가운데 정렬, 가장 큰 글자(8배)로 도크 번호
+      escpos.extend_from_slice(&[0x1B, b'a', 1, 0x1D, b'!', 0x77]);
+      escpos.extend_from_slice(format!("{dock}\n").as_bytes());
+      // 글자 크기를 되돌리고 세 줄을 보낸 뒤 자른다. 자르는 장치가 없는 프린터는 자르기 명령을 무시한다.
+      escpos.extend_from_slice(&[0x1D, b'!', 0, 0x1B, b'd', 3, 0x1D, b'V', 66, 0]);
+      escpos
+    }
+  }
+}
+
+/// 흑백 이미지를 ESC/POS 래스터 이미지 명령(GS v 0)으로 만드는 함수.
+/// 한 줄의 바이트 수와 줄 수 뒤에 한 바이트에 8픽셀씩(왼쪽이 최상위 비트) 검은 칸을 1로 보낸다.
+pub fn escpos_raster(image: &Bitmap) -> Vec<u8> {
+  let row_bytes = image.width.div_ceil(8);
+  let [x_low, x_high] = (row_bytes as u16).to_le_bytes();
+  let [y_low, y_high] = (image.height as u16).to_le_bytes();
+  let mut escpos = vec![0x1D, b'v', b'0', 0, x_low, x_high, y_low, y_high];
+  for y in 0..image.height {
+    for byte in 0..row_bytes {
+      escpos.push((0..8).filter(|bit| image.ink(byte * 8 + bit, y)).fold(0, |packed, bit| packed | 0x80 >> bit));
+    }
+  }
+  escpos
+}
+
+// ZPL의 ^FH로 보내는 필드 내용. 명령 문자(^, ~)와 16진수 표시 문자(_)를 16진수로 바꾼다.
+fn zpl_field_text(text: &str) -> String {
+  text.replace('_', "_5F").replace('^', "_5E").replace('~', "_7E")
+}
+
+/// 라벨 템플릿으로 라벨 하나를 프린터 명령으로 만드는 함수. size는 dpi 해상도에서의 라벨 크기(dot)이다.
+/// ZPL은 필드마다 글자, 바코드(^BC), 사각형 명령으로 만들며, ESC/POS는 내장 글꼴로 그린 이미지로 보낸다.
+pub fn template_commands(
+  language: PrinterLanguage,
+  template: &LabelTemplate,
+  label: &QueuedLabel,
+  group_index: usize,
+  size: LabelSize,
+  dpi: u32,
+  shift: (i32, i32),
+) -> Result<Vec<u8>, String> {
+  let (dock, marker) = (label.dock, label.marker.as_str());
+  let group = GroupNumber::new(group_index);
+  match language {
+    PrinterLanguage::Zpl => {
+      let dots = |mm: f64| (mm * f64::from(dpi) / MM_PER_INCH).round() as u32;
+      let label_width_mm = f64::from(size.width) * MM_PER_INCH / f64::from(dpi);
+      // ^CI28: 필드 내용을 UTF-8로 읽는다.
+      let mut zpl = format!("^CI28\n^PW{}\n^LL{}\n", size.width, size.height);
+      for field in &template.fields {
+        let (x, y, height) = (dots(field.x), dots(field.y), dots(field.height).max(1));
+        let width = dots(field.width_in(label_width_mm));
+        match field.kind {
+          FieldKind::Box => {
+            let thickness = dots(field.thickness()).max(1);
+            zpl.push_str(&format!("^FO{x},{y}^GB{width},{height},{thickness}^FS\n"));
+          }
+          FieldKind::Barcode => {
+            let data = field.content(dock, group, marker);
+            let total: u32 = code128(&data)?.iter().map(|&module| u32::from(module)).sum();
+            let module_width = (width / total).max(1);
+            let left = x + field.align.offset(f64::from(width), f64::from(total * module_width)) as u32;
+            // ^BC의 데이터에서 >는 코드 세트를 바꾸는 문자이므로 ><로 보낸다.
+            zpl.push_str(&format!("^FO{left},{y}^BY{module_width}^BCN,{height},N,N,N^FD{}^FS\n", data.replace('>', "><")));
+          }
+          FieldKind::Dock | FieldKind::Group | FieldKind::Marker | FieldKind::Text => {
+            let text = field.content(dock, group, marker);
+            if text.is_empty() {
+              continue;
+            }
+            // 글자 하나의 폭을 글자 높이의 0.6배로 보고 필드 폭을 넘지 않게 한다.
+            let text_height = height.min(width * 5 / (3 * text.chars().count() as u32)).max(1);
+            let justify = match field.align {
+              FieldAlign::Left => 'L',
+              FieldAlign::Center => 'C',
+              FieldAlign::Right => 'R',
+            };
+            zpl.push_str(&format!(
+              "^FO{x},{y}^FB{width},1,0,{justify}^A0N,{text_height},{text_height}^FH^FD{}^FS\n",
+              zpl_field_text(&text)
+            ));
+          }
+        }
+      }
+      zpl.push_str("^XZ\n");
+      let mut commands = b"^XA\n".to_vec();
+      commands.extend(shift_commands(language, shift));
+      commands.extend(zpl.into_bytes());
+      Ok(commands)
+    }
+    PrinterLanguage::EscPos => {
+      let image = render_template(template, dock, group, marker, size, dpi, &LabelFont::block())?;
+      let mut escpos = vec![0x1B, b'@'];
+      escpos.extend(shift_commands(language, shift));
+      escpos.extend(escpos_raster(&image));
+      // 세 줄을 보낸 뒤 자른다.
+      escpos.extend_from_slice(&[0x1B, b'd', 3, 0x1D, b'V', 66, 0]);
+      Ok(escpos)
+    }
+  }
+}
+
+// --confirm-each-group에서 작업자가 고른 동작.
+enum Confirmation {
+  Print,
+  Skip,
+  Stop,
+}
+
+// 그룹을 보내기 전에 stderr로 묻고 표준 입력의 한 줄을 읽는다. 입력이 끝났다면(EOF) 멈춘다.
+fn confirm_group(group_index: usize, docks: &[DockId]) -> Result<Confirmation, String> {
+  let docks_text = docks.iter().map(|dock| dock.to_string()).collect::<Vec<_>>().join(", ");
+  eprint!("{}", tr("printing.confirm", &[("group", &group_index), ("docks", &docks_text), ("labels", &docks.len())]));
+  io::stderr().flush().ok();
+  let mut line = String::new();
+  if io::stdin().lock().read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+    eprintln!();
+    return Ok(Confirmation::Stop);
+  }
+  Ok(match line.trim().to_ascii_lowercase().as_str() {
+    "s" | "skip" => Confirmation::Skip,
+    "q" | "quit" | "stop" => Confirmation::Stop,
+    _ => Confirmation::Print,
+  })
+}
+
+/// 그룹 사이의 진행 방식. 작업자의 확인(--confirm-each-group)과 그룹을 보낸 뒤 기다리는 시간(--delay-ms)이다.
+pub struct Pacing {
+  pub confirm_each_group: bool,
+  pub delay_ms: Option<u64>,
+}
+
+/// plan의 모든 라벨을 출력 순서대로 --serial의 프린터로 보내는 함수. 보낸 라벨 수를 반환한다.
+/// 그룹마다 인쇄 작업을 --print-queue의 대기열에 먼저 기록한 뒤 보내므로,
+/// 프린터가 멈춰 보내지 못한 그룹은 plan을 다시 계산하지 않고 `queue retry`로 다시 보낼 수 있다.
+pub fn print_labels(args: &Args, result: &ProcessingResult) -> Result<usize, String> {
+  let Some(device) = &args.serial else {
+    return Ok(0);
+  };
+  let printer = PrinterSettings {
+    device: device.clone(),
+    baud: args.baud,
+    printer_language: args.printer_language,
+    label_size: args.label_size,
+    label_offset: args.label_offset,
+    printer_dpi: args.printer_dpi,
+    template: args.label_template.as_deref().map(load_template).transpose()?,
+  };
+  let mut queue = PrintQueue::load(&args.print_queue)?;
+  queue.remove_finished();
+  let ids: Vec<u64> = result
+    .result_groups
+    .iter()
+    .enumerate()
+    .map(|(i, group)| {
+      let labels = group.docks.iter().map(|&dock| QueuedLabel { dock, marker: label_marker(group, dock, result).to_string() }).collect();
+      queue.submit(&printer, i + 1, labels)
+    })
+    .collect();
+  queue.save(&args.print_queue)?;
+  let pacing = Pacing { confirm_each_group: args.confirm_each_group, delay_ms: args.delay_ms };
+  send_jobs(&mut queue, &args.print_queue, &ids, &pacing)
+}
+
+/// 대기열의 작업들을 ids 순서대로 각 작업의 프린터로 보내는 함수. 보낸 라벨 수를 반환한다.
+/// 작업을 하나 보낼 때마다 상태를 대기열 파일(path)에 기록한다. 보내지 못한 작업은 실패로 남기고 멈춘다.
+/// 그룹마다 버퍼를 비워 앞 그룹이 인쇄되는 동안 다음 그룹을 보내도록 한다.
+pub fn send_jobs(queue: &mut PrintQueue, path: &Path, ids: &[u64], pacing: &Pacing) -> Result<usize, String> {
+  // 같은 프린터로 보내는 작업들은 연결 하나를 함께 쓴다.
+  let mut connection: Option<(String, u32, Box<dyn Write>)> = None;
+  let mut sent = 0;
+  for (n, &id) in ids.iter().enumerate() {
+    let job = queue.job_mut(id)?.clone();
+    if pacing.confirm_each_group {
+      let docks: Vec<DockId> = job.labels.iter().map(|label| label.dock).collect();
+      match confirm_group(job.group_index, &docks)? {
+        Confirmation::Print => {}
+        Confirmation::Skip => {
+          eprintln!("{}", tr("printing.skipped", &[("group", &job.group_index)]));
+          queue.job_mut(id)?.status = JobStatus::Cancelled;
+          queue.save(path)?;
+          continue;
+        }
+        Confirmation::Stop => {
+          eprintln!("{}", tr("printing.stopped", &[("group", &job.group_index)]));
+          eprintln!("{}", tr("printing.queued", &[("path", &path.display())]));
+          break;
+        }
+      }
+    }
+    let outcome = send_job(&mut connection, &job);
+    let entry = queue.job_mut(id)?;
+    (entry.status, entry.error) = match &outcome {
+      Ok(()) => (JobStatus::Sent, None),
+      Err(e) => (JobStatus::Failed, Some(e.clone())),
+    };
+    queue.save(path)?;
+    if let Err(e) = outcome {
+      eprintln!("{}", tr("printing.queued", &[("path", &path.display())]));
+      return Err(e);
+    }
+    sent += job.labels.len();
+    // 마지막 그룹 뒤에는 기다리지 않는다.
+    if let Some(delay) = pacing.delay_ms
+      && n + 1 < ids.len()
+    {
+      thread::sleep(Duration::from_millis(delay));
+    }
+  }
+  Ok(sent)
+}
+
+// 작업 하나의 라벨들을 보내고 버퍼를 비운다. 열려 있는 연결이 다른 프린터의 것이라면 닫고 새로 연다.
+fn send_job(connection: &mut Option<(String, u32, Box<dyn Write>)>, job: &PrintJob) -> Result<(), String> {
+  let settings = &job.printer;
+  // 템플릿에 라벨 크기가 있다면 그 크기로 인쇄한다.
+  let dimensions = settings.template.as_ref().and_then(|template| template.size).unwrap_or(settings.label_size);
+  let size = dimensions.to_pixels(settings.printer_dpi)?;
+  let shift = settings.label_offset.to_dots(settings.printer_dpi);
+  let printer = match connection.take() {
+    Some((device, baud, printer)) if device == settings.device && baud == settings.baud => printer,
+    _ => open_printer(&settings.device, settings.baud)?,
+  };
+  let (_, _, printer) = connection.insert((settings.device.clone(), settings.baud, printer));
+  let failed = |e: io::Error| format!("Failed to send labels to printer '{}': {e}", settings.device);
+  for label in &job.labels {
+    let commands = match &settings.template {
+      Some(template) => template_commands(settings.printer_language, template, label, job.group_index, size, settings.printer_dpi, shift)?,
+      None => label_commands(settings.printer_language, label.dock, job.group_index, &label.marker, size, shift),
+    };
+    printer.write_all(&commands).map_err(failed)?;
+  }
+  printer.flush().map_err(failed)
+}
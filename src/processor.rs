@@ -1,18 +1,89 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use crate::{cli::Args, models::Priority};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
+use crate::{
+  cli::Args,
+  crews::CrewAssigner,
+  i18n::tr,
+  intervals::IntervalSet,
+  plan::plan_checksum,
+  post::PostPipeline,
+  models::{DockId, DockRange, Group, GroupKind, Priority, PriorityRange, RunStamp},
+  rules::GroupingRules,
+  schedule::arrival_window,
+  slots::assign_slots,
+};
+
+// plan 파일, HTTP API, 실행 기록이 같은 입력에 대해 항상 같은 JSON을 내도록 map과 set은 정렬된 타입을 사용한다.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessingResult {
-  pub result_groups: Vec<Vec<u32>>,
-  pub priorities: HashMap<u32, Priority>,
-  pub all_exception_docks: HashSet<u32>,
+  pub result_groups: Vec<Group>,
+  pub priorities: BTreeMap<DockId, Priority>,
+  pub all_exception_docks: BTreeSet<DockId>,
   pub fpp: u16,
   pub spp: u16,
   pub gpp: u16,
-  pub final_exception_groups: Vec<Vec<u32>>,
+  pub final_exception_groups: Vec<Vec<DockId>>,
+  // 도크별 도착 예정 시각(자정 기준 분). --arrivals가 지정되지 않았다면 비어있다.
+  pub arrivals: BTreeMap<DockId, u32>,
+  // 처리 중 발생한 경고 메세지들. 출력 시 stderr로 출력된다.
+  #[serde(default)]
+  pub warnings: Vec<String>,
+  // --stamp로 붙인 실행 ID와 시각. 저장된 plan을 다시 출력하더라도 처음 계산한 실행의 값이 유지된다.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub stamp: Option<RunStamp>,
+  // 그룹 구성의 checksum. process_docks가 모든 그룹을 모은 뒤 계산하며, 이전 버전의 plan 파일에는 없다.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub checksum: Option<String>,
+}
+
+/// plan 계산 요청. 입력 인자와 파일 등에서 읽어온 데이터를 복제하지 않고 빌려서 사용하므로,
+/// 서버나 데몬에서 같은 데이터로 여러 번 계산하더라도 요청을 만드는 비용이 들지 않는다.
+#[derive(Clone, Copy)]
+pub struct PlanRequest<'a> {
+  pub args: &'a Args,
+  // 도크별 도착 예정 시각(자정 기준 분). --arrivals로 읽어온다.
+  pub arrivals: Option<&'a BTreeMap<DockId, u32>>,
+  // 이전 plan의 결과 그룹들. --previous와 --stable이 함께 지정된 경우에만 채워진다.
+  pub previous_groups: &'a [Group],
+  // 현장별 그룹핑 규칙. --rules로 읽어온다.
+  pub rules: Option<&'a dyn GroupingRules>,
+}
+
+impl<'a> PlanRequest<'a> {
+  /// 입력 인자만으로 계산하는 요청을 만든다.
+  pub fn new(args: &'a Args) -> Self {
+    PlanRequest { args, arrivals: None, previous_groups: &[], rules: None }
+  }
+}
+
+/// plan을 계산하여 모든 그룹을 담은 ProcessingResult를 반환하는 함수.
+pub fn process_docks(request: PlanRequest<'_>) -> ProcessingResult {
+  let mut result_groups = Vec::new();
+  let mut result = process_docks_with(request, |_, group| result_groups.push(group));
+  // 시간대는 전체 그룹 수를 알아야 나눌 수 있으므로 그룹을 모두 모은 뒤 배정한다.
+  assign_slots(&mut result_groups, request.args);
+  result.checksum = Some(plan_checksum(&result_groups));
+  result.result_groups = result_groups;
+  result
 }
 
-pub fn process_docks(args: &Args) -> ProcessingResult {
+/// plan을 계산하면서 그룹이 만들어지는 즉시 출력 순서대로 emit에 넘기는 함수.
+/// emit은 그룹과 함께 그룹을 제외한 계산 결과(우선순위, 예외 그룹, 경고 등)를 받으므로 그룹을 바로 출력할 수 있다.
+/// 반환되는 ProcessingResult의 result_groups는 비어있으므로, 그룹을 모두 모아둘 필요가 없는 곳에서
+/// 매우 큰 범위를 처리하더라도 메모리 사용량이 그룹 수에 비례하지 않는다.
+pub fn process_docks_with(request: PlanRequest<'_>, mut emit: impl FnMut(&ProcessingResult, Group)) -> ProcessingResult {
+  let PlanRequest { args, arrivals, previous_groups, rules } = request;
+  let no_arrivals = BTreeMap::new();
+  let arrivals = arrivals.unwrap_or(&no_arrivals);
+  // 이전 plan에서 각 도크가 속했던 그룹의 index. --stable 모드에서 그룹 경계를 유지하기 위해 사용한다.
+  let previous_group_of: HashMap<DockId, usize> = previous_groups
+    .iter()
+    .enumerate()
+    .flat_map(|(i, g)| g.docks.iter().map(move |&d| (d, i)))
+    .collect();
 
   // per_page 값 결정 로직
   // first와 second는 optional한 값이므로 값이 없다면 per_page를 따르도록 한다.
@@ -20,39 +91,49 @@ pub fn process_docks(args: &Args) -> ProcessingResult {
   let spp = args.second_priority_per_page.unwrap_or(args.per_page);
   let gpp = args.per_page; // general per page(third)
 
-  // 1. 입력된 우선순위 및 예외 도크 정리
-  // -f 65-66 71 56 62 와 같이 입력했다면 [[65, 66], [71], [56], [62]] 이런식인데, 이걸 flatten을 이용해서
-  // [65, 66, 71, 56, 62] 이렇게 만들어 HashSet에 저장해준다.
-  let first_priority_docks: HashSet<u32> = args.first_priority.clone().into_iter().flatten().collect();
-  let second_priority_docks: HashSet<u32> = args.second_priority.clone().into_iter().flatten().collect();
-  
+  // 1. 입력된 예외 도크 정리
   // 예외 그룹 처리: 각 예외 그룹을 정렬하고, 전체 예외 도크 집합을 만듦.
-  // 최종적인 exception_group Vec들이 들어갈 Vec이다.
-  let mut final_exception_groups: Vec<Vec<u32>> = Vec::new();
-  // args.exception_groups_raw에서의 모든 예외 도크들을 담는 HashSet.
-  let mut all_exception_docks: HashSet<u32> = HashSet::new();
+  // 최종적인 exception_group Vec들이 들어갈 Vec이다. 각 그룹이 몇 번째 -e 입력으로부터 만들어졌는지도 함께 담는다.
+  let mut exception_definitions: Vec<(usize, Vec<DockId>)> = Vec::new();
+  // args.exception_groups_raw에서의 모든 예외 도크들을 담는 BTreeSet.
+  let mut all_exception_docks: BTreeSet<DockId> = BTreeSet::new();
   // 범위 밖을 벗어난 입력값이 있다면 해당 값을 경고 메세지에 지정한 뒤 경고 메세지들을 저장하여 나중에 출력하기 위한 Vec다.
   let mut warnings: Vec<String> = Vec::new();
 
+  // --skip으로 제외된 도크들과 현장에 없는 도크들. 예외 그룹과 우선순위에서도 빠지며, 어떤 그룹에도 들어가지 않는다.
+  let skip: Vec<PriorityRange> = args.skipped_ranges().into_iter().map(PriorityRange::from).collect();
+  let skipped_docks = priority_docks(&skip, args, "label.skip", &mut warnings);
+  // --break-on-gap의 빈 곳은 --skip, --closed-docks-url(이나 서버의 사용 중지 도크), --site-docks 어디서든 올 수 있으므로
+  // 계산할 때 처리 범위 안에 빈 곳이 하나도 없을 때만 알린다.
+  if args.break_on_gap && skipped_docks.is_empty() {
+    warnings.push(tr("warning.break_on_gap_unused", &[]));
+  }
+  // --solo로 지정된 도크들. 우선순위나 per-page와 관계없이 항상 혼자 한 그룹이 되며, 예외 그룹에서도 빠진다.
+  let solo: Vec<PriorityRange> = args.solo.iter().copied().map(PriorityRange::from).collect();
+  let solo_docks: BTreeSet<DockId> = priority_docks(&solo, args, "label.solo", &mut warnings)
+    .into_iter()
+    .filter(|dock| !skipped_docks.contains(dock))
+    .collect();
+
   // args에서 exception_groups_raw에 접근하여 각 raw_ex_group Vec을 순회한다.
-  for raw_ex_group in &args.exception_groups_raw {
-    // raw_ex_group에서 각 숫자들을 검사하여 min과 max 사이의 값인지를 필터링하여 current_ex_group을 얻는다.
-    let mut current_ex_group: Vec<u32> = raw_ex_group.iter()
-      .filter(|&d| {
-        // raw_ex_group의 각 숫자가 min과 max 사이의 값인지를 필터링한다.
-        if d >= &args.min && d <= &args.max { true } 
-        else { // min max 값 이외의 범위에 있는 숫자라면 ignored되고 해당 숫자는 경고 메세지에 저장되어 
-          // 이 메세지를 warnings에 담아둔다.
-          warnings.push(
-            format!("Warning: Exception dock {} is outside the specified range [{}-{}] and will be ignored.", 
-              d, args.min, args.max));
-          false // 이 경우에는 false로 처리하여 필터링한다.
-        }
-      }).copied().collect();
-    // current_ex_group을 sort한다.
+  for (source, raw_ex_group) in args.exception_groups_raw.iter().enumerate() {
+    // raw_ex_group의 각 범위를 min과 max 사이로 잘라낸 뒤 펼쳐서 current_ex_group을 얻는다.
+    // min max 값 이외의 범위에 있는 부분은 ignored되고 해당 부분은 경고 메세지에 저장된다.
+    // 제외된 도크는 예외 그룹에서도 빠진다.
+    let mut current_ex_group: Vec<DockId> = Vec::new();
+    for &range in &raw_ex_group.ranges {
+      current_ex_group.extend(clamp_to_range(range, args, "label.exception", &mut warnings).docks().filter(|dock| !skipped_docks.contains(dock)));
+    }
+    // 혼자 출력되어야 하는 도크는 예외 그룹에서 빼고 경고한다.
+    current_ex_group.retain(|dock| {
+      let solo = solo_docks.contains(dock);
+      if solo {
+        warnings.push(tr("warning.solo_exception", &[("dock", dock)]));
+      }
+      !solo
+    });
+    // 쉼표로 이은 이웃하지 않은 도크들(52,57,61)은 입력 순서와 관계없이 번호 순으로 출력한다.
     current_ex_group.sort_unstable();
-    // current_ex_group에서 중복 항목을 제거한다.
-    current_ex_group.dedup();
     // 만약 current_ex_group이 비어있지 않다면
     if !current_ex_group.is_empty() {
       let mut filtered_group = Vec::new();
@@ -66,73 +147,131 @@ pub fn process_docks(args: &Args) -> ProcessingResult {
           all_exception_docks.insert(dock);
         } else { // 만약 all_exception_docks가 현재 dock를 포함한다면(중복)
           // warnings에 push하고 해당 dock의 경고 메세지를 warnings Vec에 저장해둔다.
-          warnings.push(format!("Warning: Dock {dock} in exception group already part of another exception group. Ignoring."));
+          warnings.push(tr("warning.duplicate_exception", &[("dock", &dock)]));
         }
       }
       // 현재의 crrent_ex_group의 순회가 종료된 후 filtered_group이 무언가 있다면
       if !filtered_group.is_empty() {
-        // final_exception_groups에 filtered_group을 insert한다.
-        final_exception_groups.push(filtered_group);
+        // exception_definitions에 입력 순서와 함께 filtered_group을 insert한다.
+        exception_definitions.push((source, filtered_group));
       }
     }
   }
-  // final_exception_groups을 sort하는데, 각 그룹들의 첫머리 숫자 기준으로 sort한다.
+  // exception_definitions을 sort하는데, 각 그룹들의 첫머리 숫자 기준으로 sort한다.
   // group의 .first로 첫 숫자를 추출하고, cloned로 복사한뒤 unwrap_or로 해당 숫자를 얻거나 u32의 MAX값을 추출한다.
-  // 추출한 값을 기준으로 final_exception_groups를 sort한다. 
-  final_exception_groups.sort_unstable_by_key(|group| group.first().cloned().unwrap_or(u32::MAX));
+  // 추출한 값을 기준으로 exception_definitions를 sort한다. 
+  exception_definitions.sort_unstable_by_key(|(_, group)| group.first().cloned().unwrap_or(DockId::MAX));
+  let final_exception_groups: Vec<Vec<DockId>> = exception_definitions.iter().map(|(_, g)| g.clone()).collect();
 
   // 2. 각 도크에 우선순위 할당 (예외 도크 제외)
-  // 도크 숫자를 key로, Priority를 value로 갖는 HashMap을 생성한다. 
-  let mut priorities: HashMap<u32, Priority> = HashMap::new();
+  // -f 65-66 71 56 62 와 같이 입력했다면 [65-66, 71, 56, 62] 이런식인데, 이 범위들을 min max 사이로 잘라낸 뒤 펼쳐서
+  // [56, 62, 65, 66, 71] 이렇게 BTreeSet에 저장해준다. 범위 밖의 부분은 경고 메세지로 남긴다.
+  let first_priority_docks = priority_docks(&args.first_priority, args, "label.first", &mut warnings);
+  let second_priority_docks = priority_docks(&args.second_priority, args, "label.second", &mut warnings);
+
+  // 도크 숫자를 key로, Priority를 value로 갖는 BTreeMap을 생성한다. 
+  let mut priorities: BTreeMap<DockId, Priority> = BTreeMap::new();
 
   // 1차 그룹의 dock들을 순회한다.
   for &dock in &first_priority_docks {
-    // 각 dock가 min보다 크거나 같고, max보다 작거나 같고, all_exception_docks에 포함되지 않았다면
-    if dock >= args.min && dock <= args.max && !all_exception_docks.contains(&dock) {
-      // 해당 dock를 priorites HashMap에 dock를 key로, Priority::First를 value로 insert한다.
+    // 각 dock가 all_exception_docks에 포함되지 않았고 제외된 도크도 아니라면
+    if !all_exception_docks.contains(&dock) && !skipped_docks.contains(&dock) {
+      // 해당 dock를 priorites BTreeMap에 dock를 key로, Priority::First를 value로 insert한다.
       priorities.insert(dock, Priority::First);
-    } // 그게 아니라 min max 범위를 벗어난 값이 있다면
-    else if !(dock >= args.min && dock <= args.max) { // 범위 밖 경고
-      // warnings에 해당 dock의 경고 메세지를 저장한다.
-      warnings.push(format!(
-        "Warning: First priority dock {} is outside the specified range [{}-{}] and will be ignored.",
-        dock, args.min, args.max
-      ));
     }
   }
 
   // 2차 그룹도 1차 그룹과 같은 방식으로 처리한다.
   for &dock in &second_priority_docks {
-    if dock >= args.min && dock <= args.max && !all_exception_docks.contains(&dock) {
+    if !all_exception_docks.contains(&dock) && !skipped_docks.contains(&dock) {
       // 이 경우에는 Priority::Second를 값으로 넣어둔다.
       priorities.entry(dock).or_insert(Priority::Second);
-    } else if !(dock >= args.min && dock <= args.max) { // 범위 밖 경고
-       warnings.push(format!(
-        "Warning: Second priority dock {} is outside the specified range [{}-{}] and will be ignored.",
-        dock, args.min, args.max
-      ));
+    }
+  }
+  // --spread가 지정되었다면 1차 도크로부터 spread 이내의 3차 도크들을 2차로 올린다.
+  // 가까운 1차 도크들의 주변 구간은 겹치므로 구간 집합으로 합친 뒤 펼친다.
+  if args.spread > 0 {
+    let mut spread_area = IntervalSet::new();
+    for (&dock, _) in priorities.iter().filter(|(_, prio)| **prio == Priority::First) {
+      spread_area.insert_range(dock.saturating_sub(args.spread).max(args.min), dock.saturating_add(args.spread).min(args.max));
+    }
+    for dock in spread_area.iter().flat_map(|range| range.docks()) {
+      if !all_exception_docks.contains(&dock) && !skipped_docks.contains(&dock) {
+        priorities.entry(dock).or_insert(Priority::Second);
+      }
     }
   }
   // 3차 우선순위는 나중에 그룹핑 시점에 기본값으로 처리한다.
 
-  // 경고 메시지를 출력한다.
-  for warning in warnings {
-    eprintln!("{warning}");
-  }
+  // 처리할 전체 도크 순서 = min부터 max까지의 처리할 모든 도크의 순회 순서이다.
+  // row_size가 지정되었다면 도크들을 serpentine(지그재그) 순회 순서로 정렬하여 담는다.
+  // 그룹 확장은 이 순서를 따르므로 그룹 역시 실제 주행 경로를 따라 만들어진다.
+  // 단순 오름차순이라면 도크 목록을 만들지 않고 번호로 바로 계산한다.
+  let all_docks_in_range = match (args.row_size, arrivals.is_empty()) {
+    (None, true) => DockOrder::Ascending { min: args.min, max: args.max },
+    (row_size, _) => {
+      let mut docks: Vec<DockId> = match row_size {
+        Some(row_size) => serpentine_order(args.min, args.max, row_size),
+        None => args.processing_range().docks().collect(),
+      };
+      // 도착 스케줄이 있다면 도크들을 (도착 시간대, 도크 번호) 순으로 정렬한다.
+      // 스케줄에 없는 도크들은 가장 마지막 시간대로 취급되어 뒤에 모인다.
+      if !arrivals.is_empty() {
+        docks.sort_by_key(|&d| (arrival_window(arrivals, d, args.arrival_window).unwrap_or(u32::MAX), d));
+      }
+      DockOrder::Listed(docks)
+    }
+  };
+  // 오름차순 순회에서 짝 도크, --stable, 그룹핑 규칙이 없다면 3차 그룹은 다음 1차/2차/예외 도크 직전까지의
+  // 연속된 도크로만 이루어지므로, 도크를 하나씩 확인하지 않고 한 번에 확장할 수 있다.
+  let fast_third_runs = matches!(all_docks_in_range, DockOrder::Ascending { .. })
+    && args.pair_offset.is_none()
+    && previous_group_of.is_empty()
+    && rules.is_none();
 
-  // 처리할 전체 도크 목록 = min부터 max까지의 처리할 모든 도크가 담긴 Vec이다.
-  let all_docks_in_range: Vec<u32> = (args.min..=args.max).collect();
+  // 그룹을 제외한 계산 결과. 그룹핑이 시작되면 바뀌지 않으므로 그룹과 함께 emit에 넘긴다.
+  let context = ProcessingResult {
+    result_groups: Vec::new(),
+    priorities,
+    all_exception_docks,
+    fpp,
+    spp,
+    gpp,
+    final_exception_groups,
+    arrivals: arrivals.clone(),
+    warnings,
+    stamp: None,
+    checksum: None,
+  };
+  let (priorities, all_exception_docks) = (&context.priorities, &context.all_exception_docks);
+
+  // --crews가 지정되었다면 그룹이 만들어지는 순서대로 작업조를 배정한 뒤 넘긴다.
+  let mut crews = CrewAssigner::new(args);
+  let mut emit_assigned = |mut group: Group| {
+    group.crew = crews.as_mut().map(|crews| crews.assign(group.docks.len()));
+    emit(&context, group);
+  };
+  // --post 후처리 단계가 있다면 그룹들이 단계들을 거친 뒤 작업조를 배정한다.
+  let mut pipeline = PostPipeline::new(request, priorities);
+  let mut emit = |group: Group| match pipeline.as_mut() {
+    Some(pipeline) => pipeline.push(group, &mut emit_assigned),
+    None => emit_assigned(group),
+  };
 
   // 4. 최종 그룹핑 로직
-  // 최종 결과 그룹들을 저장할 빈 벡터를 생성한다.
-  let mut result_groups: Vec<Vec<u32>> = Vec::new();
-  // 그룹핑 과정에서 이미 처리된 그룹인지를 contains를 이용하여 빠르게 추적하기 위한 HashSet이다.
-  let mut processed_docks_in_grouping: HashSet<u32> = HashSet::new();
-
-  // min부터 max까지의 도크들이 담긴 all_docks_in_range를 처음 도크(min)부터 순회한다.
-  for &current_dock in &all_docks_in_range {
-    // current_dock가 어떤 그룹에 포함되어서 processed_docks_in_grouping에 포함되었다면
-    if processed_docks_in_grouping.contains(&current_dock) {
+  // 지금까지 emit한 그룹 수. 다음 그룹의 index가 된다.
+  let mut group_count = 0;
+  // 그룹핑 과정에서 이미 처리된 그룹인지를 contains를 이용하여 빠르게 추적하기 위한 구간 집합이다.
+  // 그룹은 대부분 연속된 도크로 이루어지므로 처리된 도크들은 몇 개의 구간으로 합쳐진다.
+  let mut processed_docks_in_grouping = IntervalSet::new();
+
+  // all_docks_in_range를 처음 도크부터 순회한다. current_idx는 다음에 확인할 도크의 순회 index이다.
+  let mut current_idx = 0;
+  while current_idx < all_docks_in_range.len() {
+    let current_dock = all_docks_in_range.get(current_idx);
+    current_idx += 1;
+    // current_dock가 어떤 그룹에 포함되어서 processed_docks_in_grouping에 포함되었거나 제외된 도크라면
+    if processed_docks_in_grouping.contains(current_dock) || skipped_docks.contains(&current_dock) {
       // 더이상 처리할 필요가 없으므로 continue한다.
       continue;
     }
@@ -141,18 +280,21 @@ pub fn process_docks(args: &Args) -> ProcessingResult {
     let mut is_exception_start = false;
     // 만약 현재 도크가 exception_group의 도크라면 해당 ex_group을 all_exception_docks에서 찾아
     // 여기에 저장한다. 이 data는 optional한 data이다.
-    let mut current_exception_group_data: Option<Vec<u32>> = None;
+    let mut current_exception_group_data: Option<(usize, &Vec<DockId>)> = None;
 
     // current_dock가 전체 예외 도크 Set에 포함됐다면 이 도크는 예외도크이므로
     if all_exception_docks.contains(&current_dock) {
-      // final_exception_groups를 순회하며 어떤 예외 도크 그룹에 속하는지 파악한다.
-      for ex_g in &final_exception_groups {
-        // 만약 current_dock가 ex_g의 first와 일치한다면 이 도크는 ex_g의 도크이므로
-        if ex_g.first() == Some(&current_dock) {
+      // exception_definitions를 순회하며 어떤 예외 도크 그룹에 속하는지 파악한다.
+      for (source, ex_g) in &exception_definitions {
+        // 만약 ex_g가 current_dock을 포함한다면 이 도크는 ex_g의 도크이다.
+        // 이미 출력된 예외 그룹의 도크들은 processed_docks_in_grouping에 의해 위에서 건너뛰어지므로,
+        // 여기까지 왔다면 current_dock은 순회 순서상 ex_g에서 처음 만나는 도크, 즉 시작점이다.
+        // (오름차순 순회에서는 ex_g의 first이고, serpentine 역방향 줄에서는 ex_g의 마지막 도크가 된다.)
+        if ex_g.contains(&current_dock) {
           // is_exception_start을 true로 만들고
           is_exception_start = true;
-          // current_exception_group_data에 ex_g를 복제하여 넣어놓는다.
-          current_exception_group_data = Some(ex_g.clone());
+          // current_exception_group_data에 ex_g를 넣어놓는다.
+          current_exception_group_data = Some((*source, ex_g));
           // 일치하는 예외 그룹을 찾았으므로 루프를 빠져나온다.
           break;
         }
@@ -162,40 +304,144 @@ pub fn process_docks(args: &Args) -> ProcessingResult {
     // 위 과정에서 만약 is_exception_start가 true이고, current_exception_group_data에 무언가 있다면
     if is_exception_start && current_exception_group_data.is_some() {
       // current_exception_group_data에서 ex_group을 추출한뒤
-      if let Some(ex_group) = current_exception_group_data {
-        // result_groups에 clone하여 push한다.
-        result_groups.push(ex_group.clone());
-        // 또한 이 ex_group의 dock들을 
-        for &dock_in_ex in &ex_group {
+      if let Some((source, ex_group)) = current_exception_group_data {
+        let mut docks = ex_group.clone();
+        // --pad-exceptions라면 per-page보다 작은 예외 그룹을 뒤따르는 3차 도크들로 채운다.
+        // 일반 그룹의 확장과 같이 1차/2차/예외 도크, 이미 처리된 도크, --pp-range 구간 경계, 도착 시간대 경계에서 멈춘다.
+        if args.pad_exceptions {
+          let (_, _, gpp) = args.per_pages_at(current_dock);
+          let section = args.per_page_section(current_dock);
+          let mut pad_idx = current_idx;
+          while docks.len() < usize::from(gpp) && pad_idx < all_docks_in_range.len() {
+            let candidate = all_docks_in_range.get(pad_idx);
+            pad_idx += 1;
+            // 예외 그룹 자신의 도크와 제외된 도크는 건너뛴다. --break-on-gap이라면 제외된 도크에서 멈춘다.
+            if ex_group.contains(&candidate) || (skipped_docks.contains(&candidate) && !args.break_on_gap) {
+              continue;
+            }
+            if skipped_docks.contains(&candidate)
+              || processed_docks_in_grouping.contains(candidate)
+              || all_exception_docks.contains(&candidate)
+              || priorities.contains_key(&candidate)
+              || solo_docks.contains(&candidate)
+              || args.per_page_section(candidate) != section
+              || (!arrivals.is_empty()
+                && arrival_window(arrivals, candidate, args.arrival_window)
+                  != arrival_window(arrivals, current_dock, args.arrival_window))
+            {
+              break;
+            }
+            docks.push(candidate);
+          }
+        }
+        // 예외 그룹으로 emit한다.
+        emit(Group {
+          docks: docks.clone(),
+          kind: GroupKind::Exception,
+          tier: Priority::Third,
+          index: group_count,
+          exception_source: Some(source),
+          crew: None,
+          slot: None,
+          composition: None,
+        });
+        group_count += 1;
+        // 또한 이 그룹의 dock들을 
+        for dock_in_ex in docks {
           // processed_docks_in_grouping에 insert하여 추후 루핑 과정에서  
           // 이 도크 순서가 온다면 이것을 빠르게 확인하여 건너뛰도록 한다.
           processed_docks_in_grouping.insert(dock_in_ex);
         }
       }
+    } // current_dock가 --solo 도크라면 우선순위의 per-page와 관계없이 도크 하나만으로 그룹을 만든다.
+    else if solo_docks.contains(&current_dock) {
+      emit(Group {
+        docks: vec![current_dock],
+        kind: GroupKind::Regular,
+        tier: priorities.get(&current_dock).copied().unwrap_or(Priority::Third),
+        index: group_count,
+        exception_source: None,
+        crew: None,
+        slot: None,
+        composition: None,
+      });
+      group_count += 1;
+      processed_docks_in_grouping.insert(current_dock);
     } // current_dock가 예외 그룹의 시작점이 아니고, 모든 예외 그룹(all_exception_docks)에도 속하지 않는다면
     else if !all_exception_docks.contains(&current_dock) {
       // 새로운 일반 그룹(regular_group)을 생성하고
-      let mut regular_group: Vec<u32> = Vec::new();
+      let mut regular_group: Vec<DockId> = Vec::new();
       // current_dock을 regular_group에 push한다.
       regular_group.push(current_dock);
       // 또한 processed_docks_in_grouping에도 추가하여 processed된 그룹으로 지정한다.
       processed_docks_in_grouping.insert(current_dock);
+      // pair_offset이 지정되었다면 통로 건너편의 짝 도크(current_dock + offset)도 같은 그룹에 바로 붙인다.
+      if let Some(partner) = pair_partner(current_dock, args, &processed_docks_in_grouping, all_exception_docks, &skipped_docks, &solo_docks) {
+        regular_group.push(partner);
+        processed_docks_in_grouping.insert(partner);
+      }
       
-      // priorities HashMap으로 부터 current_dock을 key로 하는 Priority를 얻는다.
+      // current_dock이 --pp-range 구간에 속한다면 구간의 per-page를 사용한다.
+      let (fpp, spp, gpp) = args.per_pages_at(current_dock);
+      let current_section = args.per_page_section(current_dock);
+
+      // priorities BTreeMap으로 부터 current_dock을 key로 하는 Priority를 얻는다.
       // 만약 이것을 얻을 수 없다면 current_dock_priority는 Priority::Third로 할당된다.
       let current_dock_priority = priorities.get(&current_dock).unwrap_or(&Priority::Third);
       // current_dock_priority를 match하여 각 Priority에 맞는 per_page를 얻은 뒤 변수 current_target_per_page에 할당한다.
+      // 그룹핑 규칙이 current_dock에 대한 목표 크기를 정한다면 per_page 대신 그 값을 사용한다.
       let current_target_per_page = match current_dock_priority {
         Priority::First => fpp,
         Priority::Second => spp,
         Priority::Third => gpp,
       };
+      let current_target_per_page = rules
+        .and_then(|rules| rules.target_size(current_dock))
+        .unwrap_or(usize::from(current_target_per_page));
       
-      // 현재 도크 기준 다음 도크의 index를 찾아 변수에 할당한다.
-      let mut next_dock_idx_in_range = all_docks_in_range.iter()
-        .position(|&d| d == current_dock).unwrap_or(0) + 1;
-      // .position에서 현재 도크와 같은 도크의 index를 찾을 수 없다면 unwrap_or로 안전하게 0을 배출하여 처리한다.
-      // 이는 unwrap으로 인한 프로그램 강제 종료를 막기 위함임.
+      // 현재 도크 기준 다음 도크의 index. current_idx는 이미 current_dock 다음 자리를 가리키고 있으므로 그대로 사용한다.
+      // (도크 목록에서 current_dock을 다시 찾으면 Listed 순회에서 그룹마다 O(n)이 걸린다.)
+      let mut next_dock_idx_in_range = current_idx;
+
+      // [빠른 확장] 3차 그룹을 다음 1차/2차/예외/제외/solo 도크 직전, per_page, max 중 가장 먼저 닿는 곳까지 한 번에 확장한다.
+      // 확장을 멈춘 자리의 도크는 아래 확장 루프가 평소처럼 확인한다.
+      // current_dock이 가장 큰 도크 번호라면 더 확장할 도크가 없다.
+      if fast_third_runs
+        && *current_dock_priority == Priority::Third
+        && let Some(next_dock) = current_dock.checked_add(1)
+      {
+        let next_special = [
+          priorities.range(next_dock..).next().map(|(&d, _)| d),
+          all_exception_docks.range(next_dock..).next().copied(),
+          skipped_docks.range(next_dock..).next().copied(),
+          solo_docks.range(next_dock..).next().copied(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(DockId::MAX);
+        // 그룹은 --pp-range 구간 경계를 넘지 않으므로, 구간 안이라면 구간의 끝, 구간 밖이라면 다음 구간의 직전까지만 확장한다.
+        let section_end = match current_section {
+          Some(i) => args.per_page_ranges[i].range.end,
+          None => args
+            .per_page_ranges
+            .iter()
+            .map(|section| section.range.start)
+            .filter(|&start| start > current_dock)
+            .min()
+            .map_or(DockId::MAX, |start| start.saturating_sub(1)),
+        };
+        let end = current_dock
+          .saturating_add(u32::from(gpp) - 1)
+          .min(next_special.saturating_sub(1))
+          .min(section_end)
+          .min(args.max);
+        if end > current_dock {
+          regular_group.extend(DockRange { start: next_dock, end }.docks());
+          processed_docks_in_grouping.insert_range(next_dock, end);
+          next_dock_idx_in_range += (end.get() - current_dock.get()) as usize;
+        }
+      }
 
       // --- [그룹 확장 루프] ---
       // 다음 조건들이 모두 만족하는 동안 그룹을 확장합니다:
@@ -208,13 +454,24 @@ pub fn process_docks(args: &Args) -> ProcessingResult {
       // 그럼 처음 51 도크가 regular_group에 담기게되고, 이때의 len은 1이다. 그런데 51 도크의 current_taget_per_page는
       // 2 이므로 while문이 진행된다.
       // --2. 확인할 다음 도크가 전체 도크 범위(`all_docks_in_range`) 안에 있다.
-      while regular_group.len() < current_target_per_page.into() && next_dock_idx_in_range < all_docks_in_range.len() {
+      while regular_group.len() < current_target_per_page && next_dock_idx_in_range < all_docks_in_range.len() {
         // current_dock 다음 dock로 지명된 후보이다.
-        let next_dock_candidate = all_docks_in_range[next_dock_idx_in_range];
+        let next_dock_candidate = all_docks_in_range.get(next_dock_idx_in_range);
 
-        // [확장 중단 조건 1] next_dock_candidate가 이미 처리된 도크이거나 예외 그룹에 속해있으면 그룹 확장을 중단한다.
-        if processed_docks_in_grouping.contains(&next_dock_candidate) || 
-          all_exception_docks.contains(&next_dock_candidate) {
+        // 제외된 도크는 그룹에 넣지 않고 건너뛰어 그 다음 도크로 확장을 이어간다.
+        // --break-on-gap이라면 제외된 도크 양옆은 실제로 떨어져 있으므로 그룹 확장을 중단한다.
+        if skipped_docks.contains(&next_dock_candidate) {
+          if args.break_on_gap {
+            break;
+          }
+          next_dock_idx_in_range += 1;
+          continue;
+        }
+
+        // [확장 중단 조건 1] next_dock_candidate가 이미 처리된 도크이거나 예외 그룹에 속해있거나 solo 도크라면 그룹 확장을 중단한다.
+        if processed_docks_in_grouping.contains(next_dock_candidate) || 
+          all_exception_docks.contains(&next_dock_candidate) ||
+          solo_docks.contains(&next_dock_candidate) {
           break;
         }
 
@@ -242,18 +499,187 @@ pub fn process_docks(args: &Args) -> ProcessingResult {
           break;
         }
 
+        // [확장 중단 조건 3] 도착 스케줄이 있다면 그룹은 하나의 도착 시간대 안에서만 확장된다.
+        if !arrivals.is_empty()
+          && arrival_window(arrivals, next_dock_candidate, args.arrival_window)
+            != arrival_window(arrivals, regular_group[0], args.arrival_window)
+        {
+          break;
+        }
+
+        // [확장 중단 조건 4] --stable 모드에서는 이전 plan에서 그룹의 첫 도크와 다른 그룹이었던 도크를
+        // 붙이지 않는다. 이렇게 하면 이전 그룹 경계가 유지되어 다시 출력해야 하는 라벨이 줄어든다.
+        if !previous_group_of.is_empty()
+          && previous_group_of.get(&next_dock_candidate) != previous_group_of.get(&regular_group[0])
+        {
+          break;
+        }
+
+        // [확장 중단 조건 5] --pp-range 구간마다 그룹 크기가 다르므로 그룹은 구간 경계를 넘어 확장되지 않는다.
+        if args.per_page_section(next_dock_candidate) != current_section {
+          break;
+        }
+
+        // [확장 중단 조건 6] 짝 도크가 있다면 후보와 짝 도크가 한 묶음으로 그룹에 들어가야 하므로,
+        // 둘을 모두 넣을 자리가 없다면 그룹 확장을 중단한다.
+        let next_partner =
+          pair_partner(next_dock_candidate, args, &processed_docks_in_grouping, all_exception_docks, &skipped_docks, &solo_docks);
+        if next_partner.is_some() && regular_group.len() + 2 > current_target_per_page {
+          break;
+        }
+
+        // [확장 중단 조건 7] 그룹핑 규칙이 현재 그룹과 후보 사이에서 그룹을 끊으라고 한다면 그룹 확장을 중단한다.
+        if rules.is_some_and(|rules| rules.should_break(&regular_group, next_dock_candidate)) {
+          break;
+        }
+
         // 확장 중단 조건을 모두 통과했다면 regular_group에 next_dock_candidate을 push한다.
         regular_group.push(next_dock_candidate);
         // 또한 next_dock_candidate을 processed에 추가한다.
         processed_docks_in_grouping.insert(next_dock_candidate);
+        // 짝 도크가 있다면 후보 바로 뒤에 함께 넣는다.
+        if let Some(partner) = next_partner {
+          regular_group.push(partner);
+          processed_docks_in_grouping.insert(partner);
+        }
         // 또, next_dock_candidate의 index인 next_dock_idx_in_range를 +1해준다.
         // 만약 next_dock_idx_in_range가 all_docks_in_range.len()를 넘어선다면 while문은 즉시 종료된다.
         next_dock_idx_in_range += 1;
       }
-      // 그룹 확장 while 루프가 모두 종료되면 확장이 종료된 regular_group을 result_group에 push한다.
-      result_groups.push(regular_group);
+      // 그룹 확장 while 루프가 모두 종료되면 확장이 종료된 regular_group을 emit한다.
+      emit(Group {
+        docks: regular_group,
+        kind: GroupKind::Regular,
+        tier: *current_dock_priority,
+        index: group_count,
+        exception_source: None,
+        crew: None,
+        slot: None,
+        composition: None,
+      });
+      group_count += 1;
+      // 확장 루프가 확인한 자리까지의 도크들은 모두 이 그룹에 들어갔으므로 다음 순회는 그 뒤부터 시작한다.
+      current_idx = next_dock_idx_in_range;
+    }
+  }
+  // 후처리 단계들이 들고 있던 그룹들을 넘긴다.
+  if let Some(pipeline) = pipeline.as_mut() {
+    pipeline.flush(&mut emit_assigned);
+  }
+  // pipeline이 빌린 priorities를 결과로 옮길 수 있도록 여기서 버린다.
+  drop(pipeline);
+
+  context
+}
+
+/// 입력된 도크 범위를 min..=max로 잘라낸 도크들을 반환하는 함수.
+/// 잘려나간 앞뒤 부분은 경고 메세지로 남긴다. label은 경고 메세지에 쓰이는 입력 이름의 메세지 key이다(예: "label.exception").
+fn clamp_to_range(
+  range: DockRange,
+  args: &Args,
+  label: &str,
+  warnings: &mut Vec<String>,
+) -> DockRange {
+  if range.start < args.min {
+    warnings.push(out_of_range_warning(label, range.start, range.end.min(args.min.saturating_sub(1)), args));
+  }
+  if range.end > args.max {
+    warnings.push(out_of_range_warning(label, range.start.max(args.max.saturating_add(1)), range.end, args));
+  }
+  DockRange { start: range.start.max(args.min), end: range.end.min(args.max) }
+}
+
+/// -f 또는 -s로 입력된 범위들을 합친 뒤 min..=max 안에 있는 도크들을 반환하는 함수.
+/// 겹치는 범위는 합쳐지므로 범위 밖의 도크는 한 번만 경고된다. 제외 범위(!)에 속한 도크들은 빠진다.
+fn priority_docks(ranges: &[PriorityRange], args: &Args, label: &str, warnings: &mut Vec<String>) -> BTreeSet<DockId> {
+  let mut merged = IntervalSet::new();
+  let mut excluded = IntervalSet::new();
+  for range in ranges {
+    match range.excluded {
+      true => excluded.insert_range(range.range.start, range.range.end),
+      false => merged.insert_range(range.range.start, range.range.end),
+    }
+  }
+  merged
+    .iter()
+    .flat_map(|range| clamp_to_range(range, args, label, warnings).docks())
+    .filter(|&dock| !excluded.contains(dock))
+    .collect()
+}
+
+/// 처리 범위 밖의 입력 도크(start..=end)에 대한 경고 메세지를 만드는 함수.
+fn out_of_range_warning(label: &str, start: DockId, end: DockId, args: &Args) -> String {
+  let label = tr(label, &[]);
+  let key = match start == end {
+    true => "warning.out_of_range_one",
+    false => "warning.out_of_range_many",
+  };
+  tr(key, &[("label", &label), ("start", &start), ("end", &end), ("min", &args.min), ("max", &args.max)])
+}
+
+/// pair_offset이 지정된 경우 dock의 통로 건너편 짝 도크(dock + offset)를 반환하는 함수.
+/// 짝 도크가 처리 범위 밖이거나, 이미 다른 그룹에 들어갔거나, 예외 도크, 제외된 도크 또는 solo 도크라면 None을 반환한다.
+/// 짝 도크는 우선순위와 관계없이 dock과 같은 그룹에 묶인다.
+fn pair_partner(
+  dock: DockId,
+  args: &Args,
+  processed: &IntervalSet,
+  all_exception_docks: &BTreeSet<DockId>,
+  skipped_docks: &BTreeSet<DockId>,
+  solo_docks: &BTreeSet<DockId>,
+) -> Option<DockId> {
+  let partner = dock.checked_add(args.pair_offset?)?;
+  if partner > args.max
+    || processed.contains(partner)
+    || all_exception_docks.contains(&partner)
+    || skipped_docks.contains(&partner)
+    || solo_docks.contains(&partner)
+  {
+    return None;
+  }
+  Some(partner)
+}
+
+/// min..=max 범위의 도크들을 row_size개씩 한 줄로 나눈 뒤, 짝수 번째 줄(0, 2, ...)은 오름차순,
+/// 홀수 번째 줄(1, 3, ...)은 내림차순으로 이어붙여 serpentine 순회 순서의 Vec을 만드는 함수.
+fn serpentine_order(min: DockId, max: DockId, row_size: u32) -> Vec<DockId> {
+  let docks: Vec<DockId> = DockRange { start: min, end: max }.docks().collect();
+  docks
+    .chunks(row_size as usize)
+    .enumerate()
+    .flat_map(|(row, chunk)| {
+      // 홀수 번째 줄이라면 역순으로 뒤집는다.
+      if row % 2 == 1 {
+        chunk.iter().rev().copied().collect::<Vec<DockId>>()
+      } else {
+        chunk.to_vec()
+      }
+    })
+    .collect()
+}
+
+/// 그룹 확장이 따르는 도크 순회 순서.
+enum DockOrder {
+  // min부터 max까지의 오름차순. 도크 목록을 만들지 않고 번호로 바로 계산한다.
+  Ascending { min: DockId, max: DockId },
+  // serpentine 순회나 도착 시간대로 정렬된 도크 목록
+  Listed(Vec<DockId>),
+}
+
+impl DockOrder {
+  /// 순회할 도크 수.
+  fn len(&self) -> usize {
+    match self {
+      DockOrder::Ascending { min, max } => (max.get() - min.get()) as usize + 1,
+      DockOrder::Listed(docks) => docks.len(),
     }
   }
 
-  ProcessingResult { result_groups, priorities, all_exception_docks, fpp, spp, gpp, final_exception_groups }
-}
\ No newline at end of file
+  /// 순회 순서상 idx번째 도크.
+  fn get(&self, idx: usize) -> DockId {
+    match self {
+      DockOrder::Ascending { min, .. } => DockId(min.get() + idx as u32),
+      DockOrder::Listed(docks) => docks[idx],
+    }
+  }
+}
@@ -0,0 +1,154 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  cli::PrinterLanguage,
+  models::DockId,
+  raster::{LabelDimensions, LabelOffset},
+  template::LabelTemplate,
+};
+
+/// --print-queue에 경로를 지정하지 않았을 때 사용하는 기본 인쇄 대기열 파일.
+pub const DEFAULT_QUEUE_FILE: &str = "dock_print_queue.json";
+
+/// 인쇄 작업의 상태.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+  // 아직 보내지 않았다 (--confirm-each-group에서 멈춘 뒤의 그룹 포함)
+  Pending,
+  Sent,
+  // 보내는 도중 프린터 연결이나 쓰기에 실패했다
+  Failed,
+  // `queue cancel` 또는 --confirm-each-group에서 건너뛰었다
+  Cancelled,
+}
+
+impl JobStatus {
+  /// `queue list`에 표시하는 이름.
+  pub fn name(self) -> &'static str {
+    match self {
+      JobStatus::Pending => "pending",
+      JobStatus::Sent => "sent",
+      JobStatus::Failed => "failed",
+      JobStatus::Cancelled => "cancelled",
+    }
+  }
+
+  /// 아직 인쇄되지 않아 `queue retry`로 다시 보낼 작업인지 여부.
+  pub fn is_unfinished(self) -> bool {
+    matches!(self, JobStatus::Pending | JobStatus::Failed)
+  }
+}
+
+/// 작업을 보낼 프린터와 라벨 설정. 작업을 만든 실행의 --serial 관련 인자들이다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrinterSettings {
+  pub device: String,
+  pub baud: u32,
+  pub printer_language: PrinterLanguage,
+  pub label_size: LabelDimensions,
+  #[serde(default)]
+  pub label_offset: LabelOffset,
+  pub printer_dpi: u32,
+  // --label-template의 내용. 다시 보낼 때 템플릿 파일이 바뀌었거나 없어도 같은 라벨을 인쇄한다.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub template: Option<LabelTemplate>,
+}
+
+/// 인쇄할 라벨 한 장.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedLabel {
+  pub dock: DockId,
+  // 라벨의 기호 (@, *, EX 또는 빈 문자열)
+  pub marker: String,
+}
+
+/// 그룹 하나의 라벨들을 보내는 인쇄 작업.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrintJob {
+  pub id: u64,
+  pub submitted_at: String,
+  #[serde(flatten)]
+  pub printer: PrinterSettings,
+  // 출력 순서상 그룹 번호 (1부터)
+  pub group_index: usize,
+  pub labels: Vec<QueuedLabel>,
+  pub status: JobStatus,
+  // 마지막으로 실패한 이유
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+/// 인쇄 대기열 파일의 내용. 작업은 보낼 순서(id 순서)대로 저장된다.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PrintQueue {
+  next_id: u64,
+  pub jobs: Vec<PrintJob>,
+}
+
+impl PrintQueue {
+  /// 대기열 파일을 읽는 함수. 파일이 없다면 빈 대기열이다.
+  pub fn load(path: &Path) -> Result<Self, String> {
+    match fs::read_to_string(path) {
+      Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Invalid print queue file '{}': {e}", path.display())),
+      Err(e) if e.kind() == ErrorKind::NotFound => Ok(PrintQueue::default()),
+      Err(e) => Err(format!("Failed to read print queue file '{}': {e}", path.display())),
+    }
+  }
+
+  /// 대기열을 파일에 저장하는 함수. 쓰는 도중 중단되어도 이전 내용이 남도록 임시 파일에 쓴 뒤 바꿔 넣는다.
+  pub fn save(&self, path: &Path) -> Result<(), String> {
+    let failed = |e: std::io::Error| format!("Failed to write print queue file '{}': {e}", path.display());
+    let json = serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize print queue: {e}"))?;
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, json + "\n").map_err(failed)?;
+    fs::rename(&temp, path).map_err(failed)
+  }
+
+  /// 끝난(보냈거나 취소된) 작업들을 지우는 함수. 새 인쇄를 시작할 때 파일이 계속 커지지 않도록 한다.
+  pub fn remove_finished(&mut self) {
+    self.jobs.retain(|job| job.status.is_unfinished());
+  }
+
+  /// 그룹 하나의 라벨들을 대기 중인 작업으로 추가하고 id를 반환하는 함수.
+  pub fn submit(&mut self, printer: &PrinterSettings, group_index: usize, labels: Vec<QueuedLabel>) -> u64 {
+    self.next_id += 1;
+    self.jobs.push(PrintJob {
+      id: self.next_id,
+      submitted_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+      printer: printer.clone(),
+      group_index,
+      labels,
+      status: JobStatus::Pending,
+      error: None,
+    });
+    self.next_id
+  }
+
+  /// id의 작업을 찾는 함수.
+  pub fn job_mut(&mut self, id: u64) -> Result<&mut PrintJob, String> {
+    self.jobs.iter_mut().find(|job| job.id == id).ok_or_else(|| format!("No print job #{id} in the queue."))
+  }
+
+  /// 보내지 못한(대기 중이거나 실패한) 작업들의 id.
+  pub fn unfinished_ids(&self) -> Vec<u64> {
+    self.jobs.iter().filter(|job| job.status.is_unfinished()).map(|job| job.id).collect()
+  }
+
+  /// 다시 보낼 작업들의 id. 지정된 id가 없다면 보내지 못한 모든 작업이다.
+  pub fn retry_ids(&self, ids: &[u64]) -> Result<Vec<u64>, String> {
+    if ids.is_empty() {
+      return Ok(self.unfinished_ids());
+    }
+    for &id in ids {
+      if !self.jobs.iter().any(|job| job.id == id) {
+        return Err(format!("No print job #{id} in the queue."));
+      }
+    }
+    Ok(ids.to_vec())
+  }
+}
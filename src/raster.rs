@@ -0,0 +1,503 @@
+use std::{borrow::Cow, fmt, fs, path::Path};
+
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont, point};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  barcode::code128,
+  models::DockId,
+  preview::{GLYPH_HEIGHT, GLYPH_WIDTH, glyph},
+  template::{FieldKind, GroupNumber, LabelTemplate},
+};
+
+/// 인치당 밀리미터
+pub const MM_PER_INCH: f64 = 25.4;
+
+// 라벨 이미지 한 변의 최대 픽셀 수. 잘못 입력한 DPI나 크기로 거대한 이미지를 만들지 않도록 한다.
+const MAX_LABEL_PIXELS: u32 = 10_000;
+
+// 인쇄 위치 보정값의 최대 크기(밀리미터). 라벨 한 장을 넘게 옮겨야 한다면 용지 설정이 잘못된 것이다.
+const MAX_OFFSET_MM: f64 = 100.0;
+
+/// 흑백 비트맵 이미지. 라벨 이미지를 그리고 PNG나 터미널 그래픽으로 내보내는 데 사용한다.
+pub struct Bitmap {
+  pub width: u32,
+  pub height: u32,
+  // 위에서부터 한 줄씩, true가 검은 칸이다.
+  pixels: Vec<bool>,
+}
+
+/// 라벨 하나의 픽셀 크기.
+#[derive(Debug, Clone, Copy)]
+pub struct LabelSize {
+  pub width: u32,
+  pub height: u32,
+}
+
+/// 라벨의 실제 크기(밀리미터). 예: 100x60
+/// JSON에서는 명령줄과 같은 "100x60" 형식의 문자열로 표현한다.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct LabelDimensions {
+  pub width_mm: f64,
+  pub height_mm: f64,
+}
+
+impl Default for LabelDimensions {
+  fn default() -> Self {
+    LabelDimensions { width_mm: 100.0, height_mm: 60.0 }
+  }
+}
+
+impl fmt::Display for LabelDimensions {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}x{}", self.width_mm, self.height_mm)
+  }
+}
+
+impl TryFrom<String> for LabelDimensions {
+  type Error = String;
+
+  fn try_from(s: String) -> Result<Self, Self::Error> {
+    parse_label_dimensions(&s)
+  }
+}
+
+impl From<LabelDimensions> for String {
+  fn from(size: LabelDimensions) -> Self {
+    size.to_string()
+  }
+}
+
+impl JsonSchema for LabelDimensions {
+  fn schema_name() -> Cow<'static, str> {
+    "LabelDimensions".into()
+  }
+
+  fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "description": "Label size in millimeters, WIDTHxHEIGHT (\"100x60\")",
+      "type": "string",
+      "pattern": "^\\s*\\d+(\\.\\d+)?\\s*[xX]\\s*\\d+(\\.\\d+)?\\s*(mm)?\\s*$"
+    })
+  }
+}
+
+/// 프린터가 라벨을 찍는 위치의 보정값(밀리미터). 양수는 오른쪽(x)과 아래쪽(y)으로 옮긴다. 예: 1.5,-0.5
+/// `calibrate`로 찾은 값이며, JSON과 설정 파일에서는 명령줄과 같은 "1.5,-0.5" 형식의 문자열로 표현한다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct LabelOffset {
+  pub x_mm: f64,
+  pub y_mm: f64,
+}
+
+impl fmt::Display for LabelOffset {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{},{}", self.x_mm, self.y_mm)
+  }
+}
+
+impl TryFrom<String> for LabelOffset {
+  type Error = String;
+
+  fn try_from(s: String) -> Result<Self, Self::Error> {
+    parse_label_offset(&s)
+  }
+}
+
+impl From<LabelOffset> for String {
+  fn from(offset: LabelOffset) -> Self {
+    offset.to_string()
+  }
+}
+
+impl JsonSchema for LabelOffset {
+  fn schema_name() -> Cow<'static, str> {
+    "LabelOffset".into()
+  }
+
+  fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "description": "Print position correction in millimeters, X,Y (\"1.5,-0.5\"); positive moves right and down",
+      "type": "string",
+      "pattern": "^\\s*-?\\d+(\\.\\d+)?\\s*,\\s*-?\\d+(\\.\\d+)?\\s*$"
+    })
+  }
+}
+
+impl LabelOffset {
+  /// dpi 해상도에서의 dot 수 (x, y).
+  pub fn to_dots(self, dpi: u32) -> (i32, i32) {
+    let dots = |mm: f64| (mm * f64::from(dpi) / MM_PER_INCH).round() as i32;
+    (dots(self.x_mm), dots(self.y_mm))
+  }
+}
+
+/// "1.5,-0.5" 형식의 인쇄 위치 보정값(밀리미터, x,y)을 파싱하는 함수. clap의 value_parser로 사용됩니다.
+pub fn parse_label_offset(s: &str) -> Result<LabelOffset, String> {
+  let invalid = || format!("Invalid label offset: '{s}'. Expected X,Y in millimeters between -{MAX_OFFSET_MM} and {MAX_OFFSET_MM} (e.g., 1.5,-0.5).");
+  let (x, y) = s.trim().split_once(',').ok_or_else(invalid)?;
+  let parse = |mm: &str| mm.trim().parse::<f64>().ok().filter(|mm| mm.abs() <= MAX_OFFSET_MM).ok_or_else(invalid);
+  Ok(LabelOffset { x_mm: parse(x)?, y_mm: parse(y)? })
+}
+
+/// "100x60" 형식의 라벨 크기(밀리미터, 폭x높이)를 파싱하는 함수. clap의 value_parser로 사용됩니다.
+/// 단위를 붙인 "100x60mm"도 받는다.
+pub fn parse_label_dimensions(s: &str) -> Result<LabelDimensions, String> {
+  let invalid = || format!("Invalid label size: '{s}'. Expected WIDTHxHEIGHT in millimeters (e.g., 100x60).");
+  let trimmed = s.trim();
+  let (width, height) = trimmed.strip_suffix("mm").unwrap_or(trimmed).split_once(['x', 'X']).ok_or_else(invalid)?;
+  let parse = |mm: &str| mm.trim().parse::<f64>().ok().filter(|mm| mm.is_finite() && *mm > 0.0).ok_or_else(invalid);
+  Ok(LabelDimensions { width_mm: parse(width)?, height_mm: parse(height)? })
+}
+
+impl LabelDimensions {
+  /// dpi 해상도에서의 픽셀 크기를 구하는 함수. 너무 크거나 1픽셀보다 작다면 에러를 반환한다.
+  pub fn to_pixels(self, dpi: u32) -> Result<LabelSize, String> {
+    let pixels = |mm: f64| (mm * f64::from(dpi) / MM_PER_INCH).round();
+    let (width, height) = (pixels(self.width_mm), pixels(self.height_mm));
+    if width < 1.0 || height < 1.0 || width > f64::from(MAX_LABEL_PIXELS) || height > f64::from(MAX_LABEL_PIXELS) {
+      return Err(format!(
+        "Label size {}x{} mm at {dpi} DPI is {width}x{height} pixels; each side must be 1 to {MAX_LABEL_PIXELS} pixels.",
+        self.width_mm, self.height_mm
+      ));
+    }
+    Ok(LabelSize { width: width as u32, height: height as u32 })
+  }
+}
+
+/// 라벨 글자를 그리는 글꼴. 지정하지 않았다면 내장된 큰 글자 글꼴(--preview와 같은 모양)을 사용한다.
+/// 굵게(--bold)와 기울임(--italic)은 글꼴 파일과 관계없이 그린 글자의 획을 넓히거나 기울여 만든다.
+pub struct LabelFont {
+  face: FontFace,
+  pub bold: bool,
+  pub italic: bool,
+}
+
+enum FontFace {
+  Block,
+  // TrueType/OpenType 글꼴 파일
+  Outline(FontVec),
+}
+
+// 기울임 글자에서 글자 높이 1픽셀당 오른쪽으로 미는 폭 (약 11도)
+const ITALIC_SLANT: f64 = 0.2;
+
+impl LabelFont {
+  /// 내장된 큰 글자 글꼴.
+  pub fn block() -> Self {
+    LabelFont { face: FontFace::Block, bold: false, italic: false }
+  }
+
+  /// 글꼴 파일을 읽는 함수. 글꼴 모음(.ttc)이라면 첫 번째 글꼴을 사용한다.
+  pub fn load(path: &Path) -> Result<Self, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read font '{}': {e}", path.display()))?;
+    let font = FontVec::try_from_vec(data)
+      .map_err(|_| format!("Invalid font '{}': not a TrueType or OpenType font.", path.display()))?;
+    Ok(LabelFont { face: FontFace::Outline(font), bold: false, italic: false })
+  }
+
+  // 내장된 큰 글자 글꼴인지 여부. 내장 글꼴은 글자 높이를 한 칸(GLYPH_HEIGHT) 단위로 맞춘다.
+  fn is_block(&self) -> bool {
+    matches!(self.face, FontFace::Block)
+  }
+
+  /// text의 모든 글자를 이 글꼴로 그릴 수 있는지 확인하는 함수.
+  /// 한글 같은 글자를 글꼴이 갖고 있지 않다면 빈 칸이나 네모로 인쇄되지 않도록 에러를 반환한다.
+  pub fn check_text(&self, text: &str) -> Result<(), String> {
+    let missing: Vec<String> = text
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .filter(|&c| match &self.face {
+        FontFace::Block => glyph(c).is_none(),
+        FontFace::Outline(font) => font.glyph_id(c).0 == 0,
+      })
+      .map(|c| format!("'{c}'"))
+      .collect();
+    if missing.is_empty() {
+      return Ok(());
+    }
+    let font = match self.face {
+      FontFace::Block => "the built-in block font (digits, A-Z and - . : / @ *)",
+      FontFace::Outline(_) => "the --font file",
+    };
+    Err(format!(
+      "Label text '{text}' has characters missing from {font}: {}. Use a --font that covers them (for Korean, e.g. Noto Sans CJK or NanumGothic).",
+      missing.join(", ")
+    ))
+  }
+
+  // 굵은 글자에서 획마다 오른쪽으로 더 칠하는 픽셀 수.
+  fn bold_stroke(&self, height: u32) -> u32 {
+    if self.bold { (height / 12).max(1) } else { 0 }
+  }
+
+  // 기울임으로 글자 위쪽이 오른쪽으로 밀려나는 최대 픽셀 수.
+  fn italic_shift(&self, height: u32) -> u32 {
+    if self.italic { (f64::from(height) * ITALIC_SLANT).ceil() as u32 } else { 0 }
+  }
+
+  // 글자 높이(외곽선 글꼴은 ascent)가 height 픽셀일 때 text의 픽셀 폭.
+  fn text_width(&self, text: &str, height: u32) -> u32 {
+    let width = match &self.face {
+      FontFace::Block => text_width(text, (height / GLYPH_HEIGHT as u32).max(1)),
+      FontFace::Outline(font) => {
+        let font = font.as_scaled(outline_scale(font, height));
+        let mut width = 0.0;
+        let mut previous = None;
+        for c in text.chars() {
+          let id = font.glyph_id(c);
+          if let Some(previous) = previous {
+            width += font.kern(previous, id);
+          }
+          width += font.h_advance(id);
+          previous = Some(id);
+        }
+        width.ceil() as u32
+      }
+    };
+    width + self.bold_stroke(height) + self.italic_shift(height)
+  }
+
+  // text를 (x, y)부터 글자 높이 height 픽셀로 그린다. 외곽선 글꼴은 절반 이상 덮인 픽셀만 검게 칠한다.
+  fn draw(&self, bitmap: &mut Bitmap, text: &str, x: u32, y: u32, height: u32) {
+    let stroke = i64::from(self.bold_stroke(height));
+    let slant = if self.italic { ITALIC_SLANT } else { 0.0 };
+    // 기울임은 글자 높이의 아래쪽(기준선)에서 위로 갈수록 오른쪽으로 민다.
+    let bottom = i64::from(y + height);
+    let mut plot = |px: i64, py: i64| {
+      let left = px + ((bottom - py) as f64 * slant).round() as i64;
+      let right = left + stroke + 1;
+      if py >= 0 && right > 0 {
+        let left = left.max(0);
+        bitmap.fill_rect(left as u32, py as u32, (right - left) as u32, 1);
+      }
+    };
+    match &self.face {
+      FontFace::Block => {
+        let cell = (height / GLYPH_HEIGHT as u32).max(1);
+        for (i, c) in text.chars().enumerate() {
+          let Some(rows) = glyph(c) else { continue };
+          let left = i64::from(x + i as u32 * (GLYPH_WIDTH as u32 + 1) * cell);
+          for (row, line) in rows.iter().enumerate() {
+            for (col, _) in line.char_indices().filter(|(_, c)| *c == '#') {
+              for dy in 0..cell {
+                for dx in 0..cell {
+                  plot(left + i64::from(col as u32 * cell + dx), i64::from(y + row as u32 * cell + dy));
+                }
+              }
+            }
+          }
+        }
+      }
+      FontFace::Outline(font) => {
+        let scaled = font.as_scaled(outline_scale(font, height));
+        // 글자의 기준선은 글자 높이의 아래쪽이다.
+        let baseline = y as f32 + scaled.ascent();
+        let mut cursor = x as f32;
+        let mut previous = None;
+        for c in text.chars() {
+          let id = scaled.glyph_id(c);
+          if let Some(previous) = previous {
+            cursor += scaled.kern(previous, id);
+          }
+          let positioned = id.with_scale_and_position(scaled.scale(), point(cursor, baseline));
+          if let Some(outlined) = font.outline_glyph(positioned) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+              if coverage >= 0.5 {
+                plot(bounds.min.x as i64 + i64::from(gx), bounds.min.y as i64 + i64::from(gy));
+              }
+            });
+          }
+          cursor += scaled.h_advance(id);
+          previous = Some(id);
+        }
+      }
+    }
+  }
+}
+
+// 글꼴의 ascent가 height 픽셀이 되는 크기. ab_glyph의 크기는 ascent - descent의 픽셀 높이이다.
+fn outline_scale(font: &FontVec, height: u32) -> PxScale {
+  let ascent = font.ascent_unscaled().max(1.0);
+  PxScale::from(height as f32 * font.height_unscaled() / ascent)
+}
+
+impl Bitmap {
+  /// 흰 바탕의 빈 비트맵을 만드는 함수.
+  pub fn new(width: u32, height: u32) -> Self {
+    Bitmap { width, height, pixels: vec![false; width as usize * height as usize] }
+  }
+
+  /// (x, y) 칸이 검은색인지 여부. 이미지 밖은 흰색이다.
+  pub fn ink(&self, x: u32, y: u32) -> bool {
+    x < self.width && y < self.height && self.pixels[(y * self.width + x) as usize]
+  }
+
+  /// (x, y)에서 시작하는 width x height 사각형을 검게 칠하는 함수. 이미지 밖으로 나간 부분은 잘린다.
+  pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
+    for row in y..(y + height).min(self.height) {
+      for col in x..(x + width).min(self.width) {
+        self.pixels[(row * self.width + col) as usize] = true;
+      }
+    }
+  }
+
+  /// 큰 글자 글꼴로 text를 (x, y)부터 그리는 함수. 글꼴의 한 칸은 cell x cell 픽셀이다.
+  /// 글꼴에 없는 글자는 빈 칸으로 남긴다.
+  pub fn draw_text(&mut self, text: &str, x: u32, y: u32, cell: u32) {
+    for (i, c) in text.chars().enumerate() {
+      let Some(rows) = glyph(c) else { continue };
+      let left = x + i as u32 * (GLYPH_WIDTH as u32 + 1) * cell;
+      for (row, line) in rows.iter().enumerate() {
+        for (col, _) in line.char_indices().filter(|(_, c)| *c == '#') {
+          self.fill_rect(left + col as u32 * cell, y + row as u32 * cell, cell, cell);
+        }
+      }
+    }
+  }
+
+  /// 이미지들을 gap 픽셀 간격으로 왼쪽부터 나란히 붙인 이미지를 만드는 함수. 높이는 가장 높은 이미지에 맞춘다.
+  pub fn beside(images: &[Bitmap], gap: u32) -> Bitmap {
+    let width = images.iter().map(|image| image.width).sum::<u32>() + gap * (images.len() as u32).saturating_sub(1);
+    let height = images.iter().map(|image| image.height).max().unwrap_or(0);
+    let mut joined = Bitmap::new(width, height);
+    let mut left = 0;
+    for image in images {
+      for y in 0..image.height {
+        for x in 0..image.width {
+          if image.ink(x, y) {
+            joined.pixels[(y * width + left + x) as usize] = true;
+          }
+        }
+      }
+      left += image.width + gap;
+    }
+    joined
+  }
+
+  /// 8비트 흑백 PNG로 인코딩하는 함수. dpi가 있다면 라벨 프로그램이 실제 크기를 알 수 있도록 해상도를 기록한다.
+  pub fn to_png(&self, dpi: Option<u32>) -> Result<Vec<u8>, String> {
+    let data: Vec<u8> = self.pixels.iter().map(|&ink| if ink { 0 } else { 255 }).collect();
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    if let Some(dpi) = dpi {
+      // PNG의 해상도 단위는 미터당 픽셀이다.
+      let per_meter = (f64::from(dpi) * 1000.0 / MM_PER_INCH).round() as u32;
+      encoder.set_pixel_dims(Some(png::PixelDimensions { xppu: per_meter, yppu: per_meter, unit: png::Unit::Meter }));
+    }
+    encoder
+      .write_header()
+      .and_then(|mut writer| writer.write_image_data(&data))
+      .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+    Ok(png)
+  }
+}
+
+/// 큰 글자 글꼴로 쓴 text의 픽셀 폭.
+pub fn text_width(text: &str, cell: u32) -> u32 {
+  (text.chars().count() as u32 * (GLYPH_WIDTH as u32 + 1)).saturating_sub(1) * cell
+}
+
+/// 라벨 하나를 그리는 함수. 텍스트 미리보기(--preview)와 같은 배치로,
+/// 테두리 안의 위쪽 줄에 그룹 번호(왼쪽)와 기호(오른쪽)를, 그 아래에 도크 번호를 가능한 한 크게 그린다.
+/// small_height는 위쪽 줄의 글자 높이(픽셀)이며, None이라면 라벨 높이에 비례한다.
+pub fn render_label(dock: DockId, group: GroupNumber, marker: &str, size: LabelSize, small_height: Option<u32>, font: &LabelFont) -> Bitmap {
+  let LabelSize { width, height } = size;
+  let mut label = Bitmap::new(width, height);
+
+  // 테두리 두께와 작은 글자의 크기, 여백은 라벨 높이에 비례한다.
+  let border = (height / 48).max(1);
+  let small = (height / 40).max(1);
+  let small_height = small_height.unwrap_or(GLYPH_HEIGHT as u32 * small).max(1);
+  label.fill_rect(0, 0, width, border);
+  label.fill_rect(0, height.saturating_sub(border), width, border);
+  label.fill_rect(0, 0, border, height);
+  label.fill_rect(width.saturating_sub(border), 0, border, height);
+
+  // 위쪽 줄: 그룹 번호와 기호
+  let margin = border + 2 * small;
+  font.draw(&mut label, &format!("G{group}"), margin, margin, small_height);
+  let marker_left = width.saturating_sub(margin + font.text_width(marker, small_height));
+  font.draw(&mut label, marker, marker_left, margin, small_height);
+
+  // 나머지 영역의 가운데에 도크 번호를 들어가는 가장 큰 크기로 그린다.
+  let digits = dock.to_string();
+  let top = margin + small_height + 2 * small;
+  let area_width = width.saturating_sub(2 * margin);
+  let area_height = height.saturating_sub(top + margin);
+  // 글자 높이 1픽셀당 폭으로 area_width에 들어가는 최대 높이를 구한다. 내장 글꼴은 한 칸 단위로 맞춘다.
+  let unit_width = font.text_width(&digits, GLYPH_HEIGHT as u32 * 100) as f64 / (GLYPH_HEIGHT as f64 * 100.0);
+  let mut digit_height = ((area_width as f64 / unit_width.max(f64::MIN_POSITIVE)) as u32).min(area_height).max(1);
+  if font.is_block() {
+    digit_height = (digit_height / GLYPH_HEIGHT as u32).max(1) * GLYPH_HEIGHT as u32;
+  }
+  let left = margin + area_width.saturating_sub(font.text_width(&digits, digit_height)) / 2;
+  let y = top + area_height.saturating_sub(digit_height) / 2;
+  font.draw(&mut label, &digits, left, y, digit_height);
+  label
+}
+
+/// 라벨 템플릿으로 라벨 하나를 그리는 함수. size는 dpi 해상도에서의 라벨 크기(픽셀)이다.
+/// 글은 필드 폭에 들어가도록 글자 크기를 줄이며, 바코드는 필드 폭에 들어가는 가장 굵은 막대로 그린다.
+pub fn render_template(
+  template: &LabelTemplate,
+  dock: DockId,
+  group: GroupNumber,
+  marker: &str,
+  size: LabelSize,
+  dpi: u32,
+  font: &LabelFont,
+) -> Result<Bitmap, String> {
+  let mut label = Bitmap::new(size.width, size.height);
+  let pixels = |mm: f64| (mm * f64::from(dpi) / MM_PER_INCH).round() as u32;
+  let label_width_mm = f64::from(size.width) * MM_PER_INCH / f64::from(dpi);
+  for field in &template.fields {
+    let (x, y, height) = (pixels(field.x), pixels(field.y), pixels(field.height).max(1));
+    let width = pixels(field.width_in(label_width_mm));
+    match field.kind {
+      FieldKind::Box => {
+        let thickness = pixels(field.thickness()).max(1);
+        label.fill_rect(x, y, width, thickness);
+        label.fill_rect(x, (y + height).saturating_sub(thickness), width, thickness);
+        label.fill_rect(x, y, thickness, height);
+        label.fill_rect((x + width).saturating_sub(thickness), y, thickness, height);
+      }
+      FieldKind::Barcode => {
+        let modules = code128(&field.content(dock, group, marker))?;
+        let total: u32 = modules.iter().map(|&module| u32::from(module)).sum();
+        let module_width = (width / total).max(1);
+        let mut left = x + field.align.offset(f64::from(width), f64::from(total * module_width)) as u32;
+        // 막대와 공백이 번갈아 오며 막대부터 시작한다.
+        for (i, &module) in modules.iter().enumerate() {
+          let bar_width = u32::from(module) * module_width;
+          if i % 2 == 0 {
+            label.fill_rect(left, y, bar_width, height);
+          }
+          left += bar_width;
+        }
+      }
+      FieldKind::Dock | FieldKind::Group | FieldKind::Marker | FieldKind::Text => {
+        let text = field.content(dock, group, marker);
+        if text.is_empty() {
+          continue;
+        }
+        font.check_text(&text)?;
+        let natural_width = font.text_width(&text, height).max(1);
+        let mut text_height = if natural_width > width { (u64::from(height) * u64::from(width) / u64::from(natural_width)) as u32 } else { height };
+        // 내장 글꼴은 한 칸 단위로 맞춘다.
+        if font.is_block() {
+          text_height = (text_height / GLYPH_HEIGHT as u32).max(1) * GLYPH_HEIGHT as u32;
+        }
+        let left = x + field.align.offset(f64::from(width), f64::from(font.text_width(&text, text_height))) as u32;
+        font.draw(&mut label, &text, left, y, text_height.max(1));
+      }
+    }
+  }
+  Ok(label)
+}
@@ -0,0 +1,44 @@
+use std::{
+  collections::HashMap,
+  time::{Duration, Instant},
+};
+
+/// 키(token 지문 또는 IP 주소)마다 분당 요청 수를 제한하는 token bucket.
+/// 한도만큼은 한꺼번에 보낼 수 있고, 그 뒤로는 1분에 한도만큼씩 다시 채워진다.
+pub struct RateLimiter {
+  per_minute: u32,
+  buckets: HashMap<String, Bucket>,
+}
+
+// 키 하나의 남은 요청 수와 마지막으로 계산한 시각.
+struct Bucket {
+  tokens: f64,
+  updated: Instant,
+}
+
+impl RateLimiter {
+  /// 키마다 분당 per_minute개의 요청을 허용하는 제한기를 만든다.
+  pub fn new(per_minute: u32) -> Self {
+    RateLimiter { per_minute, buckets: HashMap::new() }
+  }
+
+  /// key의 요청 하나를 허용한다면 Ok, 한도를 넘었다면 다음 요청이 가능해질 때까지의 시간을 Err로 반환하는 함수.
+  pub fn check(&mut self, key: &str) -> Result<(), Duration> {
+    let now = Instant::now();
+    let capacity = f64::from(self.per_minute);
+    let per_second = capacity / 60.0;
+    let refilled = |bucket: &Bucket| (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * per_second).min(capacity);
+    // 다시 가득 찬 bucket은 처음 보는 키와 같으므로 지워서, 다녀간 IP 주소들이 쌓이지 않도록 한다.
+    self.buckets.retain(|_, bucket| refilled(bucket) < capacity);
+
+    let bucket = self.buckets.entry(key.to_string()).or_insert(Bucket { tokens: capacity, updated: now });
+    bucket.tokens = refilled(bucket);
+    bucket.updated = now;
+    if bucket.tokens >= 1.0 {
+      bucket.tokens -= 1.0;
+      Ok(())
+    } else {
+      Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+    }
+  }
+}
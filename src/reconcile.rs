@@ -0,0 +1,113 @@
+use std::{
+  collections::{BTreeSet, HashMap},
+  fs,
+  path::Path,
+};
+
+use crate::{models::DockId, processor::ProcessingResult};
+
+/// plan보다 늦게 나올 그룹의 도크를 먼저 처리한 뒤 스캔된 도크.
+/// 그룹 번호는 출력 순서 기준 1부터 시작한다.
+pub struct OutOfOrderScan {
+  pub dock: DockId,
+  // 스캔 파일에서의 순서 (1부터)
+  pub scan_index: usize,
+  // 도크가 속한 plan 그룹
+  pub group: usize,
+  // 이 도크보다 먼저 스캔된 도크들 중 가장 늦은 plan 그룹
+  pub after_group: usize,
+}
+
+/// 계획된 그룹 순서와 실제 스캔 순서를 비교한 결과.
+pub struct Reconciliation {
+  // 스캔 파일의 스캔 수
+  pub scans: usize,
+  pub out_of_order: Vec<OutOfOrderScan>,
+  // plan에 있지만 한 번도 스캔되지 않은 도크들 (오름차순)
+  pub missed: Vec<DockId>,
+  // 스캔되었지만 plan에 없는 도크들 (오름차순)
+  pub unplanned: Vec<DockId>,
+  // 두 번 이상 스캔된 도크들 (오름차순)
+  pub rescanned: Vec<DockId>,
+}
+
+impl Reconciliation {
+  /// plan대로 모든 도크가 순서에 맞게 한 번씩 처리되었는지 여부.
+  pub fn is_clean(&self) -> bool {
+    self.out_of_order.is_empty() && self.missed.is_empty() && self.unplanned.is_empty() && self.rescanned.is_empty()
+  }
+}
+
+/// 핸디 스캐너에서 내려받은 스캔 CSV 파일을 읽어 스캔된 순서대로 도크 번호를 반환하는 함수.
+/// 각 줄의 첫 필드가 도크 번호이며 나머지 필드(스캔 시각, 작업자 등)는 무시한다.
+/// 빈 줄과 `#` 주석 줄, 그리고 숫자가 아닌 첫 줄(헤더)은 무시한다.
+pub fn load_scans(path: &Path) -> Result<Vec<DockId>, String> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read scan file '{}': {e}", path.display()))?;
+
+  let mut scans: Vec<DockId> = Vec::new();
+  for (line_idx, line) in content.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let dock_field = line.split(',').next().unwrap_or_default().trim();
+    match dock_field.parse::<DockId>() {
+      Ok(dock) => scans.push(dock),
+      // 첫 줄의 도크 필드가 숫자가 아니라면 헤더로 간주한다.
+      Err(_) if line_idx == 0 => continue,
+      Err(_) => {
+        return Err(format!(
+          "Invalid dock number '{dock_field}' on line {} of '{}'.",
+          line_idx + 1,
+          path.display()
+        ));
+      }
+    }
+  }
+  Ok(scans)
+}
+
+/// plan의 그룹 순서와 실제 스캔 순서(scans)를 비교하는 함수.
+/// 먼저 스캔된 도크들의 가장 늦은 그룹보다 앞선 그룹의 도크가 스캔되면 순서를 어긴 것으로 본다.
+/// 다시 스캔된 도크는 처음 스캔만 순서 비교에 사용한다.
+pub fn reconcile(result: &ProcessingResult, scans: &[DockId]) -> Reconciliation {
+  // 도크 -> 출력 순서상 그룹 번호 (1부터)
+  let group_of: HashMap<DockId, usize> = result
+    .result_groups
+    .iter()
+    .enumerate()
+    .flat_map(|(i, group)| group.docks.iter().map(move |&dock| (dock, i + 1)))
+    .collect();
+
+  let mut seen: BTreeSet<DockId> = BTreeSet::new();
+  let mut rescanned: BTreeSet<DockId> = BTreeSet::new();
+  let mut unplanned: BTreeSet<DockId> = BTreeSet::new();
+  let mut out_of_order: Vec<OutOfOrderScan> = Vec::new();
+  // 지금까지 스캔된 도크들 중 가장 늦은 그룹
+  let mut latest_group = 0;
+  for (i, &dock) in scans.iter().enumerate() {
+    if !seen.insert(dock) {
+      rescanned.insert(dock);
+      continue;
+    }
+    let Some(&group) = group_of.get(&dock) else {
+      unplanned.insert(dock);
+      continue;
+    };
+    if group < latest_group {
+      out_of_order.push(OutOfOrderScan { dock, scan_index: i + 1, group, after_group: latest_group });
+    }
+    latest_group = latest_group.max(group);
+  }
+
+  let mut missed: Vec<DockId> = group_of.keys().copied().filter(|dock| !seen.contains(dock)).collect();
+  missed.sort_unstable();
+  Reconciliation {
+    scans: scans.len(),
+    out_of_order,
+    missed,
+    unplanned: unplanned.into_iter().collect(),
+    rescanned: rescanned.into_iter().collect(),
+  }
+}
@@ -0,0 +1,145 @@
+use std::io::{self, BufRead, Write};
+
+use clap::{CommandFactory, FromArgMatches};
+
+use crate::{
+  cli::{Args, ReplayOverrides, parse_dock_ranges, parse_exception_group, parse_priority_range},
+  printer::print_results,
+  processor::{PlanRequest, process_docks},
+};
+
+/// 초기 인자가 없을 때 사용하는 그룹당 도크 수.
+const DEFAULT_REPL_PER_PAGE: &str = "3";
+
+const HELP: &str = "\
+Commands:
+  first <docks...>         set 1st priority docks (e.g. `first 65-66 71`, `first` clears)
+  second <docks...>        set 2nd priority docks
+  except <docks...>        set exception groups (e.g. `except 51-53 60,64`)
+  pp <n>                   docks per group
+  fpp <n|off>              docks per group for 1st priority
+  spp <n|off>              docks per group for 2nd priority
+  min <n> / max <n>        dock range bounds
+  range <a-b>              set min and max at once
+  strict-first <on|off>    group 1st priority docks only with each other
+  strict-second <on|off>   group 2nd priority docks only with each other
+  marker <on|off>          mark priority docks in the output (@, *)
+  show                     print the current plan
+  help                     show this help
+  quit                     leave the REPL";
+
+/// 명령을 한 줄씩 입력받아 인자를 바꾸고, 바꿀 때마다 plan을 다시 계산하여 출력하는 대화형 모드.
+/// initial은 기본 실행과 같은 형식의 시작 인자이며, -p가 없다면 `-p 3`으로 시작한다.
+pub fn run_repl(initial: &[String]) -> Result<(), String> {
+  let mut initial = initial.to_vec();
+  let has_per_page = initial.iter().any(|a| a.starts_with("-p") || a.starts_with("--per-page"));
+  if !has_per_page {
+    initial.splice(0..0, ["-p".to_string(), DEFAULT_REPL_PER_PAGE.to_string()]);
+  }
+  let matches = ReplayOverrides::command()
+    .name("repl")
+    .try_get_matches_from(&initial)
+    .map_err(|e| e.to_string())?;
+  let mut args = ReplayOverrides::from_arg_matches(&matches).map_err(|e| e.to_string())?.args;
+  args.validate_input()?;
+
+  eprintln!("Type `help` for commands, `quit` to exit.");
+  show_plan(&args);
+
+  let stdin = io::stdin();
+  let mut line = String::new();
+  loop {
+    eprint!("dock> ");
+    io::stderr().flush().ok();
+    line.clear();
+    // EOF(Ctrl-D)라면 종료한다.
+    if stdin.lock().read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+      eprintln!();
+      return Ok(());
+    }
+
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next() else { continue };
+    let params: Vec<&str> = words.collect();
+    match command {
+      "quit" | "exit" => return Ok(()),
+      "help" => eprintln!("{HELP}"),
+      "show" => show_plan(&args),
+      _ => {
+        // 변경한 인자가 유효하지 않다면 이전 상태를 그대로 유지한다.
+        let mut changed = args.clone();
+        match apply_command(&mut changed, command, &params).and_then(|_| changed.validate_input()) {
+          Ok(()) => {
+            args = changed;
+            show_plan(&args);
+          }
+          Err(e) => eprintln!("Error: {e}"),
+        }
+      }
+    }
+  }
+}
+
+/// 명령 하나를 args에 적용하는 함수.
+fn apply_command(args: &mut Args, command: &str, params: &[&str]) -> Result<(), String> {
+  match command {
+    "first" => args.first_priority = parse_docks(params, parse_priority_range)?,
+    "second" => args.second_priority = parse_docks(params, parse_priority_range)?,
+    "except" => args.exception_groups_raw = parse_docks(params, parse_exception_group)?,
+    "pp" => args.per_page = parse_number(command, params)?,
+    "fpp" => args.first_priority_per_page = parse_optional_number(command, params)?,
+    "spp" => args.second_priority_per_page = parse_optional_number(command, params)?,
+    "min" => args.min = parse_number(command, params)?,
+    "max" => args.max = parse_number(command, params)?,
+    "range" => {
+      let range = parse_dock_ranges(single_param(command, params)?)?;
+      args.min = range.start;
+      args.max = range.end;
+    }
+    "strict-first" => args.strict_first = parse_switch(command, params)?,
+    "strict-second" => args.strict_second = parse_switch(command, params)?,
+    "marker" => args.print_marker = parse_switch(command, params)?,
+    _ => return Err(format!("Unknown command `{command}`. Type `help` for commands.")),
+  }
+  Ok(())
+}
+
+/// 현재 인자로 plan을 계산하여 출력하는 함수.
+fn show_plan(args: &Args) {
+  print_results(args, &process_docks(PlanRequest::new(args)));
+}
+
+/// 도크 목록 인자들을 CLI의 -f, -s, -e와 같은 방식으로 파싱한다.
+fn parse_docks<T>(params: &[&str], parse: fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+  params.iter().map(|p| parse(p)).collect()
+}
+
+/// 인자가 정확히 하나인지 확인하고 반환한다.
+fn single_param<'a>(command: &str, params: &[&'a str]) -> Result<&'a str, String> {
+  match params {
+    [param] => Ok(param),
+    _ => Err(format!("`{command}` takes exactly one value.")),
+  }
+}
+
+fn parse_number<T: std::str::FromStr>(command: &str, params: &[&str]) -> Result<T, String> {
+  let param = single_param(command, params)?;
+  param.parse().map_err(|_| format!("`{command}` expects a number, got '{param}'."))
+}
+
+/// 숫자 또는 설정을 지우는 `off`를 파싱한다.
+fn parse_optional_number(command: &str, params: &[&str]) -> Result<Option<u16>, String> {
+  match single_param(command, params)? {
+    "off" => Ok(None),
+    _ => parse_number(command, params).map(Some),
+  }
+}
+
+/// `on`/`off`를 파싱한다.
+fn parse_switch(command: &str, params: &[&str]) -> Result<bool, String> {
+  match single_param(command, params)? {
+    "on" => Ok(true),
+    "off" => Ok(false),
+    other => Err(format!("`{command}` expects `on` or `off`, got '{other}'.")),
+  }
+}
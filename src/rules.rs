@@ -0,0 +1,97 @@
+use std::{cell::RefCell, fs, path::Path};
+
+use rhai::{AST, Array, Dynamic, Engine, Scope};
+
+use crate::models::DockId;
+
+/// 현장마다 다른 그룹핑 정책을 processor에 끼워넣기 위한 규칙.
+/// 기본 규칙(우선순위, strict 모드, per-page 등)에 더해 적용된다.
+pub trait GroupingRules {
+  /// 지금까지 만들어진 그룹(current_group)에 next_dock을 붙이지 않고 새 그룹을 시작해야 하는지 여부.
+  fn should_break(&self, current_group: &[DockId], next_dock: DockId) -> bool;
+
+  /// first_dock으로 시작하는 일반 그룹의 목표 도크 수. None이라면 우선순위별 per-page를 따른다.
+  fn target_size(&self, first_dock: DockId) -> Option<usize>;
+}
+
+// 스크립트 하나가 실행할 수 있는 최대 연산 수. 잘못된 스크립트가 무한 루프에 빠져도 plan 계산이 멈추지 않도록 한다.
+const MAX_SCRIPT_OPERATIONS: u64 = 1_000_000;
+
+/// Rhai 스크립트로 작성된 그룹핑 규칙 (`--rules site_rules.rhai`).
+///
+/// 스크립트는 아래 함수 중 필요한 것만 정의하면 된다.
+///
+/// ```text
+/// // 그룹을 여기서 끊어야 한다면 true
+/// fn should_break(current_group, next_dock) { next_dock == 61 }
+/// // first_dock으로 시작하는 그룹의 목표 크기. 0 이하를 반환하면 per-page를 따른다.
+/// fn target_size(first_dock) { if first_dock >= 70 { 2 } else { 0 } }
+/// ```
+pub struct RhaiRules {
+  engine: Engine,
+  ast: AST,
+  has_should_break: bool,
+  has_target_size: bool,
+  // 스크립트 실행 중 처음 발생한 에러. 규칙 함수는 에러를 반환할 수 없으므로 여기에 남겨두고 계산이 끝난 뒤 확인한다.
+  error: RefCell<Option<String>>,
+}
+
+impl RhaiRules {
+  /// 스크립트 파일을 읽어 컴파일하는 함수. 규칙 함수가 하나도 정의되지 않았다면 에러를 반환한다.
+  pub fn load(path: &Path) -> Result<Self, String> {
+    let script = fs::read_to_string(path)
+      .map_err(|e| format!("Failed to read rules script '{}': {e}", path.display()))?;
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+    let ast = engine
+      .compile(&script)
+      .map_err(|e| format!("Invalid rules script '{}': {e}", path.display()))?;
+    let defines = |name: &str, params: usize| ast.iter_functions().any(|f| f.name == name && f.params.len() == params);
+    let (has_should_break, has_target_size) = (defines("should_break", 2), defines("target_size", 1));
+    if !has_should_break && !has_target_size {
+      return Err(format!(
+        "Rules script '{}' defines neither `should_break(current_group, next_dock)` nor `target_size(first_dock)`.",
+        path.display()
+      ));
+    }
+    Ok(RhaiRules { engine, ast, has_should_break, has_target_size, error: RefCell::new(None) })
+  }
+
+  /// 스크립트 실행 중 발생한 첫 에러. 에러가 있었다면 계산된 plan은 규칙이 일부 적용되지 않은 것이다.
+  pub fn error(&self) -> Option<String> {
+    self.error.borrow().clone()
+  }
+
+  // 스크립트 함수를 호출하고, 실패했다면 에러를 기록한 뒤 None을 반환한다.
+  // 이미 에러가 기록되었다면 결과를 버릴 plan이므로 더 이상 스크립트를 실행하지 않는다.
+  fn call<T: Clone + Send + Sync + 'static>(&self, name: &str, args: impl rhai::FuncArgs) -> Option<T> {
+    if self.error.borrow().is_some() {
+      return None;
+    }
+    match self.engine.call_fn::<T>(&mut Scope::new(), &self.ast, name, args) {
+      Ok(value) => Some(value),
+      Err(e) => {
+        self.error.borrow_mut().get_or_insert_with(|| format!("Rules script function `{name}` failed: {e}"));
+        None
+      }
+    }
+  }
+}
+
+impl GroupingRules for RhaiRules {
+  fn should_break(&self, current_group: &[DockId], next_dock: DockId) -> bool {
+    if !self.has_should_break {
+      return false;
+    }
+    let group: Array = current_group.iter().map(|&d| Dynamic::from(i64::from(d.get()))).collect();
+    self.call::<bool>("should_break", (group, i64::from(next_dock.get()))).unwrap_or(false)
+  }
+
+  fn target_size(&self, first_dock: DockId) -> Option<usize> {
+    if !self.has_target_size {
+      return None;
+    }
+    let size = self.call::<i64>("target_size", (i64::from(first_dock.get()),))?;
+    usize::try_from(size).ok().filter(|&size| size > 0)
+  }
+}
@@ -0,0 +1,89 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{i18n::tr, models::DockId};
+
+// 하루의 분 수
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// 트럭 도착 스케줄 CSV 파일을 읽어 도크 번호를 key로, 도착 예정 시각(자정 기준 분)을 value로 갖는 BTreeMap을 반환하는 함수.
+/// 각 줄은 `dock,HH:MM` 형식이며, 빈 줄과 `#` 주석 줄, 그리고 숫자가 아닌 첫 줄(헤더)은 무시한다.
+/// 같은 도크가 여러 번 나온다면 가장 이른 도착 시각을 사용한다.
+pub fn load_arrivals(path: &Path) -> Result<BTreeMap<DockId, u32>, String> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read arrival schedule '{}': {e}", path.display()))?;
+
+  let mut arrivals: BTreeMap<DockId, u32> = BTreeMap::new();
+  for (line_idx, line) in content.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let mut fields = line.split(',').map(str::trim);
+    let dock_field = fields.next().unwrap_or_default();
+    let time_field = fields.next().unwrap_or_default();
+
+    // 첫 줄의 도크 필드가 숫자가 아니라면 헤더로 간주한다.
+    let Ok(dock) = dock_field.parse::<DockId>() else {
+      if line_idx == 0 {
+        continue;
+      }
+      return Err(format!(
+        "Invalid dock number '{dock_field}' on line {} of '{}'.",
+        line_idx + 1,
+        path.display()
+      ));
+    };
+    let minutes = parse_time_of_day(time_field).ok_or_else(|| {
+      format!(
+        "Invalid arrival time '{time_field}' on line {} of '{}'. Expected HH:MM.",
+        line_idx + 1,
+        path.display()
+      )
+    })?;
+    // 이미 더 이른 도착 시각이 있다면 그것을 유지한다.
+    arrivals
+      .entry(dock)
+      .and_modify(|m| *m = (*m).min(minutes))
+      .or_insert(minutes);
+  }
+  Ok(arrivals)
+}
+
+/// "HH:MM" 형식의 시각을 자정 기준 분(minute)으로 변환하는 함수. 형식이 잘못되었다면 None을 반환한다.
+pub fn parse_time_of_day(s: &str) -> Option<u32> {
+  let (h, m) = s.split_once(':')?;
+  let hours = h.trim().parse::<u32>().ok()?;
+  let minutes = m.trim().parse::<u32>().ok()?;
+  if hours >= 24 || minutes >= 60 {
+    return None;
+  }
+  Some(hours * 60 + minutes)
+}
+
+/// 자정 기준 분을 "HH:MM" 형식의 문자열로 변환하는 함수.
+/// 자정을 넘는 시각(예: 23:00 시간대의 끝)은 다음 날의 시각으로 표시한다 ("24:00" 대신 "00:00").
+pub fn format_time_of_day(minutes: u32) -> String {
+  let minutes = minutes % MINUTES_PER_DAY;
+  format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// 도크의 도착 시각이 속하는 시간대(window)의 index를 반환하는 함수.
+/// 스케줄에 없는 도크는 None이 된다.
+pub fn arrival_window(arrivals: &BTreeMap<DockId, u32>, dock: DockId, window_minutes: u32) -> Option<u32> {
+  arrivals.get(&dock).map(|m| m / window_minutes)
+}
+
+/// 시간대 index를 "HH:MM-HH:MM" 형식의 그룹 헤더 문자열로 변환하는 함수.
+pub fn format_window(window: Option<u32>, window_minutes: u32) -> String {
+  match window {
+    Some(w) => {
+      let start = w.saturating_mul(window_minutes);
+      format!(
+        "{}-{}",
+        format_time_of_day(start),
+        format_time_of_day(start.saturating_add(window_minutes))
+      )
+    }
+    None => tr("window.unscheduled", &[]),
+  }
+}
@@ -0,0 +1,765 @@
+use std::{
+  net::TcpListener,
+  path::PathBuf,
+  sync::{Arc, Mutex, PoisonError},
+  time::Duration,
+};
+
+use chrono::{SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+  audit::{AuditEntry, append_audit, read_audit, sha256_hex},
+  batch::{BatchJob, BatchResult, run_api_job},
+  cli::{Args, ServeOptions},
+  config::{Config, ReloadableConfig},
+  i18n::tr,
+  metadata::{DockMetadataUpdate, check_dock_db, closed_docks, delete_dock, get_dock, list_docks, put_dock},
+  models::{DockId, RunStamp},
+  openapi::openapi_document,
+  plan::{SCHEMA_VERSION, plan_to_json},
+  processor::{PlanRequest, ProcessingResult, process_docks},
+  ratelimit::RateLimiter,
+  tls::{TlsFrontend, server_tls_config},
+  verify::verify,
+  websocket::{Broadcaster, accept_key},
+};
+
+/// HTTP API 서버를 host:port에 띄우고 요청을 처리하는 함수. 서버가 종료되지 않는 한 반환하지 않는다.
+///
+/// - `POST /plan`: CLI와 같은 인자를 JSON으로 받아 plan(`{"args": ..., "result": ...}`)을 반환한다.
+/// - `POST /plan/batch`: 작업 목록(JSON 배열)을 받아 작업별 결과 배열을 반환한다.
+/// - `GET /openapi.json`: API의 OpenAPI 문서를 반환한다.
+/// - `GET /healthz`, `GET /readyz`: 로드밸런서용 상태 확인.
+/// - `GET /version`: crate 버전과 설정 프로필 이름.
+/// - `GET /docks`, `GET|PUT|DELETE /docks/{dock}`: --dock-db가 지정되었다면 도크 메타데이터(이름, 가중치, 사용 중지 여부)를
+///   조회하고 바꾼다. 사용 중지된 도크들은 plan 요청의 skip 목록에 더해진다.
+/// - `POST /profiles/{name}/plan`, `POST /profiles/{name}/plan/batch`: 설정 파일의 프로필 인자 위에 요청의 필드들을
+///   덮어써 계산한다. `X-Dock-Profile` 헤더로도 프로필을 고를 수 있다. 지역 창고마다 범위, 기호, 라벨 설정이 다르더라도
+///   서버 하나로 처리한다.
+/// - `GET /audit`: --audit-log가 지정되었다면 plan 요청들의 감사 기록(`?limit=N`이면 마지막 N건)을 반환한다.
+/// - `GET /ws`: WebSocket 연결. 도크 전광판들이 연결해 두면 plan 엔드포인트가 계산한 plan을 바로 받는다.
+///   브라우저는 WebSocket에 헤더를 붙일 수 없으므로 token은 `?token=`으로도 받는다.
+///
+/// bearer token이 설정되어 있다면 plan 엔드포인트(`/plan`, `/plan/batch`), 도크 메타데이터 엔드포인트, `/audit`과 `/ws`는 인증이 필요하다.
+/// 설정 파일이 바뀌면 다음 요청부터 새 프로필과 token을 사용한다.
+///
+/// --tls-cert, --tls-key가 있다면 HTTPS로 서비스한다. --cors-origin(또는 설정 파일의 `cors_origins`)의 origin에서 온
+/// 브라우저 요청에는 CORS 헤더를 붙이고, preflight(`OPTIONS`) 요청에 응답한다.
+///
+/// plan 요청(배치라면 작업 하나)의 범위는 --max-range-docks개 도크까지이며, --rate-limit이 있다면 token(token이 설정되지
+/// 않았다면 IP 주소)마다 분당 그만큼의 plan 요청만 받고 나머지는 429로 거절한다.
+///
+/// `grpc` 기능으로 빌드했다면 --grpc-port에 같은 plan API의 gRPC 서버(proto/dock_sorter.proto)를 함께 띄운다.
+pub fn serve(options: &ServeOptions) -> Result<(), String> {
+  let mut state = ServerState::from_options(options)?;
+  if state.tokens.is_empty() {
+    eprintln!("Warning: No API tokens configured. Planning endpoints are open to anyone.");
+  }
+
+  let (host, port) = (options.host.as_str(), options.port);
+  let server = match (&options.tls_cert, &options.tls_key) {
+    (Some(cert), Some(key)) => {
+      let tls_config = server_tls_config(cert, key)?;
+      let listener = TcpListener::bind((host, port)).map_err(|e| format!("Failed to listen on {host}:{port}: {e}"))?;
+      // HTTP 서버는 TLS 앞단만 접속하는 내부 주소에서 듣는다.
+      let server = Server::http("127.0.0.1:0").map_err(|e| format!("Failed to start the HTTP server: {e}"))?;
+      let backend = server.server_addr().to_ip().ok_or("Failed to start the HTTP server: no TCP address.")?;
+      state.tls = Some(TlsFrontend::spawn(listener, backend, tls_config));
+      eprintln!("Listening on https://{host}:{port}");
+      server
+    }
+    _ => {
+      let server = Server::http((host, port)).map_err(|e| format!("Failed to listen on {host}:{port}: {e}"))?;
+      eprintln!("Listening on http://{host}:{port}");
+      server
+    }
+  };
+
+  // gRPC 서버와 token, 프로필, 감사 기록, 요청 한도를 함께 쓴다.
+  let state = Arc::new(Mutex::new(state));
+  #[cfg(feature = "grpc")]
+  if let Some(grpc_port) = options.grpc_port {
+    crate::grpc::spawn(host, grpc_port, Arc::clone(&state))?;
+  }
+
+  for request in server.incoming_requests() {
+    let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+    state.reload_config();
+    handle_request(request, &mut state);
+  }
+  Ok(())
+}
+
+/// bearer token 목록을 읽어올 환경 변수. 여러 token은 쉼표로 구분한다.
+const TOKENS_ENV: &str = "DOCK_SORTER_TOKENS";
+
+/// 요청 처리에 필요한 서버 상태. gRPC 서버도 같은 상태로 plan 요청을 처리한다.
+pub struct ServerState {
+  // GET /version에 표시되는 설정 프로필 이름
+  profile: Option<String>,
+  // plan 요청에 허용되는 bearer token 목록 (설정 파일 + 환경 변수)
+  tokens: Vec<String>,
+  // 요청마다 고를 수 있는 설정 프로필들
+  config: ReloadableConfig,
+  // 도크 메타데이터 SQLite 데이터베이스 (--dock-db)
+  dock_db: Option<PathBuf>,
+  // plan 요청을 기록하는 감사 기록 파일 (--audit-log)
+  audit_log: Option<PathBuf>,
+  // 계산된 plan을 받는 WebSocket 클라이언트들 (GET /ws)
+  boards: Broadcaster,
+  // --cors-origin으로 허용한 origin들 (설정 파일의 cors_origins와 함께 쓴다)
+  cors_origins: Vec<String>,
+  // HTTPS로 서비스할 때의 TLS 앞단 (--tls-cert)
+  tls: Option<TlsFrontend>,
+  // plan 요청 하나가 펼칠 수 있는 최대 도크 수 (--max-range-docks)
+  max_range_docks: u64,
+  // token(또는 IP 주소)별 plan 요청 한도 (--rate-limit)
+  rate_limiter: Option<RateLimiter>,
+}
+
+impl ServerState {
+  /// serve 옵션과 설정 파일, 환경 변수로부터 서버 상태를 만든다.
+  fn from_options(options: &ServeOptions) -> Result<Self, String> {
+    // 요청이 들어온 뒤에야 잘못된 프로필을 알게 되지 않도록 미리(다시 읽을 때도) 확인한다.
+    let config = ReloadableConfig::load(options.config.as_deref(), |config| {
+      for (name, args) in &config.profiles {
+        let mut args = args.clone();
+        config.apply_tiers(&mut args).and_then(|()| args.validate_input()).map_err(|e| format!("Invalid profile '{name}': {e}"))?;
+      }
+      Ok(())
+    })?;
+    let tokens = server_tokens(config.get());
+    if let Some(path) = &options.dock_db {
+      check_dock_db(path)?;
+    }
+    Ok(ServerState {
+      profile: options.profile.clone(),
+      tokens,
+      config,
+      dock_db: options.dock_db.clone(),
+      audit_log: options.audit_log.clone(),
+      boards: Broadcaster::default(),
+      cors_origins: options.cors_origins.clone(),
+      tls: None,
+      max_range_docks: options.max_range_docks,
+      rate_limiter: options.rate_limit.map(RateLimiter::new),
+    })
+  }
+
+  /// 설정 파일이 바뀌었다면 다시 읽어 프로필과 token 목록을 바꾼다.
+  pub fn reload_config(&mut self) {
+    if self.config.reload_if_changed() {
+      let was_protected = !self.tokens.is_empty();
+      self.tokens = server_tokens(self.config.get());
+      if was_protected && self.tokens.is_empty() {
+        eprintln!("Warning: No API tokens configured. Planning endpoints are open to anyone.");
+      }
+    }
+  }
+
+  /// 요청의 `Authorization: Bearer <token>` 헤더가 허용된 token인지 확인한다.
+  fn is_authorized(&self, request: &Request) -> bool {
+    self.accepts_token(presented_token(request))
+  }
+
+  /// 호출자가 보낸 bearer token이 허용된 token인지 확인한다. token이 하나도 설정되지 않았다면 모든 요청을 허용한다.
+  pub fn accepts_token(&self, token: Option<&str>) -> bool {
+    if self.tokens.is_empty() {
+      return true;
+    }
+    match token {
+      Some(token) => self.tokens.iter().any(|t| constant_time_eq(t.as_bytes(), token.as_bytes())),
+      None => false,
+    }
+  }
+
+  /// 감사 기록에 남기는 호출자의 token 지문(SHA-256의 앞 16자리 hex). token이 설정되지 않은 서버라면 None이다.
+  /// token 자체는 기록하지 않는다.
+  fn token_fingerprint(&self, token: Option<&str>) -> Option<String> {
+    if self.tokens.is_empty() {
+      return None;
+    }
+    token.map(|token| sha256_hex(token.as_bytes())[..16].to_string())
+  }
+
+  /// plan 요청이 요청 한도 안에 있는지 확인한다. token이 설정되지 않은 서버라면 caller(IP 주소)별로 센다.
+  /// 한도를 넘었다면 다시 요청할 수 있을 때까지의 시간이 에러이다.
+  pub fn check_rate_limit(&mut self, token: Option<&str>, caller: &str) -> Result<(), Duration> {
+    let key = self.token_fingerprint(token).unwrap_or_else(|| caller.to_string());
+    match &mut self.rate_limiter {
+      Some(limiter) => limiter.check(&key),
+      None => Ok(()),
+    }
+  }
+
+  /// `GET /version`의 내용.
+  pub fn version(&self) -> VersionBody {
+    VersionBody {
+      version: env!("CARGO_PKG_VERSION"),
+      profile: self.profile.clone(),
+      schema_version: SCHEMA_VERSION,
+      profiles: self.config.get().profiles.keys().cloned().collect(),
+    }
+  }
+
+  /// plan 요청 하나를 계산하여 상태 코드와 JSON 본문을 반환한다. 인증과 요청 한도는 호출하는 쪽에서 먼저 확인한다.
+  /// --audit-log가 지정되었다면 요청마다 감사 기록을 남기며, 기록하지 못했다면 plan을 돌려주지 않고 실패한다.
+  /// 계산된 plan은 연결된 전광판들(`GET /ws`)에 보낸다.
+  pub fn plan(&mut self, call: &PlanCall) -> (u16, String) {
+    let profile_args = match call.profile.map(|name| self.config.get().profile(name)).transpose() {
+      Ok(profile_args) => profile_args,
+      Err(e) => return (404, error_json(&e)),
+    };
+    let compute = if call.batch { batch_json } else { plan_json };
+    let (status, response) = match compute(call.body, self, profile_args) {
+      Ok(json) => (200, json),
+      Err(e) => (400, error_json(&e)),
+    };
+    if let Some(path) = &self.audit_log {
+      let entry = AuditEntry {
+        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        caller: call.caller.clone(),
+        token: self.token_fingerprint(call.token),
+        endpoint: call.endpoint.to_string(),
+        profile: call.profile.map(str::to_string),
+        status,
+        inputs_hash: sha256_hex(call.body.as_bytes()),
+        plan_hash: (status == 200).then(|| sha256_hex(response.as_bytes())),
+      };
+      if let Err(e) = append_audit(path, &entry) {
+        eprintln!("Error: {e}");
+        return (500, error_json(&tr("error.audit_failed", &[])));
+      }
+    }
+    if status == 200 && !self.boards.is_empty() {
+      let plan: Value = serde_json::from_str(&response).unwrap_or_default();
+      self.boards.broadcast(&json!({ "endpoint": call.endpoint, "profile": call.profile, "response": plan }).to_string());
+    }
+    (status, response)
+  }
+
+  /// 요청을 보낸 클라이언트의 IP 주소. HTTPS라면 TLS 앞단이 받은 연결의 주소이다.
+  fn caller(&self, request: &Request) -> String {
+    let addr = request.remote_addr().map(|&addr| self.tls.as_ref().map_or(addr, |tls| tls.client_addr(addr)));
+    addr.map(|addr| addr.ip().to_string()).unwrap_or_default()
+  }
+
+  /// 요청의 Origin이 허용되었다면 응답에 붙일 `Access-Control-Allow-Origin` 값. `*`을 허용했다면 `*`이다.
+  fn allowed_origin(&self, request: &Request) -> Option<String> {
+    let origin = request.headers().iter().find(|h| h.field.equiv("Origin"))?.value.as_str();
+    let config = self.config.get();
+    let mut allowed = self.cors_origins.iter().chain(&config.server.cors_origins);
+    allowed
+      .find(|allowed| *allowed == "*" || allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+      .map(|allowed| if allowed == "*" { "*".to_string() } else { origin.to_string() })
+  }
+}
+
+/// 허용된 origin의 요청에 대한 응답에 붙이는 CORS 헤더들.
+fn cors_headers(origin: Option<&str>) -> Vec<Header> {
+  let Some(origin) = origin else {
+    return Vec::new();
+  };
+  let mut headers = vec![Header::from_bytes("Access-Control-Allow-Origin", origin).expect("valid header")];
+  // origin마다 응답 헤더가 다르므로 캐시가 origin별로 저장하도록 한다.
+  if origin != "*" {
+    headers.push(Header::from_bytes("Vary", "Origin").expect("valid header"));
+  }
+  headers
+}
+
+/// 설정 파일의 token들과 환경 변수의 token들.
+fn server_tokens(config: &Config) -> Vec<String> {
+  let mut tokens = config.server.tokens.clone();
+  if let Ok(env_tokens) = std::env::var(TOKENS_ENV) {
+    tokens.extend(env_tokens.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from));
+  }
+  tokens
+}
+
+/// 요청으로 설정 프로필을 고르는 헤더.
+const PROFILE_HEADER: &str = "X-Dock-Profile";
+
+/// 요청이 고른 설정 프로필 이름과, 경로에서 `/profiles/{name}`을 뺀 엔드포인트 경로를 반환하는 함수.
+/// 경로와 `X-Dock-Profile` 헤더가 서로 다른 프로필을 가리킨다면 에러이다.
+fn requested_profile(request: &Request) -> Result<(Option<String>, String), String> {
+  let path = url_path(request.url());
+  let (from_path, endpoint) = match path.strip_prefix("/profiles/").and_then(|rest| rest.split_once('/')) {
+    Some((name, rest)) => (Some(name.to_string()), format!("/{rest}")),
+    None => (None, path.to_string()),
+  };
+  let from_header = request
+    .headers()
+    .iter()
+    .find(|h| h.field.equiv(PROFILE_HEADER))
+    .map(|h| h.value.as_str().trim().to_string());
+  match (from_path, from_header) {
+    (Some(path), Some(header)) if path != header => {
+      Err(tr("error.profile_mismatch", &[("path", &path), ("header", &header), ("name", &PROFILE_HEADER)]))
+    }
+    (from_path, from_header) => Ok((from_path.or(from_header), endpoint)),
+  }
+}
+
+/// 요청 URL에서 쿼리 문자열(`?` 이후)을 뺀 경로.
+fn url_path(url: &str) -> &str {
+  url.split_once('?').map_or(url, |(path, _)| path)
+}
+
+/// 요청의 `Authorization: Bearer <token>` 헤더에 담긴 token. `/ws`는 `?token=` 쿼리로도 받는다.
+fn presented_token(request: &Request) -> Option<&str> {
+  let from_header = request
+    .headers()
+    .iter()
+    .find(|h| h.field.equiv("Authorization"))
+    .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+    .map(str::trim);
+  from_header.or_else(|| {
+    let query = request.url().strip_prefix("/ws?")?;
+    query.split('&').find_map(|pair| pair.strip_prefix("token="))
+  })
+}
+
+/// token 비교 시간으로 token 내용을 추측할 수 없도록 길이가 같다면 항상 모든 바이트를 비교한다.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// `GET /healthz`, `GET /readyz`의 응답 본문.
+#[derive(Serialize, JsonSchema)]
+#[schemars(description = "Health or readiness status")]
+pub struct StatusBody {
+  /// `ok` for /healthz, `ready` for /readyz
+  pub status: &'static str,
+}
+
+/// `GET /version`의 응답 본문.
+#[derive(Serialize, JsonSchema)]
+#[schemars(description = "Server version information")]
+pub struct VersionBody {
+  /// Crate version of the running server
+  pub version: &'static str,
+  /// Active configuration profile, if any
+  pub profile: Option<String>,
+  /// Version of the plan format returned by the planning endpoints
+  pub schema_version: u32,
+  /// Config profiles that requests can select with `/profiles/{name}/plan` or the `X-Dock-Profile` header
+  pub profiles: Vec<String>,
+}
+
+/// 요청 하나를 경로와 메서드에 따라 처리하고 응답하는 함수.
+fn handle_request(mut request: Request, state: &mut ServerState) {
+  let cors = cors_headers(state.allowed_origin(&request).as_deref());
+  // 브라우저가 다른 origin에 요청하기 전에 보내는 preflight. 인증 헤더 없이 오므로 인증보다 먼저 응답한다.
+  let is_preflight = request.headers().iter().any(|h| h.field.equiv("Access-Control-Request-Method"));
+  if request.method() == &Method::Options && is_preflight {
+    respond_preflight(request, cors);
+    return;
+  }
+  let (profile, endpoint) = match requested_profile(&request) {
+    Ok(selected) => selected,
+    Err(e) => {
+      respond_json(request, 400, error_json(&e), &cors);
+      return;
+    }
+  };
+  // 엔드포인트는 쿼리 문자열을 뺀 경로로 구분한다.
+  let path = url_path(request.url()).to_string();
+  let is_dock_endpoint = path == "/docks" || path.starts_with("/docks/");
+  let is_audit_endpoint = path == "/audit";
+  let is_ws_endpoint = path == "/ws";
+  let is_plan_endpoint = matches!(endpoint.as_str(), "/plan" | "/plan/batch");
+  // 프로필 경로 아래에는 plan 엔드포인트만 있다.
+  if endpoint != path && !is_plan_endpoint {
+    respond_json(request, 404, error_json(&tr("error.not_found", &[])), &cors);
+    return;
+  }
+  if (is_plan_endpoint || is_dock_endpoint || is_audit_endpoint || is_ws_endpoint) && !state.is_authorized(&request) {
+    respond_json(request, 401, error_json(&tr("error.unauthorized", &[])), &cors);
+    return;
+  }
+  if is_plan_endpoint && let Err(wait) = state.check_rate_limit(presented_token(&request), &state.caller(&request)) {
+    let retry_after = wait.as_secs_f64().ceil() as u64;
+    let mut headers = cors;
+    headers.push(Header::from_bytes("Retry-After", retry_after.to_string()).expect("valid header"));
+    respond_json(request, 429, error_json(&tr("error.rate_limited", &[("seconds", &retry_after)])), &headers);
+    return;
+  }
+  if is_ws_endpoint {
+    let caller = state.caller(&request);
+    accept_websocket(request, &caller, &mut state.boards);
+    return;
+  }
+
+  let (status, body) = match (request.method(), endpoint.as_str()) {
+    // 프로세스가 요청에 응답할 수 있다면 살아있는 것으로 본다.
+    (Method::Get, "/healthz") => (200, to_json(&StatusBody { status: "ok" })),
+    // 서버는 외부 의존성 없이 리스닝을 시작한 순간부터 요청을 처리할 수 있다.
+    (Method::Get, "/readyz") => (200, to_json(&StatusBody { status: "ready" })),
+    (Method::Get, "/version") => (200, to_json(&state.version())),
+    (Method::Post, "/plan") => plan_endpoint(&mut request, state, profile.as_deref(), false),
+    (Method::Post, "/plan/batch") => plan_endpoint(&mut request, state, profile.as_deref(), true),
+    (_, "/plan" | "/plan/batch") => (405, error_json(&tr("error.method_not_allowed", &[("methods", &"POST")]))),
+    (Method::Get, "/openapi.json") => (200, openapi_document().to_string()),
+    _ if is_dock_endpoint => {
+      let method = request.method().clone();
+      dock_endpoint(&mut request, state, &method, &path)
+    }
+    (Method::Get, _) if is_audit_endpoint => audit_endpoint(state, request.url()),
+    _ if is_audit_endpoint => (405, error_json(&tr("error.method_not_allowed", &[("methods", &"GET")]))),
+    _ => (404, error_json(&tr("error.not_found", &[]))),
+  };
+  respond_json(request, status, body, &cors);
+}
+
+/// CORS preflight 요청에 응답하는 함수. 허용되지 않은 origin이라면 403이다.
+fn respond_preflight(request: Request, cors: Vec<Header>) {
+  if cors.is_empty() {
+    respond_json(request, 403, error_json(&tr("error.origin_not_allowed", &[])), &[]);
+    return;
+  }
+  let mut response = Response::empty(204);
+  for header in cors.into_iter().chain([
+    Header::from_bytes("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, OPTIONS").expect("valid header"),
+    Header::from_bytes("Access-Control-Allow-Headers", format!("Authorization, Content-Type, {PROFILE_HEADER}")).expect("valid header"),
+    // 배차 화면이 요청마다 preflight를 다시 보내지 않도록 10분 동안 캐시한다.
+    Header::from_bytes("Access-Control-Max-Age", "600").expect("valid header"),
+  ]) {
+    response.add_header(header);
+  }
+  if let Err(e) = request.respond(response) {
+    eprintln!("Warning: Failed to send response: {e}");
+  }
+}
+
+/// `GET /ws` 요청을 WebSocket 연결로 바꿔 plan을 받는 클라이언트로 추가하는 함수.
+fn accept_websocket(request: Request, caller: &str, boards: &mut Broadcaster) {
+  let header = |name: &'static str| request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.as_str().trim().to_string());
+  let is_upgrade = header("Upgrade").is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+  let is_version_13 = header("Sec-WebSocket-Version").as_deref() == Some("13");
+  let key = header("Sec-WebSocket-Key");
+  match (request.method(), key) {
+    (Method::Get, Some(key)) if is_upgrade && is_version_13 => {
+      let response = Response::empty(101)
+        .with_header(Header::from_bytes("Sec-WebSocket-Accept", accept_key(&key)).expect("valid header"));
+      boards.add(request.upgrade("websocket", response));
+      eprintln!("WebSocket client connected from {caller}.");
+    }
+    (Method::Get, _) => respond_json(request, 400, error_json(&tr("error.websocket_upgrade", &[])), &[]),
+    _ => respond_json(request, 405, error_json(&tr("error.method_not_allowed", &[("methods", &"GET")])), &[]),
+  }
+}
+
+/// 요청 본문을 문자열로 읽는 함수.
+fn read_body(request: &mut Request) -> Result<String, String> {
+  let mut body = String::new();
+  request
+    .as_reader()
+    .read_to_string(&mut body)
+    .map_err(|e| tr("error.request_body", &[("error", &e)]))?;
+  Ok(body)
+}
+
+/// HTTP나 gRPC로 받은 plan 요청 하나.
+pub struct PlanCall<'a> {
+  /// 감사 기록에 남기는 요청 경로 (`/plan`, gRPC 메서드 경로 등)
+  pub endpoint: &'a str,
+  /// 여러 plan을 한 번에 계산하는 요청인지 여부 (`/plan/batch`)
+  pub batch: bool,
+  /// 요청이 고른 설정 프로필 이름
+  pub profile: Option<&'a str>,
+  /// 호출자가 보낸 bearer token
+  pub token: Option<&'a str>,
+  /// 호출자의 IP 주소
+  pub caller: String,
+  /// 요청 JSON
+  pub body: &'a str,
+}
+
+/// plan 요청의 본문을 읽어 계산하는 함수. profile은 요청이 고른 설정 프로필 이름이다.
+fn plan_endpoint(request: &mut Request, state: &mut ServerState, profile: Option<&str>, batch: bool) -> (u16, String) {
+  let body = match read_body(request) {
+    Ok(body) => body,
+    Err(e) => return (400, error_json(&e)),
+  };
+  let call = PlanCall {
+    endpoint: request.url(),
+    batch,
+    profile,
+    token: presented_token(request),
+    caller: state.caller(request),
+    body: &body,
+  };
+  state.plan(&call)
+}
+
+/// `GET /audit` 요청을 처리하는 함수. `?limit=N`이면 마지막 N건만 반환한다.
+fn audit_endpoint(state: &ServerState, url: &str) -> (u16, String) {
+  let Some(path) = &state.audit_log else {
+    return (404, error_json(&tr("error.audit_disabled", &[])));
+  };
+  let query = url.split_once('?').map_or("", |(_, query)| query);
+  let mut limit = None;
+  for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+    match pair.split_once('=') {
+      Some(("limit", value)) => match value.parse::<usize>() {
+        Ok(value) => limit = Some(value),
+        Err(_) => return (400, error_json(&tr("error.invalid_limit", &[("value", &value)]))),
+      },
+      _ => return (400, error_json(&tr("error.unknown_query", &[("pair", &pair)]))),
+    }
+  }
+  match read_audit(path, limit) {
+    Ok(entries) => (200, to_json(&entries)),
+    Err(e) => (500, error_json(&e)),
+  }
+}
+
+/// JSON 요청 본문을 Args로 파싱하고 plan을 계산하여 JSON 문자열로 반환하는 함수.
+fn plan_json(body: &str, state: &ServerState, profile: Option<&Args>) -> Result<String, String> {
+  let mut args: Args = match profile {
+    Some(profile) => serde_json::from_str(body).and_then(|request| serde_json::from_value(with_profile(request, profile))),
+    None => serde_json::from_str(body),
+  }
+  .map_err(|e| tr("error.invalid_request", &[("error", &e)]))?;
+  state.config.get().apply_site(&mut args);
+  state.config.get().apply_tiers(&mut args)?;
+  add_closed_docks(&mut args, state)?;
+  args.check_range_cap(state.max_range_docks)?;
+  let result = compute_api_plan(&args)?;
+  plan_to_json(&args, &result)
+}
+
+/// 프로필의 plan 인자 위에 요청 JSON 객체의 필드들을 덮어쓴 JSON을 만드는 함수. 목록 필드도 통째로 바뀐다.
+/// 프로필의 명령줄 전용 인자(--save-plan 등)는 CLI와 데몬에서 실행할 때를 위한 것이므로 빼고 합친다.
+fn with_profile(request: Value, profile: &Args) -> Value {
+  let (Value::Object(fields), Ok(Value::Object(mut merged))) = (&request, serde_json::to_value(profile)) else {
+    // 객체가 아닌 요청은 Args로 읽을 때 에러가 된다.
+    return request;
+  };
+  for (field, is_set) in cli_only_fields(profile) {
+    if is_set {
+      merged.remove(field);
+    }
+  }
+  merged.extend(fields.clone());
+  Value::Object(merged)
+}
+
+/// 도크 메타데이터에서 사용 중지로 표시된 도크들을 요청의 skip 목록에 더하는 함수.
+/// 응답의 args에도 포함되므로 어떤 도크가 빠졌는지 알 수 있다.
+fn add_closed_docks(args: &mut Args, state: &ServerState) -> Result<(), String> {
+  if let Some(path) = &state.dock_db {
+    args.skip.extend(closed_docks(path)?);
+  }
+  Ok(())
+}
+
+/// `/docks`, `/docks/{dock}` 요청을 처리하여 상태 코드와 JSON 본문을 반환하는 함수.
+fn dock_endpoint(request: &mut Request, state: &ServerState, method: &Method, url: &str) -> (u16, String) {
+  let Some(path) = &state.dock_db else {
+    return (404, error_json(&tr("error.dock_db_disabled", &[])));
+  };
+  let dock = match url.strip_prefix("/docks/") {
+    Some(number) => match number.parse::<DockId>() {
+      Ok(dock) => Some(dock),
+      Err(_) => return (400, error_json(&tr("error.invalid_dock", &[("number", &number)]))),
+    },
+    None => None,
+  };
+  let not_found = |dock: DockId| (404, error_json(&tr("error.no_dock_metadata", &[("dock", &dock)])));
+  let server_error = |e: String| (500, error_json(&e));
+  match (method, dock) {
+    (Method::Get, None) => list_docks(path).map_or_else(server_error, |docks| (200, to_json(&docks))),
+    (Method::Get, Some(dock)) => match get_dock(path, dock) {
+      Ok(Some(metadata)) => (200, to_json(&metadata)),
+      Ok(None) => not_found(dock),
+      Err(e) => server_error(e),
+    },
+    (Method::Put, Some(dock)) => {
+      let update = read_body(request)
+        .and_then(|body| serde_json::from_str::<DockMetadataUpdate>(&body).map_err(|e| tr("error.invalid_request", &[("error", &e)])))
+        .and_then(|update| update.validate().map(|()| update));
+      match update {
+        Ok(update) => put_dock(path, dock, &update).map_or_else(server_error, |metadata| (200, to_json(&metadata))),
+        Err(e) => (400, error_json(&e)),
+      }
+    }
+    (Method::Delete, Some(dock)) => match delete_dock(path, dock) {
+      Ok(Some(metadata)) => (200, to_json(&metadata)),
+      Ok(None) => not_found(dock),
+      Err(e) => server_error(e),
+    },
+    (_, None) => (405, error_json(&tr("error.method_not_allowed", &[("methods", &"GET")]))),
+    (_, Some(_)) => (405, error_json(&tr("error.method_not_allowed", &[("methods", &"GET, PUT, DELETE")]))),
+  }
+}
+
+/// API(HTTP 서버, 데몬)로 들어온 인자를 검사하고 plan을 계산하는 함수.
+pub fn compute_api_plan(args: &Args) -> Result<ProcessingResult, String> {
+  reject_cli_only_fields(args)?;
+  args.validate_input()?;
+  let request = PlanRequest::new(args);
+  let mut result = process_docks(request);
+  if args.self_check {
+    verify(&result, &request)?;
+  }
+  if args.stamp {
+    result.stamp = Some(RunStamp::now());
+  }
+  Ok(result)
+}
+
+/// JSON 배열로 들어온 작업들을 각각 계산하여 결과 배열의 JSON 문자열로 반환하는 함수.
+/// 개별 작업의 실패는 해당 작업 결과의 error로 표시되며, 요청 전체가 실패하지는 않는다.
+fn batch_json(body: &str, state: &ServerState, profile: Option<&Args>) -> Result<String, String> {
+  let mut jobs: Vec<BatchJob> = match profile {
+    Some(profile) => serde_json::from_str::<Vec<Value>>(body).and_then(|requests| {
+      requests.into_iter().map(|request| serde_json::from_value(with_profile(request, profile))).collect()
+    }),
+    None => serde_json::from_str(body),
+  }
+  .map_err(|e| tr("error.invalid_request", &[("error", &e)]))?;
+  for job in &mut jobs {
+    state.config.get().apply_site(&mut job.args);
+    state.config.get().apply_tiers(&mut job.args)?;
+    add_closed_docks(&mut job.args, state)?;
+  }
+  // 작업마다의 상한만으로는 큰 작업 여러 개를 담은 요청 하나가 서버를 오래 붙잡을 수 있으므로, 요청 전체의 도크 수도 같은 상한으로 제한한다.
+  let total_docks: u64 = jobs.iter().map(|job| job.args.processing_range().dock_count()).sum();
+  if total_docks > state.max_range_docks {
+    return Err(tr(
+      "error.batch_cap",
+      &[("jobs", &jobs.len()), ("count", &total_docks), ("cap", &state.max_range_docks)],
+    ));
+  }
+  let results: Vec<_> = jobs
+    .into_iter()
+    .map(|job| match job.args.check_range_cap(state.max_range_docks) {
+      Ok(()) => run_api_job(job),
+      Err(e) => BatchResult { schema_version: SCHEMA_VERSION, name: job.name, args: None, result: None, error: Some(e) },
+    })
+    .collect();
+  serde_json::to_string_pretty(&results).map_err(|e| tr("error.serialize_results", &[("error", &e)]))
+}
+
+/// 서버의 파일을 읽거나 쓰는 인자, 서버에서 외부로 요청을 보내거나 명령을 실행하거나 서버 머신의 클립보드를 건드리는 인자,
+/// 서버를 보호하는 범위 상한을 바꾸는 인자들은 HTTP 요청에서 허용하지 않는다.
+pub fn reject_cli_only_fields(args: &Args) -> Result<(), String> {
+  match cli_only_fields(args).iter().find(|(_, is_set)| *is_set) {
+    Some((field, _)) => Err(tr("error.cli_only_field", &[("field", &field)])),
+    None => Ok(()),
+  }
+}
+
+/// HTTP 요청에서 허용하지 않는 인자들의 이름과, args에 지정되었는지 여부.
+fn cli_only_fields(args: &Args) -> Vec<(&'static str, bool)> {
+  vec![
+    ("auto_priority", args.auto_priority.is_some()),
+    ("arrivals", args.arrivals.is_some()),
+    ("closed_docks_url", args.closed_docks_url.is_some()),
+    ("wms_url", args.wms_url.is_some()),
+    ("save_plan", args.save_plan.is_some()),
+    ("previous", args.previous.is_some()),
+    ("history", args.history.is_some()),
+    ("notify_url", args.notify_url.is_some()),
+    ("slack_webhook", args.slack_webhook.is_some()),
+    ("teams_webhook", args.teams_webhook.is_some()),
+    ("upload", args.upload.is_some()),
+    ("email", !args.email.is_empty()),
+    ("smtp", args.smtp.is_some()),
+    ("mqtt", args.mqtt.is_some()),
+    ("watch", args.watch.is_some()),
+    ("config", args.config.is_some()),
+    ("clipboard", args.clipboard),
+    ("max_range_docks", args.max_range_docks.is_some()),
+    ("expect", args.expect.is_some()),
+    ("exec_per_group", args.exec_per_group.is_some()),
+    ("on_complete", args.on_complete.is_some()),
+    ("rules", args.rules.is_some()),
+    ("plugin", args.plugin.is_some()),
+    ("sign_key", args.sign_key.is_some()),
+    ("dates", args.dates.is_some()),
+    ("date_manifest", args.date_manifest.is_some()),
+    ("serial", args.serial.is_some()),
+  ]
+}
+
+/// 에러 응답 본문. OpenAPI 문서의 에러 스키마로도 사용된다.
+#[derive(Serialize, JsonSchema)]
+#[schemars(description = "Error response")]
+pub struct ErrorBody {
+  /// Human readable error message
+  pub error: String,
+}
+
+/// 에러 메세지를 `{"error": "..."}` 형식의 JSON 문자열로 만든다.
+fn error_json(message: &str) -> String {
+  to_json(&ErrorBody { error: message.to_string() })
+}
+
+/// 응답 본문 타입을 JSON 문자열로 직렬화한다. 응답 타입들은 직렬화에 실패하지 않는다.
+fn to_json<T: Serialize>(body: &T) -> String {
+  serde_json::to_string(body).unwrap_or_default()
+}
+
+/// JSON 본문과 상태 코드, CORS 헤더로 응답하는 함수. 응답 전송에 실패하면 stderr에 기록만 한다.
+fn respond_json(request: Request, status: u16, body: String, cors: &[Header]) {
+  let mut response = Response::from_string(body)
+    .with_status_code(status)
+    .with_header(Header::from_bytes("Content-Type", "application/json").expect("valid header"));
+  for header in cors {
+    response.add_header(header.clone());
+  }
+  // 인증 실패 응답에는 사용해야 하는 인증 방식을 알려준다.
+  if status == 401 {
+    response.add_header(Header::from_bytes("WWW-Authenticate", "Bearer").expect("valid header"));
+  }
+  if let Err(e) = request.respond(response) {
+    eprintln!("Warning: Failed to send response: {e}");
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::Parser;
+
+  use super::*;
+
+  #[derive(Parser)]
+  struct TestServe {
+    #[command(flatten)]
+    options: ServeOptions,
+  }
+
+  // 설정 파일 없이 serve 옵션만으로 서버 상태를 만든다.
+  fn state(options: &[&str]) -> ServerState {
+    let options = TestServe::try_parse_from(["serve"].iter().chain(options)).expect("invalid serve options").options;
+    ServerState::from_options(&options).expect("failed to build server state")
+  }
+
+  #[test]
+  fn rejects_inverted_range() {
+    let error = plan_json(r#"{"per_page": 3, "min": 80, "max": 51}"#, &state(&[]), None).expect_err("inverted range was planned");
+    assert_eq!(error, tr("error.min_max", &[("min", &80), ("max", &51)]));
+  }
+
+  #[test]
+  fn reports_inverted_range_in_batch_job() {
+    let body = batch_json(r#"[{"per_page": 3, "min": 80, "max": 51}]"#, &state(&[]), None).expect("batch failed");
+    assert!(body.contains(&tr("error.min_max", &[("min", &80), ("max", &51)])), "{body}");
+  }
+
+  #[test]
+  fn caps_total_docks_of_batch() {
+    let job = r#"{"per_page": 3, "min": 1, "max": 600}"#;
+    let state = state(&["--max-range-docks", "1000"]);
+    assert!(batch_json(&format!("[{job}]"), &state, None).is_ok());
+    let error = batch_json(&format!("[{job}, {job}]"), &state, None).expect_err("batch over the cap was planned");
+    assert!(error.contains("1200"), "{error}");
+  }
+}
@@ -0,0 +1,91 @@
+use std::{env, fs};
+
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// 서명 문자열의 알고리즘 접두사. 다른 알고리즘을 추가하더라도 기존 서명과 구분할 수 있도록 값 앞에 붙인다.
+const SIGNATURE_PREFIX: &str = "hmac-sha256:";
+
+/// `--sign-key`로 지정된 key 위치(`env:NAME` 또는 `file:PATH`)에서 서명 key를 읽는 함수.
+/// key 자체를 명령줄에 쓰면 실행 기록이나 프로세스 목록에 남으므로 위치만 받는다.
+pub fn read_sign_key(source: &str) -> Result<Vec<u8>, String> {
+  read_secret(source, "Signing key")
+}
+
+/// `env:NAME` 또는 `file:PATH`에서 비밀 값(서명 key, SMTP 비밀번호 등)을 읽는 함수. what은 에러 메세지에 쓰는 이름이다.
+pub fn read_secret(source: &str, what: &str) -> Result<Vec<u8>, String> {
+  let secret = if let Some(name) = source.strip_prefix("env:") {
+    env::var(name).map_err(|_| format!("{what} environment variable `{name}` is not set."))?.into_bytes()
+  } else if let Some(path) = source.strip_prefix("file:") {
+    let mut secret = fs::read(path).map_err(|e| format!("Failed to read {} file '{path}': {e}", what.to_lowercase()))?;
+    // 편집기가 붙이는 마지막 줄바꿈은 값에 포함하지 않는다.
+    while secret.last().is_some_and(|byte| matches!(byte, b'\n' | b'\r')) {
+      secret.pop();
+    }
+    secret
+  } else {
+    return Err(format!("Invalid {} source: '{source}'. Expected env:NAME or file:PATH.", what.to_lowercase()));
+  };
+  if secret.is_empty() {
+    return Err(format!("{what} from '{source}' is empty."));
+  }
+  Ok(secret)
+}
+
+/// plan JSON에 HMAC-SHA256 서명을 `signature` 필드로 붙인 JSON 문자열을 반환하는 함수.
+///
+/// 서명 대상은 `signature` 필드를 뺀 plan을 object key 순서대로 정렬하여 공백 없이 직렬화한 문자열이므로,
+/// 받는 쪽(WMS 등)은 파일의 들여쓰기나 key 순서와 관계없이 같은 방법으로 다시 계산하여 확인할 수 있다.
+pub fn sign_plan_json(json: &str, key: &[u8]) -> Result<String, String> {
+  let mut plan: Value = serde_json::from_str(json).map_err(|e| format!("Failed to sign plan: {e}"))?;
+  let signature = signature_of(&plan, key)?;
+  let Some(fields) = plan.as_object_mut() else {
+    return Err("Failed to sign plan: the plan is not a JSON object.".to_string());
+  };
+  fields.insert("signature".to_string(), Value::String(format!("{SIGNATURE_PREFIX}{signature}")));
+  serde_json::to_string_pretty(&plan).map_err(|e| format!("Failed to serialize plan: {e}"))
+}
+
+/// plan JSON의 `signature` 필드가 key로 만든 서명과 일치하는지 확인하는 함수.
+/// 서명이 없거나, 다른 key로 서명되었거나, 서명한 뒤 내용이 바뀌었다면 에러를 반환한다.
+pub fn verify_plan_signature(json: &str, key: &[u8]) -> Result<(), String> {
+  let mut plan: Value = serde_json::from_str(json).map_err(|e| format!("Invalid plan: {e}"))?;
+  let recorded = match plan.as_object_mut().and_then(|fields| fields.remove("signature")) {
+    Some(Value::String(signature)) => signature,
+    Some(_) => return Err("Plan signature must be a string.".to_string()),
+    None => return Err("Plan is not signed (export it with --sign-key).".to_string()),
+  };
+  let Some(recorded) = recorded.strip_prefix(SIGNATURE_PREFIX) else {
+    return Err(format!("Unsupported plan signature '{recorded}'. Expected {SIGNATURE_PREFIX}<hex>."));
+  };
+  let recorded = decode_hex(recorded).ok_or_else(|| "Plan signature is not valid hex.".to_string())?;
+  let mut mac = HmacSha256::new_from_slice(key).map_err(|e| e.to_string())?;
+  mac.update(canonical_json(&plan)?.as_bytes());
+  // 비교 시간으로 서명을 추측할 수 없도록 상수 시간 비교를 사용한다.
+  mac
+    .verify_slice(&recorded)
+    .map_err(|_| "Signature mismatch: the plan was edited after signing or was signed with a different key.".to_string())
+}
+
+// plan의 서명을 hex 문자열로 계산한다.
+fn signature_of(plan: &Value, key: &[u8]) -> Result<String, String> {
+  let mut mac = HmacSha256::new_from_slice(key).map_err(|e| e.to_string())?;
+  mac.update(canonical_json(plan)?.as_bytes());
+  Ok(mac.finalize().into_bytes().iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+// 서명 대상 문자열. serde_json의 Value는 object key를 정렬하여 저장하므로 공백 없이 직렬화하면 순서가 항상 같다.
+fn canonical_json(plan: &Value) -> Result<String, String> {
+  serde_json::to_string(plan).map_err(|e| format!("Failed to serialize plan: {e}"))
+}
+
+// 두 자리씩 끊어 hex 문자열을 바이트로 바꾼다. 길이가 홀수이거나 hex가 아닌 문자가 있다면 None.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+  if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+    return None;
+  }
+  (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect()
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use clap::Parser;
+
+use crate::{
+  cli::{ReplayOverrides, SimulateOptions},
+  demo::{random_scenario, seeded_rng},
+  models::{GroupKind, Priority},
+  processor::{PlanRequest, process_docks},
+};
+
+// 분포 막대의 최대 길이(문자 수).
+const BAR_WIDTH: usize = 40;
+
+// per-page 값 하나로 모든 시나리오를 계산한 결과의 집계.
+#[derive(Default)]
+struct Tally {
+  // 그룹 수 -> 그 그룹 수가 나온 시나리오 수
+  group_counts: BTreeMap<usize, u32>,
+  groups: usize,
+  docks: usize,
+  regular_groups: usize,
+  // per-page를 모두 채운 일반 그룹 수
+  full_groups: usize,
+}
+
+/// `simulate` 서브커맨드를 실행하는 함수.
+/// 같은 무작위 시나리오들을 -p의 각 값으로 계산하여 그룹 수 분포와 평균 그룹 크기를 출력한다.
+pub fn run_simulate(options: &SimulateOptions) -> Result<(), String> {
+  if options.runs == 0 {
+    return Err("`--runs` must be 1 or greater.".to_string());
+  }
+  if options.per_page.contains(&0) {
+    return Err("Docks per group (`-p`) must be 1 or greater.".to_string());
+  }
+
+  let mut rng = seeded_rng(&options.scenario);
+  let mut tallies: Vec<Tally> = options.per_page.iter().map(|_| Tally::default()).collect();
+  for _ in 0..options.runs {
+    let scenario = random_scenario(&mut rng, &options.scenario)?;
+    for (&per_page, tally) in options.per_page.iter().zip(&mut tallies) {
+      let mut args = ReplayOverrides::try_parse_from(["-p", &per_page.to_string()]).map_err(|e| e.to_string())?.args;
+      scenario.apply(&mut args);
+      args.validate_input()?;
+      let result = process_docks(PlanRequest::new(&args));
+      *tally.group_counts.entry(result.result_groups.len()).or_default() += 1;
+      tally.groups += result.result_groups.len();
+      for group in &result.result_groups {
+        tally.docks += group.docks.len();
+        if group.kind == GroupKind::Exception {
+          continue;
+        }
+        // 일반 그룹이 우선순위에 맞는 per-page를 모두 채웠는지 확인한다.
+        let target = match group.tier {
+          Priority::First => result.fpp,
+          Priority::Second => result.spp,
+          Priority::Third => result.gpp,
+        };
+        tally.regular_groups += 1;
+        if group.docks.len() >= usize::from(target) {
+          tally.full_groups += 1;
+        }
+      }
+    }
+  }
+
+  let scenario = &options.scenario;
+  println!(
+    "Simulation: {} runs over docks {}, {} first, {} second, {} exception groups per run",
+    options.runs, scenario.docks, scenario.first, scenario.second, scenario.exceptions
+  );
+  for (per_page, tally) in options.per_page.iter().zip(&tallies) {
+    let (min, max) = match (tally.group_counts.keys().next(), tally.group_counts.keys().next_back()) {
+      (Some(&min), Some(&max)) => (min, max),
+      _ => (0, 0),
+    };
+    println!(
+      "\n-p {per_page}: groups min {min} / avg {:.2} / max {max}, average group size {:.2}, full groups {:.1}%",
+      tally.groups as f64 / f64::from(options.runs),
+      tally.docks as f64 / tally.groups.max(1) as f64,
+      tally.full_groups as f64 * 100.0 / tally.regular_groups.max(1) as f64,
+    );
+    // 그룹 수 분포를 가장 많이 나온 그룹 수의 막대가 BAR_WIDTH가 되도록 그린다.
+    let most = tally.group_counts.values().copied().max().unwrap_or(1);
+    for (&groups, &runs) in &tally.group_counts {
+      let bar = "#".repeat((runs as usize * BAR_WIDTH).div_ceil(most as usize));
+      println!("  {groups:>4} groups: {runs:>6} {bar}");
+    }
+  }
+  Ok(())
+}
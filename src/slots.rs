@@ -0,0 +1,34 @@
+use crate::{
+  cli::Args,
+  models::Group,
+  schedule::{format_time_of_day, parse_time_of_day},
+};
+
+/// --slots로 지정된 시간대들에 그룹들을 출력 순서대로 나누어 각 그룹의 slot을 채우는 함수.
+///
+/// --dock-minutes로 도크당 예상 작업 시간이 주어졌다면 첫 시간대부터 그룹들의 작업 시간을 차례로 쌓아
+/// 그룹이 시작하는 시각이 속한 시간대에 넣는다. --crews로 작업조가 배정되었다면 작업조마다 따로 쌓는다.
+/// 작업 시간이 주어지지 않았다면 그룹 수를 시간대들에 고르게 나눈다.
+/// 마지막 시간대는 끝이 없으므로 남은 그룹은 모두 마지막 시간대에 들어간다.
+pub fn assign_slots(groups: &mut [Group], args: &Args) {
+  // validate_input이 형식을 확인했으므로 잘못된 시각은 없다.
+  let starts: Vec<u32> = args.slots.iter().filter_map(|slot| parse_time_of_day(slot)).collect();
+  let Some(&first_start) = starts.first() else {
+    return;
+  };
+  let group_count = groups.len();
+  // 작업조별로 다음 그룹을 시작할 시각 (자정 기준 분)
+  let mut cursors = vec![first_start; args.crews.map_or(1, usize::from)];
+  for (i, group) in groups.iter_mut().enumerate() {
+    let slot = match args.dock_minutes {
+      Some(minutes) => {
+        let cursor = &mut cursors[group.crew.map_or(0, |crew| usize::from(crew) - 1)];
+        let slot = starts.iter().rposition(|&start| start <= *cursor).unwrap_or(0);
+        *cursor = cursor.saturating_add(minutes.saturating_mul(group.docks.len() as u32));
+        slot
+      }
+      None => i * starts.len() / group_count,
+    };
+    group.slot = Some(format_time_of_day(starts[slot]));
+  }
+}
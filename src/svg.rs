@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use crate::{
+  barcode::code128,
+  map::{MapCell, MapShade},
+  models::{DockId, GroupKind},
+  plan::PlanFile,
+  raster::LabelDimensions,
+  template::{FieldAlign, FieldKind, GroupNumber, LabelTemplate},
+};
+
+// 전체 배치도에서 그릴 수 있는 최대 도크 수. 벽에 붙일 배치도에 필요한 정도로 제한한다.
+const MAX_OVERVIEW_DOCKS: u64 = 10_000;
+
+// 전체 배치도에서 도크 칸 하나의 크기와 칸 사이 간격 (SVG 사용자 단위)
+const CELL_WIDTH: u32 = 60;
+const CELL_HEIGHT: u32 = 80;
+const CELL_GAP: u32 = 6;
+const TITLE_HEIGHT: u32 = 40;
+
+// 그룹 색상. 이웃한 그룹이 구분되도록 순서대로 돌아가며 사용한다.
+const GROUP_COLORS: [&str; 10] =
+  ["#8ecae6", "#ffb703", "#90be6d", "#f28482", "#b8b8ff", "#f6bd60", "#84a59d", "#ffafcc", "#a3c4f3", "#cdb4db"];
+// 어느 그룹에도 속하지 않은 도크(건너뛴 도크 등)의 색상
+const UNPLANNED_COLOR: &str = "#e0e0e0";
+
+// 배치도 띠(export map)에서 도크 칸 하나의 크기 (SVG 사용자 단위)
+const STRIP_CELL_WIDTH: u32 = 28;
+const STRIP_CELL_HEIGHT: u32 = 48;
+const STRIP_MARGIN: u32 = 24;
+
+/// XML 텍스트와 속성 값에 그대로 넣을 수 없는 문자들을 바꾸는 함수.
+fn escape(text: &str) -> String {
+  text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// 라벨 하나의 SVG 문서를 만드는 함수. 좌표 단위는 밀리미터이며 PNG 라벨과 같은 배치이다.
+pub fn label_svg(dock: DockId, group: GroupNumber, marker: &str, size: LabelDimensions, font_family: &str) -> String {
+  let LabelDimensions { width_mm: width, height_mm: height } = size;
+  // 테두리 두께와 작은 글자 크기는 PNG 라벨처럼 라벨 높이에 비례한다.
+  let border = height / 48.0;
+  let small = height / 8.0;
+  let margin = border + height / 20.0;
+  let digits = dock.to_string();
+  // 도크 번호는 남은 영역의 높이에 맞추되, 숫자 하나의 폭을 글자 크기의 0.6배로 보고 라벨 폭을 넘지 않게 한다.
+  let top = margin + small * 1.2;
+  let digit_size = (height - top - margin).min((width - 2.0 * margin) / (0.6 * digits.len() as f64));
+  let font = escape(font_family);
+
+  let mut svg: Vec<String> = Vec::new();
+  svg.push(format!(
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}mm" height="{height}mm" viewBox="0 0 {width} {height}">"#
+  ));
+  svg.push(format!(
+    r#"  <rect x="{0}" y="{0}" width="{1}" height="{2}" fill="white" stroke="black" stroke-width="{3}"/>"#,
+    border / 2.0,
+    width - border,
+    height - border,
+    border
+  ));
+  svg.push(format!(
+    r#"  <text x="{margin}" y="{}" font-family="{font}" font-size="{small}" font-weight="bold">G{group}</text>"#,
+    margin + small
+  ));
+  if !marker.is_empty() {
+    svg.push(format!(
+      r#"  <text x="{}" y="{}" font-family="{font}" font-size="{small}" font-weight="bold" text-anchor="end">{}</text>"#,
+      width - margin,
+      margin + small,
+      escape(marker)
+    ));
+  }
+  svg.push(format!(
+    r#"  <text x="{}" y="{}" font-family="{font}" font-size="{digit_size}" font-weight="bold" text-anchor="middle" dominant-baseline="central">{digits}</text>"#,
+    width / 2.0,
+    (top + height - margin) / 2.0
+  ));
+  svg.push("</svg>\n".to_string());
+  svg.join("\n")
+}
+
+/// 라벨 템플릿으로 라벨 하나의 SVG 문서를 만드는 함수. 좌표 단위는 밀리미터이다.
+/// 글자 폭은 글자 크기의 0.6배로 어림하여 필드 폭에 들어가도록 글자 크기를 줄인다.
+pub fn template_svg(
+  template: &LabelTemplate,
+  dock: DockId,
+  group: GroupNumber,
+  marker: &str,
+  size: LabelDimensions,
+  font_family: &str,
+) -> Result<String, String> {
+  let LabelDimensions { width_mm: width, height_mm: height } = size;
+  let font = escape(font_family);
+  let mut svg: Vec<String> = Vec::new();
+  svg.push(format!(
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}mm" height="{height}mm" viewBox="0 0 {width} {height}">"#
+  ));
+  svg.push(format!(r#"  <rect width="{width}" height="{height}" fill="white"/>"#));
+  for field in &template.fields {
+    let (x, y, field_width) = (field.x, field.y, field.width_in(width));
+    match field.kind {
+      FieldKind::Box => {
+        let thickness = field.thickness();
+        svg.push(format!(
+          r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black" stroke-width="{thickness}"/>"#,
+          x + thickness / 2.0,
+          y + thickness / 2.0,
+          (field_width - thickness).max(0.0),
+          (field.height - thickness).max(0.0)
+        ));
+      }
+      FieldKind::Barcode => {
+        let modules = code128(&field.content(dock, group, marker))?;
+        let total: u32 = modules.iter().map(|&module| u32::from(module)).sum();
+        let module_width = field_width / f64::from(total);
+        let mut left = x;
+        for (i, &module) in modules.iter().enumerate() {
+          let bar_width = f64::from(module) * module_width;
+          if i % 2 == 0 {
+            svg.push(format!(r#"  <rect x="{left}" y="{y}" width="{bar_width}" height="{}"/>"#, field.height));
+          }
+          left += bar_width;
+        }
+      }
+      FieldKind::Dock | FieldKind::Group | FieldKind::Marker | FieldKind::Text => {
+        let text = field.content(dock, group, marker);
+        if text.is_empty() {
+          continue;
+        }
+        let font_size = field.height.min(field_width / (0.6 * text.chars().count() as f64));
+        let (anchor_x, anchor) = match field.align {
+          FieldAlign::Left => (x, "start"),
+          FieldAlign::Center => (x + field_width / 2.0, "middle"),
+          FieldAlign::Right => (x + field_width, "end"),
+        };
+        svg.push(format!(
+          r#"  <text x="{anchor_x}" y="{}" font-family="{font}" font-size="{font_size}" font-weight="bold" text-anchor="{anchor}">{}</text>"#,
+          y + font_size,
+          escape(&text)
+        ));
+      }
+    }
+  }
+  svg.push("</svg>\n".to_string());
+  Ok(svg.join("\n"))
+}
+
+/// plan의 도크 전체를 한 장에 그린 배치도 SVG를 만드는 함수.
+/// 도크마다 칸 하나이며 같은 그룹의 도크는 같은 색으로 칠하고, 예외 그룹은 점선 테두리로 표시한다.
+/// --row-size가 지정된 plan이라면 한 줄에 그만큼의 도크를 그린다.
+pub fn overview_svg(plan: &PlanFile, font_family: &str) -> Result<String, String> {
+  let (min, max) = (plan.args.min, plan.args.max);
+  let total = plan.args.processing_range().dock_count();
+  if total > MAX_OVERVIEW_DOCKS {
+    return Err(format!("Dock range {min}-{max} is too large for an overview (at most {MAX_OVERVIEW_DOCKS} docks)."));
+  }
+  let columns = plan.args.row_size.map_or(total, u64::from).clamp(1, total) as u32;
+  let rows = (total as u32).div_ceil(columns);
+
+  // 도크 -> (출력 순서상 그룹 번호, 그룹 종류)
+  let group_of: HashMap<DockId, (usize, GroupKind)> = plan
+    .result
+    .result_groups
+    .iter()
+    .enumerate()
+    .flat_map(|(i, group)| group.docks.iter().map(move |&dock| (dock, (i + 1, group.kind))))
+    .collect();
+
+  let width = columns * (CELL_WIDTH + CELL_GAP) + CELL_GAP;
+  let height = TITLE_HEIGHT + rows * (CELL_HEIGHT + CELL_GAP) + CELL_GAP;
+  let font = escape(font_family);
+  let mut svg: Vec<String> = Vec::new();
+  svg.push(format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#));
+  svg.push(format!(r#"  <rect width="{width}" height="{height}" fill="white"/>"#));
+  svg.push(format!(
+    r#"  <text x="{CELL_GAP}" y="28" font-family="{font}" font-size="20" font-weight="bold">Docks {min}-{max}: {} groups</text>"#,
+    plan.result.result_groups.len()
+  ));
+  for dock in plan.args.processing_range().docks() {
+    let index = dock.get() - min.get();
+    let x = CELL_GAP + (index % columns) * (CELL_WIDTH + CELL_GAP);
+    let y = TITLE_HEIGHT + (index / columns) * (CELL_HEIGHT + CELL_GAP);
+    let (fill, dash, group_label) = match group_of.get(&dock) {
+      Some(&(group, kind)) => (
+        GROUP_COLORS[(group - 1) % GROUP_COLORS.len()],
+        if kind == GroupKind::Exception { r#" stroke-dasharray="4 3""# } else { "" },
+        format!("G{group}"),
+      ),
+      None => (UNPLANNED_COLOR, "", String::new()),
+    };
+    svg.push(format!(
+      r#"  <rect x="{x}" y="{y}" width="{CELL_WIDTH}" height="{CELL_HEIGHT}" rx="4" fill="{fill}" stroke="black" stroke-width="2"{dash}/>"#
+    ));
+    let center = x + CELL_WIDTH / 2;
+    svg.push(format!(
+      r#"  <text x="{center}" y="{}" font-family="{font}" font-size="20" font-weight="bold" text-anchor="middle">{dock}</text>"#,
+      y + 36
+    ));
+    if !group_label.is_empty() {
+      svg.push(format!(
+        r#"  <text x="{center}" y="{}" font-family="{font}" font-size="14" text-anchor="middle">{group_label}</text>"#,
+        y + 62
+      ));
+    }
+  }
+  svg.push("</svg>\n".to_string());
+  Ok(svg.join("\n"))
+}
+
+// 배치도 띠에서 도크 칸의 배경색과 글자색. 우선순위가 높을수록 진하다.
+fn strip_colors(shade: MapShade) -> (&'static str, &'static str) {
+  match shade {
+    MapShade::First => ("#1d3557", "white"),
+    MapShade::Second => ("#457b9d", "white"),
+    MapShade::Third => ("#a8dadc", "black"),
+    MapShade::Exception => ("#f4a261", "black"),
+    MapShade::Skipped => ("#bdbdbd", "black"),
+    MapShade::Unplanned => ("white", "black"),
+  }
+}
+
+/// 처리 범위를 가로 띠 하나로 그린 배치도 SVG를 만드는 함수.
+/// 도크 칸은 우선순위에 따라 진하기를 달리하고, 예외 그룹과 건너뛴 도크는 다른 색으로, 그룹 경계는 굵은 선으로 그린다.
+pub fn map_svg(cells: &[MapCell], font_family: &str) -> String {
+  let width = cells.len() as u32 * STRIP_CELL_WIDTH + 2 * STRIP_MARGIN;
+  // 위쪽은 그룹 번호, 아래쪽은 범례 자리이다.
+  let top = STRIP_MARGIN + 16;
+  let height = top + STRIP_CELL_HEIGHT + STRIP_MARGIN + 24;
+  let font = escape(font_family);
+  let mut svg: Vec<String> = Vec::new();
+  svg.push(format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#));
+  svg.push(format!(r#"  <rect width="{width}" height="{height}" fill="white"/>"#));
+
+  let mut previous_group: Option<Option<usize>> = None;
+  for (i, cell) in cells.iter().enumerate() {
+    let x = STRIP_MARGIN + i as u32 * STRIP_CELL_WIDTH;
+    let (fill, text_color) = strip_colors(cell.shade);
+    svg.push(format!(
+      r##"  <rect x="{x}" y="{top}" width="{STRIP_CELL_WIDTH}" height="{STRIP_CELL_HEIGHT}" fill="{fill}" stroke="#666" stroke-width="0.5"/>"##
+    ));
+    if cell.shade == MapShade::Skipped {
+      svg.push(format!(
+        r#"  <line x1="{x}" y1="{top}" x2="{}" y2="{}" stroke="black" stroke-width="1"/>"#,
+        x + STRIP_CELL_WIDTH,
+        top + STRIP_CELL_HEIGHT
+      ));
+    }
+    svg.push(format!(
+      r#"  <text x="{}" y="{}" font-family="{font}" font-size="10" fill="{text_color}" text-anchor="middle">{}</text>"#,
+      x + STRIP_CELL_WIDTH / 2,
+      top + STRIP_CELL_HEIGHT / 2 + 4,
+      cell.dock
+    ));
+    // 그룹이 바뀌는 곳에 경계선을 긋고, 그룹이 시작되는 칸 위에 그룹 번호를 쓴다.
+    if previous_group != Some(cell.group) {
+      if i > 0 {
+        svg.push(format!(
+          r#"  <line x1="{x}" y1="{}" x2="{x}" y2="{}" stroke="black" stroke-width="3"/>"#,
+          top - 6,
+          top + STRIP_CELL_HEIGHT + 6
+        ));
+      }
+      if let Some(group) = cell.group {
+        svg.push(format!(
+          r#"  <text x="{}" y="{}" font-family="{font}" font-size="11" font-weight="bold">G{group}</text>"#,
+          x + 2,
+          top - 8
+        ));
+      }
+    }
+    previous_group = Some(cell.group);
+  }
+
+  // 범례
+  let legend_y = top + STRIP_CELL_HEIGHT + STRIP_MARGIN;
+  let legend = [
+    (MapShade::First, "first"),
+    (MapShade::Second, "second"),
+    (MapShade::Third, "general"),
+    (MapShade::Exception, "exception"),
+    (MapShade::Skipped, "skipped"),
+  ];
+  for (i, (shade, name)) in legend.into_iter().enumerate() {
+    let x = STRIP_MARGIN + i as u32 * 90;
+    svg.push(format!(
+      r##"  <rect x="{x}" y="{}" width="12" height="12" fill="{}" stroke="#666" stroke-width="0.5"/>"##,
+      legend_y - 10,
+      strip_colors(shade).0
+    ));
+    svg.push(format!(r#"  <text x="{}" y="{legend_y}" font-family="{font}" font-size="11">{name}</text>"#, x + 16));
+  }
+  svg.push("</svg>\n".to_string());
+  svg.join("\n")
+}
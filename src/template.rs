@@ -0,0 +1,188 @@
+use std::{fmt, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{models::DockId, raster::LabelDimensions};
+
+// box 필드의 선 두께를 생략했을 때의 두께(밀리미터)
+const DEFAULT_BOX_THICKNESS: f64 = 0.5;
+
+/// 라벨 템플릿. 라벨 위에 놓이는 필드들의 위치와 크기로 라벨 모양을 정한다.
+/// 좌표와 크기의 단위는 밀리미터이며, 원점은 라벨의 왼쪽 위이다.
+///
+/// ```toml
+/// size = "100x60"
+///
+/// [[fields]]
+/// type = "box"
+/// x = 0
+/// y = 0
+/// height = 60
+///
+/// [[fields]]
+/// type = "group"
+/// x = 4
+/// y = 4
+/// height = 6
+///
+/// [[fields]]
+/// type = "dock"
+/// x = 4
+/// y = 14
+/// width = 92
+/// height = 28
+/// align = "center"
+///
+/// [[fields]]
+/// type = "barcode"
+/// x = 20
+/// y = 46
+/// width = 60
+/// height = 10
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LabelTemplate {
+  // 라벨 크기. 지정되었다면 명령줄의 라벨 크기 대신 사용한다.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub size: Option<LabelDimensions>,
+  pub fields: Vec<TemplateField>,
+}
+
+/// 라벨에 표시되는 그룹 번호. 출력 순서상 번호(1부터)이며, --page-numbers로 저장된 plan이라면
+/// 전체 그룹(페이지) 수를 함께 "3/12"처럼 표시하여 떨어뜨린 라벨 묶음을 순서대로 다시 모을 수 있게 한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupNumber {
+  pub index: usize,
+  pub of: Option<usize>,
+}
+
+impl GroupNumber {
+  /// 전체 그룹 수 없이 번호만 표시한다.
+  pub fn new(index: usize) -> Self {
+    GroupNumber { index, of: None }
+  }
+}
+
+impl fmt::Display for GroupNumber {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.of {
+      Some(of) => write!(f, "{}/{of}", self.index),
+      None => write!(f, "{}", self.index),
+    }
+  }
+}
+
+/// 템플릿 필드의 종류.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+  // 도크 번호
+  Dock,
+  // 출력 순서상 그룹 번호 (G1, G2, ...)
+  Group,
+  // 1차(@), 2차(*), 예외(EX) 기호
+  Marker,
+  // 도크 번호의 Code 128 바코드
+  Barcode,
+  // text에 적힌 글
+  Text,
+  // 테두리 사각형
+  Box,
+}
+
+/// 필드 폭 안에서 내용을 놓는 위치.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldAlign {
+  #[default]
+  Left,
+  Center,
+  Right,
+}
+
+impl FieldAlign {
+  /// available 폭 안에 used 폭의 내용을 놓을 때 왼쪽에서 띄울 거리.
+  pub fn offset(self, available: f64, used: f64) -> f64 {
+    let free = (available - used).max(0.0);
+    match self {
+      FieldAlign::Left => 0.0,
+      FieldAlign::Center => free / 2.0,
+      FieldAlign::Right => free,
+    }
+  }
+}
+
+/// 라벨 위에 놓이는 필드 하나.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateField {
+  #[serde(rename = "type")]
+  pub kind: FieldKind,
+  pub x: f64,
+  pub y: f64,
+  // 필드의 폭. 생략하면 라벨의 오른쪽 끝까지이다. 글은 이 폭에 들어가도록 글자 크기를 줄인다.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub width: Option<f64>,
+  // 글자 크기 또는 바코드와 사각형의 높이
+  pub height: f64,
+  #[serde(default)]
+  pub align: FieldAlign,
+  // 내용. {dock}, {group}, {marker}는 라벨의 값으로 바뀐다.
+  // 생략하면 필드 종류에 따라 "{dock}"(dock, barcode), "G{group}"(group), "{marker}"(marker)이다.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+  // box의 선 두께
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub thickness: Option<f64>,
+}
+
+impl TemplateField {
+  /// 라벨 하나에서 이 필드에 들어갈 내용. box는 빈 문자열이다.
+  pub fn content(&self, dock: DockId, group: GroupNumber, marker: &str) -> String {
+    let template = match (&self.text, self.kind) {
+      (Some(text), _) => text.as_str(),
+      (None, FieldKind::Dock | FieldKind::Barcode) => "{dock}",
+      (None, FieldKind::Group) => "G{group}",
+      (None, FieldKind::Marker) => "{marker}",
+      (None, FieldKind::Text | FieldKind::Box) => "",
+    };
+    template.replace("{dock}", &dock.to_string()).replace("{group}", &group.to_string()).replace("{marker}", marker)
+  }
+
+  /// 라벨 폭이 label_width일 때 이 필드의 폭.
+  pub fn width_in(&self, label_width: f64) -> f64 {
+    self.width.unwrap_or((label_width - self.x).max(0.0))
+  }
+
+  /// box의 선 두께.
+  pub fn thickness(&self) -> f64 {
+    self.thickness.unwrap_or(DEFAULT_BOX_THICKNESS)
+  }
+}
+
+/// 라벨 템플릿 파일을 읽는 함수. 확장자가 .toml이라면 TOML로, 그 외에는 YAML(또는 JSON)로 읽는다.
+pub fn load_template(path: &Path) -> Result<LabelTemplate, String> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| format!("Failed to read label template '{}': {e}", path.display()))?;
+  let invalid = |e: String| format!("Invalid label template '{}': {e}", path.display());
+  let template: LabelTemplate = match path.extension().and_then(|extension| extension.to_str()) {
+    Some("toml") => toml::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+    _ => serde_yaml::from_str(&content).map_err(|e| invalid(e.to_string()))?,
+  };
+  for (i, field) in template.fields.iter().enumerate() {
+    let kind = format!("{:?}", field.kind).to_lowercase();
+    let bad = |reason: &str| invalid(format!("field {} ({kind}): {reason}", i + 1));
+    let lengths = [Some(field.x), Some(field.y), field.width, Some(field.height), field.thickness];
+    if lengths.iter().flatten().any(|mm| !mm.is_finite() || *mm < 0.0) {
+      return Err(bad("positions and sizes must be zero or more millimeters"));
+    }
+    if field.height == 0.0 || field.width == Some(0.0) {
+      return Err(bad("width and height must be greater than 0"));
+    }
+    if field.kind == FieldKind::Text && field.text.as_deref().is_none_or(str::is_empty) {
+      return Err(bad("text fields need a `text`"));
+    }
+  }
+  Ok(template)
+}
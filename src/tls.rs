@@ -0,0 +1,159 @@
+use std::{
+  collections::HashMap,
+  io::{self, ErrorKind, Read, Write},
+  net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+  path::Path,
+  sync::{Arc, Mutex, MutexGuard, PoisonError},
+  thread,
+  time::Duration,
+};
+
+use rustls::{
+  ServerConfig, ServerConnection,
+  pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+};
+
+/// TLS handshake를 마쳐야 하는 시간. 연결만 열어 두고 아무것도 보내지 않는 클라이언트가 스레드를 붙잡지 않도록 한다.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// PEM 인증서 체인 파일과 개인 키 파일로 TLS 서버 설정을 만드는 함수.
+pub fn server_tls_config(cert: &Path, key: &Path) -> Result<Arc<ServerConfig>, String> {
+  let certs = CertificateDer::pem_file_iter(cert)
+    .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+    .map_err(|e| format!("Failed to read TLS certificate '{}': {e}", cert.display()))?;
+  if certs.is_empty() {
+    return Err(format!("No certificate found in '{}'.", cert.display()));
+  }
+  let key_der = PrivateKeyDer::from_pem_file(key)
+    .map_err(|e| format!("Failed to read TLS private key '{}': {e}", key.display()))?;
+  let config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| e.to_string())?
+    .with_no_client_auth()
+    .with_single_cert(certs, key_der)
+    .map_err(|e| format!("Invalid TLS certificate or private key: {e}"))?;
+  Ok(Arc::new(config))
+}
+
+// 내부 연결의 앞단 쪽 주소 → 실제 클라이언트 주소
+type PeerMap = Arc<Mutex<HashMap<SocketAddr, SocketAddr>>>;
+
+/// HTTPS 연결을 받아 TLS를 풀고, 평문 HTTP를 내부 주소의 HTTP 서버로 넘기는 앞단.
+/// tiny_http는 rustls 연결을 직접 받을 수 없으므로 HTTP 서버는 127.0.0.1의 임의 포트에서 듣고, 밖에는 이 앞단만 열린다.
+pub struct TlsFrontend {
+  peers: PeerMap,
+}
+
+impl TlsFrontend {
+  /// listener로 들어오는 연결마다 스레드를 띄워 backend로 중계한다.
+  pub fn spawn(listener: TcpListener, backend: SocketAddr, config: Arc<ServerConfig>) -> TlsFrontend {
+    let peers = PeerMap::default();
+    let frontend_peers = Arc::clone(&peers);
+    thread::spawn(move || {
+      for client in listener.incoming().flatten() {
+        let (config, peers) = (Arc::clone(&config), Arc::clone(&frontend_peers));
+        // handshake 실패(잘못된 인증서를 거부한 브라우저, 평문 HTTP 요청 등)는 그 연결만 닫는다.
+        thread::spawn(move || proxy(client, backend, config, &peers));
+      }
+    });
+    TlsFrontend { peers }
+  }
+
+  /// HTTP 서버가 본 요청의 주소(내부 연결)를 실제 클라이언트 주소로 바꾸는 함수.
+  pub fn client_addr(&self, backend_peer: SocketAddr) -> SocketAddr {
+    lock(&self.peers).get(&backend_peer).copied().unwrap_or(backend_peer)
+  }
+}
+
+// 다른 스레드가 panic했더라도 값은 그대로 쓸 수 있다.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+  mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// 연결 하나를 중계한다. handshake 뒤에는 클라이언트 → backend는 이 스레드가, backend → 클라이언트는 새 스레드가 옮긴다.
+fn proxy(mut client: TcpStream, backend: SocketAddr, config: Arc<ServerConfig>, peers: &PeerMap) -> io::Result<()> {
+  let peer = client.peer_addr()?;
+  let mut tls = ServerConnection::new(config).map_err(io::Error::other)?;
+  client.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+  while tls.is_handshaking() {
+    tls.complete_io(&mut client)?;
+  }
+  client.set_read_timeout(None)?;
+
+  let mut upstream = TcpStream::connect(backend)?;
+  let local = upstream.local_addr()?;
+  lock(peers).insert(local, peer);
+  let tls = Arc::new(Mutex::new(tls));
+
+  let responses = {
+    let (tls, mut client, mut upstream) = (Arc::clone(&tls), client.try_clone()?, upstream.try_clone()?);
+    thread::spawn(move || {
+      let mut buf = [0u8; 16 * 1024];
+      while let Ok(n @ 1..) = upstream.read(&mut buf) {
+        let mut tls = lock(&tls);
+        if tls.writer().write_all(&buf[..n]).and_then(|()| write_tls(&mut tls, &mut client)).is_err() {
+          break;
+        }
+      }
+      // backend가 연결을 닫으면 클라이언트와의 연결도 닫는다.
+      let mut tls = lock(&tls);
+      tls.send_close_notify();
+      let _ = write_tls(&mut tls, &mut client);
+      let _ = client.shutdown(Shutdown::Both);
+    })
+  };
+
+  let _ = forward_requests(&mut client, &mut upstream, &tls);
+  let _ = upstream.shutdown(Shutdown::Write);
+  let _ = responses.join();
+  lock(peers).remove(&local);
+  Ok(())
+}
+
+// 클라이언트가 보낸 TLS 레코드를 풀어 backend로 보낸다. 클라이언트가 연결을 닫으면 끝난다.
+fn forward_requests(client: &mut TcpStream, upstream: &mut TcpStream, tls: &Mutex<ServerConnection>) -> io::Result<()> {
+  let mut buf = [0u8; 16 * 1024];
+  loop {
+    // handshake와 함께 받은 요청(TLS 1.3)처럼 이미 받아 둔 레코드도 풀어 보낸다.
+    let mut plain = Vec::new();
+    let closed = {
+      let mut tls = lock(tls);
+      process_packets(&mut tls, client)?;
+      match tls.reader().read_to_end(&mut plain) {
+        Ok(_) => true,
+        Err(e) if e.kind() == ErrorKind::WouldBlock => false,
+        Err(e) => return Err(e),
+      }
+    };
+    upstream.write_all(&plain)?;
+    if closed {
+      return Ok(());
+    }
+
+    let n = client.read(&mut buf)?;
+    if n == 0 {
+      return Ok(());
+    }
+    let mut tls = lock(tls);
+    let mut records = &buf[..n];
+    while !records.is_empty() {
+      tls.read_tls(&mut records)?;
+      process_packets(&mut tls, client)?;
+    }
+  }
+}
+
+// 받은 레코드들을 처리한다. 에러라면 클라이언트에 alert를 보낸 뒤 끝낸다.
+fn process_packets(tls: &mut ServerConnection, client: &mut TcpStream) -> io::Result<()> {
+  let state = tls.process_new_packets();
+  write_tls(tls, client)?;
+  state.map(|_| ()).map_err(io::Error::other)
+}
+
+// 보낼 TLS 레코드를 모두 클라이언트에 쓴다.
+fn write_tls(tls: &mut ServerConnection, client: &mut TcpStream) -> io::Result<()> {
+  while tls.wants_write() {
+    tls.write_tls(client)?;
+  }
+  client.flush()
+}
@@ -0,0 +1,147 @@
+use std::env;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+// 지역을 지정하지 않았을 때의 S3 지역
+const DEFAULT_S3_REGION: &str = "us-east-1";
+
+/// `--upload`로 지정된, 내보낸 파일을 올릴 위치.
+/// 경로가 비어 있거나 '/'로 끝난다면 그 아래에 파일 이름으로 올리고, 그 외에는 경로 자체가 올릴 객체(파일)이다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadTarget {
+  // s3://bucket/prefix/ (AWS 서명 버전 4로 서명한 PUT)
+  S3 { bucket: String, prefix: String },
+  // http:// 또는 https:// URL로 보내는 PUT
+  Http(String),
+}
+
+/// `s3://BUCKET/PREFIX/` 또는 `http(s)://...` 형식의 업로드 위치를 파싱하는 함수.
+pub fn parse_upload_target(s: &str) -> Result<UploadTarget, String> {
+  if let Some(rest) = s.strip_prefix("s3://") {
+    let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+    if bucket.is_empty() {
+      return Err(format!("Invalid upload destination '{s}': the S3 bucket name is missing."));
+    }
+    return Ok(UploadTarget::S3 { bucket: bucket.to_string(), prefix: prefix.to_string() });
+  }
+  if s.starts_with("http://") || s.starts_with("https://") {
+    return Ok(UploadTarget::Http(s.to_string()));
+  }
+  Err(format!("Invalid upload destination '{s}'. Expected s3://BUCKET/PREFIX/ or an http(s):// URL."))
+}
+
+// 위치가 디렉터리(비었거나 '/'로 끝남)라면 name을 붙인다.
+fn join_name(base: &str, name: &str) -> String {
+  if base.is_empty() || base.ends_with('/') { format!("{base}{name}") } else { base.to_string() }
+}
+
+impl UploadTarget {
+  /// name 파일을 올렸을 때의 위치. 상태 메세지에 표시한다.
+  pub fn location(&self, name: &str) -> String {
+    match self {
+      UploadTarget::S3 { bucket, prefix } => format!("s3://{bucket}/{}", join_name(prefix, name)),
+      UploadTarget::Http(url) => join_name(url, name),
+    }
+  }
+}
+
+/// 파일 내용 body를 name이라는 이름으로 올리는 함수. 올린 위치를 반환한다.
+/// S3는 AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY(, AWS_SESSION_TOKEN) 환경 변수의 자격 증명으로 서명하며,
+/// AWS_REGION(또는 AWS_DEFAULT_REGION)의 지역으로 보낸다. AWS_ENDPOINT_URL이 지정되었다면 그 주소(MinIO 등)로 보낸다.
+pub fn upload(target: &UploadTarget, name: &str, body: &[u8], content_type: &str) -> Result<String, String> {
+  let location = target.location(name);
+  let failed = |e: ureq::Error| format!("Failed to upload '{name}' to {location}: {e}");
+  match target {
+    UploadTarget::Http(url) => {
+      ureq::put(&join_name(url, name)).header("Content-Type", content_type).send(body).map_err(failed)?;
+    }
+    UploadTarget::S3 { bucket, prefix } => {
+      let key = join_name(prefix, name);
+      let credentials = S3Credentials::from_env()?;
+      let region = env::var("AWS_REGION").or_else(|_| env::var("AWS_DEFAULT_REGION")).unwrap_or_else(|_| DEFAULT_S3_REGION.to_string());
+      // 엔드포인트를 지정했다면 경로에 버킷을 넣고, 그 외에는 AWS의 버킷 주소를 사용한다.
+      let (base, path) = match env::var("AWS_ENDPOINT_URL_S3").or_else(|_| env::var("AWS_ENDPOINT_URL")) {
+        Ok(endpoint) => (endpoint.trim_end_matches('/').to_string(), format!("/{bucket}/{}", uri_encode(&key))),
+        Err(_) => (format!("https://{bucket}.s3.{region}.amazonaws.com"), format!("/{}", uri_encode(&key))),
+      };
+      let host = base.split_once("://").map_or(base.as_str(), |(_, host)| host).to_string();
+      let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+      let payload_hash = hex(&Sha256::digest(body));
+      let authorization = credentials.authorization(&region, &host, &path, &amz_date, &payload_hash);
+      let mut request = ureq::put(&format!("{base}{path}"))
+        .header("Content-Type", content_type)
+        .header("x-amz-content-sha256", &payload_hash)
+        .header("x-amz-date", &amz_date)
+        .header("Authorization", &authorization);
+      if let Some(token) = &credentials.session_token {
+        request = request.header("x-amz-security-token", token);
+      }
+      request.send(body).map_err(failed)?;
+    }
+  }
+  Ok(location)
+}
+
+// 환경 변수에서 읽은 AWS 자격 증명
+struct S3Credentials {
+  access_key: String,
+  secret_key: String,
+  session_token: Option<String>,
+}
+
+impl S3Credentials {
+  fn from_env() -> Result<Self, String> {
+    let required = |name: &str| env::var(name).map_err(|_| format!("S3 upload needs the `{name}` environment variable."));
+    Ok(S3Credentials {
+      access_key: required("AWS_ACCESS_KEY_ID")?,
+      secret_key: required("AWS_SECRET_ACCESS_KEY")?,
+      session_token: env::var("AWS_SESSION_TOKEN").ok(),
+    })
+  }
+
+  // AWS 서명 버전 4로 PUT 요청의 Authorization 헤더 값을 만든다.
+  // 서명하는 헤더는 host, x-amz-content-sha256, x-amz-date(와 x-amz-security-token)이다.
+  fn authorization(&self, region: &str, host: &str, path: &str, amz_date: &str, payload_hash: &str) -> String {
+    let mut headers = vec![("host", host), ("x-amz-content-sha256", payload_hash), ("x-amz-date", amz_date)];
+    if let Some(token) = &self.session_token {
+      headers.push(("x-amz-security-token", token));
+    }
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{name}:{}\n", value.trim())).collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!("PUT\n{path}\n\n{canonical_headers}\n{signed_headers}\n{payload_hash}");
+
+    let date = &amz_date[..8];
+    let scope = format!("{date}/{region}/s3/aws4_request");
+    let string_to_sign = format!("AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}", hex(&Sha256::digest(canonical_request.as_bytes())));
+    let signing_key = [date, region, "s3", "aws4_request"]
+      .iter()
+      .fold(format!("AWS4{}", self.secret_key).into_bytes(), |key, part| hmac_sha256(&key, part.as_bytes()));
+    let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+    format!("AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}", self.access_key)
+  }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+  let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+  mac.update(data);
+  mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+// S3 객체 key를 URI 경로로 인코딩한다. 영문자, 숫자, '-', '_', '.', '~', '/' 외의 바이트는 %XX로 바꾼다.
+fn uri_encode(key: &str) -> String {
+  key
+    .bytes()
+    .map(|byte| match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (byte as char).to_string(),
+      _ => format!("%{byte:02X}"),
+    })
+    .collect()
+}
@@ -0,0 +1,388 @@
+use crate::{
+  i18n::tr,
+  intervals::IntervalSet,
+  models::{DockId, GroupKind, Priority},
+  processor::{PlanRequest, ProcessingResult},
+};
+
+// 에러 메세지에 나열할 최대 위반 수. 나머지는 개수만 표시한다.
+const MAX_REPORTED: usize = 10;
+
+/// 계산된 plan이 항상 지켜야 하는 규칙들을 확인하는 함수.
+/// 위반이 있다면 위반 내용들을 나열한 에러 메세지를 반환한다.
+///
+/// - 처리 범위(min..max)의 모든 도크가 정확히 한 그룹에 한 번씩 나타난다. 단, --skip으로 제외된 도크와
+///   --site-docks의 범위들 사이에 있어 현장에 없는 도크는 어떤 그룹에도 나타나지 않는다.
+///   (--repeat-groups로 연속으로 반복된 그룹은 하나로 본다.)
+/// - 모든 예외 그룹이 쪼개지거나 다른 도크와 섞이지 않고 그대로 하나의 그룹으로 나타난다.
+///   (--pad-exceptions라면 예외 그룹 뒤에 per-page까지 3차 도크들이 붙을 수 있다.)
+/// - 일반 그룹은 예외 도크를 포함하지 않으며, 그룹의 크기가 해당 우선순위의 per-page(--pp-range 구간이라면 구간의 값)를 넘지 않는다.
+///   (--pair-offset이 있다면 짝 도크 한 쌍은 per-page가 1이더라도 함께 묶인다.
+///   그룹핑 규칙이 목표 크기를 정할 수 있으므로 --rules가 있다면 크기는 확인하지 않는다.)
+/// - --solo 도크는 다른 도크와 같은 그룹에 들어가지 않는다.
+pub fn verify(result: &ProcessingResult, request: &PlanRequest) -> Result<(), String> {
+  let args = request.args;
+  let mut violations: Vec<String> = Vec::new();
+
+  // 1. 모든 도크가 범위 안에서 정확히 한 번씩 나타나는지 확인한다.
+  let mut seen = IntervalSet::new();
+  let skipped_ranges = args.skipped_ranges();
+  let skipped = |dock: DockId| skipped_ranges.iter().any(|range| range.contains(dock));
+  for (i, group) in result.result_groups.iter().enumerate() {
+    // --repeat-groups로 바로 앞 그룹을 반복한 그룹은 이미 확인한 도크들이다.
+    if args.repeats_of(group.tier) > 1 && i > 0 && result.result_groups[i - 1].docks == group.docks {
+      continue;
+    }
+    for &dock in &group.docks {
+      if skipped(dock) {
+        violations.push(tr("verify.skipped_dock", &[("group", &group.index), ("dock", &dock)]));
+      } else if dock < args.min || dock > args.max {
+        violations.push(tr("verify.outside_range", &[("group", &group.index), ("dock", &dock), ("min", &args.min), ("max", &args.max)]));
+      } else if seen.contains(dock) {
+        violations.push(tr("verify.duplicate", &[("dock", &dock), ("group", &group.index)]));
+      }
+      seen.insert(dock);
+    }
+  }
+  // 제외된 도크들은 처리된 것으로 보고, 처리된 구간들 사이의 빈 곳이 누락된 도크들이다.
+  for range in &skipped_ranges {
+    seen.insert_range(range.start, range.end);
+  }
+  let mut next_expected = Some(args.min);
+  for range in seen.iter().filter(|r| r.end >= args.min && r.start <= args.max) {
+    if let Some(expected) = next_expected
+      && expected < range.start
+    {
+      violations.push(missing_message(expected, range.start.saturating_sub(1)));
+    }
+    next_expected = range.end.checked_add(1);
+  }
+  if let Some(expected) = next_expected
+    && expected <= args.max
+  {
+    violations.push(missing_message(expected, args.max));
+  }
+
+  // 2. 예외 그룹들이 그대로 하나의 그룹으로 나타나는지 확인한다.
+  for ex_group in &result.final_exception_groups {
+    let intact = result.result_groups.iter().any(|group| {
+      let padding = &group.docks[ex_group.len().min(group.docks.len())..];
+      group.kind == GroupKind::Exception
+        && group.docks.starts_with(ex_group)
+        && (padding.is_empty()
+          || args.pad_exceptions
+            && group.docks.len() <= group.docks.iter().map(|&dock| usize::from(args.per_pages_at(dock).2)).max().unwrap_or(0).max(ex_group.len())
+            && padding.iter().all(|dock| !result.priorities.contains_key(dock) && !result.all_exception_docks.contains(dock)))
+    });
+    if !intact {
+      let docks: Vec<String> = ex_group.iter().map(DockId::to_string).collect();
+      violations.push(tr("verify.split_exception", &[("docks", &docks.join(", "))]));
+    }
+  }
+
+  // 3. 일반 그룹의 예외 도크 포함 여부와 크기를 확인한다.
+  for group in result.result_groups.iter().filter(|group| group.kind == GroupKind::Regular) {
+    if let Some(dock) = group.docks.iter().find(|dock| result.all_exception_docks.contains(dock)) {
+      violations.push(tr("verify.exception_in_regular", &[("group", &group.index), ("dock", &dock)]));
+    }
+    // --pp-range 구간에서 시작하는 그룹은 구간의 per-page를 따른다. plan 파일에서 읽은 빈 그룹은 크기를 확인할 것이 없다.
+    let Some(&first) = group.docks.first() else {
+      continue;
+    };
+    let (fpp, spp, gpp) = args.per_pages_at(first);
+    let per_page = match group.tier {
+      Priority::First => fpp,
+      Priority::Second => spp,
+      Priority::Third => gpp,
+    };
+    let limit = match args.pair_offset {
+      Some(_) => usize::from(per_page).max(2),
+      None => usize::from(per_page),
+    };
+    if request.rules.is_none() && group.docks.len() > limit {
+      violations.push(tr(
+        "verify.oversized",
+        &[("group", &group.index), ("tier", &group.tier.name()), ("size", &group.docks.len()), ("per_page", &per_page)],
+      ));
+    }
+  }
+
+  // 4. --solo 도크들이 혼자 한 그룹인지 확인한다.
+  for group in result.result_groups.iter().filter(|group| group.docks.len() > 1) {
+    if let Some(dock) = group.docks.iter().find(|&&dock| args.solo.iter().any(|range| range.contains(dock))) {
+      violations.push(tr("verify.solo", &[("group", &group.index), ("dock", &dock)]));
+    }
+  }
+
+  if violations.is_empty() {
+    return Ok(());
+  }
+  let mut message = tr("verify.header", &[("count", &violations.len())]);
+  for violation in violations.iter().take(MAX_REPORTED) {
+    message.push_str(&format!("\n  - {violation}"));
+  }
+  if violations.len() > MAX_REPORTED {
+    message.push_str(&format!("\n  {}", tr("verify.more", &[("count", &(violations.len() - MAX_REPORTED))])));
+  }
+  Err(message)
+}
+
+// 누락된 도크(들)에 대한 메세지.
+fn missing_message(start: DockId, end: DockId) -> String {
+  match start == end {
+    true => tr("verify.missing_one", &[("dock", &start)]),
+    false => tr("verify.missing_many", &[("start", &start), ("end", &end)]),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fmt;
+
+  use clap::Parser;
+  use quickcheck::{Arbitrary, Gen, QuickCheck, TestResult};
+
+  use super::*;
+  use crate::{
+    cli::{Args, ReplayOverrides},
+    i18n::{Lang, set_lang},
+    models::Group,
+    plan_from_str,
+    processor::process_docks,
+  };
+
+  // CLI와 같은 형식의 인자 문자열로 Args를 만든다.
+  fn args(input: &str) -> Args {
+    let args = ReplayOverrides::try_parse_from(input.split_whitespace()).expect("invalid test arguments").args;
+    args.validate_input().expect("invalid test arguments");
+    args
+  }
+
+  // 인자로 plan을 계산하고, change로 plan을 고친 뒤 확인한다.
+  // 위반 메세지는 영어 문구로 확인한다.
+  fn check_with(input: &str, change: impl FnOnce(&mut Vec<Group>)) -> Result<(), String> {
+    set_lang(Lang::En);
+    let args = args(input);
+    let request = PlanRequest::new(&args);
+    let mut result = process_docks(request);
+    change(&mut result.result_groups);
+    verify(&result, &request)
+  }
+
+  fn check(input: &str) -> Result<(), String> {
+    check_with(input, |_| {})
+  }
+
+  // plan을 고쳐 규칙을 어기게 했을 때 needle이 포함된 위반이 보고되는지 확인한다.
+  fn assert_violation(input: &str, needle: &str, change: impl FnOnce(&mut Vec<Group>)) {
+    let error = check_with(input, change).expect_err("verify accepted a broken plan");
+    assert!(error.contains(needle), "expected '{needle}' in:\n{error}");
+  }
+
+  fn group_with(groups: &mut [Group], dock: u32) -> &mut Group {
+    groups.iter_mut().find(|group| group.docks.contains(&DockId(dock))).expect("dock is not in any group")
+  }
+
+  #[test]
+  fn accepts_computed_plans() {
+    for input in [
+      "-p 3 --min 51 --max 70",
+      "-p 3 --min 51 --max 70 -f 52 55-56 -s 60-62 --fp 2 --sp 4",
+      "-p 3 --min 51 --max 70 -e 55-57 -e 60,62",
+      "-p 4 --min 51 --max 70 -e 55-56 --pad-exceptions",
+      "-p 1 --min 51 --max 70 --pair-offset 10",
+      "-p 3 --min 51 --max 80 --pp-range 60-69=5 --pp-range 75-80=1",
+      "-p 3 --min 51 --max 70 --skip 55-57 64",
+      "-p 3 --min 51 --max 70 --skip 55-57 --break-on-gap",
+      "-p 3 --min 51 --max 70 --solo 60 65",
+      "-p 3 --min 51 --max 70 -f 52 --repeat-groups 2",
+      "-p 3 --min 51 --max 62 --site-docks 51-55 60-62 --break-on-gap",
+      "-p 3 --min 51 --max 62 --site-docks 51-55 60-62",
+      "-p 3 --min 51 --max 70 -e 55-57 --skip 56 --solo 60 --pair-offset 10 -f 52",
+    ] {
+      if let Err(error) = check(input) {
+        panic!("{input}: {error}");
+      }
+    }
+  }
+
+  #[test]
+  fn reports_missing_dock() {
+    assert_violation("-p 3 --min 51 --max 60", "Dock 52 does not appear", |groups| {
+      group_with(groups, 52).docks.retain(|&dock| dock != DockId(52));
+    });
+  }
+
+  #[test]
+  fn reports_duplicate_dock() {
+    assert_violation("-p 3 --min 51 --max 60", "Dock 52 appears more than once", |groups| {
+      groups.last_mut().unwrap().docks.push(DockId(52));
+    });
+  }
+
+  #[test]
+  fn reports_repeated_group_apart_from_its_copy() {
+    assert_violation("-p 3 --min 51 --max 60 --repeat-groups 2", "Dock 51 appears more than once", |groups| {
+      let repeated = groups.remove(1);
+      groups.push(repeated);
+    });
+  }
+
+  #[test]
+  fn reports_dock_outside_range() {
+    assert_violation("-p 3 --min 51 --max 60", "outside the range 51-60", |groups| {
+      groups.last_mut().unwrap().docks.push(DockId(61));
+    });
+  }
+
+  #[test]
+  fn reports_skipped_dock() {
+    assert_violation("-p 3 --min 51 --max 60 --skip 55", "contains skipped dock 55", |groups| {
+      groups.last_mut().unwrap().docks.push(DockId(55));
+    });
+  }
+
+  #[test]
+  fn reports_dock_between_site_ranges() {
+    assert_violation("-p 3 --min 51 --max 62 --site-docks 51-55 60-62", "contains skipped dock 57", |groups| {
+      groups.last_mut().unwrap().docks.push(DockId(57));
+    });
+  }
+
+  #[test]
+  fn reports_split_exception_group() {
+    assert_violation("-p 3 --min 51 --max 60 -e 54-56", "does not appear as one intact group", |groups| {
+      let group = group_with(groups, 54);
+      let split = Group { docks: group.docks.split_off(1), ..group.clone() };
+      groups.push(split);
+    });
+  }
+
+  #[test]
+  fn reports_padding_without_pad_exceptions() {
+    assert_violation("-p 4 --min 51 --max 60 -e 54-55", "does not appear as one intact group", |groups| {
+      let moved = group_with(groups, 56).docks.remove(0);
+      group_with(groups, 54).docks.push(moved);
+    });
+  }
+
+  #[test]
+  fn reports_exception_dock_in_regular_group() {
+    assert_violation("-p 3 --min 51 --max 60 -e 55", "contains exception dock 55", |groups| {
+      group_with(groups, 51).docks.push(DockId(55));
+    });
+  }
+
+  #[test]
+  fn reports_oversized_group() {
+    assert_violation("-p 3 --min 51 --max 60", "more than its per-page of 3", |groups| {
+      let moved = group_with(groups, 54).docks.remove(0);
+      group_with(groups, 51).docks.push(moved);
+    });
+  }
+
+  #[test]
+  fn reports_oversized_group_in_per_page_range() {
+    assert_violation("-p 3 --min 51 --max 60 --pp-range 51-55=1", "more than its per-page of 1", |groups| {
+      let moved = group_with(groups, 56).docks.remove(0);
+      group_with(groups, 51).docks.push(moved);
+    });
+  }
+
+  #[test]
+  fn reports_solo_dock_with_others() {
+    assert_violation("-p 3 --min 51 --max 60 --solo 55", "contains solo dock 55", |groups| {
+      let moved = group_with(groups, 56).docks.remove(0);
+      group_with(groups, 55).docks.push(moved);
+    });
+  }
+
+  // 임의로 만든 CLI 인자 조합. 앞의 세 옵션(-p, --min, --max)은 항상 있고, 나머지 옵션들은 있을 수도 없을 수도 있다.
+  // 옵션의 도크들은 처리 범위 밖으로 조금 벗어날 수 있다.
+  #[derive(Clone)]
+  struct PlanInput {
+    options: Vec<String>,
+  }
+
+  impl fmt::Debug for PlanInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      write!(f, "{}", self.options.join(" "))
+    }
+  }
+
+  // 0..n 중 하나.
+  fn below(g: &mut Gen, n: u32) -> u32 {
+    u32::arbitrary(g) % n
+  }
+
+  impl Arbitrary for PlanInput {
+    fn arbitrary(g: &mut Gen) -> Self {
+      let min = 1 + below(g, 100);
+      let max = min + below(g, 60);
+      let dock = |g: &mut Gen| (min + below(g, max - min + 7)).saturating_sub(3).max(1);
+      let range = |g: &mut Gen| {
+        let start = dock(g);
+        match below(g, 4) {
+          0 => start.to_string(),
+          len => format!("{start}-{}", start + len),
+        }
+      };
+      let mut options = vec![format!("-p {}", 1 + below(g, 4)), format!("--min {min}"), format!("--max {max}")];
+      let mut maybe = |g: &mut Gen, option: &mut dyn FnMut(&mut Gen) -> String| {
+        if below(g, 3) == 0 {
+          options.push(option(g));
+        }
+      };
+      maybe(g, &mut |g| format!("-f {} {}", range(g), if bool::arbitrary(g) { format!("!{}", dock(g)) } else { range(g) }));
+      maybe(g, &mut |g| format!("-s {} {}", range(g), range(g)));
+      maybe(g, &mut |g| format!("-e {}", range(g)));
+      maybe(g, &mut |g| format!("-e {},{}", dock(g), dock(g)));
+      maybe(g, &mut |g| format!("--skip {}", range(g)));
+      maybe(g, &mut |g| format!("--solo {}", dock(g)));
+      maybe(g, &mut |g| format!("--fp {}", 1 + below(g, 4)));
+      maybe(g, &mut |g| format!("--sp {}", 1 + below(g, 4)));
+      maybe(g, &mut |g| format!("--pp-range {}={}", range(g), 1 + below(g, 4)));
+      maybe(g, &mut |g| format!("--pair-offset {}", 1 + below(g, 10)));
+      maybe(g, &mut |g| format!("--row-size {}", 2 + below(g, 10)));
+      maybe(g, &mut |g| format!("--repeat-groups {}", 2 + below(g, 2)));
+      maybe(g, &mut |g| {
+        // 처리 범위의 양 끝을 포함하고 가운데에 빈 곳이 있는 현장.
+        let gap = min + below(g, max - min + 1);
+        format!("--site-docks {min}-{} {}-{max}", gap.saturating_sub(1).max(min), (gap + 2).min(max))
+      });
+      for flag in ["--pad-exceptions", "--break-on-gap", "--strict-first", "--strict-second"] {
+        maybe(g, &mut |_| flag.to_string());
+      }
+      PlanInput { options }
+    }
+
+    // 실패한 입력은 옵션을 하나씩 빼 보며 줄인다. -p, --min, --max는 빼지 않는다.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+      let input = self.clone();
+      Box::new((3..self.options.len()).map(move |i| {
+        let mut options = input.options.clone();
+        options.remove(i);
+        PlanInput { options }
+      }))
+    }
+  }
+
+  // plan_from_str가 받아들인 입력이라면 계산된 plan은 verify를 통과해야 한다.
+  // 잘못된 인자 조합(plan_from_str의 에러)은 건너뛴다.
+  fn computed_plan_verifies(input: PlanInput) -> TestResult {
+    let input = input.options.join(" ");
+    let Ok(result) = plan_from_str(&input) else {
+      return TestResult::discard();
+    };
+    let args = ReplayOverrides::try_parse_from(input.split_whitespace()).expect("plan_from_str accepted the arguments").args;
+    match verify(&result, &PlanRequest::new(&args)) {
+      Ok(()) => TestResult::passed(),
+      Err(error) => TestResult::error(format!("{input}: {error}")),
+    }
+  }
+
+  #[test]
+  fn accepts_generated_plans() {
+    QuickCheck::new().tests(1000).quickcheck(computed_plan_verifies as fn(PlanInput) -> TestResult);
+  }
+}
@@ -0,0 +1,44 @@
+use std::{
+  fs,
+  path::Path,
+  thread,
+  time::{Duration, SystemTime},
+};
+
+/// 파일 변경을 확인하는 주기.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// 변경을 감지한 뒤, 파일을 쓰는 프로그램이 쓰기를 마칠 때까지 기다리는 시간.
+const SETTLE_DELAY: Duration = Duration::from_millis(200);
+
+/// run을 한 번 실행한 뒤, path의 수정 시각이 바뀔 때마다 다시 실행하는 함수. 프로세스가 종료될 때까지 반환하지 않는다.
+/// 다시 실행하는 중 발생한 에러는 출력만 하고 감시를 계속한다.
+pub fn watch_file(path: &Path, mut run: impl FnMut() -> Result<(), String>) -> Result<(), String> {
+  let mut last_modified = modified_time(path)?;
+  if let Err(e) = run() {
+    eprintln!("Error: {e}");
+  }
+  eprintln!("\nWatching '{}' for changes. Press Ctrl-C to stop.", path.display());
+
+  loop {
+    thread::sleep(POLL_INTERVAL);
+    // 파일이 교체되는 도중에는 잠시 존재하지 않을 수 있으므로 에러는 무시하고 다음 주기에 다시 확인한다.
+    let Ok(modified) = modified_time(path) else { continue };
+    if modified == last_modified {
+      continue;
+    }
+    thread::sleep(SETTLE_DELAY);
+    last_modified = modified_time(path).unwrap_or(modified);
+
+    eprintln!("\n--- '{}' changed, re-planning ---", path.display());
+    if let Err(e) = run() {
+      eprintln!("Error: {e}");
+    }
+  }
+}
+
+/// 파일의 마지막 수정 시각을 반환하는 함수.
+fn modified_time(path: &Path) -> Result<SystemTime, String> {
+  fs::metadata(path)
+    .and_then(|meta| meta.modified())
+    .map_err(|e| format!("Failed to watch '{}': {e}", path.display()))
+}
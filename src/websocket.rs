@@ -0,0 +1,126 @@
+use std::{
+  io::Write,
+  sync::{
+    Arc,
+    mpsc::{self, Receiver, RecvTimeoutError, Sender},
+  },
+  thread,
+  time::Duration,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use tiny_http::ReadWrite;
+
+/// Sec-WebSocket-Accept를 만들 때 key 뒤에 붙이는 값 (RFC 6455).
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// 보낼 plan이 없을 때 연결이 살아 있는지 확인하는 ping 주기. 끊긴 연결은 ping을 보내지 못해 정리된다.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+// 프레임 opcode
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_PING: u8 = 0x9;
+
+/// 클라이언트의 Sec-WebSocket-Key로 handshake 응답의 Sec-WebSocket-Accept 값을 만드는 함수.
+pub fn accept_key(key: &str) -> String {
+  BASE64.encode(sha1(format!("{}{HANDSHAKE_GUID}", key.trim()).as_bytes()))
+}
+
+/// 서버가 보내는 (mask하지 않은) 프레임 하나. 메세지는 조각내지 않는다.
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+  let mut frame = vec![0x80 | opcode];
+  match payload.len() {
+    len @ 0..=125 => frame.push(len as u8),
+    len @ 126..=0xFFFF => {
+      frame.push(126);
+      frame.extend_from_slice(&(len as u16).to_be_bytes());
+    }
+    len => {
+      frame.push(127);
+      frame.extend_from_slice(&(len as u64).to_be_bytes());
+    }
+  }
+  frame.extend_from_slice(payload);
+  frame
+}
+
+/// 연결된 WebSocket 클라이언트들. 클라이언트마다 보내는 스레드가 있어, 느리거나 멈춘 화면이 서버의 요청 처리를 막지 않는다.
+#[derive(Default)]
+pub struct Broadcaster {
+  clients: Vec<Sender<Arc<Vec<u8>>>>,
+}
+
+impl Broadcaster {
+  /// handshake를 마친 연결을 클라이언트로 추가하는 함수.
+  pub fn add(&mut self, stream: Box<dyn ReadWrite + Send>) {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || send_frames(stream, receiver));
+    self.clients.push(sender);
+  }
+
+  /// 연결된 모든 클라이언트에 text 메세지를 보내는 함수. 연결이 끊긴 클라이언트는 목록에서 뺀다.
+  pub fn broadcast(&mut self, text: &str) {
+    let frame = Arc::new(frame(OPCODE_TEXT, text.as_bytes()));
+    self.clients.retain(|client| client.send(Arc::clone(&frame)).is_ok());
+  }
+
+  /// 연결된 클라이언트가 없는지 여부.
+  pub fn is_empty(&self) -> bool {
+    self.clients.is_empty()
+  }
+}
+
+// 클라이언트 하나에 프레임들을 보내는 스레드. 쓰기에 실패하면(연결이 끊기면) 끝나며, 그 뒤의 broadcast에서 목록에서 빠진다.
+// 클라이언트가 보내는 프레임(pong, close 등)은 읽지 않는다.
+fn send_frames(mut stream: Box<dyn ReadWrite + Send>, receiver: Receiver<Arc<Vec<u8>>>) {
+  loop {
+    let frame = match receiver.recv_timeout(PING_INTERVAL) {
+      Ok(frame) => frame,
+      Err(RecvTimeoutError::Timeout) => Arc::new(frame(OPCODE_PING, b"")),
+      Err(RecvTimeoutError::Disconnected) => return,
+    };
+    if stream.write_all(&frame).and_then(|()| stream.flush()).is_err() {
+      return;
+    }
+  }
+}
+
+// SHA-1 (RFC 3174). WebSocket handshake에만 쓰며, 보안 용도로 쓰지 않는다.
+fn sha1(data: &[u8]) -> [u8; 20] {
+  let mut state: [u32; 5] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476, 0xC3D2_E1F0];
+  let mut message = data.to_vec();
+  message.push(0x80);
+  while message.len() % 64 != 56 {
+    message.push(0);
+  }
+  message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+  for block in message.chunks_exact(64) {
+    let mut words = [0u32; 80];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+      *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    for i in 16..80 {
+      words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e] = state;
+    for (i, word) in words.iter().enumerate() {
+      let (f, k) = match i {
+        0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+        20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+        40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+        _ => (b ^ c ^ d, 0xCA62_C1D6),
+      };
+      let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+      (e, d, c, b, a) = (d, c, b.rotate_left(30), a, temp);
+    }
+    for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+      *value = value.wrapping_add(add);
+    }
+  }
+
+  let mut digest = [0u8; 20];
+  for (bytes, value) in digest.chunks_exact_mut(4).zip(state) {
+    bytes.copy_from_slice(&value.to_be_bytes());
+  }
+  digest
+}
@@ -0,0 +1,206 @@
+use chrono::Local;
+use serde_json::Value;
+
+use crate::{
+  cli::{Args, parse_dock_ranges},
+  models::{DockId, DockRange},
+  signing::read_secret,
+};
+
+/// JSON 경로의 한 단계.
+#[derive(Debug, Clone, PartialEq)]
+enum PathStep {
+  // .key 또는 ['key']
+  Key(String),
+  // [0]
+  Index(usize),
+  // .* 또는 [*]: 배열의 모든 항목이나 객체의 모든 값
+  Wildcard,
+  // [?(@.a.b == 'x')]: 배열의 항목(객체의 값) 중 @ 아래 경로의 값이 value와 같은 것
+  Filter { keys: Vec<String>, value: Value },
+}
+
+impl PathStep {
+  // value에 이 단계를 적용한 값들.
+  fn apply<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+    let children = || -> Vec<&'a Value> {
+      match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(object) => object.values().collect(),
+        _ => Vec::new(),
+      }
+    };
+    match self {
+      PathStep::Key(key) => value.as_object().and_then(|object| object.get(key)).into_iter().collect(),
+      PathStep::Index(index) => value.as_array().and_then(|items| items.get(*index)).into_iter().collect(),
+      PathStep::Wildcard => children(),
+      PathStep::Filter { keys, value: expected } => children()
+        .into_iter()
+        .filter(|child| keys.iter().try_fold(*child, |current, key| current.get(key)).is_some_and(|found| json_equal(found, expected)))
+        .collect(),
+    }
+  }
+}
+
+// 필터 비교. 숫자는 표기(1, 1.0)와 관계없이 값으로 비교한다.
+fn json_equal(a: &Value, b: &Value) -> bool {
+  match (a.as_f64(), b.as_f64()) {
+    (Some(x), Some(y)) => x == y,
+    _ => a == b,
+  }
+}
+
+/// WMS 응답에서 도크들을 고르는 JSON 경로. `$`에서 시작하며 `.key`, `['key']`, `[0]`, `[*]`, `.*`,
+/// `[?(@.key == 'value')]` 단계를 지원한다. 예: `$.docks[?(@.level == 'hot')].dock`
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+  steps: Vec<PathStep>,
+}
+
+impl JsonPath {
+  /// root에서 경로가 가리키는 값들.
+  pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+    self.steps.iter().fold(vec![root], |values, step| values.into_iter().flat_map(|value| step.apply(value)).collect())
+  }
+}
+
+/// JSON 경로 문자열을 읽는 함수.
+pub fn parse_json_path(path: &str) -> Result<JsonPath, String> {
+  let invalid = |reason: &str| format!("Invalid JSON path '{path}': {reason}");
+  let mut rest = path.trim().strip_prefix('$').ok_or_else(|| invalid("it must start with `$`"))?;
+  let mut steps = Vec::new();
+  while !rest.is_empty() {
+    if let Some(after) = rest.strip_prefix('.') {
+      let end = after.find(['.', '[']).unwrap_or(after.len());
+      let step = match &after[..end] {
+        "" => return Err(invalid("empty key after `.`")),
+        "*" => PathStep::Wildcard,
+        key => PathStep::Key(key.to_string()),
+      };
+      steps.push(step);
+      rest = &after[end..];
+    } else if let Some(after) = rest.strip_prefix("[?(") {
+      let end = after.find(")]").ok_or_else(|| invalid("unclosed filter; expected `)]`"))?;
+      steps.push(parse_filter(&after[..end]).map_err(|reason| invalid(&reason))?);
+      rest = &after[end + 2..];
+    } else if let Some(after) = rest.strip_prefix('[') {
+      let end = after.find(']').ok_or_else(|| invalid("unclosed `[`"))?;
+      let inner = after[..end].trim();
+      let step = if inner == "*" {
+        PathStep::Wildcard
+      } else if let Some(key) = unquote(inner) {
+        PathStep::Key(key.to_string())
+      } else {
+        PathStep::Index(inner.parse().map_err(|_| invalid(&format!("'[{inner}]' is not an index, a quoted key or `*`")))?)
+      };
+      steps.push(step);
+      rest = &after[end + 1..];
+    } else {
+      return Err(invalid(&format!("unexpected '{rest}'; expected `.` or `[`")));
+    }
+  }
+  Ok(JsonPath { steps })
+}
+
+// 'key' 또는 "key"의 따옴표 안.
+fn unquote(s: &str) -> Option<&str> {
+  s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')).or_else(|| s.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+}
+
+// `@.a.b == 'x'` 형식의 필터 조건을 읽는다. 값은 따옴표로 감싼 문자열이거나 JSON 값(숫자, true, null 등)이다.
+fn parse_filter(condition: &str) -> Result<PathStep, String> {
+  let (left, right) = condition.split_once("==").ok_or("filters must have the form `@.key == value`")?;
+  let keys: Vec<String> = match left.trim().strip_prefix('@') {
+    Some(keys) => keys.split('.').skip(1).map(str::to_string).collect(),
+    None => return Err("the left side of a filter must start with `@`".to_string()),
+  };
+  if keys.is_empty() || keys.iter().any(String::is_empty) {
+    return Err("the left side of a filter must be `@.key` or `@.key.key`".to_string());
+  }
+  let right = right.trim();
+  let value = match unquote(right) {
+    Some(text) => Value::String(text.to_string()),
+    None => serde_json::from_str(right).map_err(|_| format!("'{right}' is not a quoted string, number, boolean or null"))?,
+  };
+  Ok(PathStep::Filter { keys, value })
+}
+
+// 도크 번호(55) 또는 범위 문자열("60-61") 하나를 읽는다.
+fn dock_range(item: &Value) -> Result<DockRange, String> {
+  match item {
+    Value::Number(number) => number
+      .as_u64()
+      .and_then(|dock| u32::try_from(dock).ok())
+      .map(|dock| DockRange::single(DockId(dock)))
+      .ok_or_else(|| format!("'{number}' is not a dock number")),
+    Value::String(range) => parse_dock_ranges(range),
+    other => Err(format!("'{other}' is not a dock number or range")),
+  }
+}
+
+// url에 GET 요청을 보내 JSON 응답을 읽는다. what은 에러 메세지에 쓰는 이름이다.
+fn get_json(url: &str, token: Option<&str>, what: &str) -> Result<Value, String> {
+  let mut request = ureq::get(url).header("Accept", "application/json");
+  if let Some(token) = token {
+    request = request.header("Authorization", &format!("Bearer {token}"));
+  }
+  let body = request
+    .call()
+    .and_then(|mut response| response.body_mut().read_to_string())
+    .map_err(|e| format!("Failed to fetch {what} from '{url}': {e}"))?;
+  serde_json::from_str(&body).map_err(|e| format!("Invalid {what} from '{url}': {e}"))
+}
+
+/// 사용 중지된 도크 목록을 URL에서 받아오는 함수.
+/// 응답은 JSON 배열이거나 `docks` 배열을 가진 객체이며, 각 항목은 도크 번호(55) 또는 범위 문자열("60-61")이다.
+pub fn fetch_closed_docks(url: &str) -> Result<Vec<DockRange>, String> {
+  let invalid = |reason: String| format!("Invalid closed dock list from '{url}': {reason}");
+  let json = get_json(url, None, "closed dock list")?;
+  let items = match &json {
+    Value::Array(items) => items,
+    Value::Object(object) => match object.get("docks") {
+      Some(Value::Array(items)) => items,
+      _ => return Err(invalid("expected an array or an object with a `docks` array".to_string())),
+    },
+    _ => return Err(invalid("expected an array or an object with a `docks` array".to_string())),
+  };
+  items.iter().map(|item| dock_range(item).map_err(invalid)).collect()
+}
+
+/// WMS에서 받은 1차, 2차 도크들.
+#[derive(Debug, Clone, Default)]
+pub struct WmsPriorities {
+  pub first: Vec<DockRange>,
+  pub second: Vec<DockRange>,
+}
+
+/// WMS에 오늘(--date가 있다면 그 날짜)의 바쁜 도크들을 물어 1차, 2차 도크로 나누는 함수.
+/// URL의 {date}는 YYYY-MM-DD 날짜로 바뀌며, --wms-token이 있다면 Bearer 토큰으로 보낸다.
+/// 응답에서 --wms-first, --wms-second 경로가 가리키는 값들이 도크 번호나 범위 문자열, 또는 그 배열이어야 한다.
+pub fn fetch_wms_priorities(args: &Args) -> Result<WmsPriorities, String> {
+  let Some(url) = &args.wms_url else {
+    return Ok(WmsPriorities::default());
+  };
+  let date = args.date.clone().unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+  let url = url.replace("{date}", &date);
+  let token = match &args.wms_token {
+    Some(source) => Some(String::from_utf8(read_secret(source, "WMS token")?).map_err(|_| "WMS token is not valid UTF-8.".to_string())?),
+    None => None,
+  };
+  let json = get_json(&url, token.as_deref(), "WMS response")?;
+
+  let docks = |path: &str| -> Result<Vec<DockRange>, String> {
+    let invalid = |reason: String| format!("Invalid WMS response from '{url}' at '{path}': {reason}");
+    parse_json_path(path)?
+      .select(&json)
+      .into_iter()
+      // 경로가 배열을 가리킨다면 그 항목들이 도크이다.
+      .flat_map(|value| match value {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+      })
+      .map(|item| dock_range(item).map_err(invalid))
+      .collect()
+  };
+  Ok(WmsPriorities { first: docks(&args.wms_first)?, second: docks(&args.wms_second)? })
+}